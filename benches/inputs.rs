@@ -0,0 +1,87 @@
+use core::hint::black_box;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use libtas_movie::{
+    cache::{decode_cache, encode_cache},
+    generate::{GeneratorConfig, MouseGenerator, random_movie},
+    inputs::{Input, Inputs, KeyboardInput},
+    section::SectionRegistry,
+    testkit::{movie_with_mouse_path, sample_movie},
+};
+
+/// The number of frames of the benchmarked movies.
+const FRAMES: usize = 300_000;
+
+/// Parses keyboard-only inputs the way the crate did before the byte-level parser,
+/// allocating each line and splitting with `str::split`.
+fn parse_with_split(s: &str) -> Vec<Input> {
+    s.lines()
+        .map(|line| {
+            let mut input = Input::default();
+            for section in line.split('|').map(str::to_owned) {
+                if let Some(keys) = section.strip_prefix('K') {
+                    let keys = keys
+                        .split(':')
+                        .map(|key| u32::from_str_radix(key, 16))
+                        .collect::<Result<_, _>>()
+                        .unwrap();
+                    input.keyboard = Some(KeyboardInput(keys));
+                }
+            }
+            input
+        })
+        .collect()
+}
+
+fn keyboard(c: &mut Criterion) {
+    let inputs = sample_movie(FRAMES, 60).inputs.to_string();
+
+    let mut group = c.benchmark_group("keyboard");
+    group.bench_function("bytes", |b| {
+        b.iter(|| black_box(&inputs).parse::<Inputs>().unwrap());
+    });
+    group.bench_function("split", |b| {
+        b.iter(|| parse_with_split(black_box(&inputs)));
+    });
+    group.finish();
+}
+
+fn mouse(c: &mut Criterion) {
+    let path = (0..FRAMES as i32)
+        .map(|frame| (frame % 640, frame / 640))
+        .collect::<Vec<_>>();
+    let inputs = movie_with_mouse_path(&path, 60).inputs.to_string();
+
+    c.bench_function("mouse", |b| {
+        b.iter(|| black_box(&inputs).parse::<Inputs>().unwrap());
+    });
+}
+
+fn random(c: &mut Criterion) {
+    let config = GeneratorConfig {
+        frames: FRAMES,
+        mouse: Some(MouseGenerator::default()),
+        ..GeneratorConfig::default()
+    };
+    let inputs = random_movie(&config, 0).inputs.to_string();
+
+    c.bench_function("random", |b| {
+        b.iter(|| black_box(&inputs).parse::<Inputs>().unwrap());
+    });
+}
+
+fn cache(c: &mut Criterion) {
+    let path = (0..FRAMES as i32)
+        .map(|frame| (frame % 640, frame / 640))
+        .collect::<Vec<_>>();
+    let movie = movie_with_mouse_path(&path, 60);
+    let cache = encode_cache(&movie, &[0; 32]);
+    let registry = SectionRegistry::builtin();
+
+    c.bench_function("cache", |b| {
+        b.iter(|| decode_cache(black_box(&cache), &[0; 32], &registry).unwrap());
+    });
+}
+
+criterion_group!(benches, keyboard, mouse, random, cache);
+criterion_main!(benches);
@@ -0,0 +1,250 @@
+//! Command-line tool for working with libTAS movies.
+
+use std::{env, process::ExitCode};
+
+/// The usage of the tool.
+const USAGE: &str = "\
+usage: ltm <command> [arguments]
+
+commands:
+    convert <input> <output>
+        convert a movie between formats detected from the file names or contents
+    diff <old.ltm> <new.ltm> [--no-color]
+        show the changed config keys and frames between two movies,
+        colored when writing to a terminal unless --no-color is given
+    lint <movie.ltm> [--config <lint.ini>] [--allow|--warn|--deny <rule>]...
+        check the movie against the lint rules, failing if any denied rule is broken;
+        the config file has one `rule = allow|warn|deny` per line
+    repair <movie.ltm> [-o <output.ltm>]
+        repair stale frame counts and lengths, annotations without a trailing newline,
+        and repeated keys, saving the movie in place or to <output.ltm>
+    script <script.rhai> <movie.ltm> [-o <output.ltm>]
+        run a script on the movie, saving it in place or to <output.ltm>
+    validate <movie.ltm> [--game <executable>]
+        report the warnings and inconsistencies of the movie,
+        and check the MD5 hash of the game executable";
+
+fn main() -> ExitCode {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let result = match args.first().map(String::as_str) {
+        Some("convert") => convert(&args[1..]),
+        Some("diff") => diff(&args[1..]),
+        Some("lint") => lint(&args[1..]),
+        Some("repair") => repair(&args[1..]),
+        Some("script") => script(&args[1..]),
+        Some("validate") => validate(&args[1..]),
+        _ => Err(USAGE.to_owned()),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{message}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Splits `args` into positional arguments and the value of `-o`, if any.
+fn split_output(args: &[String]) -> Result<(Vec<&str>, Option<&str>), String> {
+    let mut positional = vec![];
+    let mut output = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "-o" {
+            let path = args
+                .next()
+                .ok_or_else(|| "missing path after -o".to_owned())?;
+            output = Some(path.as_str());
+        } else {
+            positional.push(arg.as_str());
+        }
+    }
+    Ok((positional, output))
+}
+
+/// Runs `ltm convert`.
+fn convert(args: &[String]) -> Result<(), String> {
+    use libtas_movie::format::FormatRegistry;
+
+    let [from, to] = args else {
+        return Err(USAGE.to_owned());
+    };
+    FormatRegistry::builtin()
+        .convert(from, to)
+        .map_err(|err| format!("cannot convert {from} into {to}: {err:?}"))
+}
+
+/// Runs `ltm diff`.
+fn diff(args: &[String]) -> Result<(), String> {
+    use std::io::IsTerminal as _;
+
+    use libtas_movie::{diff::MovieDiff, movie::load_movie};
+
+    let (old_path, new_path, color) = match args {
+        [old, new] => (old, new, std::io::stdout().is_terminal()),
+        [old, new, flag] if flag == "--no-color" => (old, new, false),
+        _ => return Err(USAGE.to_owned()),
+    };
+    let old = load_movie(old_path).map_err(|err| format!("cannot load {old_path}: {err:?}"))?;
+    let new = load_movie(new_path).map_err(|err| format!("cannot load {new_path}: {err:?}"))?;
+    print!("{}", MovieDiff::new(&old, &new).render(color));
+    Ok(())
+}
+
+/// Runs `ltm lint`.
+fn lint(args: &[String]) -> Result<(), String> {
+    use libtas_movie::{
+        lint::{Linter, Severity},
+        movie::load_movie,
+    };
+
+    let mut linter = Linter::builtin();
+    let mut movie_path = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let severity = match arg.as_str() {
+            "--config" => {
+                let path = args.next().ok_or_else(|| USAGE.to_owned())?;
+                let text = std::fs::read_to_string(path)
+                    .map_err(|err| format!("cannot read {path}: {err}"))?;
+                linter
+                    .configure(&text)
+                    .map_err(|err| format!("{path}: invalid line: {}", err.0))?;
+                continue;
+            }
+            "--allow" => Severity::Allow,
+            "--warn" => Severity::Warn,
+            "--deny" => Severity::Deny,
+            _ if movie_path.is_none() => {
+                movie_path = Some(arg);
+                continue;
+            }
+            _ => return Err(USAGE.to_owned()),
+        };
+        let rule = args.next().ok_or_else(|| USAGE.to_owned())?;
+        if !linter.set_severity(rule, severity) {
+            return Err(format!("unknown lint rule: {rule}"));
+        }
+    }
+    let movie_path = movie_path.ok_or_else(|| USAGE.to_owned())?;
+
+    let movie =
+        load_movie(movie_path).map_err(|err| format!("cannot load {movie_path}: {err:?}"))?;
+    let lints = linter.run(&movie);
+    for lint in &lints {
+        println!("{lint}");
+    }
+    let denied = lints
+        .iter()
+        .filter(|lint| lint.severity == Severity::Deny)
+        .count();
+    if denied > 0 {
+        return Err(format!("{movie_path}: {denied} denied lint(s)"));
+    }
+    println!("{movie_path}: {} lint(s)", lints.len());
+    Ok(())
+}
+
+/// Runs `ltm repair`.
+fn repair(args: &[String]) -> Result<(), String> {
+    use libtas_movie::{movie::load_movie, repair::RepairOptions};
+
+    let (positional, output) = split_output(args)?;
+    let [movie_path] = positional[..] else {
+        return Err(USAGE.to_owned());
+    };
+    let mut movie =
+        load_movie(movie_path).map_err(|err| format!("cannot load {movie_path}: {err:?}"))?;
+    let repairs = movie.repair(&RepairOptions::default());
+    for repair in &repairs {
+        println!("{repair:?}");
+    }
+
+    // an unrepaired movie is only written when saving elsewhere
+    if repairs.is_empty() && output.is_none() {
+        println!("nothing to repair");
+        return Ok(());
+    }
+    let output = output.unwrap_or(movie_path);
+    movie
+        .save_to_path(output)
+        .map_err(|err| format!("cannot save {output}: {err}"))
+}
+
+/// Runs `ltm script`.
+#[cfg(feature = "scripting")]
+fn script(args: &[String]) -> Result<(), String> {
+    use libtas_movie::{movie::load_movie, scripting::run_script};
+
+    let (positional, output) = split_output(args)?;
+    let [script_path, movie_path] = positional[..] else {
+        return Err(USAGE.to_owned());
+    };
+    let script = std::fs::read_to_string(script_path)
+        .map_err(|err| format!("cannot read {script_path}: {err}"))?;
+    let mut movie =
+        load_movie(movie_path).map_err(|err| format!("cannot load {movie_path}: {err:?}"))?;
+    run_script(&mut movie, &script).map_err(|err| format!("{script_path}: {}", err.0))?;
+
+    let output = output.unwrap_or(movie_path);
+    movie
+        .save_to_path(output)
+        .map_err(|err| format!("cannot save {output}: {err}"))
+}
+
+/// Runs `ltm script`, which needs the `scripting` feature.
+#[cfg(not(feature = "scripting"))]
+fn script(args: &[String]) -> Result<(), String> {
+    split_output(args)?;
+    Err("ltm was built without the `scripting` feature".to_owned())
+}
+
+/// Runs `ltm validate`.
+fn validate(args: &[String]) -> Result<(), String> {
+    use std::io::Write as _;
+
+    use libtas_movie::{hash::md5_of_file_with_progress, movie::LoadOptions};
+
+    let (movie_path, game) = match args {
+        [movie_path] => (movie_path, None),
+        [movie_path, flag, game] if flag == "--game" => (movie_path, Some(game)),
+        _ => return Err(USAGE.to_owned()),
+    };
+    let (movie, warnings) = LoadOptions::default()
+        .load_with_warnings(movie_path)
+        .map_err(|err| format!("cannot load {movie_path}: {err:?}"))?;
+    let mut problems = 0;
+    for warning in &warnings.0 {
+        println!("warning: {warning:?}");
+    }
+    for inconsistency in movie.inconsistencies() {
+        println!("inconsistency: {inconsistency:?}");
+        problems += 1;
+    }
+
+    if let Some(game) = game {
+        let md5 = md5_of_file_with_progress(game, |read, size| {
+            eprint!(
+                "\rhashing {game}: {}%",
+                (read * 100).checked_div(size).unwrap_or(100)
+            );
+            let _ = std::io::stderr().flush();
+        })
+        .map_err(|err| format!("cannot read {game}: {err}"))?;
+        eprintln!();
+        match movie.config.general.md5 {
+            Some(expected) if expected == md5 => println!("md5 matches: {md5}"),
+            Some(expected) => {
+                println!("md5 mismatch: movie {expected}, game {md5}");
+                problems += 1;
+            }
+            None => println!("md5 is not recorded in the movie, game {md5}"),
+        }
+    }
+
+    if problems > 0 {
+        return Err(format!("{movie_path}: {problems} problem(s) found"));
+    }
+    println!("{movie_path} is valid");
+    Ok(())
+}
@@ -0,0 +1,142 @@
+//! Module that defines alternative input sequences stored in a movie.
+//!
+//! Branches are stored in the `branches.txt` entry, each as a `[name]` line
+//! followed by its inputs in the format of the `inputs` file.
+
+use core::{ops::Range, str::FromStr as _};
+
+use crate::{
+    diff::diff_inputs,
+    inputs::Inputs,
+    movie::LibTASMovie,
+    section::{InvalidSectionError, MovieSection},
+};
+
+/// A named alternative input sequence.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Branch {
+    /// The name of the branch, without a newline.
+    pub name: String,
+    /// The inputs of the branch.
+    pub inputs: Inputs,
+}
+
+/// The branches of a movie, in the order they were created.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Branches(pub Vec<Branch>);
+
+impl Branches {
+    /// Returns the branch `name`.
+    pub fn get(&self, name: &str) -> Option<&Branch> {
+        self.0.iter().find(|branch| branch.name == name)
+    }
+
+    /// Returns the names of the branches.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(|branch| branch.name.as_str())
+    }
+}
+
+impl MovieSection for Branches {
+    fn name() -> &'static str {
+        "branches.txt"
+    }
+
+    fn parse(data: &[u8]) -> Result<Self, InvalidSectionError> {
+        let Ok(s) = core::str::from_utf8(data) else {
+            return Err(InvalidSectionError("not UTF-8".to_owned()));
+        };
+
+        let mut branches = vec![];
+        let mut current: Option<(&str, String)> = None;
+        for line in s.lines() {
+            if let Some(name) = line
+                .strip_prefix('[')
+                .and_then(|line| line.strip_suffix(']'))
+            {
+                branches.extend(current.take());
+                current = Some((name, String::new()));
+            } else if let Some((_, inputs)) = &mut current {
+                inputs.push_str(line);
+                inputs.push('\n');
+            } else if !line.is_empty() {
+                return Err(InvalidSectionError(line.to_owned()));
+            }
+        }
+        branches.extend(current);
+
+        branches
+            .into_iter()
+            .map(|(name, inputs)| match Inputs::from_str(&inputs) {
+                Ok(inputs) => Ok(Branch {
+                    name: name.to_owned(),
+                    inputs,
+                }),
+                Err(err) => Err(InvalidSectionError(format!("{name}: {err:?}"))),
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+
+    fn serialize(&self) -> Vec<u8> {
+        let mut data = String::new();
+        for branch in &self.0 {
+            data += &format!("[{}]\n{}", branch.name, branch.inputs);
+        }
+        data.into_bytes()
+    }
+}
+
+impl LibTASMovie {
+    /// Returns the branches of the movie.
+    pub fn branches(&self) -> Option<&Branches> {
+        self.sections.get::<Branches>()
+    }
+
+    /// Saves the current inputs as the branch `name`, replacing any branch with the same name.
+    /// Returns `false` without saving if `name` contains a newline.
+    pub fn create_branch(&mut self, name: &str) -> bool {
+        if name.contains('\n') {
+            return false;
+        }
+        let branch = Branch {
+            name: name.to_owned(),
+            inputs: self.inputs.clone(),
+        };
+        let branches = self.sections.get_or_insert_default::<Branches>();
+        match branches.0.iter_mut().find(|old| old.name == name) {
+            Some(old) => *old = branch,
+            None => branches.0.push(branch),
+        }
+        true
+    }
+
+    /// Removes and returns the branch `name`.
+    pub fn remove_branch(&mut self, name: &str) -> Option<Branch> {
+        let branches = self.sections.get_mut::<Branches>()?;
+        let index = branches.0.iter().position(|branch| branch.name == name)?;
+        Some(branches.0.remove(index))
+    }
+
+    /// Replaces the current inputs with the inputs of the branch `name`,
+    /// updating the frame count and the length. Returns the previous inputs,
+    /// or `None` if there is no such branch.
+    ///
+    /// The previous inputs are not saved; use [`LibTASMovie::create_branch`] first to keep them.
+    pub fn switch_branch(&mut self, name: &str) -> Option<Inputs> {
+        let inputs = self.branches()?.get(name)?.inputs.clone();
+        let previous = core::mem::replace(&mut self.inputs, inputs);
+        self.sync_length();
+        Some(previous)
+    }
+
+    /// Returns the ranges of frames that differ between the branches `a` and `b`,
+    /// or `None` if either branch does not exist.
+    pub fn diff_branches(&self, a: &str, b: &str) -> Option<Vec<Range<usize>>> {
+        let branches = self.branches()?;
+        Some(diff_inputs(
+            &branches.get(a)?.inputs,
+            &branches.get(b)?.inputs,
+        ))
+    }
+}
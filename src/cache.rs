@@ -0,0 +1,450 @@
+//! Module that defines a sidecar cache of movies, which loads faster than the `.ltm` file.
+//!
+//! The cache is a binary file next to the movie, named by [`cache_path`].
+//! It stores each frame as the difference from the previous frame,
+//! with an index of frames stored in full,
+//! and the SHA-256 hash of the `.ltm` file to tell when the cache is stale.
+//!
+//! The format is private to this crate and may change between versions,
+//! in which case old caches are ignored.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use sha2::{Digest as _, Sha256};
+
+use crate::{
+    inputs::{ControllerInput, Input, Inputs, KeyboardInput, MouseInput, ReferenceMode},
+    movie::{EntryError, LibTASMovie, LoadError, LoadOptions},
+    section::SectionRegistry,
+    time::Framerate,
+};
+
+/// The magic bytes at the start of a cache, followed by the format version.
+const MAGIC: &[u8; 8] = b"LTMCACHE";
+/// The version of the format.
+const VERSION: u32 = 2;
+/// The number of frames between two frames stored in full.
+pub const INDEX_INTERVAL: usize = 4096;
+
+/// Flags of the parts of a frame that differ from the previous frame.
+const KEYBOARD: u8 = 1 << 0;
+const MOUSE: u8 = 1 << 1;
+const CONTROLLERS: u8 = 1 << 2;
+const FRAMERATE: u8 = 1 << 3;
+const UNKNOWN: u8 = 1 << 4;
+
+/// An error while reading a cache.
+#[derive(Debug)]
+pub enum CacheError {
+    /// The cache was written for another version of the `.ltm` file.
+    Stale,
+    /// The cache is corrupted, or was written in another version of the format.
+    Invalid,
+    /// An entry of the cache cannot be parsed.
+    Load(LoadError),
+}
+
+/// Returns the path of the cache of the movie file `path`, which is `path` with `.cache` appended.
+pub fn cache_path<P: AsRef<Path>>(path: P) -> PathBuf {
+    let mut path = path.as_ref().as_os_str().to_owned();
+    path.push(".cache");
+    PathBuf::from(path)
+}
+
+/// Returns the SHA-256 hash of the contents of a `.ltm` file, used to validate caches.
+pub fn file_hash(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(data).into()
+}
+
+/// Encodes `movie` into a cache of the `.ltm` file whose hash is `file_hash`.
+pub fn encode_cache(movie: &LibTASMovie, file_hash: &[u8; 32]) -> Vec<u8> {
+    let mut writer = Writer(vec![]);
+    writer.0.extend_from_slice(MAGIC);
+    writer.u32(VERSION);
+    writer.0.extend_from_slice(file_hash);
+
+    writer.bytes(movie.config.to_string().as_bytes());
+    writer.bytes(movie.annotations.as_bytes());
+    writer.bytes(movie.editor.to_string().as_bytes());
+    let sections = movie.sections.entries().collect::<Vec<_>>();
+    writer.len(sections.len());
+    for (name, data) in &sections {
+        writer.bytes(name.as_bytes());
+        writer.bytes(data);
+    }
+    writer.len(movie.inputs.1.len());
+    for (frame, line) in &movie.inputs.1 {
+        writer.len(*frame);
+        writer.bytes(line.as_bytes());
+    }
+
+    let frames = &movie.inputs.0;
+    let mut offsets = vec![];
+    let mut data = Writer(vec![]);
+    let default = Input::default();
+    for (frame, input) in frames.iter().enumerate() {
+        let previous = if frame % INDEX_INTERVAL == 0 {
+            offsets.push(data.0.len());
+            &default
+        } else {
+            &frames[frame - 1]
+        };
+        data.frame(input, previous);
+    }
+    writer.len(frames.len());
+    for offset in offsets {
+        writer.len(offset);
+    }
+    writer.bytes(&data.0);
+    writer.0
+}
+
+/// Decodes a cache of the `.ltm` file whose hash is `file_hash`,
+/// parsing the custom sections registered in `sections`.
+pub fn decode_cache(
+    data: &[u8],
+    file_hash: &[u8; 32],
+    sections: &SectionRegistry,
+) -> Result<LibTASMovie, CacheError> {
+    let mut reader = Reader(data);
+    if reader.take(MAGIC.len()) != Some(MAGIC) || reader.u32() != Some(VERSION) {
+        return Err(CacheError::Invalid);
+    }
+    match reader.take(32) {
+        Some(hash) if hash == file_hash => {}
+        Some(_) => return Err(CacheError::Stale),
+        None => return Err(CacheError::Invalid),
+    }
+    decode_movie(&mut reader, sections)
+        .ok_or(CacheError::Invalid)?
+        .map_err(CacheError::Load)
+}
+
+/// Decodes the movie after the header of a cache.
+/// Returns `None` if the cache is malformed.
+fn decode_movie(
+    reader: &mut Reader<'_>,
+    sections: &SectionRegistry,
+) -> Option<Result<LibTASMovie, LoadError>> {
+    let mut movie = LibTASMovie::default();
+    let config = reader.str()?;
+    let annotations = reader.str()?;
+    let editor = reader.str()?;
+    if let Err(err) = movie.load_config(config) {
+        return Some(Err(LoadError::invalid_entry(
+            "config.ini",
+            EntryError::Config(err),
+        )));
+    }
+    movie.load_annotations(annotations);
+    if let Err(err) = movie.load_editor(editor) {
+        return Some(Err(LoadError::invalid_entry(
+            "editor.ini",
+            EntryError::Editor(err),
+        )));
+    }
+    for _ in 0..reader.len()? {
+        let name = reader.str()?;
+        let data = reader.bytes()?;
+        if let Err(err) = movie.sections.load(sections, name, data) {
+            return Some(Err(LoadError::invalid_entry(
+                name,
+                EntryError::Section(err),
+            )));
+        }
+    }
+
+    let other_lines = reader.len()?;
+    let mut lines = Vec::with_capacity(other_lines.min(reader.0.len()));
+    for _ in 0..other_lines {
+        lines.push((reader.len()?, reader.str()?.to_owned()));
+    }
+
+    let len = reader.len()?;
+    // skip the index, which is only needed to decode from the middle
+    reader.take(len.div_ceil(INDEX_INTERVAL).checked_mul(8)?)?;
+    let mut frames = Reader(reader.bytes()?);
+    let mut inputs: Vec<Input> = Vec::with_capacity(len.min(frames.0.len()));
+    let default = Input::default();
+    for frame in 0..len {
+        let previous = if frame % INDEX_INTERVAL == 0 {
+            &default
+        } else {
+            &inputs[frame - 1]
+        };
+        let input = frames.frame(previous)?;
+        inputs.push(input);
+    }
+    if !frames.0.is_empty() || !reader.0.is_empty() {
+        return None;
+    }
+    movie.inputs = Inputs(inputs, lines);
+    Some(Ok(movie))
+}
+
+/// Loads the movie file `path` with the default [`LoadOptions`], using its cache if valid.
+///
+/// Otherwise, the movie is loaded from `path` and the cache is written,
+/// ignoring errors while writing it.
+pub fn load_movie_cached<P: AsRef<Path>>(path: P) -> Result<LibTASMovie, LoadError> {
+    let path = path.as_ref();
+    let data = fs::read(path).map_err(LoadError::FileError)?;
+    let hash = file_hash(&data);
+    let options = LoadOptions::default();
+
+    let cache = cache_path(path);
+    if let Ok(cached) = fs::read(&cache)
+        && let Ok(movie) = decode_cache(&cached, &hash, &options.sections)
+    {
+        return Ok(movie);
+    }
+
+    let movie = options.load_from_reader(data.as_slice())?;
+    let _ = fs::write(cache, encode_cache(&movie, &hash));
+    Ok(movie)
+}
+
+/// A buffer to encode a cache into.
+struct Writer(Vec<u8>);
+
+impl Writer {
+    fn u8(&mut self, value: u8) {
+        self.0.push(value);
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn u64(&mut self, value: u64) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn len(&mut self, len: usize) {
+        self.u64(len as u64);
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.len(bytes.len());
+        self.0.extend_from_slice(bytes);
+    }
+
+    /// Writes the parts of `input` that differ from `previous`.
+    fn frame(&mut self, input: &Input, previous: &Input) {
+        let mut flags = 0;
+        if input.keyboard != previous.keyboard {
+            flags |= KEYBOARD;
+        }
+        if input.mouse != previous.mouse {
+            flags |= MOUSE;
+        }
+        if input.controllers != previous.controllers {
+            flags |= CONTROLLERS;
+        }
+        // compared as written, as `120/2` equals `60/1`
+        if input.framerate.map(|rate| (rate.num, rate.den))
+            != previous.framerate.map(|rate| (rate.num, rate.den))
+        {
+            flags |= FRAMERATE;
+        }
+        if input.unknown != previous.unknown {
+            flags |= UNKNOWN;
+        }
+        self.u8(flags);
+
+        if flags & KEYBOARD != 0 {
+            match &input.keyboard {
+                Some(keyboard) => {
+                    self.len(keyboard.0.len() + 1);
+                    for &key in &keyboard.0 {
+                        self.u32(key);
+                    }
+                }
+                None => self.len(0),
+            }
+        }
+        if flags & MOUSE != 0 {
+            match &input.mouse {
+                Some(mouse) => {
+                    let buttons = [
+                        mouse.left_click,
+                        mouse.middle_click,
+                        mouse.right_click,
+                        mouse.button4,
+                        mouse.button5,
+                    ];
+                    let buttons = buttons
+                        .iter()
+                        .enumerate()
+                        .fold(0, |bits, (index, &pressed)| {
+                            bits | u8::from(pressed) << index
+                        });
+                    self.u8(1 + u8::from(mouse.reference_mode == ReferenceMode::Relative));
+                    self.u8(buttons);
+                    self.u32(mouse.xpos as u32);
+                    self.u32(mouse.ypos as u32);
+                }
+                None => self.u8(0),
+            }
+        }
+        if flags & CONTROLLERS != 0 {
+            self.len(input.controllers.len());
+            for controller in &input.controllers {
+                self.u8(controller.number);
+                self.bytes(controller.state.as_bytes());
+            }
+        }
+        if flags & FRAMERATE != 0 {
+            match input.framerate {
+                Some(framerate) => {
+                    self.u8(1);
+                    self.u64(framerate.num);
+                    self.u64(framerate.den);
+                }
+                None => self.u8(0),
+            }
+        }
+        if flags & UNKNOWN != 0 {
+            self.len(input.unknown.len());
+            for section in &input.unknown {
+                self.bytes(section.as_bytes());
+            }
+        }
+    }
+}
+
+/// A cursor over a cache, whose methods return `None` at the end of the data.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let (taken, rest) = self.0.split_at_checked(len)?;
+        self.0 = rest;
+        Some(taken)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.take(4)?.try_into().ok()?))
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        Some(u64::from_le_bytes(self.take(8)?.try_into().ok()?))
+    }
+
+    fn len(&mut self) -> Option<usize> {
+        self.u64()?.try_into().ok()
+    }
+
+    fn bytes(&mut self) -> Option<&'a [u8]> {
+        let len = self.len()?;
+        self.take(len)
+    }
+
+    fn str(&mut self) -> Option<&'a str> {
+        core::str::from_utf8(self.bytes()?).ok()
+    }
+
+    /// Reads a frame written as the difference from `previous`.
+    fn frame(&mut self, previous: &Input) -> Option<Input> {
+        let flags = self.u8()?;
+        if flags & !(KEYBOARD | MOUSE | CONTROLLERS | FRAMERATE | UNKNOWN) != 0 {
+            return None;
+        }
+
+        let keyboard = if flags & KEYBOARD != 0 {
+            match self.len()? {
+                0 => None,
+                len => {
+                    let keys = self.take((len - 1).checked_mul(4)?)?;
+                    let keys = keys
+                        .chunks_exact(4)
+                        .map(|key| u32::from_le_bytes([key[0], key[1], key[2], key[3]]))
+                        .collect();
+                    Some(KeyboardInput(keys))
+                }
+            }
+        } else {
+            previous.keyboard.clone()
+        };
+
+        let mouse = if flags & MOUSE != 0 {
+            match self.u8()? {
+                0 => None,
+                mode @ (1 | 2) => {
+                    let buttons = self.u8()?;
+                    let pressed = |index: u8| buttons & 1 << index != 0;
+                    Some(MouseInput {
+                        reference_mode: match mode {
+                            1 => ReferenceMode::Absolute,
+                            _ => ReferenceMode::Relative,
+                        },
+                        left_click: pressed(0),
+                        middle_click: pressed(1),
+                        right_click: pressed(2),
+                        button4: pressed(3),
+                        button5: pressed(4),
+                        xpos: self.u32()? as i32,
+                        ypos: self.u32()? as i32,
+                    })
+                }
+                _ => return None,
+            }
+        } else {
+            previous.mouse
+        };
+
+        let controllers = if flags & CONTROLLERS != 0 {
+            let len = self.len()?;
+            let mut controllers = Vec::with_capacity(len.min(self.0.len()));
+            for _ in 0..len {
+                controllers.push(ControllerInput {
+                    number: self.u8()?,
+                    state: self.str()?.to_owned(),
+                });
+            }
+            controllers
+        } else {
+            previous.controllers.clone()
+        };
+
+        let framerate = if flags & FRAMERATE != 0 {
+            match self.u8()? {
+                0 => None,
+                1 => Some(Framerate {
+                    num: self.u64()?,
+                    den: self.u64()?,
+                }),
+                _ => return None,
+            }
+        } else {
+            previous.framerate
+        };
+
+        let unknown = if flags & UNKNOWN != 0 {
+            let len = self.len()?;
+            let mut unknown = Vec::with_capacity(len.min(self.0.len()));
+            for _ in 0..len {
+                unknown.push(self.str()?.to_owned());
+            }
+            unknown
+        } else {
+            previous.unknown.clone()
+        };
+
+        Some(Input {
+            keyboard,
+            mouse,
+            controllers,
+            flags: (),
+            unknown,
+            framerate,
+        })
+    }
+}
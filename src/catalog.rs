@@ -0,0 +1,258 @@
+//! Module that defines an index of the movie files in a directory tree.
+
+use core::{fmt::Write as _, time::Duration};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use sha2::{Digest as _, Sha256};
+
+use crate::{
+    movie::{LibTASMovie, LoadError, load_config_from_reader, load_movie},
+    pass::Normalize,
+    version::Version,
+};
+
+/// The metadata of a movie file in a [`Catalog`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CatalogEntry {
+    /// The path of the movie file.
+    pub path: PathBuf,
+    /// The name of the game executable.
+    pub game_name: String,
+    /// Author(s) of the movie.
+    pub authors: String,
+    /// The number of frames in the movie.
+    pub frame_count: u64,
+    /// The movie length written in the config.
+    pub length: Duration,
+    /// The MD5 hash of the game executable in lowercase hexadecimal, or empty if unknown.
+    pub md5: String,
+    /// The version of libTAS that wrote the movie.
+    pub libtas_version: Version,
+    /// The SHA-256 hash of the movie file, in lowercase hexadecimal.
+    pub file_hash: String,
+}
+
+impl CatalogEntry {
+    /// Reads the metadata of `data`, the contents of the movie file `path`.
+    /// Only the config is parsed.
+    pub fn from_bytes(path: impl Into<PathBuf>, data: &[u8]) -> Result<Self, LoadError> {
+        let config = load_config_from_reader(data)?;
+        let general = &config.general;
+        Ok(Self {
+            path: path.into(),
+            game_name: general.game_name.clone(),
+            authors: general.authors.clone(),
+            frame_count: general.frame_count,
+            length: general.length.into(),
+            md5: general.md5.map_or_else(String::new, |md5| md5.to_string()),
+            libtas_version: general.libtas_version(),
+            file_hash: hex(&Sha256::digest(data)),
+        })
+    }
+
+    /// Reads the metadata of the movie file `path`.
+    pub fn from_path(path: impl Into<PathBuf>) -> Result<Self, LoadError> {
+        let path = path.into();
+        match fs::read(&path) {
+            Ok(data) => Self::from_bytes(path, &data),
+            Err(err) => Err(LoadError::FileError(err)),
+        }
+    }
+}
+
+/// An index of the `.ltm` files in a directory tree.
+#[derive(Debug, Default)]
+pub struct Catalog {
+    /// The movies that were read, sorted by path.
+    pub entries: Vec<CatalogEntry>,
+    /// The `.ltm` files that failed to load, sorted by path.
+    pub errors: Vec<(PathBuf, LoadError)>,
+}
+
+impl Catalog {
+    /// Scans `root` recursively for `.ltm` files, reading only their configs.
+    /// Fails if a directory cannot be read.
+    pub fn scan<P: AsRef<Path>>(root: P) -> std::io::Result<Self> {
+        let mut paths = vec![];
+        let mut directories = vec![root.as_ref().to_path_buf()];
+        while let Some(directory) = directories.pop() {
+            for entry in fs::read_dir(directory)? {
+                let path = entry?.path();
+                if path.is_dir() {
+                    directories.push(path);
+                } else if path.extension().is_some_and(|extension| extension == "ltm") {
+                    paths.push(path);
+                }
+            }
+        }
+        paths.sort();
+
+        let mut catalog = Self::default();
+        for path in paths {
+            match CatalogEntry::from_path(&path) {
+                Ok(entry) => catalog.entries.push(entry),
+                Err(err) => catalog.errors.push((path, err)),
+            }
+        }
+        Ok(catalog)
+    }
+
+    /// Returns the entries as a JSON array of objects, one per line.
+    /// The length is written in seconds.
+    pub fn to_json(&self) -> String {
+        let mut json = "[".to_owned();
+        for (index, entry) in self.entries.iter().enumerate() {
+            if index != 0 {
+                json.push(',');
+            }
+            let _ = write!(
+                json,
+                "\n  {{\"path\": {}, \"game_name\": {}, \"authors\": {}, \"frame_count\": {}, \
+                 \"length\": {}.{:09}, \"md5\": {}, \"libtas_version\": {}, \"file_hash\": {}}}",
+                json_string(&entry.path.to_string_lossy()),
+                json_string(&entry.game_name),
+                json_string(&entry.authors),
+                entry.frame_count,
+                entry.length.as_secs(),
+                entry.length.subsec_nanos(),
+                json_string(&entry.md5),
+                json_string(&entry.libtas_version.to_string()),
+                json_string(&entry.file_hash),
+            );
+        }
+        json.push_str("\n]\n");
+        json
+    }
+
+    /// Returns the entries as CSV with a header row.
+    /// The length is written in seconds.
+    pub fn to_csv(&self) -> String {
+        let mut csv =
+            "path,game_name,authors,frame_count,length,md5,libtas_version,file_hash\n".to_owned();
+        for entry in &self.entries {
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{}.{:09},{},{},{}",
+                csv_field(&entry.path.to_string_lossy()),
+                csv_field(&entry.game_name),
+                csv_field(&entry.authors),
+                entry.frame_count,
+                entry.length.as_secs(),
+                entry.length.subsec_nanos(),
+                csv_field(&entry.md5),
+                entry.libtas_version,
+                entry.file_hash,
+            );
+        }
+        csv
+    }
+}
+
+impl LibTASMovie {
+    /// Returns the SHA-256 hash of the contents of the movie, in lowercase hexadecimal.
+    ///
+    /// The hash is computed over the serialized entries with normalized inputs,
+    /// so it does not depend on the compression, the order of the archive entries,
+    /// or the order of keys within a frame.
+    pub fn content_hash(&self) -> String {
+        let mut inputs = self.inputs.clone();
+        inputs.apply_pass(Normalize);
+        let config = self.config.to_string();
+        let inputs = inputs.to_string();
+        let editor = self.editor.to_string();
+        let mut entries = vec![
+            ("annotations.txt", self.annotations.as_bytes().to_vec()),
+            ("config.ini", config.into_bytes()),
+            ("editor.ini", editor.into_bytes()),
+            ("inputs", inputs.into_bytes()),
+        ];
+        entries.extend(self.sections.entries());
+        entries.sort();
+
+        let mut hasher = Sha256::new();
+        for (name, data) in entries {
+            hasher.update((name.len() as u64).to_le_bytes());
+            hasher.update(name);
+            hasher.update((data.len() as u64).to_le_bytes());
+            hasher.update(data);
+        }
+        hex(&hasher.finalize())
+    }
+}
+
+impl Catalog {
+    /// Returns the groups of movies with the same [content hash](LibTASMovie::content_hash),
+    /// sorted by path.
+    ///
+    /// Only movies with the same game MD5 and frame count are fully loaded and hashed.
+    /// Movies that fail to load are skipped.
+    pub fn find_duplicates(&self) -> Vec<Vec<&CatalogEntry>> {
+        let mut candidates: Vec<&CatalogEntry> = self.entries.iter().collect();
+        candidates.sort_by(|a, b| {
+            (&a.md5, a.frame_count, &a.path).cmp(&(&b.md5, b.frame_count, &b.path))
+        });
+
+        let mut duplicates = vec![];
+        for group in candidates
+            .chunk_by(|a, b| a.md5 == b.md5 && a.frame_count == b.frame_count)
+            .filter(|group| group.len() > 1)
+        {
+            let mut hashed: Vec<_> = group
+                .iter()
+                .filter_map(|entry| {
+                    let movie = load_movie(&entry.path).ok()?;
+                    Some((movie.content_hash(), *entry))
+                })
+                .collect();
+            hashed.sort_by(|a, b| (&a.0, &a.1.path).cmp(&(&b.0, &b.1.path)));
+            duplicates.extend(
+                hashed
+                    .chunk_by(|a, b| a.0 == b.0)
+                    .filter(|same| same.len() > 1)
+                    .map(|same| same.iter().map(|(_, entry)| *entry).collect()),
+            );
+        }
+        duplicates.sort_by(|a: &Vec<&CatalogEntry>, b| a[0].path.cmp(&b[0].path));
+        duplicates
+    }
+}
+
+/// Returns `bytes` in lowercase hexadecimal.
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut hex, byte| {
+        let _ = write!(hex, "{byte:02x}");
+        hex
+    })
+}
+
+/// Returns `s` as a quoted JSON string.
+pub(crate) fn json_string(s: &str) -> String {
+    let mut json = "\"".to_owned();
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c < ' ' => {
+                let _ = write!(json, "\\u{:04x}", u32::from(c));
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// Returns `s` as a CSV field, quoted if needed.
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_owned()
+    }
+}
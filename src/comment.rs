@@ -0,0 +1,116 @@
+//! Module that defines free-text comments attached to frames.
+//!
+//! Comments are stored in the `comments.txt` entry, one `frame text` line per frame,
+//! where backslashes and newlines of the text are escaped as `\\` and `\n`.
+
+use std::collections::BTreeMap;
+
+use crate::{
+    movie::LibTASMovie,
+    section::{InvalidSectionError, MovieSection},
+};
+
+/// The comments of a movie, by frame.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FrameComments(pub BTreeMap<u64, String>);
+
+impl FrameComments {
+    /// Re-indexes the comments after replacing `removed` frames at `at` with `inserted` frames.
+    ///
+    /// Comments of replaced frames are kept, comments of deleted frames are removed,
+    /// and comments after the edit are shifted.
+    pub fn splice(&mut self, at: u64, removed: u64, inserted: u64) {
+        let after = self.0.split_off(&at);
+        for (frame, text) in after {
+            if frame >= at + removed {
+                self.0.insert(frame - removed + inserted, text);
+            } else if frame - at < inserted {
+                self.0.insert(frame, text);
+            }
+        }
+    }
+}
+
+/// Escapes backslashes and newlines of `text`.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+/// Reverts [`escape`], or returns `None` on an invalid escape sequence.
+fn unescape(text: &str) -> Option<String> {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        unescaped.push(match c {
+            '\\' => match chars.next()? {
+                '\\' => '\\',
+                'n' => '\n',
+                _ => return None,
+            },
+            c => c,
+        });
+    }
+    Some(unescaped)
+}
+
+impl MovieSection for FrameComments {
+    fn name() -> &'static str {
+        "comments.txt"
+    }
+
+    fn parse(data: &[u8]) -> Result<Self, InvalidSectionError> {
+        let Ok(s) = core::str::from_utf8(data) else {
+            return Err(InvalidSectionError("not UTF-8".to_owned()));
+        };
+
+        let mut comments = Self::default();
+        for line in s.lines().filter(|line| !line.is_empty()) {
+            let comment = line
+                .split_once(' ')
+                .and_then(|(frame, text)| Some((frame.parse().ok()?, unescape(text)?)));
+            let Some((frame, text)) = comment else {
+                return Err(InvalidSectionError(line.to_owned()));
+            };
+            comments.0.insert(frame, text);
+        }
+        Ok(comments)
+    }
+
+    fn serialize(&self) -> Vec<u8> {
+        let mut data = String::new();
+        for (frame, text) in &self.0 {
+            data += &format!("{frame} {}\n", escape(text));
+        }
+        data.into_bytes()
+    }
+}
+
+impl LibTASMovie {
+    /// Returns the comment of `frame`.
+    pub fn comment(&self, frame: u64) -> Option<&str> {
+        self.sections
+            .get::<FrameComments>()?
+            .0
+            .get(&frame)
+            .map(String::as_str)
+    }
+
+    /// Sets the comment of `frame`, returning the previous comment.
+    pub fn set_comment(&mut self, frame: u64, text: impl Into<String>) -> Option<String> {
+        self.sections
+            .get_or_insert_default::<FrameComments>()
+            .0
+            .insert(frame, text.into())
+    }
+
+    /// Removes and returns the comment of `frame`.
+    /// The `comments.txt` entry is removed with the last comment.
+    pub fn remove_comment(&mut self, frame: u64) -> Option<String> {
+        let comments = self.sections.get_mut::<FrameComments>()?;
+        let text = comments.0.remove(&frame);
+        if comments.0.is_empty() {
+            self.sections.remove::<FrameComments>();
+        }
+        text
+    }
+}
@@ -1,53 +1,446 @@
 //! Module that defines a config of a movie file.
 
-use core::{fmt::Display, str::FromStr};
+use core::{fmt::Display, str::FromStr, time::Duration};
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    path::Path,
+    time::{SystemTime, SystemTimeError, UNIX_EPOCH},
+};
+
+use crate::{
+    hash::Md5Hash,
+    inputs::Rect,
+    time::{Framerate, TimeSpec},
+};
 
 /// An error while parsing a config, containing the string that caused the error.
 #[derive(Debug)]
 #[expect(dead_code)]
 pub struct InvalidConfigError(String);
 
+/// The value that QSettings writes for an invalid (unset) value.
+const INVALID_VALUE: &str = "@Invalid()";
+
+/// Returns `s` escaped as QSettings writes a string value into `config.ini`.
+///
+/// Backslashes, double quotes, and control characters are escaped with backslashes,
+/// and characters outside ASCII are written as `\x` with their UTF-16 code units,
+/// which both Qt 5 and Qt 6 read back.
+/// The value is quoted if it contains `;`, `,`, or `=`, or starts or ends with a space.
+///
+/// # Example
+/// ```
+/// use libtas_movie::config::escape_value;
+/// assert_eq!(escape_value("a, b"), "\"a, b\"");
+/// assert_eq!(escape_value("line\n\"quoted\""), "line\\n\\\"quoted\\\"");
+/// ```
+pub fn escape_value(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut needs_quotes = false;
+    let mut escape_next_if_digit = false;
+    for unit in s.encode_utf16() {
+        let c = char::from_u32(unit.into()).unwrap_or(char::REPLACEMENT_CHARACTER);
+        if matches!(c, ';' | ',' | '=') {
+            needs_quotes = true;
+        }
+        if escape_next_if_digit && c.is_ascii_hexdigit() {
+            result.push_str(&format!("\\x{unit:x}"));
+            continue;
+        }
+        escape_next_if_digit = false;
+        match c {
+            '\0' => {
+                result.push_str("\\0");
+                escape_next_if_digit = true;
+            }
+            '\x07' => result.push_str("\\a"),
+            '\x08' => result.push_str("\\b"),
+            '\x0c' => result.push_str("\\f"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            '\x0b' => result.push_str("\\v"),
+            '"' | '\\' => {
+                result.push('\\');
+                result.push(c);
+            }
+            _ if unit <= 0x1f || unit >= 0x7f => {
+                result.push_str(&format!("\\x{unit:x}"));
+                escape_next_if_digit = true;
+            }
+            _ => result.push(c),
+        }
+    }
+    if needs_quotes || result.starts_with(' ') || result.ends_with(' ') {
+        format!("\"{result}\"")
+    } else {
+        result
+    }
+}
+
+/// Returns the string value written as `s` in `config.ini`, undoing [`escape_value`]
+/// like QSettings reads it.
+///
+/// Quotes are removed, escape sequences are replaced, and spaces around unquoted parts
+/// are trimmed. Unlike QSettings, unquoted commas are kept instead of making a list.
+///
+/// # Example
+/// ```
+/// use libtas_movie::config::unescape_value;
+/// assert_eq!(unescape_value("\"a, b\""), "a, b");
+/// assert_eq!(unescape_value("caf\\xe9"), "café");
+/// ```
+pub fn unescape_value(s: &str) -> String {
+    let mut units: Vec<u16> = Vec::with_capacity(s.len());
+    let mut quoted = false;
+    // the length of `units` up to the last character that is not trimmed
+    let mut kept = 0;
+    let mut chars = s.trim_start().chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                kept = units.len();
+                continue;
+            }
+            '\\' => {
+                let Some(escaped) = chars.next() else {
+                    break;
+                };
+                match escaped {
+                    'a' => units.push(0x07),
+                    'b' => units.push(0x08),
+                    'f' => units.push(0x0c),
+                    'n' => units.push(0x0a),
+                    'r' => units.push(0x0d),
+                    't' => units.push(0x09),
+                    'v' => units.push(0x0b),
+                    'x' => {
+                        let mut value = 0u32;
+                        while let Some(digit) = chars.peek().and_then(|c| c.to_digit(16)) {
+                            value = value.wrapping_mul(16).wrapping_add(digit);
+                            chars.next();
+                        }
+                        units.push(value as u16);
+                    }
+                    '0'..='7' => {
+                        let mut value = escaped.to_digit(8).unwrap_or_else(|| unreachable!());
+                        while let Some(digit) = chars.peek().and_then(|c| c.to_digit(8)) {
+                            value = value.wrapping_mul(8).wrapping_add(digit);
+                            chars.next();
+                        }
+                        units.push(value as u16);
+                    }
+                    // a line continuation
+                    '\n' => {}
+                    other => {
+                        let mut buffer = [0; 2];
+                        units.extend_from_slice(other.encode_utf16(&mut buffer));
+                    }
+                }
+                kept = units.len();
+                continue;
+            }
+            _ => {
+                let mut buffer = [0; 2];
+                units.extend_from_slice(c.encode_utf16(&mut buffer));
+            }
+        }
+        if quoted || !c.is_whitespace() {
+            kept = units.len();
+        }
+    }
+    units.truncate(kept);
+    String::from_utf16_lossy(&units)
+}
+
+/// A value of a modeled key, written as QSettings writes it into `config.ini`.
+///
+/// libTAS writes `config.ini` with QSettings, which marks unset values as `@Invalid()`,
+/// and escapes strings starting with `@` by doubling the `@`,
+/// before escaping them with [`escape_value`].
+/// An `@Invalid()` value reads as an empty string, zero, or `false`, like QSettings reads it.
+trait IniValue: Sized {
+    /// Parses a value as written in `config.ini`.
+    fn from_ini(s: &str) -> Option<Self>;
+    /// Returns the value as written in `config.ini`, or `None` if the key is not written.
+    fn to_ini(&self) -> Option<String>;
+
+    /// Returns whether the value is an unset optional value.
+    fn is_unset(&self) -> bool {
+        false
+    }
+
+    /// Whether an unset optional value is written as an empty value,
+    /// instead of its key not being written.
+    const WRITE_UNSET: bool = false;
+
+    /// Whether a malformed value is kept verbatim as an unmodeled key, leaving the value unset,
+    /// instead of failing to parse the config.
+    const LENIENT: bool = false;
+}
+
+impl IniValue for String {
+    fn from_ini(s: &str) -> Option<Self> {
+        let s = unescape_value(s);
+        if s == INVALID_VALUE {
+            return Some(Self::new());
+        }
+        match s.strip_prefix('@') {
+            Some(rest) if rest.starts_with('@') => Some(rest.to_owned()),
+            _ => Some(s),
+        }
+    }
+
+    fn to_ini(&self) -> Option<String> {
+        if self.starts_with('@') {
+            Some(escape_value(&format!("@{self}")))
+        } else {
+            Some(escape_value(self))
+        }
+    }
+}
+
+/// An optional value, whose key is only written if it is set,
+/// or written as an empty value if [`IniValue::WRITE_UNSET`].
+impl<T: IniValue> IniValue for Option<T> {
+    fn from_ini(s: &str) -> Option<Self> {
+        if T::WRITE_UNSET && (s.is_empty() || s == INVALID_VALUE) {
+            return Some(None);
+        }
+        T::from_ini(s).map(Some)
+    }
+
+    fn to_ini(&self) -> Option<String> {
+        match self {
+            Some(value) => value.to_ini(),
+            None => T::WRITE_UNSET.then(String::new),
+        }
+    }
+
+    fn is_unset(&self) -> bool {
+        self.is_none()
+    }
+
+    const LENIENT: bool = T::LENIENT;
+}
+
+/// An MD5 hash, which libTAS writes as an empty value if it is unknown.
+/// A malformed hash is kept verbatim and reported as a warning.
+impl IniValue for Md5Hash {
+    fn from_ini(s: &str) -> Option<Self> {
+        s.parse().ok()
+    }
+
+    fn to_ini(&self) -> Option<String> {
+        Some(self.to_string())
+    }
+
+    const WRITE_UNSET: bool = true;
+
+    const LENIENT: bool = true;
+}
+
+/// A list of controller types, written as QSettings writes a list of strings.
+impl IniValue for Vec<ControllerType> {
+    fn from_ini(s: &str) -> Option<Self> {
+        if s == INVALID_VALUE {
+            return Some(Self::new());
+        }
+        Some(
+            s.split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(ControllerType::from_name)
+                .collect(),
+        )
+    }
+
+    fn to_ini(&self) -> Option<String> {
+        let names: Vec<_> = self.iter().map(ControllerType::name).collect();
+        Some(names.join(", "))
+    }
+}
+
+macro_rules! impl_ini_value {
+    ($($type:ty),*) => {
+        $(
+            impl IniValue for $type {
+                fn from_ini(s: &str) -> Option<Self> {
+                    if s == INVALID_VALUE {
+                        return Some(Self::default());
+                    }
+                    s.parse().ok()
+                }
+
+                fn to_ini(&self) -> Option<String> {
+                    Some(self.to_string())
+                }
+            }
+        )*
+    };
+}
+
+impl_ini_value!(u32, u64, i64);
+
+/// A boolean, whose case is ignored like [`Config::normalize`] does.
+impl IniValue for bool {
+    fn from_ini(s: &str) -> Option<Self> {
+        if s == INVALID_VALUE || s.eq_ignore_ascii_case("false") {
+            Some(false)
+        } else if s.eq_ignore_ascii_case("true") {
+            Some(true)
+        } else {
+            None
+        }
+    }
+
+    fn to_ini(&self) -> Option<String> {
+        Some(self.to_string())
+    }
+}
+
 macro_rules! impl_str_io {
     (
         $struct:ident,
         $group_marker:literal,
-        $($key:literal => $field:ident: $type:ty),*
+        $($key:literal => $($field:ident).+: $type:ty),*
     ) => {
-        impl FromStr for $struct {
-            type Err = InvalidConfigError;
+        impl $struct {
+            /// Returns the value of `key` as written in the config, if `key` is modeled.
+            pub(crate) fn get_key(&self, key: &str) -> Option<String> {
+                match key {
+                    $(
+                        $key => self.$($field).+.to_ini(),
+                    )*
+                    _ => None,
+                }
+            }
 
-            fn from_str(s: &str) -> Result<Self, Self::Err> {
+            /// Sets `key` to `value`, returning whether `key` is modeled.
+            pub(crate) fn set_key(
+                &mut self,
+                key: &str,
+                value: &str,
+            ) -> Result<bool, InvalidConfigError> {
+                match key {
+                    $(
+                        $key => self.$($field).+ = <$type>::from_ini(value).ok_or_else(
+                            || InvalidConfigError(key.to_owned())
+                        )?,
+                    )*
+                    _ => return Ok(false),
+                }
+                Ok(true)
+            }
+
+            /// Parses the group, also returning the unmodeled keys in their original order.
+            pub(crate) fn parse_with_extra(
+                s: &str,
+            ) -> Result<(Self, Vec<(String, String)>), InvalidConfigError> {
                 if !s.starts_with($group_marker) {
                     return Err(InvalidConfigError($group_marker.to_owned()));
                 }
+                Self::parse_lines(s.lines().skip(1))
+            }
+
+            /// Returns whether a malformed value of `key` is kept as an unmodeled key.
+            fn is_lenient(key: &str) -> bool {
+                match key {
+                    $(
+                        $key => <$type>::LENIENT,
+                    )*
+                    _ => false,
+                }
+            }
 
+            /// Parses the lines of the group after the group marker,
+            /// also returning the unmodeled keys in their original order.
+            ///
+            /// The malformed values of lenient keys are returned as unmodeled keys.
+            pub(crate) fn parse_lines<'s>(
+                lines: impl Iterator<Item = &'s str>,
+            ) -> Result<(Self, Vec<(String, String)>), InvalidConfigError> {
                 let mut config = Self::default();
-                for line in s.lines().skip(1) {
+                let mut extra = vec![];
+                for line in lines {
                     let Some((key, value)) = line.split_once('=') else {
                         return Err(InvalidConfigError(line.to_owned()));
                     };
-                    match key {
-                        $(
-                            $key => config.$field = value.parse::<$type>().map_err(
-                                |_| InvalidConfigError(key.to_owned())
-                            )?,
-                        )*
-                        _ => {}
+                    match config.set_key(key, value) {
+                        Ok(true) => {}
+                        Ok(false) => extra.push((key.to_owned(), value.to_owned())),
+                        Err(_) if Self::is_lenient(key) => {
+                            extra.push((key.to_owned(), value.to_owned()));
+                        }
+                        Err(err) => return Err(err),
                     }
                 }
-                Ok(config)
+                Ok((config, extra))
             }
         }
 
-        impl Display for $struct {
-            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        impl FromStr for $struct {
+            type Err = InvalidConfigError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Self::parse_with_extra(s).map(|(config, _)| config)
+            }
+        }
+
+        impl $struct {
+            /// Writes the group with the unmodeled keys `extra`, merging both in key order.
+            ///
+            /// The relative order of `extra` is kept, so a group whose keys are sorted,
+            /// as libTAS writes them, is written back in the same order.
+            fn fmt_with_extra(
+                &self,
+                f: &mut core::fmt::Formatter<'_>,
+                extra: &[(String, String)],
+            ) -> core::fmt::Result {
                 writeln!(f, $group_marker)?;
+                self.fmt_keys(f, extra)
+            }
+
+            /// Writes the keys without the group marker, merged with `extra` in key order.
+            ///
+            /// A key of `extra` that is also modeled holds a malformed value,
+            /// which is written while the modeled value is unset.
+            fn fmt_keys(
+                &self,
+                f: &mut core::fmt::Formatter<'_>,
+                extra: &[(String, String)],
+            ) -> core::fmt::Result {
+                let mut extra = extra.iter().peekable();
                 $(
-                    writeln!(f, "{}={}", $key, self.$field)?;
+                    while let Some((key, value)) = extra.next_if(|(key, _)| key.as_str() < $key) {
+                        writeln!(f, "{key}={value}")?;
+                    }
+                    let malformed = extra.next_if(|(key, _)| key == $key);
+                    match malformed {
+                        Some((_, value)) if self.$($field).+.is_unset() => {
+                            writeln!(f, "{}={}", $key, value)?;
+                        }
+                        _ => {
+                            if let Some(value) = self.$($field).+.to_ini() {
+                                writeln!(f, "{}={}", $key, value)?;
+                            }
+                        }
+                    }
                 )*
+                for (key, value) in extra {
+                    writeln!(f, "{key}={value}")?;
+                }
                 Ok(())
             }
         }
+
+        impl Display for $struct {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                self.fmt_with_extra(f, &[])
+            }
+        }
     };
 }
 
@@ -58,6 +451,8 @@ pub struct GeneralConfig {
     pub authors: String,
     /// Whether or not the game automatically restarts after being closed.
     pub auto_restart: bool,
+    /// The type of each controller, or `None` if not recorded.
+    pub controller_types: Option<Vec<ControllerType>>,
     /// The number of frames in the movie.
     pub frame_count: u64,
     /// Denominator of the framerate.
@@ -66,39 +461,49 @@ pub struct GeneralConfig {
     /// Numerator of the framerate.
     /// That is, the framerate is `framerate_num/framerate_den`.
     pub framerate_num: u64,
+    /// The command-line arguments of the game, or `None` if not recorded.
+    pub game_args: Option<String>,
     /// The name of the game executable.
     pub game_name: String,
-    /// The fractional part of the initial system time in seconds (monotonic),
-    /// expressed as nanoseconds.
-    pub initial_monotonic_time_nsec: u64,
-    /// The integer part of the initial system time in seconds (monotonic).
-    pub initial_monotonic_time_sec: u64,
-    /// The fractional part of the initial system time in seconds,
-    /// expressed as nanoseconds.
-    pub initial_time_nsec: u64,
-    /// The integer part of the initial system time in seconds.
-    pub initial_time_sec: u64,
-    /// The fractional part of the movie length in seconds,
-    /// expressed as nanoseconds.
-    pub length_nsec: u64,
-    /// The integer part of the movie length in seconds.
-    pub length_sec: u64,
+    /// The path of the game executable, or `None` if not recorded.
+    pub game_path: Option<String>,
+    /// The initial monotonic time, stored as the `initial_monotonic_time_sec`
+    /// and `initial_monotonic_time_nsec` keys.
+    pub initial_monotonic_time: TimeSpec,
+    /// The initial system time since the Unix epoch, stored as the `initial_time_sec`
+    /// and `initial_time_nsec` keys.
+    pub initial_time: TimeSpec,
+    /// Whether or not controller inputs are sent to the game, or `None` if not recorded.
+    /// See [`GeneralConfig::joystick_support`].
+    pub joystick_support: Option<bool>,
+    /// Whether or not keyboard inputs are sent to the game, or `None` if not recorded,
+    /// as older versions of libTAS do not write it.
+    /// See [`GeneralConfig::keyboard_support`].
+    pub keyboard_support: Option<bool>,
+    /// The movie length, stored as the `length_sec` and `length_nsec` keys.
+    pub length: TimeSpec,
     /// The major version of libTAS (`a` in `a.b.c`).
     pub libtas_major_version: u32,
     /// The minor version of libTAS (`b` in `a.b.c`).
     pub libtas_minor_version: u32,
     /// The patch version of libTAS (`c` in `a.b.c`).
     pub libtas_patch_version: u32,
-    /// The [MD5 hash](https://en.wikipedia.org/wiki/MD5) of the game executable.
-    pub md5: String,
+    /// The MD5 hash of the game executable, or `None` if unknown.
+    pub md5: Option<Md5Hash>,
     /// Whether or not mouse inputs are sent to the game.
     pub mouse_support: bool,
     /// The number of controllers (up to 4).
     pub nb_controllers: u32,
     /// The number of rerecords.
     pub rerecord_count: u64,
-    /// TODO: what does this mean?
+    /// The frame count when the movie was saved, which is the frame of the savestate
+    /// the movie belongs to when saved along with a savestate,
+    /// and equal to `frame_count` when saved at the end of the movie.
     pub savestate_frame_count: u64,
+    /// The height of the game screen in pixels, or `None` if not recorded.
+    pub screen_height: Option<u32>,
+    /// The width of the game screen in pixels, or `None` if not recorded.
+    pub screen_width: Option<u32>,
     /// Whether or not the framerate can change in the middle of the movie.
     pub variable_framerate: bool,
 }
@@ -108,24 +513,31 @@ impl_str_io!(
     "[General]",
     "authors" => authors: String,
     "auto_restart" => auto_restart: bool,
+    "controller_types" => controller_types: Option<Vec<ControllerType>>,
     "frame_count" => frame_count: u64,
     "framerate_den" => framerate_den: u64,
     "framerate_num" => framerate_num: u64,
+    "game_args" => game_args: Option<String>,
     "game_name" => game_name: String,
-    "initial_monotonic_time_nsec" => initial_monotonic_time_nsec: u64,
-    "initial_monotonic_time_sec" => initial_monotonic_time_sec: u64,
-    "initial_time_nsec" => initial_time_nsec: u64,
-    "initial_time_sec" => initial_time_sec: u64,
-    "length_nsec" => length_nsec: u64,
-    "length_sec" => length_sec: u64,
+    "game_path" => game_path: Option<String>,
+    "initial_monotonic_time_nsec" => initial_monotonic_time.nsec: u64,
+    "initial_monotonic_time_sec" => initial_monotonic_time.sec: u64,
+    "initial_time_nsec" => initial_time.nsec: u64,
+    "initial_time_sec" => initial_time.sec: u64,
+    "joystick_support" => joystick_support: Option<bool>,
+    "keyboard_support" => keyboard_support: Option<bool>,
+    "length_nsec" => length.nsec: u64,
+    "length_sec" => length.sec: u64,
     "libtas_major_version" => libtas_major_version: u32,
     "libtas_minor_version" => libtas_minor_version: u32,
     "libtas_patch_version" => libtas_patch_version: u32,
-    "md5" => md5: String,
+    "md5" => md5: Option<Md5Hash>,
     "mouse_support" => mouse_support: bool,
     "nb_controllers" => nb_controllers: u32,
     "rerecord_count" => rerecord_count: u64,
     "savestate_frame_count" => savestate_frame_count: u64,
+    "screen_height" => screen_height: Option<u32>,
+    "screen_width" => screen_width: Option<u32>,
     "variable_framerate" => variable_framerate: bool
 );
 
@@ -134,30 +546,270 @@ impl Default for GeneralConfig {
         Self {
             authors: "".to_owned(),
             auto_restart: false,
+            controller_types: None,
             frame_count: 0,
             framerate_den: 1,
             framerate_num: 60,
+            game_args: None,
             game_name: "".to_owned(),
-            initial_monotonic_time_nsec: 0,
-            initial_monotonic_time_sec: 1,
-            initial_time_nsec: 0,
-            initial_time_sec: 1,
-            length_nsec: 0,
-            length_sec: 0,
+            game_path: None,
+            initial_monotonic_time: TimeSpec::new(1, 0),
+            initial_time: TimeSpec::new(1, 0),
+            joystick_support: None,
+            keyboard_support: None,
+            length: TimeSpec::default(),
             libtas_major_version: 1,
             libtas_minor_version: 4,
             libtas_patch_version: 7,
-            md5: "".to_owned(),
+            md5: None,
             mouse_support: true,
             nb_controllers: 0,
             rerecord_count: 0,
             savestate_frame_count: 0,
+            screen_height: None,
+            screen_width: None,
             variable_framerate: false,
         }
     }
 }
 
-/// `mainthread_timetrack` config.
+impl GeneralConfig {
+    /// Returns the framerate `framerate_num/framerate_den`.
+    pub fn framerate(&self) -> Framerate {
+        Framerate {
+            num: self.framerate_num,
+            den: self.framerate_den,
+        }
+    }
+
+    /// Sets `framerate_num` and `framerate_den` to `framerate`.
+    pub fn set_framerate(&mut self, framerate: Framerate) {
+        self.framerate_num = framerate.num;
+        self.framerate_den = framerate.den;
+    }
+
+    /// Returns the initial system time, as seen by the game,
+    /// or `None` if it is too late to be a [`SystemTime`].
+    pub fn initial_time(&self) -> Option<SystemTime> {
+        UNIX_EPOCH.checked_add(Duration::from(self.initial_time))
+    }
+
+    /// Sets `initial_time` to `time`.
+    /// Fails if `time` is before the Unix epoch.
+    pub fn set_initial_time(&mut self, time: SystemTime) -> Result<(), SystemTimeError> {
+        self.initial_time = time.duration_since(UNIX_EPOCH)?.into();
+        Ok(())
+    }
+
+    /// Returns the initial monotonic time, as seen by the game.
+    pub fn initial_monotonic_time(&self) -> Duration {
+        self.initial_monotonic_time.into()
+    }
+
+    /// Sets `initial_monotonic_time` to `time`.
+    pub fn set_initial_monotonic_time(&mut self, time: Duration) {
+        self.initial_monotonic_time = time.into();
+    }
+
+    /// Sets the initial system time to `time`, and the initial monotonic time as `monotonic`.
+    /// Fails without changing the config if `time` is before the Unix epoch,
+    /// or if the monotonic time would become negative.
+    pub fn set_initial_times(
+        &mut self,
+        time: SystemTime,
+        monotonic: MonotonicTime,
+    ) -> Result<(), InitialTimeError> {
+        let since_epoch = time
+            .duration_since(UNIX_EPOCH)
+            .map_err(InitialTimeError::BeforeEpoch)?;
+        let old = Duration::from(self.initial_time);
+        let new_monotonic = match monotonic {
+            MonotonicTime::Unchanged => self.initial_monotonic_time(),
+            MonotonicTime::KeepOffset if since_epoch >= old => {
+                self.initial_monotonic_time() + (since_epoch - old)
+            }
+            MonotonicTime::KeepOffset => self
+                .initial_monotonic_time()
+                .checked_sub(old - since_epoch)
+                .ok_or(InitialTimeError::NegativeMonotonicTime)?,
+            MonotonicTime::Same => since_epoch,
+        };
+        self.set_initial_time(time)
+            .map_err(InitialTimeError::BeforeEpoch)?;
+        self.set_initial_monotonic_time(new_monotonic);
+        Ok(())
+    }
+
+    /// Checks that the nanoseconds of the initial times are less than a second.
+    pub fn check_initial_times(&self) -> Result<(), InitialTimeError> {
+        for (key, time) in [
+            ("initial_time_nsec", self.initial_time),
+            ("initial_monotonic_time_nsec", self.initial_monotonic_time),
+        ] {
+            if !time.is_normalized() {
+                return Err(InitialTimeError::InvalidNanoseconds(key, time.nsec));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns whether or not controller inputs are sent to the game.
+    /// If `joystick_support` is not recorded, they are sent if `nb_controllers` is not 0.
+    pub fn joystick_support(&self) -> bool {
+        self.joystick_support.unwrap_or(self.nb_controllers > 0)
+    }
+
+    /// Returns whether or not keyboard inputs are sent to the game,
+    /// which they are if `keyboard_support` is not recorded.
+    pub fn keyboard_support(&self) -> bool {
+        self.keyboard_support.unwrap_or(true)
+    }
+
+    /// Returns the type of controller `number`, counted from 1, if recorded.
+    pub fn controller_type(&self, number: u8) -> Option<&ControllerType> {
+        let index = usize::from(number).checked_sub(1)?;
+        self.controller_types.as_ref()?.get(index)
+    }
+
+    /// Returns the rectangle of pointer coordinates on the game screen,
+    /// from `(0, 0)` to `(screen_width - 1, screen_height - 1)`,
+    /// or `None` if the resolution is not recorded or empty.
+    pub fn screen_rect(&self) -> Option<Rect> {
+        let width = i32::try_from(self.screen_width?)
+            .ok()
+            .filter(|&width| width > 0)?;
+        let height = i32::try_from(self.screen_height?)
+            .ok()
+            .filter(|&height| height > 0)?;
+        Some(Rect {
+            left: 0,
+            top: 0,
+            right: width - 1,
+            bottom: height - 1,
+        })
+    }
+
+    /// Records the resolution of the game screen.
+    pub fn set_screen_size(&mut self, width: u32, height: u32) {
+        self.screen_width = Some(width);
+        self.screen_height = Some(height);
+    }
+
+    /// Returns the path of the game executable, if recorded.
+    pub fn game_path(&self) -> Option<&Path> {
+        self.game_path.as_deref().map(Path::new)
+    }
+
+    /// Sets `game_path` to `path`, replacing non-UTF-8 parts.
+    pub fn set_game_path<P: AsRef<Path>>(&mut self, path: P) {
+        self.game_path = Some(path.as_ref().to_string_lossy().into_owned());
+    }
+
+    /// Moves the game executable into the directory `dir`, keeping its file name.
+    /// Returns `false` without changing the config if the path is not recorded
+    /// or has no file name.
+    pub fn set_game_dir<P: AsRef<Path>>(&mut self, dir: P) -> bool {
+        let Some(name) = self.game_path().and_then(Path::file_name) else {
+            return false;
+        };
+        let path = dir.as_ref().join(name);
+        self.set_game_path(path);
+        true
+    }
+
+    /// Replaces the leading `old` components of the game path with `new`,
+    /// e.g. when a movie is moved to another machine.
+    /// Returns `false` without changing the config if the path does not start with `old`.
+    pub fn rebase_game_path<P: AsRef<Path>, Q: AsRef<Path>>(&mut self, old: P, new: Q) -> bool {
+        let Some(rest) = self
+            .game_path()
+            .and_then(|path| path.strip_prefix(old).ok())
+        else {
+            return false;
+        };
+        let path = new.as_ref().join(rest);
+        self.set_game_path(path);
+        true
+    }
+
+    /// Returns the initial system time as a date in UTC,
+    /// or `None` if it is out of the range of dates.
+    #[cfg(feature = "chrono")]
+    pub fn initial_datetime(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        let time = Duration::from(self.initial_time);
+        chrono::DateTime::from_timestamp(i64::try_from(time.as_secs()).ok()?, time.subsec_nanos())
+    }
+
+    /// Sets `initial_time` to `datetime`.
+    /// Fails if `datetime` is before the Unix epoch.
+    #[cfg(feature = "chrono")]
+    pub fn set_initial_datetime<Tz: chrono::TimeZone>(
+        &mut self,
+        datetime: &chrono::DateTime<Tz>,
+    ) -> Result<(), SystemTimeError> {
+        self.set_initial_time(datetime.with_timezone(&chrono::Utc).into())
+    }
+}
+
+/// How the initial monotonic time follows a change of the initial system time.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MonotonicTime {
+    /// The monotonic time does not change.
+    #[default]
+    Unchanged,
+    /// The monotonic time changes by the same amount, keeping the offset between both times.
+    KeepOffset,
+    /// The monotonic time is set to the system time since the Unix epoch.
+    Same,
+}
+
+/// An error in the initial times of a config.
+#[derive(Debug)]
+pub enum InitialTimeError {
+    /// The system time is before the Unix epoch.
+    BeforeEpoch(SystemTimeError),
+    /// The monotonic time would become negative.
+    NegativeMonotonicTime,
+    /// The key has nanoseconds of a second or more.
+    InvalidNanoseconds(&'static str, u64),
+}
+
+/// The type of a controller, as written in `controller_types`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ControllerType {
+    /// An Xbox 360 controller, written as `xbox360`.
+    Xbox360,
+    /// A DualShock 4 controller, written as `ps4`.
+    DualShock4,
+    /// A Switch Pro controller, written as `switch`.
+    SwitchPro,
+    /// Another type, kept as written.
+    Other(String),
+}
+
+impl ControllerType {
+    /// Returns the type written as `name`.
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "xbox360" => Self::Xbox360,
+            "ps4" => Self::DualShock4,
+            "switch" => Self::SwitchPro,
+            _ => Self::Other(name.to_owned()),
+        }
+    }
+
+    /// Returns the name of the type as written in `controller_types`.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Xbox360 => "xbox360",
+            Self::DualShock4 => "ps4",
+            Self::SwitchPro => "switch",
+            Self::Other(name) => name,
+        }
+    }
+}
+
+/// A timetrack group, such as `mainthread_timetrack`.
 /// Each field denotes how many times each function is called
 /// before advancing the deterministic timer, with `-1` meaning disabled.
 ///
@@ -218,17 +870,395 @@ impl Default for TimetrackConfig {
     }
 }
 
-/// Config of a movie.
+/// The threads that a timetrack group applies to,
+/// written as the name of the group without the `_timetrack` suffix.
+///
+/// Scopes are ordered by the names of their groups, as libTAS writes them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ThreadScope {
+    /// The main thread, in the `mainthread_timetrack` group.
+    Main,
+    /// The other threads, in the `secondarythread_timetrack` group.
+    Secondary,
+    /// Other threads, kept as written.
+    Other(String),
+}
+
+impl ThreadScope {
+    /// The suffix of the names of timetrack groups.
+    const SUFFIX: &str = "_timetrack";
+
+    /// Returns the scope written as `name`.
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "mainthread" => Self::Main,
+            "secondarythread" => Self::Secondary,
+            _ => Self::Other(name.to_owned()),
+        }
+    }
+
+    /// Returns the name of the scope, e.g. `mainthread`.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Main => "mainthread",
+            Self::Secondary => "secondarythread",
+            Self::Other(name) => name,
+        }
+    }
+
+    /// Returns the scope of the group named `section`, if it is a timetrack group.
+    pub fn from_section(section: &str) -> Option<Self> {
+        section
+            .strip_suffix(Self::SUFFIX)
+            .filter(|name| !name.is_empty())
+            .map(Self::from_name)
+    }
+
+    /// Returns the name of the group of the scope, e.g. `mainthread_timetrack`.
+    pub fn section(&self) -> String {
+        format!("{}{}", self.name(), Self::SUFFIX)
+    }
+}
+
+impl PartialOrd for ThreadScope {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ThreadScope {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.name().cmp(other.name())
+    }
+}
+
+/// `audio` config, with the audio format that libTAS presents to the game.
+///
+/// Older versions of libTAS do not write this group.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AudioConfig {
+    /// The number of bits per sample.
+    pub bitdepth: u32,
+    /// The number of channels.
+    pub channels: u32,
+    /// The number of samples per second.
+    pub frequency: u32,
+    /// Whether or not the audio is muted.
+    pub mute: bool,
+}
+
+impl_str_io!(
+    AudioConfig,
+    "[audio]",
+    "bitdepth" => bitdepth: u32,
+    "channels" => channels: u32,
+    "frequency" => frequency: u32,
+    "mute" => mute: bool
+);
+
+impl Default for AudioConfig {
+    fn default() -> Self {
+        Self {
+            bitdepth: 16,
+            channels: 2,
+            frequency: 44100,
+            mute: false,
+        }
+    }
+}
+
+/// A config group, or the part of a group that is not modeled by the typed structs.
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RawSection {
+    /// The name of the group without brackets, e.g. `General`.
+    pub name: String,
+    /// Keys and values in their original order.
+    pub entries: Vec<(String, String)>,
+}
+
+impl RawSection {
+    /// Parses a group that is not modeled by the typed structs.
+    pub(crate) fn parse(s: &str) -> Result<Self, InvalidConfigError> {
+        let mut lines = s.lines();
+        let Some(name) = lines
+            .next()
+            .and_then(|line| line.strip_prefix('['))
+            .and_then(|line| line.strip_suffix(']'))
+        else {
+            return Err(InvalidConfigError(s.to_owned()));
+        };
+
+        let mut entries = vec![];
+        for line in lines {
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(InvalidConfigError(line.to_owned()));
+            };
+            entries.push((key.to_owned(), value.to_owned()));
+        }
+        Ok(Self {
+            name: name.to_owned(),
+            entries,
+        })
+    }
+
+    /// Writes the entries without the group marker.
+    fn fmt_entries(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (key, value) in &self.entries {
+            writeln!(f, "{key}={value}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for RawSection {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        writeln!(f, "[{}]", self.name)?;
+        self.fmt_entries(f)
+    }
+}
+
+/// Config of a movie.
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Config {
     pub general: GeneralConfig,
-    pub mainthread_timetrack: TimetrackConfig,
+    /// The `audio` group, or `None` if the config does not have it.
+    pub audio: Option<AudioConfig>,
+    /// The timetrack groups, by the threads they apply to.
+    pub timetracks: BTreeMap<ThreadScope, TimetrackConfig>,
+    /// Keys that are not modeled by the typed fields, grouped by section in their original order.
+    /// Sections named `General`, `audio`, or a timetrack group hold the unmodeled keys
+    /// of those groups.
+    pub extra: Vec<RawSection>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            general: GeneralConfig::default(),
+            audio: None,
+            timetracks: BTreeMap::from([(ThreadScope::Main, TimetrackConfig::default())]),
+            extra: vec![],
+        }
+    }
+}
+
+impl Config {
+    /// Returns the timetrack of the main thread, if the config has it.
+    pub fn mainthread_timetrack(&self) -> Option<&TimetrackConfig> {
+        self.timetracks.get(&ThreadScope::Main)
+    }
+
+    /// Returns the timetrack of the main thread, adding it if the config does not have it.
+    pub fn mainthread_timetrack_mut(&mut self) -> &mut TimetrackConfig {
+        self.timetracks.entry(ThreadScope::Main).or_default()
+    }
+
+    /// Returns the timetrack of the secondary threads, if the config has it.
+    pub fn secondarythread_timetrack(&self) -> Option<&TimetrackConfig> {
+        self.timetracks.get(&ThreadScope::Secondary)
+    }
+
+    /// Returns the timetrack of the secondary threads, adding it if the config does not have it.
+    pub fn secondarythread_timetrack_mut(&mut self) -> &mut TimetrackConfig {
+        self.timetracks.entry(ThreadScope::Secondary).or_default()
+    }
+}
+
+impl Config {
+    /// Returns the unmodeled part of `section`.
+    fn extra_section(&self, section: &str) -> Option<&RawSection> {
+        self.extra.iter().find(|extra| extra.name == section)
+    }
+
+    /// Returns whether `section` is written by a typed struct.
+    fn is_typed_section(&self, section: &str) -> bool {
+        match section {
+            "General" => true,
+            "audio" => self.audio.is_some(),
+            _ => ThreadScope::from_section(section)
+                .is_some_and(|scope| self.timetracks.contains_key(&scope)),
+        }
+    }
+
+    /// Returns the value of `key` in `section` as written in `config.ini`,
+    /// whether or not it is modeled by the typed fields.
+    ///
+    /// # Example
+    /// ```
+    /// use libtas_movie::config::Config;
+    /// let config = Config::default();
+    /// assert_eq!(config.get_raw("General", "framerate_num").as_deref(), Some("60"));
+    /// ```
+    pub fn get_raw(&self, section: &str, key: &str) -> Option<String> {
+        let typed = match section {
+            "General" => self.general.get_key(key),
+            "audio" => self.audio.and_then(|audio| audio.get_key(key)),
+            _ => ThreadScope::from_section(section)
+                .and_then(|scope| self.timetracks.get(&scope))
+                .and_then(|timetrack| timetrack.get_key(key)),
+        };
+        if typed.is_some() {
+            return typed;
+        }
+
+        self.extra_section(section)?
+            .entries
+            .iter()
+            .find(|(entry, _)| entry == key)
+            .map(|(_, value)| value.clone())
+    }
+
+    /// Sets `key` in `section` to `value`, updating the typed field if it is modeled.
+    ///
+    /// Unmodeled keys are kept in order, with new keys and sections appended at the end.
+    /// Keys of `General`, `audio`, and timetrack groups are written among the modeled
+    /// keys in key order. Setting a modeled key of `audio` or of a timetrack group adds the group
+    /// if it is missing, with the other keys set to their defaults.
+    pub fn set_raw(
+        &mut self,
+        section: &str,
+        key: &str,
+        value: &str,
+    ) -> Result<(), InvalidConfigError> {
+        let typed = match section {
+            "General" => self.general.set_key(key, value)?,
+            "audio" => {
+                let mut audio = self.audio.unwrap_or_default();
+                let typed = audio.set_key(key, value)?;
+                if typed {
+                    self.audio = Some(audio);
+                }
+                typed
+            }
+            _ => match ThreadScope::from_section(section) {
+                Some(scope) => {
+                    let mut timetrack = self.timetracks.get(&scope).copied().unwrap_or_default();
+                    let typed = timetrack.set_key(key, value)?;
+                    if typed {
+                        self.timetracks.insert(scope, timetrack);
+                    }
+                    typed
+                }
+                None => false,
+            },
+        };
+        if typed {
+            return Ok(());
+        }
+
+        let index = match self.extra.iter().position(|extra| extra.name == section) {
+            Some(index) => index,
+            None => {
+                self.extra.push(RawSection {
+                    name: section.to_owned(),
+                    entries: vec![],
+                });
+                self.extra.len() - 1
+            }
+        };
+        let entries = &mut self.extra[index].entries;
+        match entries.iter_mut().find(|(entry, _)| entry == key) {
+            Some((_, old)) => value.clone_into(old),
+            None => entries.push((key.to_owned(), value.to_owned())),
+        }
+        Ok(())
+    }
+}
+
+impl Config {
+    /// Normalizes the unmodeled keys the way libTAS writes them, so that the config
+    /// serializes exactly like a config written by libTAS:
+    /// - keys are sorted within each group, keeping the first of repeated keys,
+    /// - boolean values are written as `true` or `false`,
+    /// - groups are sorted by name, and empty groups are removed.
+    pub fn normalize(&mut self) {
+        for section in &mut self.extra {
+            let mut entries: Vec<(String, String)> = vec![];
+            for (key, mut value) in core::mem::take(&mut section.entries) {
+                if entries.iter().any(|(other, _)| *other == key) {
+                    continue;
+                }
+                if value.eq_ignore_ascii_case("true") || value.eq_ignore_ascii_case("false") {
+                    value.make_ascii_lowercase();
+                }
+                entries.push((key, value));
+            }
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            section.entries = entries;
+        }
+        self.extra.retain(|section| !section.entries.is_empty());
+        self.extra.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+}
+
+/// The input devices that libTAS sends to the game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InputsConfig {
+    /// Whether or not keyboard inputs are sent to the game.
+    /// See [`GeneralConfig::keyboard_support`].
+    pub keyboard_support: bool,
+    /// Whether or not mouse inputs are sent to the game.
+    pub mouse_support: bool,
+    /// Whether or not controller inputs are sent to the game.
+    /// See [`GeneralConfig::joystick_support`].
+    pub joystick_support: bool,
+    /// The number of controllers (up to 4).
+    pub nb_controllers: u32,
+}
+
+impl Config {
+    /// Returns the input devices that libTAS sends to the game.
+    pub fn inputs_config(&self) -> InputsConfig {
+        InputsConfig {
+            keyboard_support: self.general.keyboard_support(),
+            mouse_support: self.general.mouse_support,
+            joystick_support: self.general.joystick_support(),
+            nb_controllers: self.general.nb_controllers,
+        }
+    }
+
+    /// Sets the input devices that libTAS sends to the game.
+    /// `keyboard_support` is only written if it is already present or turned off,
+    /// and `joystick_support` if it is already present or differs from whether
+    /// `nb_controllers` is not 0.
+    pub fn set_inputs_config(&mut self, inputs: InputsConfig) {
+        self.general.mouse_support = inputs.mouse_support;
+        self.general.nb_controllers = inputs.nb_controllers;
+        if inputs.joystick_support != (inputs.nb_controllers > 0)
+            || self.general.joystick_support.is_some()
+        {
+            self.general.joystick_support = Some(inputs.joystick_support);
+        }
+        if !inputs.keyboard_support || self.general.keyboard_support.is_some() {
+            self.general.keyboard_support = Some(inputs.keyboard_support);
+        }
+    }
 }
 
 impl Display for Config {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        writeln!(f, "{}", self.general)?;
-        write!(f, "{}", self.mainthread_timetrack)
+        let entries = |section: &str| {
+            self.extra_section(section)
+                .map_or(&[][..], |extra| extra.entries.as_slice())
+        };
+        self.general.fmt_with_extra(f, entries("General"))?;
+        if let Some(audio) = &self.audio {
+            writeln!(f)?;
+            audio.fmt_with_extra(f, entries("audio"))?;
+        }
+        for (scope, timetrack) in &self.timetracks {
+            let section = scope.section();
+            writeln!(f)?;
+            writeln!(f, "[{section}]")?;
+            timetrack.fmt_keys(f, entries(&section))?;
+        }
+        for extra in &self.extra {
+            if !self.is_typed_section(&extra.name) {
+                writeln!(f)?;
+                write!(f, "{extra}")?;
+            }
+        }
+        Ok(())
     }
 }
 
@@ -236,12 +1266,128 @@ impl FromStr for Config {
     type Err = InvalidConfigError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let Some((general, timetrack)) = s.split_once("\n\n") else {
-            return Err(InvalidConfigError("not two groups".to_owned()));
+        let mut general = None;
+        let mut audio = None;
+        let mut timetracks = BTreeMap::new();
+        let mut extra = vec![];
+        for group in s.split("\n\n").filter(|group| !group.trim().is_empty()) {
+            let (section, entries) = if group.starts_with("[General]") {
+                let (config, entries) = GeneralConfig::parse_with_extra(group)?;
+                general = Some(config);
+                ("General".to_owned(), entries)
+            } else if group.starts_with("[audio]") {
+                let (config, entries) = AudioConfig::parse_with_extra(group)?;
+                audio = Some(config);
+                ("audio".to_owned(), entries)
+            } else if let Some(scope) = group
+                .lines()
+                .next()
+                .and_then(|line| line.strip_prefix('[')?.strip_suffix(']'))
+                .and_then(ThreadScope::from_section)
+            {
+                let (config, entries) = TimetrackConfig::parse_lines(group.lines().skip(1))?;
+                let section = scope.section();
+                timetracks.insert(scope, config);
+                (section, entries)
+            } else {
+                extra.push(RawSection::parse(group)?);
+                continue;
+            };
+            if !entries.is_empty() {
+                extra.push(RawSection {
+                    name: section,
+                    entries,
+                });
+            }
+        }
+
+        let Some(general) = general else {
+            return Err(InvalidConfigError("[General]".to_owned()));
         };
         Ok(Self {
-            general: general.parse()?,
-            mainthread_timetrack: timetrack.parse()?,
+            general,
+            audio,
+            timetracks,
+            extra,
+        })
+    }
+}
+
+/// The metadata of a config, with strings borrowed from the text of `config.ini`.
+///
+/// This avoids copying the strings when a movie is loaded only to read its metadata.
+/// Use [`ConfigRef::to_config`] to parse the whole config.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConfigRef<'a> {
+    /// Authors of the movie.
+    pub authors: Cow<'a, str>,
+    /// Name of the game.
+    pub game_name: Cow<'a, str>,
+    /// MD5 hash of the game executable, or `None` if unknown.
+    pub md5: Option<Md5Hash>,
+    /// The text of `config.ini`.
+    text: &'a str,
+}
+
+impl<'a> ConfigRef<'a> {
+    /// Parses the metadata strings of `text` without copying them.
+    /// The other keys are not checked.
+    pub fn parse(text: &'a str) -> Result<Self, InvalidConfigError> {
+        let Some(general) = Self::group(text, "General") else {
+            return Err(InvalidConfigError("[General]".to_owned()));
+        };
+
+        let mut config = Self {
+            text,
+            ..Self::default()
+        };
+        for line in general {
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(InvalidConfigError(line.to_owned()));
+            };
+            match key {
+                "authors" => config.authors = Self::string(value),
+                "game_name" => config.game_name = Self::string(value),
+                // a malformed hash is unknown, like in `Config`
+                "md5" => config.md5 = IniValue::from_ini(value).flatten(),
+                _ => {}
+            }
+        }
+        Ok(config)
+    }
+
+    /// Returns the string value `value` as written in `config.ini`,
+    /// only copying it if it is escaped.
+    fn string(value: &'a str) -> Cow<'a, str> {
+        let escaped = value.starts_with(['@', ' ', '\t'])
+            || value.ends_with(char::is_whitespace)
+            || value.contains(['\\', '"']);
+        if escaped {
+            Cow::Owned(String::from_ini(value).unwrap_or_else(|| unreachable!()))
+        } else {
+            Cow::Borrowed(value)
+        }
+    }
+
+    /// Returns the lines of the group `section` after the group marker.
+    fn group(text: &'a str, section: &str) -> Option<core::str::Lines<'a>> {
+        text.split("\n\n").map(str::lines).find_map(|mut lines| {
+            let marker = lines.next()?.strip_prefix('[')?.strip_suffix(']')?;
+            (marker == section).then_some(lines)
         })
     }
+
+    /// Returns the value of `key` in `section` as written in `config.ini`.
+    pub fn get_raw(&self, section: &str, key: &str) -> Option<&'a str> {
+        Self::group(self.text, section)?.find_map(|line| {
+            line.split_once('=')
+                .filter(|(name, _)| *name == key)
+                .map(|(_, value)| value)
+        })
+    }
+
+    /// Parses the whole config.
+    pub fn to_config(&self) -> Result<Config, InvalidConfigError> {
+        Config::from_str(self.text)
+    }
 }
@@ -0,0 +1,270 @@
+//! Module that keeps the config of a movie consistent with its inputs.
+
+use crate::{
+    inputs::{ControllerInput, KeyboardInput, ReferenceMode},
+    movie::LibTASMovie,
+};
+
+/// An input that libTAS would not handle as written, because of the config.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Inconsistency {
+    /// Keyboard inputs in the frames, while `keyboard_support` is off.
+    UnsupportedKeyboard(Vec<usize>),
+    /// Mouse inputs in the frames, while `mouse_support` is off.
+    UnsupportedMouse(Vec<usize>),
+    /// Inputs of controllers above `nb_controllers` in the frames.
+    ExceedingControllers(Vec<usize>),
+    /// Controller inputs in the frames, while `joystick_support` is off.
+    UnsupportedJoystick(Vec<usize>),
+    /// The number of `controller_types` differs from `nb_controllers`.
+    ControllerTypes {
+        /// The number of controller types.
+        types: usize,
+        /// `nb_controllers`.
+        nb_controllers: u32,
+    },
+}
+
+/// How [`LibTASMovie::fix_mouse_support`] resolves mouse inputs while `mouse_support` is off.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MouseSupportFix {
+    /// Turns `mouse_support` on.
+    #[default]
+    EnableSupport,
+    /// Removes the mouse inputs.
+    StripInputs,
+}
+
+/// The inputs removed by [`LibTASMovie::sanitize_inputs`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SanitizeReport {
+    /// Frames whose keyboard inputs were removed because `keyboard_support` is off.
+    pub keyboard_frames: Vec<usize>,
+    /// Frames whose mouse inputs were removed because `mouse_support` is off.
+    pub mouse_frames: Vec<usize>,
+    /// Controller inputs above `nb_controllers`, or while `joystick_support` is off,
+    /// with their frame.
+    pub controllers: Vec<(usize, ControllerInput)>,
+    /// Keys over [`KeyboardInput::MAX_KEYS`] in a frame, with their frame.
+    pub keys: Vec<(usize, u32)>,
+}
+
+impl SanitizeReport {
+    /// Returns whether nothing was removed.
+    pub fn is_empty(&self) -> bool {
+        self.keyboard_frames.is_empty()
+            && self.mouse_frames.is_empty()
+            && self.controllers.is_empty()
+            && self.keys.is_empty()
+    }
+}
+
+impl LibTASMovie {
+    /// Returns the number of frames, which is the number of inputs.
+    ///
+    /// `frame_count` in the config may disagree if it was not updated after editing the inputs,
+    /// which is reported as a warning when loading
+    /// and checked by [`LibTASMovie::is_length_consistent`].
+    pub fn frame_count(&self) -> usize {
+        self.inputs.0.len()
+    }
+
+    /// Returns whether `frame_count` in the config is the number of frames.
+    pub fn is_frame_count_consistent(&self) -> bool {
+        self.config.general.frame_count == self.frame_count() as u64
+    }
+
+    /// Returns whether `frame_count` and the length in the config agree with the inputs,
+    /// as libTAS expects when playing the movie.
+    /// Use [`LibTASMovie::repair`] to fix them.
+    ///
+    /// The other settings are checked by [`LibTASMovie::inconsistencies`].
+    pub fn is_length_consistent(&self) -> bool {
+        self.is_frame_count_consistent() && self.duration() == self.timeline().total_duration()
+    }
+
+    /// Returns the inconsistencies between the inputs and the config.
+    pub fn inconsistencies(&self) -> Vec<Inconsistency> {
+        let mut inconsistencies = vec![];
+        if !self.config.inputs_config().keyboard_support {
+            let frames: Vec<_> = self
+                .inputs
+                .0
+                .iter()
+                .enumerate()
+                .filter(|(_, input)| input.keyboard.is_some())
+                .map(|(frame, _)| frame)
+                .collect();
+            if !frames.is_empty() {
+                inconsistencies.push(Inconsistency::UnsupportedKeyboard(frames));
+            }
+        }
+        let frames = self.frames_with_unsupported_mouse();
+        if !frames.is_empty() {
+            inconsistencies.push(Inconsistency::UnsupportedMouse(frames));
+        }
+        let frames = self.frames_exceeding_controllers();
+        if !frames.is_empty() {
+            inconsistencies.push(Inconsistency::ExceedingControllers(frames));
+        }
+        let general = &self.config.general;
+        // without `joystick_support`, these are reported as exceeding `nb_controllers`
+        if general.joystick_support == Some(false) {
+            let frames: Vec<_> = self
+                .inputs
+                .0
+                .iter()
+                .enumerate()
+                .filter(|(_, input)| !input.controllers.is_empty())
+                .map(|(frame, _)| frame)
+                .collect();
+            if !frames.is_empty() {
+                inconsistencies.push(Inconsistency::UnsupportedJoystick(frames));
+            }
+        }
+        if let Some(types) = &general.controller_types
+            && types.len() as u64 != u64::from(general.nb_controllers)
+        {
+            inconsistencies.push(Inconsistency::ControllerTypes {
+                types: types.len(),
+                nb_controllers: general.nb_controllers,
+            });
+        }
+        inconsistencies
+    }
+
+    /// Returns the frames with mouse inputs if `mouse_support` is off.
+    pub fn frames_with_unsupported_mouse(&self) -> Vec<usize> {
+        if self.config.general.mouse_support {
+            return vec![];
+        }
+        self.inputs
+            .0
+            .iter()
+            .enumerate()
+            .filter(|(_, input)| input.mouse.is_some())
+            .map(|(frame, _)| frame)
+            .collect()
+    }
+
+    /// Returns the frames with absolute pointer coordinates outside of the screen,
+    /// or no frame if the resolution is not recorded.
+    ///
+    /// See [`GeneralConfig::screen_rect`](crate::config::GeneralConfig::screen_rect).
+    pub fn frames_with_mouse_off_screen(&self) -> Vec<usize> {
+        let Some(screen) = self.config.general.screen_rect() else {
+            return vec![];
+        };
+        self.inputs
+            .0
+            .iter()
+            .enumerate()
+            .filter(|(_, input)| {
+                input.mouse.is_some_and(|mouse| {
+                    mouse.reference_mode == ReferenceMode::Absolute
+                        && !screen.contains(mouse.xpos, mouse.ypos)
+                })
+            })
+            .map(|(frame, _)| frame)
+            .collect()
+    }
+
+    /// Resolves mouse inputs while `mouse_support` is off as chosen by `fix`.
+    /// Returns whether the movie changed.
+    pub fn fix_mouse_support(&mut self, fix: MouseSupportFix) -> bool {
+        let frames = self.frames_with_unsupported_mouse();
+        if frames.is_empty() {
+            return false;
+        }
+        match fix {
+            MouseSupportFix::EnableSupport => self.config.general.mouse_support = true,
+            MouseSupportFix::StripInputs => {
+                for frame in frames {
+                    self.inputs.0[frame].mouse = None;
+                }
+            }
+        }
+        true
+    }
+
+    /// Returns the highest controller number used by the inputs, or 0 if none is used.
+    pub fn max_controller(&self) -> u32 {
+        self.inputs
+            .0
+            .iter()
+            .flat_map(|input| &input.controllers)
+            .map(|controller| u32::from(controller.number))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Raises `nb_controllers` to the highest controller number used by the inputs.
+    /// Returns whether `nb_controllers` changed. It is never lowered.
+    pub fn raise_nb_controllers(&mut self) -> bool {
+        let max = self.max_controller();
+        let general = &mut self.config.general;
+        if general.nb_controllers < max {
+            general.nb_controllers = max;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns the frames with inputs of controllers above `nb_controllers`.
+    pub fn frames_exceeding_controllers(&self) -> Vec<usize> {
+        let nb_controllers = self.config.general.nb_controllers;
+        self.inputs
+            .0
+            .iter()
+            .enumerate()
+            .filter(|(_, input)| {
+                input
+                    .controllers
+                    .iter()
+                    .any(|controller| u32::from(controller.number) > nb_controllers)
+            })
+            .map(|(frame, _)| frame)
+            .collect()
+    }
+
+    /// Removes the inputs that libTAS would ignore with the config:
+    /// keyboard, mouse, and controller inputs of unsupported devices,
+    /// controller inputs above `nb_controllers`, and keys over [`KeyboardInput::MAX_KEYS`]
+    /// in a frame.
+    pub fn sanitize_inputs(&mut self) -> SanitizeReport {
+        let inputs_config = self.config.inputs_config();
+        let nb_controllers = if inputs_config.joystick_support {
+            inputs_config.nb_controllers
+        } else {
+            0
+        };
+        let mut report = SanitizeReport::default();
+        for (frame, input) in self.inputs.0.iter_mut().enumerate() {
+            if !inputs_config.keyboard_support && input.keyboard.take().is_some() {
+                report.keyboard_frames.push(frame);
+            }
+            if let Some(KeyboardInput(keys)) = &mut input.keyboard
+                && keys.len() > KeyboardInput::MAX_KEYS
+            {
+                report.keys.extend(
+                    keys.drain(KeyboardInput::MAX_KEYS..)
+                        .map(|key| (frame, key)),
+                );
+            }
+            if !inputs_config.mouse_support && input.mouse.take().is_some() {
+                report.mouse_frames.push(frame);
+            }
+            let (kept, removed) = core::mem::take(&mut input.controllers)
+                .into_iter()
+                .partition(|controller| u32::from(controller.number) <= nb_controllers);
+            input.controllers = kept;
+            report.controllers.extend(
+                removed
+                    .into_iter()
+                    .map(|controller: ControllerInput| (frame, controller)),
+            );
+        }
+        report
+    }
+}
@@ -0,0 +1,439 @@
+//! Module that compares movies.
+
+use core::{fmt::Display, ops::Range, time::Duration};
+
+use crate::{
+    config::Config,
+    export::chapters::format_timestamp,
+    inputs::{Input, Inputs},
+    intern::InternedInputs,
+    movie::LibTASMovie,
+    segment::Segments,
+};
+
+/// Returns the ranges of frames whose inputs differ between `a` and `b`, in order.
+///
+/// Frames beyond the end of the shorter inputs are different.
+pub fn diff_inputs(a: &Inputs, b: &Inputs) -> Vec<Range<usize>> {
+    diff_frames(&a.0, &b.0)
+}
+
+/// Returns the ranges of frames that differ between `a` and `b`, in order,
+/// comparing each frame in constant time.
+///
+/// Both must be interned with the same [`FrameTable`](crate::intern::FrameTable).
+pub fn diff_interned(a: &InternedInputs, b: &InternedInputs) -> Vec<Range<usize>> {
+    diff_frames(&a.0, &b.0)
+}
+
+/// Returns the ranges of frames that differ between `a` and `b`, in order.
+fn diff_frames<T: PartialEq>(a: &[T], b: &[T]) -> Vec<Range<usize>> {
+    let len = a.len().max(b.len());
+    let mut ranges: Vec<Range<usize>> = vec![];
+    for frame in 0..len {
+        if a.get(frame) == b.get(frame) {
+            continue;
+        }
+        match ranges.last_mut() {
+            Some(range) if range.end == frame => range.end += 1,
+            _ => ranges.push(frame..frame + 1),
+        }
+    }
+    ranges
+}
+
+/// The length of a segment in a movie.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SegmentLength {
+    /// The number of frames.
+    pub frames: usize,
+    /// The duration of the frames.
+    pub duration: Duration,
+}
+
+/// The lengths of a segment in two versions of a movie.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SegmentTiming {
+    /// The label of the segment.
+    pub label: String,
+    /// The length in the old movie, or `None` if the segment is missing.
+    pub old: Option<SegmentLength>,
+    /// The length in the new movie, or `None` if the segment is missing.
+    pub new: Option<SegmentLength>,
+}
+
+impl SegmentTiming {
+    /// Returns the number of frames saved by the new movie, negative if the new movie is slower.
+    /// Returns `None` if the segment is missing from either movie.
+    pub fn frames_gained(&self) -> Option<i64> {
+        Some(self.old?.frames as i64 - self.new?.frames as i64)
+    }
+}
+
+/// A comparison of the segments of two versions of a movie, aligned by their markers.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SegmentComparison(pub Vec<SegmentTiming>);
+
+impl SegmentComparison {
+    /// Returns the number of frames saved in the segments present in both movies.
+    pub fn frames_gained(&self) -> i64 {
+        self.0.iter().filter_map(SegmentTiming::frames_gained).sum()
+    }
+}
+
+impl Display for SegmentComparison {
+    /// Writes a table with a row per segment: label, old and new frames and durations, and gain.
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let length = |length: Option<SegmentLength>| match length {
+            Some(length) => format!("{} ({})", length.frames, format_timestamp(length.duration)),
+            None => "-".to_owned(),
+        };
+        let width = self
+            .0
+            .iter()
+            .map(|timing| timing.label.chars().count())
+            .chain(["segment".len()])
+            .max()
+            .unwrap_or_default();
+        writeln!(
+            f,
+            "{:width$}  {:>20}  {:>20}  {:>7}",
+            "segment", "old", "new", "gained"
+        )?;
+        for timing in &self.0 {
+            let gained = match timing.frames_gained() {
+                Some(gained) => format!("{gained:+}"),
+                None => "-".to_owned(),
+            };
+            writeln!(
+                f,
+                "{:width$}  {:>20}  {:>20}  {:>7}",
+                timing.label,
+                length(timing.old),
+                length(timing.new),
+                gained
+            )?;
+        }
+        writeln!(
+            f,
+            "{:width$}  {:>20}  {:>20}  {:>+7}",
+            "total",
+            "",
+            "",
+            self.frames_gained()
+        )
+    }
+}
+
+/// Returns the lengths of the marker-delimited segments of `movie` with their labels.
+fn segment_lengths(movie: &LibTASMovie) -> Vec<(String, SegmentLength)> {
+    let timeline = movie.timeline();
+    Segments::from_markers(&movie.editor, &movie.inputs)
+        .iter()
+        .map(|segment| {
+            let length = SegmentLength {
+                frames: segment.frames.len(),
+                duration: timeline.time_of_frame(segment.frames.end)
+                    - timeline.time_of_frame(segment.frames.start),
+            };
+            (segment.label.clone(), length)
+        })
+        .collect()
+}
+
+/// Compares the lengths of the segments of `old` and `new`, aligned by the text of their markers.
+///
+/// See [`Segments::from_markers`] for how movies are split.
+/// Segments with the same label are aligned in order of appearance.
+/// Segments are listed in the order of `old`, followed by the segments only in `new`.
+pub fn compare_segments(old: &LibTASMovie, new: &LibTASMovie) -> SegmentComparison {
+    let mut new_segments = segment_lengths(new)
+        .into_iter()
+        .map(Some)
+        .collect::<Vec<_>>();
+    let mut timings = vec![];
+    for (label, length) in segment_lengths(old) {
+        let matching = new_segments
+            .iter_mut()
+            .find(|segment| segment.as_ref().is_some_and(|(new, _)| *new == label));
+        timings.push(SegmentTiming {
+            new: matching.and_then(Option::take).map(|(_, length)| length),
+            label,
+            old: Some(length),
+        });
+    }
+    timings.extend(
+        new_segments
+            .into_iter()
+            .flatten()
+            .map(|(label, length)| SegmentTiming {
+                label,
+                old: None,
+                new: Some(length),
+            }),
+    );
+    SegmentComparison(timings)
+}
+
+/// A config key whose value differs between two movies.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigChange {
+    /// The section of the key, such as `General`.
+    pub section: String,
+    /// The key.
+    pub key: String,
+    /// The value as written in the old movie, or `None` if the key is missing.
+    pub old: Option<String>,
+    /// The value as written in the new movie, or `None` if the key is missing.
+    pub new: Option<String>,
+}
+
+/// The kind of a change between two movies.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ChangeKind {
+    /// Only in the new movie.
+    Added,
+    /// Only in the old movie.
+    Removed,
+    /// In both movies, with different contents.
+    Changed,
+}
+
+/// A run of consecutive frames that differ between two movies.
+///
+/// A hunk is either added, removed, or changed as a whole:
+/// changed frames and frames past the end of one movie are split into separate hunks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FrameHunk {
+    /// The first frame of the hunk.
+    pub start: usize,
+    /// The inputs of the old movie, empty if the frames are added.
+    pub old: Vec<Input>,
+    /// The inputs of the new movie, empty if the frames are removed.
+    pub new: Vec<Input>,
+}
+
+impl FrameHunk {
+    /// Returns the frames of the hunk.
+    pub fn frames(&self) -> Range<usize> {
+        self.start..self.start + self.old.len().max(self.new.len())
+    }
+
+    /// Returns whether the frames are added, removed, or changed.
+    pub fn kind(&self) -> ChangeKind {
+        match (self.old.is_empty(), self.new.is_empty()) {
+            (true, _) => ChangeKind::Added,
+            (_, true) => ChangeKind::Removed,
+            _ => ChangeKind::Changed,
+        }
+    }
+}
+
+/// The differences between the config and the inputs of two movies.
+///
+/// [`Display`] writes a plain aligned listing, and [`MovieDiff::render`] can add colors.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MovieDiff {
+    /// The changed config keys, in the order of the old config,
+    /// followed by the keys only in the new config.
+    pub config: Vec<ConfigChange>,
+    /// The differing frames, in order.
+    pub hunks: Vec<FrameHunk>,
+}
+
+/// Returns the keys of `config` with their section and value, as written.
+fn config_entries(config: &Config) -> Vec<(String, String, String)> {
+    let mut section = String::new();
+    let mut entries = vec![];
+    for line in config.to_string().lines() {
+        if let Some(name) = line
+            .strip_prefix('[')
+            .and_then(|line| line.strip_suffix(']'))
+        {
+            name.clone_into(&mut section);
+        } else if let Some((key, value)) = line.split_once('=') {
+            entries.push((section.clone(), key.to_owned(), value.to_owned()));
+        }
+    }
+    entries
+}
+
+impl MovieDiff {
+    /// Compares the config and the inputs of `old` and `new`.
+    ///
+    /// Config keys are compared as written in `config.ini`,
+    /// and frames are compared at the same index like [`diff_inputs`].
+    pub fn new(old: &LibTASMovie, new: &LibTASMovie) -> Self {
+        let old_entries = config_entries(&old.config);
+        let mut new_entries = config_entries(&new.config)
+            .into_iter()
+            .map(Some)
+            .collect::<Vec<_>>();
+        let mut config = vec![];
+        for (section, key, value) in old_entries {
+            let matching = new_entries
+                .iter_mut()
+                .find(|entry| {
+                    entry
+                        .as_ref()
+                        .is_some_and(|(s, k, _)| *s == section && *k == key)
+                })
+                .and_then(Option::take)
+                .map(|(_, _, value)| value);
+            if matching.as_ref() != Some(&value) {
+                config.push(ConfigChange {
+                    section,
+                    key,
+                    old: Some(value),
+                    new: matching,
+                });
+            }
+        }
+        config.extend(
+            new_entries
+                .into_iter()
+                .flatten()
+                .map(|(section, key, value)| ConfigChange {
+                    section,
+                    key,
+                    old: None,
+                    new: Some(value),
+                }),
+        );
+
+        let (old, new) = (&old.inputs.0, &new.inputs.0);
+        let common = old.len().min(new.len());
+        let hunks = diff_frames(old, new)
+            .into_iter()
+            .flat_map(|range| {
+                // split at the end of the shorter inputs
+                let split = range.end.min(common).max(range.start);
+                [range.start..split, split..range.end]
+            })
+            .filter(|range| !range.is_empty())
+            .map(|range| FrameHunk {
+                start: range.start,
+                old: old.get(range.clone()).unwrap_or_default().to_vec(),
+                new: new.get(range).unwrap_or_default().to_vec(),
+            })
+            .collect();
+        Self { config, hunks }
+    }
+
+    /// Returns whether the movies have the same config and inputs.
+    pub fn is_empty(&self) -> bool {
+        self.config.is_empty() && self.hunks.is_empty()
+    }
+
+    /// Returns the diff rendered for a terminal, colored with ANSI escape codes if `color`.
+    ///
+    /// Each line starts with `+` for added, `-` for removed, or `~` for changed,
+    /// followed by the aligned config key or frame with the old and new values.
+    pub fn render(&self, color: bool) -> DiffRender<'_> {
+        DiffRender { diff: self, color }
+    }
+}
+
+impl Display for MovieDiff {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.render(false).fmt(f)
+    }
+}
+
+/// A [`MovieDiff`] rendered for a terminal, see [`MovieDiff::render`].
+#[derive(Clone, Copy, Debug)]
+pub struct DiffRender<'a> {
+    diff: &'a MovieDiff,
+    color: bool,
+}
+
+impl DiffRender<'_> {
+    /// Writes a line of a change of `kind`, colored if enabled.
+    fn line(
+        &self,
+        f: &mut core::fmt::Formatter<'_>,
+        kind: ChangeKind,
+        columns: core::fmt::Arguments<'_>,
+    ) -> core::fmt::Result {
+        let (mark, color) = match kind {
+            ChangeKind::Added => ('+', "\x1b[32m"),
+            ChangeKind::Removed => ('-', "\x1b[31m"),
+            ChangeKind::Changed => ('~', "\x1b[33m"),
+        };
+        if self.color {
+            writeln!(f, "{color}{mark} {columns}\x1b[0m")
+        } else {
+            writeln!(f, "{mark} {columns}")
+        }
+    }
+}
+
+impl Display for DiffRender<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let diff = self.diff;
+        if diff.is_empty() {
+            return writeln!(f, "no differences");
+        }
+
+        if !diff.config.is_empty() {
+            writeln!(f, "config:")?;
+            let names: Vec<_> = diff
+                .config
+                .iter()
+                .map(|change| format!("{}.{}", change.section, change.key))
+                .collect();
+            let width = names.iter().map(|name| name.chars().count()).max();
+            let old_width = diff
+                .config
+                .iter()
+                .map(|change| change.old.as_ref().map_or(1, |old| old.chars().count()))
+                .max();
+            let (width, old_width) = (width.unwrap_or_default(), old_width.unwrap_or_default());
+            for (change, name) in diff.config.iter().zip(&names) {
+                let kind = match (&change.old, &change.new) {
+                    (None, _) => ChangeKind::Added,
+                    (_, None) => ChangeKind::Removed,
+                    _ => ChangeKind::Changed,
+                };
+                let old = change.old.as_deref().unwrap_or("-");
+                let new = change.new.as_deref().unwrap_or("-");
+                self.line(
+                    f,
+                    kind,
+                    format_args!("{name:width$}  {old:old_width$}  {new}"),
+                )?;
+            }
+        }
+
+        if !diff.hunks.is_empty() {
+            writeln!(f, "frames:")?;
+            let last = diff.hunks.iter().map(|hunk| hunk.frames().end).max();
+            let width = last.unwrap_or_default().saturating_sub(1).to_string().len();
+            let old_width = diff
+                .hunks
+                .iter()
+                .flat_map(|hunk| &hunk.old)
+                .map(|input| input.to_string().len())
+                .max()
+                .unwrap_or(1)
+                .max(1);
+            for hunk in &diff.hunks {
+                let kind = hunk.kind();
+                for frame in hunk.frames() {
+                    let input = |inputs: &[Input]| {
+                        inputs
+                            .get(frame - hunk.start)
+                            .map_or_else(|| "-".to_owned(), Input::to_string)
+                    };
+                    let (old, new) = (input(&hunk.old), input(&hunk.new));
+                    self.line(
+                        f,
+                        kind,
+                        format_args!("{frame:>width$}  {old:old_width$}  {new}"),
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
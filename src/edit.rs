@@ -0,0 +1,316 @@
+//! Module that defines undoable edits of a movie.
+//!
+//! An [`EditSession`] owns a movie and records every mutation as an invertible [`Edit`],
+//! so editor frontends get undo and redo without keeping their own history.
+
+use core::ops::Range;
+
+use crate::{
+    config::Config,
+    inputs::{Input, KeyboardInput},
+    movie::LibTASMovie,
+};
+
+/// An invertible mutation of a movie.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Edit {
+    /// Replaces the frames `removed` at `at` with the frames `inserted`.
+    Splice {
+        at: usize,
+        removed: Vec<Input>,
+        inserted: Vec<Input>,
+    },
+    /// Replaces the config `old` with the config `new`.
+    Config { old: Box<Config>, new: Box<Config> },
+    /// Replaces the comment `old` of `frame` with the comment `new`.
+    Comment {
+        frame: u64,
+        old: Option<String>,
+        new: Option<String>,
+    },
+}
+
+impl Edit {
+    /// Returns the edit reverting this edit.
+    pub fn inverse(&self) -> Self {
+        match self {
+            Self::Splice {
+                at,
+                removed,
+                inserted,
+            } => Self::Splice {
+                at: *at,
+                removed: inserted.clone(),
+                inserted: removed.clone(),
+            },
+            Self::Config { old, new } => Self::Config {
+                old: new.clone(),
+                new: old.clone(),
+            },
+            Self::Comment { frame, old, new } => Self::Comment {
+                frame: *frame,
+                old: new.clone(),
+                new: old.clone(),
+            },
+        }
+    }
+
+    /// Returns whether the edit can be applied to `movie`,
+    /// i.e. the state it replaces is the current state of `movie`.
+    fn matches(&self, movie: &LibTASMovie) -> bool {
+        match self {
+            Self::Splice { at, removed, .. } => at
+                .checked_add(removed.len())
+                .and_then(|end| movie.inputs.0.get(*at..end))
+                .is_some_and(|frames| frames == removed.as_slice()),
+            Self::Config { old, .. } => movie.config == **old,
+            Self::Comment { frame, old, .. } => movie.comment(*frame) == old.as_deref(),
+        }
+    }
+
+    /// Applies the edit to `movie`.
+    fn apply(&self, movie: &mut LibTASMovie) {
+        match self {
+            Self::Splice {
+                at,
+                removed,
+                inserted,
+            } => {
+                movie.splice_inputs(*at..*at + removed.len(), inserted.clone());
+            }
+            Self::Config { new, .. } => movie.config = (**new).clone(),
+            Self::Comment { frame, new, .. } => match new {
+                Some(text) => {
+                    movie.set_comment(*frame, text.clone());
+                }
+                None => {
+                    movie.remove_comment(*frame);
+                }
+            },
+        }
+    }
+}
+
+/// A movie with its history of edits.
+///
+/// Each mutation is its own transaction unless it is made between
+/// [`EditSession::begin_transaction`] and [`EditSession::end_transaction`].
+#[derive(Clone, Debug)]
+pub struct EditSession {
+    movie: LibTASMovie,
+    undo: Vec<Vec<Edit>>,
+    redo: Vec<Vec<Edit>>,
+    transaction: Option<Vec<Edit>>,
+    limit: usize,
+    auto_nb_controllers: bool,
+}
+
+impl EditSession {
+    /// The default number of transactions kept in the history.
+    pub const DEFAULT_LIMIT: usize = 1000;
+
+    /// Starts a session editing `movie`.
+    pub fn new(movie: LibTASMovie) -> Self {
+        Self {
+            movie,
+            undo: vec![],
+            redo: vec![],
+            transaction: None,
+            limit: Self::DEFAULT_LIMIT,
+            auto_nb_controllers: true,
+        }
+    }
+
+    /// Returns the edited movie.
+    pub fn movie(&self) -> &LibTASMovie {
+        &self.movie
+    }
+
+    /// Ends the session and returns the edited movie.
+    pub fn into_movie(self) -> LibTASMovie {
+        self.movie
+    }
+
+    /// Sets the maximum number of transactions that can be undone, dropping the oldest ones.
+    pub fn set_history_limit(&mut self, limit: usize) {
+        self.limit = limit;
+        self.trim();
+    }
+
+    /// Sets whether splices raise `nb_controllers` when they add inputs of more controllers.
+    /// This is on by default.
+    pub fn set_auto_nb_controllers(&mut self, auto: bool) {
+        self.auto_nb_controllers = auto;
+    }
+
+    /// Starts grouping the following edits into a single transaction.
+    /// Does nothing if a transaction is already open.
+    pub fn begin_transaction(&mut self) {
+        self.transaction.get_or_insert_with(Vec::new);
+    }
+
+    /// Ends the open transaction, recording it if it contains any edit.
+    pub fn end_transaction(&mut self) {
+        if let Some(edits) = self.transaction.take()
+            && !edits.is_empty()
+        {
+            self.undo.push(edits);
+            self.trim();
+        }
+    }
+
+    /// Applies `edit` and records it.
+    /// Returns `false` without editing if the state `edit` replaces is not the current state,
+    /// such as frames `removed` by a splice that differ from the frames of the movie.
+    pub fn apply(&mut self, edit: Edit) -> bool {
+        if !edit.matches(&self.movie) {
+            return false;
+        }
+        edit.apply(&mut self.movie);
+        self.redo.clear();
+        match &mut self.transaction {
+            Some(edits) => edits.push(edit),
+            None => {
+                self.undo.push(vec![edit]);
+                self.trim();
+            }
+        }
+        true
+    }
+
+    /// Replaces the frames in `range` with `inputs`.
+    /// Returns `false` without editing if the range is out of bounds.
+    ///
+    /// Comments of deleted frames are removed as part of the same transaction.
+    /// Markers of deleted frames stay on the frame after the edit when undone.
+    /// `nb_controllers` is raised in the same transaction if the inserted frames need it,
+    /// unless turned off with [`EditSession::set_auto_nb_controllers`].
+    pub fn splice(&mut self, range: Range<usize>, inputs: Vec<Input>) -> bool {
+        let Some(removed) = self.movie.inputs.0.get(range.clone()) else {
+            return false;
+        };
+        let removed = removed.to_vec();
+        let max_controller = inputs
+            .iter()
+            .flat_map(|input| &input.controllers)
+            .map(|controller| u32::from(controller.number))
+            .max()
+            .unwrap_or(0);
+
+        let open = self.transaction.is_some();
+        self.begin_transaction();
+        let deleted = range.start + inputs.len().min(removed.len())..range.end;
+        for frame in deleted {
+            self.set_comment(frame as u64, None);
+        }
+        self.apply(Edit::Splice {
+            at: range.start,
+            removed,
+            inserted: inputs,
+        });
+        if self.auto_nb_controllers && self.movie.config.general.nb_controllers < max_controller {
+            self.edit_config(|config| config.general.nb_controllers = max_controller);
+        }
+        if !open {
+            self.end_transaction();
+        }
+        true
+    }
+
+    /// Replaces the input of `frame`.
+    /// Returns `false` without editing if the frame is out of bounds.
+    pub fn set_input(&mut self, frame: usize, input: Input) -> bool {
+        self.splice(frame..frame + 1, vec![input])
+    }
+
+    /// Presses `key` in `frame` if it is released, or releases it if it is pressed.
+    /// Returns `false` without editing if the frame is out of bounds.
+    pub fn toggle_key(&mut self, frame: usize, key: u32) -> bool {
+        let Some(input) = self.movie.inputs.0.get(frame) else {
+            return false;
+        };
+        let mut input = input.clone();
+        let mut keys = input
+            .keyboard
+            .take()
+            .map(|keyboard| keyboard.0)
+            .unwrap_or_default();
+        match keys.iter().position(|&pressed| pressed == key) {
+            Some(index) => {
+                keys.remove(index);
+            }
+            None => keys.push(key),
+        }
+        input.keyboard = (!keys.is_empty()).then_some(KeyboardInput(keys));
+        self.set_input(frame, input)
+    }
+
+    /// Sets the comment of `frame`, or removes it if `text` is `None`.
+    pub fn set_comment(&mut self, frame: u64, text: Option<String>) {
+        let old = self.movie.comment(frame).map(str::to_owned);
+        if old != text {
+            self.apply(Edit::Comment {
+                frame,
+                old,
+                new: text,
+            });
+        }
+    }
+
+    /// Edits the config with `f`.
+    pub fn edit_config(&mut self, f: impl FnOnce(&mut Config)) {
+        let old = self.movie.config.clone();
+        let mut new = old.clone();
+        f(&mut new);
+        if new != old {
+            self.apply(Edit::Config {
+                old: Box::new(old),
+                new: Box::new(new),
+            });
+        }
+    }
+
+    /// Returns whether there is a transaction to undo.
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    /// Returns whether there is a transaction to redo.
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Reverts the last transaction, ending the open transaction first.
+    /// Returns `false` if there is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        self.end_transaction();
+        let Some(edits) = self.undo.pop() else {
+            return false;
+        };
+        for edit in edits.iter().rev() {
+            edit.inverse().apply(&mut self.movie);
+        }
+        self.redo.push(edits);
+        true
+    }
+
+    /// Applies the last undone transaction again.
+    /// Returns `false` if there is nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        self.end_transaction();
+        let Some(edits) = self.redo.pop() else {
+            return false;
+        };
+        for edit in &edits {
+            edit.apply(&mut self.movie);
+        }
+        self.undo.push(edits);
+        true
+    }
+
+    /// Drops the oldest transactions over the limit.
+    fn trim(&mut self) {
+        let excess = self.undo.len().saturating_sub(self.limit);
+        self.undo.drain(..excess);
+    }
+}
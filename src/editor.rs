@@ -0,0 +1,547 @@
+//! Module that defines the TAS editor information of a movie.
+//!
+//! [`Editor`] holds the `editor.ini` entry written by libTAS.
+//! The state of external editors that libTAS does not store, such as locked columns,
+//! is kept in an [`EditorState`] sidecar file next to the movie, named by [`editor_state_path`].
+
+use core::{fmt::Display, ops::Range, str::FromStr};
+use std::path::{Path, PathBuf};
+
+use crate::config::RawSection;
+
+/// An error while parsing editor information, containing the string that caused the error.
+#[derive(Debug)]
+pub struct InvalidEditorError(pub String);
+
+/// A marker placed on a frame in the input editor.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Marker {
+    /// The frame of the marker.
+    pub frame: u64,
+    /// The text of the marker.
+    pub text: String,
+}
+
+/// The input of a column of the input editor, decoded from its `QVariant`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SingleInput {
+    /// The kind of input, such as [`SingleInput::KEYBOARD`] or a mouse coordinate.
+    pub kind: i32,
+    /// The key or button of the input.
+    pub which: u32,
+}
+
+impl SingleInput {
+    /// The kind of keyboard inputs, whose `which` is a keysym.
+    pub const KEYBOARD: i32 = 0;
+}
+
+/// A pattern of an autofire column, repeated from the first frame.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Autofire(pub Vec<bool>);
+
+impl Autofire {
+    /// Returns whether the input is pressed on `frame`.
+    pub fn is_pressed(&self, frame: u64) -> bool {
+        !self.0.is_empty() && self.0[(frame % self.0.len() as u64) as usize]
+    }
+}
+
+impl FromStr for Autofire {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.chars()
+            .map(|c| match c {
+                '0' => Ok(false),
+                '1' => Ok(true),
+                _ => Err(()),
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+impl Display for Autofire {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for &pressed in &self.0 {
+            write!(f, "{}", u8::from(pressed))?;
+        }
+        Ok(())
+    }
+}
+
+/// A column of the input editor.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InputColumn {
+    /// The input as a serialized `QVariant`, e.g. `@Variant(\0\0\0\x7f...)`.
+    pub input: String,
+    /// The name shown in the header.
+    pub name: String,
+    /// Other keys of the column, preserved as is.
+    pub extra: Vec<(String, String)>,
+}
+
+impl InputColumn {
+    /// Decodes the input of the column, or returns `None` if it is not a `SingleInput`.
+    pub fn single_input(&self) -> Option<SingleInput> {
+        let data = unescape_variant(&self.input)?;
+        let (user_type, data) = data.split_first_chunk::<4>()?;
+        let (name_len, data) = data.split_first_chunk::<4>()?;
+        if u32::from_be_bytes(*user_type) != 0x7f {
+            return None;
+        }
+        let (name, data) = data.split_at_checked(u32::from_be_bytes(*name_len) as usize)?;
+        let (kind, data) = data.split_first_chunk::<4>()?;
+        let (which, _) = data.split_first_chunk::<4>()?;
+        // the type name is written with its terminating NUL
+        (name == b"SingleInput\0").then(|| SingleInput {
+            kind: i32::from_be_bytes(*kind),
+            which: u32::from_be_bytes(*which),
+        })
+    }
+}
+
+/// Decodes the bytes of a `QVariant` written by `QSettings` as `@Variant(...)`.
+fn unescape_variant(s: &str) -> Option<Vec<u8>> {
+    let s = s.strip_prefix("@Variant(")?.strip_suffix(')')?;
+    let mut bytes = vec![];
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            bytes.push(u8::try_from(u32::from(c)).ok()?);
+            continue;
+        }
+        bytes.push(match chars.next()? {
+            '0' => 0,
+            'a' => 0x07,
+            'b' => 0x08,
+            't' => b'\t',
+            'n' => b'\n',
+            'v' => 0x0b,
+            'f' => 0x0c,
+            'r' => b'\r',
+            'x' => {
+                let mut value = 0u32;
+                while let Some(digit) = chars.peek().and_then(|c| c.to_digit(16)) {
+                    value = value * 16 + digit;
+                    chars.next();
+                }
+                u8::try_from(value).ok()?
+            }
+            c => u8::try_from(u32::from(c)).ok()?,
+        });
+    }
+    Some(bytes)
+}
+
+/// TAS editor information corresponding to `editor.ini`.
+///
+/// # Syntax
+/// `editor.ini` is written by Qt's `QSettings`, with groups sorted by name.
+/// Markers are stored as an array in the `markers` group,
+/// and columns in the `input_names` group:
+/// ```ini
+/// [input_names]
+/// 1\input=@Variant(\0\0\0\x7f\0\0\0\fSingleInput\0\0\0\0\0\0\0\0z)
+/// 1\name=z
+/// size=1
+///
+/// [markers]
+/// 1\frame=120
+/// 1\text=Level 1
+/// size=1
+/// ```
+#[derive(Clone, Debug, Default, Eq)]
+pub struct Editor {
+    /// Markers in the order they are stored.
+    pub markers: Vec<Marker>,
+    /// Whether an empty `markers` group was read, and is written back without markers.
+    /// It is ignored when there are markers.
+    pub empty_markers_group: bool,
+    /// Columns of the input editor in display order.
+    pub columns: Vec<InputColumn>,
+    /// Other groups, preserved as is.
+    pub extra: Vec<RawSection>,
+}
+
+/// Splits the entries of an array group into the fields of each element.
+fn parse_array(section: &RawSection) -> Result<Vec<Vec<(&str, &str)>>, InvalidEditorError> {
+    let mut elements: Vec<Vec<(&str, &str)>> = vec![];
+    for (key, value) in &section.entries {
+        if key == "size" {
+            continue;
+        }
+        let invalid = || InvalidEditorError(format!("{key}={value}"));
+        let Some((index, field)) = key.split_once('\\') else {
+            return Err(invalid());
+        };
+        let Some(index) = index
+            .parse::<usize>()
+            .ok()
+            .and_then(|index| index.checked_sub(1))
+        else {
+            return Err(invalid());
+        };
+        if elements.len() <= index {
+            elements.resize(index + 1, vec![]);
+        }
+        elements[index].push((field, value));
+    }
+    Ok(elements)
+}
+
+/// Builds an array group from the fields of each element.
+///
+/// Keys are sorted as strings like `QSettings` does, so `10\frame` comes before `2\frame`.
+fn array_section(name: &str, elements: Vec<Vec<(&str, String)>>) -> RawSection {
+    let size = elements.len();
+    let mut entries = vec![];
+    for (index, fields) in elements.into_iter().enumerate() {
+        for (field, value) in fields {
+            entries.push((format!("{}\\{field}", index + 1), value));
+        }
+    }
+    entries.push(("size".to_owned(), size.to_string()));
+    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+    RawSection {
+        name: name.to_owned(),
+        entries,
+    }
+}
+
+impl PartialEq for Editor {
+    fn eq(&self, other: &Self) -> bool {
+        self.markers == other.markers
+            && (!self.markers.is_empty() || self.empty_markers_group == other.empty_markers_group)
+            && self.columns == other.columns
+            && self.extra == other.extra
+    }
+}
+
+impl Editor {
+    /// Returns the marker on `frame`.
+    pub fn marker_at(&self, frame: u64) -> Option<&Marker> {
+        self.markers.iter().find(|marker| marker.frame == frame)
+    }
+
+    /// Returns the markers sorted by frame.
+    pub fn sorted_markers(&self) -> Vec<&Marker> {
+        let mut markers: Vec<_> = self.markers.iter().collect();
+        markers.sort_by_key(|marker| marker.frame);
+        markers
+    }
+
+    /// Returns the column named `name`.
+    pub fn column(&self, name: &str) -> Option<&InputColumn> {
+        self.columns.iter().find(|column| column.name == name)
+    }
+
+    /// Parses the `markers` group.
+    fn parse_markers(section: &RawSection) -> Result<Vec<Marker>, InvalidEditorError> {
+        let mut markers = vec![];
+        for fields in parse_array(section)? {
+            let mut marker = Marker::default();
+            for (field, value) in fields {
+                match field {
+                    "frame" => {
+                        marker.frame = value
+                            .parse()
+                            .map_err(|_| InvalidEditorError(format!("frame={value}")))?;
+                    }
+                    "text" => value.clone_into(&mut marker.text),
+                    _ => {}
+                }
+            }
+            markers.push(marker);
+        }
+        Ok(markers)
+    }
+
+    /// Parses the `input_names` group.
+    fn parse_columns(section: &RawSection) -> Result<Vec<InputColumn>, InvalidEditorError> {
+        let mut columns = vec![];
+        for fields in parse_array(section)? {
+            let mut column = InputColumn::default();
+            for (field, value) in fields {
+                match field {
+                    "input" => value.clone_into(&mut column.input),
+                    "name" => value.clone_into(&mut column.name),
+                    _ => column.extra.push((field.to_owned(), value.to_owned())),
+                }
+            }
+            columns.push(column);
+        }
+        Ok(columns)
+    }
+
+    /// Returns the `markers` group.
+    fn markers_section(&self) -> RawSection {
+        let elements = self
+            .markers
+            .iter()
+            .map(|marker| {
+                vec![
+                    ("frame", marker.frame.to_string()),
+                    ("text", marker.text.clone()),
+                ]
+            })
+            .collect();
+        array_section("markers", elements)
+    }
+
+    /// Returns the `input_names` group.
+    fn columns_section(&self) -> RawSection {
+        let elements = self
+            .columns
+            .iter()
+            .map(|column| {
+                let mut fields = vec![
+                    ("input", column.input.clone()),
+                    ("name", column.name.clone()),
+                ];
+                for (field, value) in &column.extra {
+                    fields.push((field, value.clone()));
+                }
+                fields
+            })
+            .collect();
+        array_section("input_names", elements)
+    }
+}
+
+impl FromStr for Editor {
+    type Err = InvalidEditorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut editor = Self::default();
+        for group in s.split("\n\n").filter(|group| !group.trim().is_empty()) {
+            let section =
+                RawSection::parse(group).map_err(|_| InvalidEditorError(group.to_owned()))?;
+            match section.name.as_str() {
+                "markers" => {
+                    editor.markers = Self::parse_markers(&section)?;
+                    editor.empty_markers_group = editor.markers.is_empty();
+                }
+                "input_names" => editor.columns = Self::parse_columns(&section)?,
+                _ => editor.extra.push(section),
+            }
+        }
+        Ok(editor)
+    }
+}
+
+impl Display for Editor {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if *self == Self::default() {
+            return Ok(());
+        }
+
+        // typed groups go in their sorted position among the other groups
+        let mut typed = vec![];
+        if !self.markers.is_empty() || self.empty_markers_group {
+            typed.push(self.markers_section());
+        }
+        if !self.columns.is_empty() {
+            typed.push(self.columns_section());
+        }
+        typed.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut typed = typed.into_iter().peekable();
+        let mut sections = vec![];
+        for section in &self.extra {
+            while let Some(next) = typed.next_if(|next| next.name < section.name) {
+                sections.push(next);
+            }
+            sections.push(section.clone());
+        }
+        sections.extend(typed);
+
+        // groups are separated by a blank line
+        for (index, section) in sections.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{section}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The state of a column of an external input editor, which libTAS does not store.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ColumnState {
+    /// The name of the column in [`Editor::columns`].
+    pub name: String,
+    /// Whether the column is locked against edits.
+    pub locked: bool,
+    /// Whether the column is hidden.
+    pub hidden: bool,
+    /// The autofire pattern of the column.
+    pub autofire: Option<Autofire>,
+}
+
+/// The scroll position and the selection of the input editor.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ViewState {
+    /// The first visible frame.
+    pub scroll_frame: u64,
+    /// The selected frames.
+    pub selection: Option<Range<u64>>,
+}
+
+/// The state of an external input editor, stored in a sidecar file next to the movie.
+///
+/// # Syntax
+/// The sidecar file uses the syntax of `editor.ini`, with columns in the `columns` group
+/// and the view in the `view` group:
+/// ```ini
+/// [columns]
+/// 1\autofire=10
+/// 1\locked=true
+/// 1\name=z
+/// size=1
+///
+/// [view]
+/// scroll_frame=100
+/// selection_end=130
+/// selection_start=120
+/// ```
+/// `locked` and `hidden` are only written when true.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EditorState {
+    /// The state of the columns that differ from the default state.
+    pub columns: Vec<ColumnState>,
+    /// The scroll position and the selection.
+    pub view: Option<ViewState>,
+}
+
+/// Returns the path of the editor state of the movie file `path`, which is `path` with `.editor` appended.
+pub fn editor_state_path<P: AsRef<Path>>(path: P) -> PathBuf {
+    let mut path = path.as_ref().as_os_str().to_owned();
+    path.push(".editor");
+    PathBuf::from(path)
+}
+
+impl EditorState {
+    /// Returns the state of the column named `name`.
+    pub fn column(&self, name: &str) -> Option<&ColumnState> {
+        self.columns.iter().find(|column| column.name == name)
+    }
+
+    /// Parses the `columns` group.
+    fn parse_columns(section: &RawSection) -> Result<Vec<ColumnState>, InvalidEditorError> {
+        let mut columns = vec![];
+        for fields in parse_array(section)? {
+            let mut column = ColumnState::default();
+            for (field, value) in fields {
+                let invalid = || InvalidEditorError(format!("{field}={value}"));
+                match field {
+                    "name" => value.clone_into(&mut column.name),
+                    "locked" => column.locked = value.parse().map_err(|_| invalid())?,
+                    "hidden" => column.hidden = value.parse().map_err(|_| invalid())?,
+                    "autofire" => column.autofire = Some(value.parse().map_err(|()| invalid())?),
+                    _ => {}
+                }
+            }
+            columns.push(column);
+        }
+        Ok(columns)
+    }
+
+    /// Parses the `view` group.
+    fn parse_view(section: &RawSection) -> Result<ViewState, InvalidEditorError> {
+        let mut view = ViewState::default();
+        let (mut start, mut end) = (None, None);
+        for (key, value) in &section.entries {
+            let value = value
+                .parse()
+                .map_err(|_| InvalidEditorError(format!("{key}={value}")))?;
+            match key.as_str() {
+                "scroll_frame" => view.scroll_frame = value,
+                "selection_start" => start = Some(value),
+                "selection_end" => end = Some(value),
+                _ => {}
+            }
+        }
+        if let (Some(start), Some(end)) = (start, end) {
+            view.selection = Some(start..end);
+        }
+        Ok(view)
+    }
+
+    /// Returns the `columns` group.
+    fn columns_section(&self) -> RawSection {
+        let elements = self
+            .columns
+            .iter()
+            .map(|column| {
+                let mut fields = vec![];
+                if let Some(autofire) = &column.autofire {
+                    fields.push(("autofire", autofire.to_string()));
+                }
+                if column.hidden {
+                    fields.push(("hidden", "true".to_owned()));
+                }
+                if column.locked {
+                    fields.push(("locked", "true".to_owned()));
+                }
+                fields.push(("name", column.name.clone()));
+                fields
+            })
+            .collect();
+        array_section("columns", elements)
+    }
+
+    /// Returns the `view` group.
+    fn view_section(view: &ViewState) -> RawSection {
+        let mut entries = vec![("scroll_frame".to_owned(), view.scroll_frame.to_string())];
+        if let Some(selection) = &view.selection {
+            entries.push(("selection_end".to_owned(), selection.end.to_string()));
+            entries.push(("selection_start".to_owned(), selection.start.to_string()));
+        }
+        RawSection {
+            name: "view".to_owned(),
+            entries,
+        }
+    }
+}
+
+impl FromStr for EditorState {
+    type Err = InvalidEditorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut state = Self::default();
+        for group in s.split("\n\n").filter(|group| !group.trim().is_empty()) {
+            let section =
+                RawSection::parse(group).map_err(|_| InvalidEditorError(group.to_owned()))?;
+            match section.name.as_str() {
+                "columns" => state.columns = Self::parse_columns(&section)?,
+                "view" => state.view = Some(Self::parse_view(&section)?),
+                _ => {}
+            }
+        }
+        Ok(state)
+    }
+}
+
+impl Display for EditorState {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut sections = vec![];
+        if !self.columns.is_empty() {
+            sections.push(self.columns_section());
+        }
+        if let Some(view) = &self.view {
+            sections.push(Self::view_section(view));
+        }
+
+        // groups are separated by a blank line
+        for (index, section) in sections.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{section}")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,8 @@
+//! Module that exports movies to other formats.
+
+#[cfg(feature = "arrow")]
+pub mod arrow;
+pub mod chapters;
+pub mod matrix;
+pub mod script;
+pub mod vcd;
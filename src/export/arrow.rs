@@ -0,0 +1,89 @@
+//! Module that exports frames as [Arrow](https://arrow.apache.org/) record batches
+//! and Parquet files, for analysis with DataFusion, pandas, or Polars.
+//!
+//! Each row is a frame, with the columns:
+//! - `frame`: the index of the frame.
+//! - `time_ns`: the time at which the frame starts, in nanoseconds.
+//!   It is an integer rather than a duration, which Parquet files cannot store.
+//! - `key_<name>` and `button_<label>`: whether each key or button of a [`ColumnSpec`]
+//!   is pressed, such as `key_z` or `button_LMB`.
+//! - `x` and `y`: the pointer coordinates, or null on frames without a mouse input.
+//! - `flags`: the unknown sections of the frame, such as flags, joined with `|`,
+//!   or null if there is none.
+//!
+//! This module is only available with the `arrow` feature,
+//! and [`write_parquet`] with the `parquet` feature.
+
+use std::sync::Arc;
+
+use arrow_array::{
+    ArrayRef, BooleanArray, Int32Array, Int64Array, RecordBatch, StringArray, UInt64Array,
+};
+use arrow_schema::{ArrowError, DataType, Field, Schema};
+
+use crate::{export::matrix::ColumnSpec, inputs::keysym_name, movie::LibTASMovie};
+
+/// Returns the frames of `movie` as a record batch, with a column for each key and button of `spec`.
+pub fn record_batch(movie: &LibTASMovie, spec: &ColumnSpec) -> Result<RecordBatch, ArrowError> {
+    let inputs = &movie.inputs;
+    let timeline = movie.timeline();
+
+    let mut fields = vec![
+        Field::new("frame", DataType::UInt64, false),
+        Field::new("time_ns", DataType::Int64, false),
+    ];
+    let mut columns: Vec<ArrayRef> = vec![
+        Arc::new(UInt64Array::from_iter_values(0..inputs.0.len() as u64)),
+        Arc::new(Int64Array::from_iter_values((0..inputs.0.len()).map(
+            |frame| {
+                timeline
+                    .time_of_frame(frame)
+                    .as_nanos()
+                    .min(i64::MAX as u128) as i64
+            },
+        ))),
+    ];
+
+    let key_names = spec
+        .keys
+        .iter()
+        .map(|&key| format!("key_{}", keysym_name(key)));
+    let button_names = spec.labels()[spec.keys.len()..]
+        .iter()
+        .map(|label| format!("button_{label}"))
+        .collect::<Vec<_>>();
+    let matrix = inputs.to_matrix(spec);
+    for (index, name) in key_names.chain(button_names).enumerate() {
+        fields.push(Field::new(name, DataType::Boolean, false));
+        columns.push(Arc::new(BooleanArray::from_iter(
+            matrix.iter().map(|row| Some(row[index])),
+        )));
+    }
+
+    let series = inputs.to_mouse_series();
+    fields.push(Field::new("x", DataType::Int32, true));
+    columns.push(Arc::new(Int32Array::from(series.x)));
+    fields.push(Field::new("y", DataType::Int32, true));
+    columns.push(Arc::new(Int32Array::from(series.y)));
+
+    fields.push(Field::new("flags", DataType::Utf8, true));
+    columns.push(Arc::new(StringArray::from_iter(inputs.0.iter().map(
+        |input| (!input.unknown.is_empty()).then(|| input.unknown.join("|")),
+    ))));
+
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)
+}
+
+/// Writes the frames of `movie` into `writer` as a Parquet file, with the columns of [`record_batch`].
+#[cfg(feature = "parquet")]
+pub fn write_parquet<W: std::io::Write + Send>(
+    movie: &LibTASMovie,
+    spec: &ColumnSpec,
+    writer: W,
+) -> Result<(), parquet::errors::ParquetError> {
+    let batch = record_batch(movie, spec)?;
+    let mut writer = parquet::arrow::ArrowWriter::try_new(writer, batch.schema(), None)?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(())
+}
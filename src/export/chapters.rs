@@ -0,0 +1,73 @@
+//! Module that exports markers as chapters and timestamps.
+
+use core::time::Duration;
+
+use crate::movie::LibTASMovie;
+
+/// Formats `time` as `HH:MM:SS.mmm`.
+pub fn format_timestamp(time: Duration) -> String {
+    let secs = time.as_secs();
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60,
+        time.subsec_millis()
+    )
+}
+
+/// Escapes the special characters of an ffmpeg metadata value.
+fn escape_metadata(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '=' | ';' | '#' | '\\' | '\n') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Returns an [ffmpeg metadata file](https://ffmpeg.org/ffmpeg-formats.html#Metadata-1)
+/// with a chapter for each marker, lasting until the next marker or the end of the movie.
+///
+/// The file can be passed to ffmpeg with `-i chapters.txt -map_metadata 1`.
+pub fn ffmpeg_chapters(movie: &LibTASMovie) -> String {
+    let timeline = movie.timeline();
+    let end = timeline.time_of_frame(timeline.len());
+    let markers = movie.editor.sorted_markers();
+
+    let mut metadata = ";FFMETADATA1\n".to_owned();
+    for (index, marker) in markers.iter().enumerate() {
+        let start = timeline.time_of_frame(marker.frame as usize);
+        let next = match markers.get(index + 1) {
+            Some(next) => timeline.time_of_frame(next.frame as usize),
+            None => end,
+        };
+        metadata += &format!(
+            "\n[CHAPTER]\nTIMEBASE=1/1000\nSTART={}\nEND={}\ntitle={}\n",
+            start.as_millis(),
+            next.max(start).as_millis(),
+            escape_metadata(&marker.text)
+        );
+    }
+    metadata
+}
+
+/// Returns a table with a `frame<TAB>HH:MM:SS.mmm<TAB>text` line for each marker, sorted by frame.
+pub fn marker_timestamps(movie: &LibTASMovie) -> String {
+    let timeline = movie.timeline();
+    movie
+        .editor
+        .sorted_markers()
+        .iter()
+        .map(|marker| {
+            format!(
+                "{}\t{}\t{}\n",
+                marker.frame,
+                format_timestamp(timeline.time_of_frame(marker.frame as usize)),
+                marker.text
+            )
+        })
+        .collect()
+}
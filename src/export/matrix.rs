@@ -0,0 +1,119 @@
+//! Module that exports inputs as matrices for data analysis.
+//!
+//! The matrices are plain nested vectors, ready to be converted into arrays or data frames.
+
+use crate::{
+    inputs::{Input, Inputs, MouseButton, keysym_name},
+    table::Column,
+};
+
+/// The columns of a button-state matrix: the chosen keys, then the chosen mouse buttons.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ColumnSpec {
+    /// Keys, by their KeySym values.
+    pub keys: Vec<u32>,
+    /// Mouse buttons.
+    pub mouse_buttons: Vec<MouseButton>,
+}
+
+impl ColumnSpec {
+    /// Returns the keys and buttons pressed in `inputs`,
+    /// keys in order of first appearance and buttons from button 1.
+    pub fn for_inputs(inputs: &Inputs) -> Self {
+        let mut spec = Self::default();
+        for column in Column::for_inputs(inputs) {
+            match column {
+                Column::Key(key) => spec.keys.push(key),
+                Column::MouseButton(button) => spec
+                    .mouse_buttons
+                    .push(MouseButton::ALL[usize::from(button) - 1]),
+                Column::MouseX | Column::MouseY => {}
+            }
+        }
+        spec
+    }
+
+    /// Returns the number of columns.
+    pub fn len(&self) -> usize {
+        self.keys.len() + self.mouse_buttons.len()
+    }
+
+    /// Returns whether there is no column.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the header of each column, e.g. `z`, `Right`, or `LMB`.
+    pub fn labels(&self) -> Vec<String> {
+        let buttons = self
+            .mouse_buttons
+            .iter()
+            .map(|&button| Column::MouseButton(button as u8 + 1).short_label());
+        self.keys
+            .iter()
+            .map(|&key| keysym_name(key))
+            .chain(buttons)
+            .collect()
+    }
+
+    /// Returns whether the key or button of each column is pressed in `input`.
+    pub fn pressed<'a>(&'a self, input: &'a Input) -> impl Iterator<Item = bool> + 'a {
+        let keys = self.keys.iter().map(|key| {
+            input
+                .keyboard
+                .as_ref()
+                .is_some_and(|keyboard| keyboard.0.contains(key))
+        });
+        let buttons = self
+            .mouse_buttons
+            .iter()
+            .map(|&button| input.mouse.is_some_and(|mouse| mouse.is_pressed(button)));
+        keys.chain(buttons)
+    }
+}
+
+/// The pointer coordinates of each frame, `None` on frames without a mouse input.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MouseSeries {
+    /// The X-coordinates.
+    pub x: Vec<Option<i32>>,
+    /// The Y-coordinates.
+    pub y: Vec<Option<i32>>,
+}
+
+impl Inputs {
+    /// Returns a matrix of frames by the columns of `spec`,
+    /// whose cells tell whether the key or button is pressed.
+    ///
+    /// # Example
+    /// ```
+    /// use libtas_movie::{export::matrix::ColumnSpec, testkit::sample_movie};
+    ///
+    /// let inputs = sample_movie(4, 60).inputs;
+    /// let spec = ColumnSpec::for_inputs(&inputs);
+    /// assert_eq!(spec.labels(), ["z", "Right"]);
+    /// assert_eq!(
+    ///     inputs.to_matrix(&spec),
+    ///     [[false, false], [true, false], [false, true], [true, true]]
+    /// );
+    /// ```
+    pub fn to_matrix(&self, spec: &ColumnSpec) -> Vec<Vec<bool>> {
+        self.0
+            .iter()
+            .map(|input| spec.pressed(input).collect())
+            .collect()
+    }
+
+    /// Returns the pointer coordinates of each frame.
+    pub fn to_mouse_series(&self) -> MouseSeries {
+        let (x, y) = self
+            .0
+            .iter()
+            .map(|input| match input.mouse {
+                Some(mouse) => (Some(mouse.xpos), Some(mouse.ypos)),
+                None => (None, None),
+            })
+            .unzip();
+        MouseSeries { x, y }
+    }
+}
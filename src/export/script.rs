@@ -0,0 +1,199 @@
+//! Module that exports inputs as `xdotool` and AutoHotkey scripts.
+//!
+//! The scripts approximate the timeline of the movie with sleeps, for demoing input sequences
+//! outside libTAS. They are not frame-accurate.
+
+use core::time::Duration;
+
+use crate::{
+    inputs::{Input, ReferenceMode, keysym_name},
+    movie::LibTASMovie,
+};
+
+/// A change of the held inputs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Action {
+    KeyDown(u32),
+    KeyUp(u32),
+    Move(i32, i32),
+    MoveRelative(i32, i32),
+    ButtonDown(u8),
+    ButtonUp(u8),
+}
+
+/// Returns the held keys and buttons 1 to 5 of `input`.
+fn held(input: &Input) -> (Vec<u32>, [bool; 5]) {
+    let keys = input
+        .keyboard
+        .as_ref()
+        .map(|keyboard| keyboard.0.clone())
+        .unwrap_or_default();
+    let buttons = input.mouse.map_or([false; 5], |mouse| {
+        [
+            mouse.left_click,
+            mouse.middle_click,
+            mouse.right_click,
+            mouse.button4,
+            mouse.button5,
+        ]
+    });
+    (keys, buttons)
+}
+
+/// Returns the changes of the held inputs with their times, releasing everything at the end.
+fn actions(movie: &LibTASMovie) -> Vec<(Duration, Vec<Action>)> {
+    let timeline = movie.timeline();
+    let mut changes = vec![];
+    let mut keys: Vec<u32> = vec![];
+    let mut buttons = [false; 5];
+    let mut position = None;
+    let release = Input::default();
+    let frames = movie.inputs.0.iter().chain([&release]).enumerate();
+    for (frame, input) in frames {
+        let mut actions = vec![];
+        let (new_keys, new_buttons) = held(input);
+        for &key in keys.iter().filter(|key| !new_keys.contains(key)) {
+            actions.push(Action::KeyUp(key));
+        }
+        for &key in new_keys.iter().filter(|key| !keys.contains(key)) {
+            actions.push(Action::KeyDown(key));
+        }
+        if let Some(mouse) = input.mouse {
+            match mouse.reference_mode {
+                ReferenceMode::Absolute if position != Some((mouse.xpos, mouse.ypos)) => {
+                    position = Some((mouse.xpos, mouse.ypos));
+                    actions.push(Action::Move(mouse.xpos, mouse.ypos));
+                }
+                ReferenceMode::Relative if (mouse.xpos, mouse.ypos) != (0, 0) => {
+                    position = None;
+                    actions.push(Action::MoveRelative(mouse.xpos, mouse.ypos));
+                }
+                _ => {}
+            }
+        }
+        for (button, (&old, &new)) in (1..).zip(buttons.iter().zip(&new_buttons)) {
+            match (old, new) {
+                (false, true) => actions.push(Action::ButtonDown(button)),
+                (true, false) => actions.push(Action::ButtonUp(button)),
+                _ => {}
+            }
+        }
+        (keys, buttons) = (new_keys, new_buttons);
+        if !actions.is_empty() {
+            changes.push((timeline.time_of_frame(frame), actions));
+        }
+    }
+    changes
+}
+
+/// Returns the milliseconds to sleep between `last` and `time`, rounding both to milliseconds
+/// so that rounding errors do not accumulate.
+fn sleep_millis(last: Duration, time: Duration) -> u128 {
+    let round = |time: Duration| (time.as_nanos() + 500_000) / 1_000_000;
+    round(time) - round(last)
+}
+
+/// Returns the `xdotool` name of a KeySym: its name if it is alphanumeric or named,
+/// or its value in hexadecimal otherwise.
+fn xdotool_key(keysym: u32) -> String {
+    let name = keysym_name(keysym);
+    if name.len() == 1 && !name.chars().all(char::is_alphanumeric) {
+        format!("{keysym:#x}")
+    } else {
+        name
+    }
+}
+
+/// Returns a shell script replaying the movie with [`xdotool`](https://github.com/jordansissel/xdotool).
+pub fn xdotool_script(movie: &LibTASMovie) -> String {
+    let mut script = format!("#!/bin/sh\n# {}\n", movie.duration_summary());
+    let mut last = Duration::ZERO;
+    for (time, actions) in actions(movie) {
+        let millis = sleep_millis(last, time);
+        if millis > 0 {
+            script += &format!("sleep {}.{:03}\n", millis / 1000, millis % 1000);
+        }
+        last = time;
+
+        let commands: Vec<String> = actions
+            .iter()
+            .map(|action| match *action {
+                Action::KeyDown(key) => format!("keydown {}", xdotool_key(key)),
+                Action::KeyUp(key) => format!("keyup {}", xdotool_key(key)),
+                Action::Move(x, y) => format!("mousemove -- {x} {y}"),
+                Action::MoveRelative(dx, dy) => format!("mousemove_relative -- {dx} {dy}"),
+                Action::ButtonDown(button) => format!("mousedown {button}"),
+                Action::ButtonUp(button) => format!("mouseup {button}"),
+            })
+            .collect();
+        script += &format!("xdotool {}\n", commands.join(" "));
+    }
+    script
+}
+
+/// Returns the AutoHotkey name of a KeySym, or `None` if there is none.
+fn autohotkey_key(keysym: u32) -> Option<String> {
+    let name = match keysym {
+        0x20 => "Space",
+        0x21..=0x7e => return Some(char::from(keysym as u8).to_string()),
+        0xff08 => "Backspace",
+        0xff09 => "Tab",
+        0xff0d => "Enter",
+        0xff1b => "Esc",
+        0xff55 => "PgUp",
+        0xff56 => "PgDn",
+        0xff63 => "Ins",
+        0xffe1 => "LShift",
+        0xffe2 => "RShift",
+        0xffe3 => "LCtrl",
+        0xffe4 => "RCtrl",
+        0xffe9 => "LAlt",
+        0xffea => "RAlt",
+        0xffff => "Del",
+        0xff50..=0xff54 | 0xff57 | 0xffbe..=0xffc9 => return Some(keysym_name(keysym)),
+        _ => return None,
+    };
+    Some(name.to_owned())
+}
+
+/// Returns an [AutoHotkey](https://www.autohotkey.com/) v2 script replaying the movie,
+/// with screen coordinates for the pointer.
+///
+/// Keys without an AutoHotkey name are left as comments.
+pub fn autohotkey_script(movie: &LibTASMovie) -> String {
+    let mut script = format!(
+        "; {}\n#Requires AutoHotkey v2.0\nCoordMode \"Mouse\", \"Screen\"\n",
+        movie.duration_summary()
+    );
+    let mut last = Duration::ZERO;
+    for (time, actions) in actions(movie) {
+        let millis = sleep_millis(last, time);
+        if millis > 0 {
+            script += &format!("Sleep {millis}\n");
+        }
+        last = time;
+
+        for action in actions {
+            let button =
+                |button: u8| ["Left", "Middle", "Right", "X1", "X2"][usize::from(button - 1)];
+            script += &match action {
+                Action::KeyDown(key) | Action::KeyUp(key) => {
+                    let state = if matches!(action, Action::KeyDown(_)) {
+                        "down"
+                    } else {
+                        "up"
+                    };
+                    match autohotkey_key(key) {
+                        Some(name) => format!("Send \"{{{name} {state}}}\"\n"),
+                        None => format!("; key {key:#x} {state}\n"),
+                    }
+                }
+                Action::Move(x, y) => format!("MouseMove {x}, {y}, 0\n"),
+                Action::MoveRelative(dx, dy) => format!("MouseMove {dx}, {dy}, 0, \"R\"\n"),
+                Action::ButtonDown(b) => format!("Click \"{} Down\"\n", button(b)),
+                Action::ButtonUp(b) => format!("Click \"{} Up\"\n", button(b)),
+            };
+        }
+    }
+    script
+}
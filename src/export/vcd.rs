@@ -0,0 +1,83 @@
+//! Module that exports button timelines as a [Value Change Dump](https://en.wikipedia.org/wiki/Value_change_dump).
+//!
+//! Each chosen key or button is a one-bit signal that is high while pressed,
+//! with times in nanoseconds from the start of the movie.
+//! Waveform viewers such as GTKWave then show the exact press and hold durations.
+
+use core::fmt::Write as _;
+
+use crate::{export::matrix::ColumnSpec, movie::LibTASMovie};
+
+/// The number of printable ASCII characters used in identifiers, from `!` to `~`.
+const IDENTIFIER_CHARS: usize = 94;
+
+/// Returns the identifier code of the `index`-th signal: `!`, `"`, ..., `~`, `!!`, `"!`, ...
+fn identifier(mut index: usize) -> String {
+    let mut id = String::new();
+    loop {
+        id.push(char::from(b'!' + (index % IDENTIFIER_CHARS) as u8));
+        index /= IDENTIFIER_CHARS;
+        if index == 0 {
+            return id;
+        }
+        index -= 1;
+    }
+}
+
+/// Returns the keys and buttons of `spec` in `movie` as a Value Change Dump.
+///
+/// The signals are named by [`ColumnSpec::labels`] in a `inputs` scope.
+/// A last timestamp marks the end of the last frame.
+///
+/// # Example
+/// ```
+/// use libtas_movie::{export::{matrix::ColumnSpec, vcd::vcd}, testkit::sample_movie};
+///
+/// let movie = sample_movie(4, 10);
+/// let dump = vcd(&movie, &ColumnSpec { keys: vec![0x7a], mouse_buttons: vec![] });
+/// assert!(dump.ends_with("#0\n$dumpvars\n0!\n$end\n#100000000\n1!\n#200000000\n0!\n#300000000\n1!\n#400000000\n"));
+/// ```
+pub fn vcd(movie: &LibTASMovie, spec: &ColumnSpec) -> String {
+    let ids: Vec<String> = (0..spec.len()).map(identifier).collect();
+    let mut text = String::new();
+    let _ = writeln!(text, "$version libtas-movie $end");
+    let _ = writeln!(text, "$timescale 1ns $end");
+    let _ = writeln!(text, "$scope module inputs $end");
+    for (id, label) in ids.iter().zip(spec.labels()) {
+        let _ = writeln!(text, "$var wire 1 {id} {label} $end");
+    }
+    let _ = writeln!(text, "$upscope $end");
+    let _ = writeln!(text, "$enddefinitions $end");
+
+    let timeline = movie.timeline();
+    let mut previous: Option<Vec<bool>> = None;
+    for (frame, input) in movie.inputs.0.iter().enumerate() {
+        let pressed: Vec<bool> = spec.pressed(input).collect();
+        let time = timeline.time_of_frame(frame).as_nanos();
+        match &previous {
+            None => {
+                let _ = writeln!(text, "#{time}\n$dumpvars");
+                for (id, &pressed) in ids.iter().zip(&pressed) {
+                    let _ = writeln!(text, "{}{id}", u8::from(pressed));
+                }
+                let _ = writeln!(text, "$end");
+            }
+            Some(previous) if *previous != pressed => {
+                let _ = writeln!(text, "#{time}");
+                for ((id, &pressed), _) in ids
+                    .iter()
+                    .zip(&pressed)
+                    .zip(previous)
+                    .filter(|((_, pressed), previous)| pressed != previous)
+                {
+                    let _ = writeln!(text, "{}{id}", u8::from(pressed));
+                }
+            }
+            Some(_) => {}
+        }
+        previous = Some(pressed);
+    }
+    let end = timeline.time_of_frame(movie.inputs.0.len()).as_nanos();
+    let _ = writeln!(text, "#{end}");
+    text
+}
@@ -0,0 +1,155 @@
+//! Module that fingerprints the frames of a movie to locate desyncs.
+//!
+//! The fingerprint of a frame covers the frame and all frames before it,
+//! so two movies have the same fingerprint on a frame if and only if (barring collisions)
+//! they agree on every frame up to it. Fingerprints can be stored alongside a movie
+//! and compared without the inputs of the other movie.
+
+use core::fmt::{Display, Write as _};
+
+use crate::inputs::{Input, Inputs};
+
+/// The offset basis of 64-bit FNV-1a.
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+/// The prime of 64-bit FNV-1a.
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// The rolling hash of a frame and all frames before it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Fingerprint(pub u64);
+
+impl Display for Fingerprint {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+/// Returns the FNV-1a hash of `data`, continued from `hash`.
+fn fnv1a(hash: u64, data: &[u8]) -> u64 {
+    data.iter().fold(hash, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+impl Inputs {
+    /// Returns the fingerprint of each frame.
+    ///
+    /// Each fingerprint hashes the frame as written in the `inputs` file, continuing from
+    /// the fingerprint of the previous frame. Lines that are not frames are not hashed.
+    pub fn fingerprints(&self) -> Vec<Fingerprint> {
+        let mut hash = FNV_OFFSET;
+        let mut line = String::new();
+        self.0
+            .iter()
+            .map(|input| {
+                line.clear();
+                let _ = writeln!(line, "{input}");
+                hash = fnv1a(hash, line.as_bytes());
+                Fingerprint(hash)
+            })
+            .collect()
+    }
+
+    /// Returns the differing frames nearest to `desync_frame` between these inputs
+    /// and `reference`, at most `limit` of them.
+    ///
+    /// `desync_frame` is typically the frame reported by libTAS when a movie desyncs.
+    pub fn locate_desync(
+        &self,
+        reference: &Self,
+        desync_frame: usize,
+        limit: usize,
+    ) -> DesyncReport {
+        let first_difference = first_divergence(&self.fingerprints(), &reference.fingerprints());
+        let len = self.0.len().max(reference.0.len());
+
+        let mut frames: Vec<usize> = (0..len)
+            .filter(|&frame| self.0.get(frame) != reference.0.get(frame))
+            .collect();
+        // the earlier frame wins ties, since a desync is caused before it is noticed
+        frames.sort_by_key(|&frame| (frame.abs_diff(desync_frame), frame));
+        frames.truncate(limit);
+        frames.sort_unstable();
+        let differences = frames
+            .into_iter()
+            .map(|frame| FrameDifference {
+                frame,
+                movie: self.0.get(frame).cloned(),
+                reference: reference.0.get(frame).cloned(),
+            })
+            .collect();
+
+        DesyncReport {
+            desync_frame,
+            first_difference,
+            differences,
+        }
+    }
+}
+
+/// Returns the first frame whose fingerprints differ between `a` and `b`,
+/// or `None` if they are identical.
+///
+/// Frames beyond the end of the shorter fingerprints are different.
+/// Since fingerprints cover all previous frames, this takes logarithmic time.
+pub fn first_divergence(a: &[Fingerprint], b: &[Fingerprint]) -> Option<usize> {
+    let common = a.len().min(b.len());
+    let (mut low, mut high) = (0, common);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if a[mid] == b[mid] {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    (low < common || a.len() != b.len()).then_some(low)
+}
+
+/// A frame whose inputs differ between a movie and a reference.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FrameDifference {
+    /// The frame.
+    pub frame: usize,
+    /// The input of the movie, or `None` if the movie ends before the frame.
+    pub movie: Option<Input>,
+    /// The input of the reference, or `None` if the reference ends before the frame.
+    pub reference: Option<Input>,
+}
+
+/// The differences between a movie and a reference around a desync.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DesyncReport {
+    /// The frame where the desync was reported.
+    pub desync_frame: usize,
+    /// The first frame where the inputs differ, or `None` if they are identical.
+    pub first_difference: Option<usize>,
+    /// The differing frames nearest to the desync, in order.
+    pub differences: Vec<FrameDifference>,
+}
+
+impl Display for DesyncReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "desync at frame {}", self.desync_frame)?;
+        match self.first_difference {
+            Some(frame) => writeln!(f, ", inputs first differ at frame {frame}")?,
+            None => return writeln!(f, ", inputs are identical"),
+        }
+        for difference in &self.differences {
+            let offset = difference.frame as i64 - self.desync_frame as i64;
+            let input = |input: &Option<Input>| {
+                input
+                    .as_ref()
+                    .map_or_else(|| "(none)".to_owned(), Input::to_string)
+            };
+            writeln!(
+                f,
+                "frame {} ({offset:+}): movie {} reference {}",
+                difference.frame,
+                input(&difference.movie),
+                input(&difference.reference),
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,345 @@
+//! Module that defines movie formats for conversion, and a registry to look them up.
+//!
+//! Other crates can support more formats by implementing [`MovieFormat`]
+//! and registering it in a [`FormatRegistry`].
+//!
+//! # Example
+//! ```
+//! use libtas_movie::{
+//!     format::{ConvertError, FormatRegistry, MovieFormat},
+//!     movie::LibTASMovie,
+//! };
+//!
+//! struct FrameCount;
+//!
+//! impl MovieFormat for FrameCount {
+//!     fn name(&self) -> &'static str {
+//!         "frame-count"
+//!     }
+//!
+//!     fn extensions(&self) -> &'static [&'static str] {
+//!         &["count"]
+//!     }
+//!
+//!     fn export(&self, movie: &LibTASMovie) -> Result<Vec<u8>, ConvertError> {
+//!         Ok(movie.inputs.0.len().to_string().into_bytes())
+//!     }
+//! }
+//!
+//! let mut registry = FormatRegistry::builtin();
+//! registry.register(FrameCount);
+//! let format = registry.detect_format("run.count", b"").unwrap();
+//! assert_eq!(format.name(), "frame-count");
+//! ```
+
+use core::fmt::Debug;
+use std::path::Path;
+
+use crate::{
+    export::{
+        matrix::ColumnSpec,
+        script::{autohotkey_script, xdotool_script},
+        vcd::vcd,
+    },
+    inputs::Inputs,
+    jsonl::JsonlError,
+    movie::{LibTASMovie, LoadError, load_movie_from_reader},
+};
+
+/// An error while converting a movie.
+#[derive(Debug)]
+pub enum ConvertError {
+    /// An error occurred while reading or writing a file.
+    Io(std::io::Error),
+    /// No registered format matches the file name or the contents.
+    UnknownFormat(String),
+    /// The format cannot be imported or exported.
+    Unsupported {
+        /// The name of the format.
+        format: &'static str,
+    },
+    /// A libTAS movie failed to load.
+    Load(LoadError),
+    /// The contents are invalid for the format, with the reason.
+    Invalid(String),
+}
+
+/// A movie format that can be imported into or exported from a [`LibTASMovie`].
+///
+/// Both directions are optional: the default implementations return
+/// [`ConvertError::Unsupported`].
+pub trait MovieFormat {
+    /// The unique name of the format.
+    fn name(&self) -> &'static str;
+
+    /// The file extensions of the format, without the leading dot.
+    fn extensions(&self) -> &'static [&'static str];
+
+    /// Returns whether `data` looks like a file of this format.
+    ///
+    /// This is used when the extension does not match any format.
+    fn detect(&self, data: &[u8]) -> bool {
+        let _ = data;
+        false
+    }
+
+    /// Reads a movie from the contents of a file.
+    fn import(&self, data: &[u8]) -> Result<LibTASMovie, ConvertError> {
+        let _ = data;
+        Err(ConvertError::Unsupported {
+            format: self.name(),
+        })
+    }
+
+    /// Writes `movie` into the contents of a file.
+    fn export(&self, movie: &LibTASMovie) -> Result<Vec<u8>, ConvertError> {
+        let _ = movie;
+        Err(ConvertError::Unsupported {
+            format: self.name(),
+        })
+    }
+}
+
+/// The libTAS movie format, `.ltm`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LtmFormat;
+
+impl MovieFormat for LtmFormat {
+    fn name(&self) -> &'static str {
+        "ltm"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["ltm"]
+    }
+
+    fn detect(&self, data: &[u8]) -> bool {
+        data.starts_with(&[0x1f, 0x8b])
+    }
+
+    fn import(&self, data: &[u8]) -> Result<LibTASMovie, ConvertError> {
+        load_movie_from_reader(data).map_err(ConvertError::Load)
+    }
+
+    fn export(&self, movie: &LibTASMovie) -> Result<Vec<u8>, ConvertError> {
+        movie.compress().map_err(ConvertError::Io)
+    }
+}
+
+/// A shell script replaying the movie with `xdotool`, from [`xdotool_script`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct XdotoolFormat;
+
+impl MovieFormat for XdotoolFormat {
+    fn name(&self) -> &'static str {
+        "xdotool"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["sh"]
+    }
+
+    fn export(&self, movie: &LibTASMovie) -> Result<Vec<u8>, ConvertError> {
+        Ok(xdotool_script(movie).into_bytes())
+    }
+}
+
+/// An AutoHotkey script replaying the movie, from [`autohotkey_script`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AutoHotkeyFormat;
+
+impl MovieFormat for AutoHotkeyFormat {
+    fn name(&self) -> &'static str {
+        "autohotkey"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["ahk"]
+    }
+
+    fn export(&self, movie: &LibTASMovie) -> Result<Vec<u8>, ConvertError> {
+        Ok(autohotkey_script(movie).into_bytes())
+    }
+}
+
+/// A Value Change Dump of the keys and buttons used by the movie, from [`vcd`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct VcdFormat;
+
+impl MovieFormat for VcdFormat {
+    fn name(&self) -> &'static str {
+        "vcd"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["vcd"]
+    }
+
+    fn export(&self, movie: &LibTASMovie) -> Result<Vec<u8>, ConvertError> {
+        Ok(vcd(movie, &ColumnSpec::for_inputs(&movie.inputs)).into_bytes())
+    }
+}
+
+/// JSON Lines of the frames, one object per frame, from [`Inputs::to_jsonl`].
+///
+/// Imported movies have the default config, with the frame count and the length filled in.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct JsonlFormat;
+
+impl MovieFormat for JsonlFormat {
+    fn name(&self) -> &'static str {
+        "jsonl"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["jsonl"]
+    }
+
+    fn import(&self, data: &[u8]) -> Result<LibTASMovie, ConvertError> {
+        let inputs = Inputs::from_jsonl(data).map_err(|err| match err {
+            JsonlError::Io(err) => ConvertError::Io(err),
+            JsonlError::Invalid { line, message } => {
+                ConvertError::Invalid(format!("line {line}: {message}"))
+            }
+        })?;
+        let mut movie = LibTASMovie::default();
+        movie.config.general.frame_count = inputs.0.len() as u64;
+        movie.inputs = inputs;
+        movie.recompute_length();
+        Ok(movie)
+    }
+
+    fn export(&self, movie: &LibTASMovie) -> Result<Vec<u8>, ConvertError> {
+        let mut jsonl = vec![];
+        movie
+            .inputs
+            .to_jsonl(&mut jsonl)
+            .map_err(ConvertError::Io)?;
+        Ok(jsonl)
+    }
+}
+
+/// A Parquet file of the frames, from [`write_parquet`](crate::export::arrow::write_parquet),
+/// with a column for each key and button used by the movie.
+///
+/// This format is only available with the `parquet` feature.
+#[cfg(feature = "parquet")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ParquetFormat;
+
+#[cfg(feature = "parquet")]
+impl MovieFormat for ParquetFormat {
+    fn name(&self) -> &'static str {
+        "parquet"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["parquet"]
+    }
+
+    fn export(&self, movie: &LibTASMovie) -> Result<Vec<u8>, ConvertError> {
+        let mut parquet = vec![];
+        let spec = ColumnSpec::for_inputs(&movie.inputs);
+        crate::export::arrow::write_parquet(movie, &spec, &mut parquet)
+            .map_err(|err| ConvertError::Io(std::io::Error::other(err)))?;
+        Ok(parquet)
+    }
+}
+
+/// The set of formats available for conversion.
+#[derive(Default)]
+pub struct FormatRegistry(Vec<Box<dyn MovieFormat>>);
+
+impl Debug for FormatRegistry {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("FormatRegistry")
+            .field(
+                &self
+                    .formats()
+                    .map(|format| format.name())
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl FormatRegistry {
+    /// Returns an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a registry of the formats defined by this crate,
+    /// such as [`LtmFormat`], [`XdotoolFormat`], [`AutoHotkeyFormat`], [`VcdFormat`], and [`JsonlFormat`],
+    /// and `ParquetFormat` with the `parquet` feature.
+    pub fn builtin() -> Self {
+        let mut registry = Self::new();
+        registry.register(LtmFormat);
+        registry.register(XdotoolFormat);
+        registry.register(AutoHotkeyFormat);
+        registry.register(VcdFormat);
+        registry.register(JsonlFormat);
+        #[cfg(feature = "parquet")]
+        registry.register(ParquetFormat);
+        registry
+    }
+
+    /// Registers `format`, replacing any format with the same name.
+    pub fn register<F: MovieFormat + 'static>(&mut self, format: F) -> &mut Self {
+        self.0.retain(|other| other.name() != format.name());
+        self.0.push(Box::new(format));
+        self
+    }
+
+    /// Returns the registered formats, in order of registration.
+    pub fn formats(&self) -> impl Iterator<Item = &dyn MovieFormat> {
+        self.0.iter().map(|format| format.as_ref())
+    }
+
+    /// Returns the format named `name`.
+    pub fn get(&self, name: &str) -> Option<&dyn MovieFormat> {
+        self.formats().find(|format| format.name() == name)
+    }
+
+    /// Returns the last registered format with the extension `extension`, ignoring case.
+    pub fn for_extension(&self, extension: &str) -> Option<&dyn MovieFormat> {
+        self.0
+            .iter()
+            .rev()
+            .map(|format| format.as_ref())
+            .find(|format| {
+                format
+                    .extensions()
+                    .iter()
+                    .any(|other| other.eq_ignore_ascii_case(extension))
+            })
+    }
+
+    /// Returns the format of the file at `path` with the contents `data`,
+    /// by its extension, or by its contents if no format has the extension.
+    pub fn detect_format<P: AsRef<Path>>(&self, path: P, data: &[u8]) -> Option<&dyn MovieFormat> {
+        path.as_ref()
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(|extension| self.for_extension(extension))
+            .or_else(|| self.formats().find(|format| format.detect(data)))
+    }
+
+    /// Converts the file at `from` into the file at `to`, detecting both formats.
+    pub fn convert<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        from: P,
+        to: Q,
+    ) -> Result<(), ConvertError> {
+        let (from, to) = (from.as_ref(), to.as_ref());
+        let data = std::fs::read(from).map_err(ConvertError::Io)?;
+        let unknown = |path: &Path| ConvertError::UnknownFormat(path.display().to_string());
+
+        let import = self
+            .detect_format(from, &data)
+            .ok_or_else(|| unknown(from))?;
+        let export = self.detect_format(to, &[]).ok_or_else(|| unknown(to))?;
+        let movie = import.import(&data)?;
+        std::fs::write(to, export.export(&movie)?).map_err(ConvertError::Io)
+    }
+}
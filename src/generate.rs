@@ -0,0 +1,197 @@
+//! Module that generates random movies from a seed.
+//!
+//! Generated movies are valid movies with random key presses and pointer moves,
+//! useful for fuzz-testing games under libTAS and for benchmarking this crate.
+//! The same configuration and seed always give the same movie.
+//!
+//! # Example
+//! ```
+//! use libtas_movie::generate::{GeneratorConfig, random_movie};
+//! let config = GeneratorConfig {
+//!     frames: 600,
+//!     ..GeneratorConfig::default()
+//! };
+//! let movie = random_movie(&config, 42);
+//! assert_eq!(movie.inputs.0.len(), 600);
+//! assert_eq!(movie, random_movie(&config, 42));
+//! ```
+
+use crate::{
+    inputs::{Input, Inputs, KeyboardInput, MouseButton, MouseInput, Rect, ReferenceMode},
+    movie::LibTASMovie,
+    pass::splitmix64,
+    time::Framerate,
+};
+
+/// The behavior of the pointer in a generated movie.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MouseGenerator {
+    /// The rectangle the pointer stays in.
+    pub bounds: Rect,
+    /// The largest move of the pointer along each axis per frame.
+    pub max_step: i32,
+    /// The coordinates written: positions for absolute, moves for relative.
+    pub reference_mode: ReferenceMode,
+    /// The buttons that may be pressed.
+    pub buttons: Vec<MouseButton>,
+    /// The probability that a released button is pressed on each frame.
+    pub press_probability: f64,
+    /// The probability that a pressed button is released on each frame.
+    pub release_probability: f64,
+}
+
+impl Default for MouseGenerator {
+    fn default() -> Self {
+        Self {
+            bounds: Rect {
+                left: 0,
+                top: 0,
+                right: 639,
+                bottom: 479,
+            },
+            max_step: 8,
+            reference_mode: ReferenceMode::Absolute,
+            buttons: vec![MouseButton::Left, MouseButton::Right],
+            press_probability: 0.02,
+            release_probability: 0.5,
+        }
+    }
+}
+
+/// The configuration of [`random_movie`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct GeneratorConfig {
+    /// The number of frames.
+    pub frames: usize,
+    /// The framerate of the movie.
+    pub framerate: Framerate,
+    /// The keys that may be pressed, as X11 keysyms.
+    pub keys: Vec<u32>,
+    /// The probability that a released key is pressed on each frame.
+    pub press_probability: f64,
+    /// The probability that a pressed key is released on each frame.
+    pub release_probability: f64,
+    /// The behavior of the pointer, or `None` for a movie without mouse inputs.
+    pub mouse: Option<MouseGenerator>,
+}
+
+impl Default for GeneratorConfig {
+    /// Returns a configuration of one minute at 60 fps, pressing `z`, `x`
+    /// and the arrow keys, without mouse inputs.
+    fn default() -> Self {
+        Self {
+            frames: 3600,
+            framerate: Framerate { num: 60, den: 1 },
+            keys: vec![0x7a, 0x78, 0xff51, 0xff52, 0xff53, 0xff54],
+            press_probability: 0.05,
+            release_probability: 0.2,
+            mouse: None,
+        }
+    }
+}
+
+/// A pseudorandom number generator with SplitMix64.
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(1);
+        splitmix64(self.0)
+    }
+
+    /// Returns `true` with `probability`.
+    fn chance(&mut self, probability: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < probability
+    }
+
+    /// Returns a number between `-max` and `max`.
+    fn step(&mut self, max: i32) -> i32 {
+        let max = max.max(0);
+        let span = 2 * u64::from(max.unsigned_abs()) + 1;
+        (self.next_u64() % span) as i32 - max
+    }
+}
+
+/// Generates a movie following `config` from `seed`.
+///
+/// Keys and buttons are held for random durations, and at most
+/// [`KeyboardInput::MAX_KEYS`] keys are held at once. The pointer starts at the
+/// center of its bounds and walks randomly within them.
+/// The frame count, the length, the framerate and `mouse_support` are filled in.
+pub fn random_movie(config: &GeneratorConfig, seed: u64) -> LibTASMovie {
+    let mut rng = Rng(splitmix64(seed));
+    let mut held: Vec<u32> = vec![];
+    let mut pointer = config.mouse.as_ref().map(|mouse| {
+        let bounds = mouse.bounds;
+        (
+            bounds.left + (bounds.right - bounds.left) / 2,
+            bounds.top + (bounds.bottom - bounds.top) / 2,
+        )
+    });
+    let mut buttons = MouseInput::default();
+
+    let inputs = (0..config.frames)
+        .map(|_| {
+            held.retain(|_| !rng.chance(config.release_probability));
+            for &key in &config.keys {
+                if held.len() < KeyboardInput::MAX_KEYS
+                    && !held.contains(&key)
+                    && rng.chance(config.press_probability)
+                {
+                    held.push(key);
+                }
+            }
+            let keyboard = (!held.is_empty()).then(|| KeyboardInput(held.clone()));
+
+            let mouse = config
+                .mouse
+                .as_ref()
+                .zip(pointer.as_mut())
+                .map(|(generator, (x, y))| {
+                    let bounds = generator.bounds;
+                    let (old_x, old_y) = (*x, *y);
+                    *x = x
+                        .saturating_add(rng.step(generator.max_step))
+                        .clamp(bounds.left, bounds.right.max(bounds.left));
+                    *y = y
+                        .saturating_add(rng.step(generator.max_step))
+                        .clamp(bounds.top, bounds.bottom.max(bounds.top));
+                    for &button in &generator.buttons {
+                        let probability = if buttons.is_pressed(button) {
+                            generator.release_probability
+                        } else {
+                            generator.press_probability
+                        };
+                        if rng.chance(probability) {
+                            buttons.set_pressed(button, !buttons.is_pressed(button));
+                        }
+                    }
+                    let (xpos, ypos) = match generator.reference_mode {
+                        ReferenceMode::Absolute => (*x, *y),
+                        ReferenceMode::Relative => (*x - old_x, *y - old_y),
+                    };
+                    MouseInput {
+                        xpos,
+                        ypos,
+                        reference_mode: generator.reference_mode,
+                        ..buttons
+                    }
+                });
+
+            Input {
+                keyboard,
+                mouse,
+                ..Input::default()
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut movie = LibTASMovie::default();
+    let general = &mut movie.config.general;
+    general.frame_count = inputs.len() as u64;
+    general.set_framerate(config.framerate);
+    general.mouse_support = config.mouse.is_some();
+    movie.inputs = Inputs::from(inputs);
+    movie.recompute_length();
+    movie
+}
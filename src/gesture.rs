@@ -0,0 +1,278 @@
+//! Module that generates common mouse gestures.
+//!
+//! Games detect a click as a press followed by a release, so each gesture writes
+//! the press frames followed by a released frame at the same position.
+//! Inputs are extended with empty frames as needed, and other inputs of the frames are kept.
+//!
+//! [`MousePath`] moves the pointer along a smooth curve through waypoints,
+//! for games that read the intermediate positions of the pointer.
+
+use core::ops::Range;
+
+use crate::inputs::{Input, Inputs, MouseButton, MouseInput, ReferenceMode};
+
+/// The progression of the pointer during a drag.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Easing {
+    /// Constant speed.
+    #[default]
+    Linear,
+    /// Starts slowly and speeds up.
+    EaseIn,
+    /// Starts fast and slows down.
+    EaseOut,
+    /// Starts and ends slowly.
+    EaseInOut,
+}
+
+impl Easing {
+    /// Returns the progression at the time `t` between 0 and 1.
+    pub fn apply(self, t: f64) -> f64 {
+        match self {
+            Self::Linear => t,
+            Self::EaseIn => t * t,
+            Self::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Self::EaseInOut => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+/// The curve followed by a [`MousePath`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Curve {
+    /// Straight lines between waypoints.
+    Polyline,
+    /// A Bezier curve with the waypoints as control points,
+    /// which only goes through the first and last waypoints.
+    Bezier,
+    /// A Catmull-Rom spline, which goes through all waypoints.
+    #[default]
+    CatmullRom,
+}
+
+/// The number of samples per segment used to measure the length of a curve.
+const SAMPLES_PER_SEGMENT: usize = 64;
+
+type Point = (f64, f64);
+
+fn lerp(a: Point, b: Point, t: f64) -> Point {
+    (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t)
+}
+
+impl Curve {
+    /// Returns the point of the curve through `points` at the time `t` between 0 and 1.
+    fn point(self, points: &[Point], t: f64) -> Point {
+        let segments = points.len().saturating_sub(1);
+        if segments == 0 {
+            return points.first().copied().unwrap_or_default();
+        }
+        if self == Self::Bezier {
+            // De Casteljau's algorithm
+            let mut points = points.to_vec();
+            while points.len() > 1 {
+                points = points.windows(2).map(|w| lerp(w[0], w[1], t)).collect();
+            }
+            return points[0];
+        }
+        let u = t.clamp(0.0, 1.0) * segments as f64;
+        let i = (u.floor() as usize).min(segments - 1);
+        let t = u - i as f64;
+        let (p1, p2) = (points[i], points[i + 1]);
+        if self == Self::Polyline {
+            return lerp(p1, p2, t);
+        }
+        let p0 = points[i.saturating_sub(1)];
+        let p3 = points[(i + 2).min(segments)];
+        let spline = |p0: f64, p1: f64, p2: f64, p3: f64| {
+            0.5 * (2.0 * p1
+                + (p2 - p0) * t
+                + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t * t
+                + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t * t * t)
+        };
+        (
+            spline(p0.0, p1.0, p2.0, p3.0),
+            spline(p0.1, p1.1, p2.1, p3.1),
+        )
+    }
+}
+
+/// A path of the pointer along a [`Curve`] through waypoints, over a number of frames.
+///
+/// The pointer moves along the curve at the speed given by an [`Easing`]:
+/// with [`Easing::Linear`], it covers the same distance on each frame.
+///
+/// # Example
+/// ```
+/// use libtas_movie::{gesture::{Curve, Easing, MousePath}, inputs::Inputs};
+/// let path = MousePath::new([(0, 0), (100, 50), (200, 0)], 30)
+///     .curve(Curve::CatmullRom)
+///     .easing(Easing::EaseInOut);
+/// let mut inputs = Inputs::default();
+/// assert_eq!(inputs.move_along(10, &path), 41);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MousePath {
+    /// The waypoints of the curve.
+    pub waypoints: Vec<(i32, i32)>,
+    /// The curve through the waypoints.
+    pub curve: Curve,
+    /// The progression of the pointer along the curve.
+    pub easing: Easing,
+    /// The number of frames to go from the first waypoint to the last.
+    pub frames: usize,
+}
+
+impl MousePath {
+    /// Returns a Catmull-Rom path at constant speed through `waypoints` over `frames` frames.
+    pub fn new(waypoints: impl IntoIterator<Item = (i32, i32)>, frames: usize) -> Self {
+        Self {
+            waypoints: waypoints.into_iter().collect(),
+            frames,
+            ..Self::default()
+        }
+    }
+
+    /// Sets the curve through the waypoints.
+    pub fn curve(mut self, curve: Curve) -> Self {
+        self.curve = curve;
+        self
+    }
+
+    /// Sets the progression of the pointer along the curve.
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Returns the positions of the pointer on each of the `frames + 1` frames of the path,
+    /// from the first waypoint to the last, or nothing without waypoints.
+    pub fn positions(&self) -> Vec<(i32, i32)> {
+        if self.waypoints.is_empty() {
+            return vec![];
+        }
+        let points: Vec<Point> = self
+            .waypoints
+            .iter()
+            .map(|&(x, y)| (f64::from(x), f64::from(y)))
+            .collect();
+
+        // sample the curve to move along it by distance rather than by time
+        let samples = SAMPLES_PER_SEGMENT * points.len().saturating_sub(1).max(1);
+        let mut lengths = vec![0.0];
+        let mut previous = points[0];
+        for sample in 1..=samples {
+            let point = self.curve.point(&points, sample as f64 / samples as f64);
+            let length = lengths[sample - 1] + (point.0 - previous.0).hypot(point.1 - previous.1);
+            lengths.push(length);
+            previous = point;
+        }
+        let total = lengths[samples];
+
+        (0..=self.frames)
+            .map(|step| {
+                let progress = self.easing.apply(step as f64 / self.frames.max(1) as f64);
+                let t = if total > 0.0 {
+                    let distance = progress * total;
+                    let i = lengths
+                        .partition_point(|&length| length < distance)
+                        .clamp(1, samples);
+                    let (before, after) = (lengths[i - 1], lengths[i]);
+                    let within = if after > before {
+                        (distance - before) / (after - before)
+                    } else {
+                        0.0
+                    };
+                    (i as f64 - 1.0 + within) / samples as f64
+                } else {
+                    progress
+                };
+                let (x, y) = self.curve.point(&points, t);
+                (x.round() as i32, y.round() as i32)
+            })
+            .collect()
+    }
+}
+
+impl Inputs {
+    /// Returns the mouse input of `frame`, extending the inputs if needed,
+    /// with the pointer moved to the absolute position `(x, y)`.
+    fn mouse_at(&mut self, frame: usize, x: i32, y: i32) -> &mut MouseInput {
+        let len = self.0.len();
+        if len <= frame {
+            self.splice_frames(
+                len..len,
+                core::iter::repeat_n(Input::default(), frame + 1 - len),
+            );
+        }
+        let mouse = self.0[frame].mouse.get_or_insert_default();
+        mouse.xpos = x;
+        mouse.ypos = y;
+        mouse.reference_mode = ReferenceMode::Absolute;
+        mouse
+    }
+
+    /// Clicks `button` at `(x, y)`: pressed on `frame` and released on the next frame.
+    /// Returns the frame after the gesture.
+    pub fn click_at(&mut self, frame: usize, x: i32, y: i32, button: MouseButton) -> usize {
+        self.hold_button(frame..frame + 1, button, x, y)
+    }
+
+    /// Double-clicks `button` at `(x, y)`: two clicks starting on `frame`, over four frames.
+    /// Returns the frame after the gesture.
+    pub fn double_click_at(&mut self, frame: usize, x: i32, y: i32, button: MouseButton) -> usize {
+        let frame = self.click_at(frame, x, y, button);
+        self.click_at(frame, x, y, button)
+    }
+
+    /// Holds `button` at `(x, y)` during `frames`, and releases it on `frames.end`.
+    /// Returns the frame after the gesture. An empty range only releases the button.
+    pub fn hold_button(
+        &mut self,
+        frames: Range<usize>,
+        button: MouseButton,
+        x: i32,
+        y: i32,
+    ) -> usize {
+        for frame in frames.clone() {
+            self.mouse_at(frame, x, y).set_pressed(button, true);
+        }
+        self.mouse_at(frames.end, x, y).set_pressed(button, false);
+        frames.end + 1
+    }
+
+    /// Drags with `button` from `from` to `to`: pressed on `frame` at `from`, held while moving
+    /// over `over_frames` frames to reach `to` on `frame + over_frames`, and released at `to`
+    /// on the next frame. Returns the frame after the gesture.
+    pub fn drag(
+        &mut self,
+        frame: usize,
+        from: (i32, i32),
+        to: (i32, i32),
+        over_frames: usize,
+        button: MouseButton,
+        easing: Easing,
+    ) -> usize {
+        for step in 0..=over_frames {
+            let progress = easing.apply(step as f64 / over_frames.max(1) as f64);
+            let lerp = |from: i32, to: i32| {
+                (f64::from(from) + f64::from(to - from) * progress).round() as i32
+            };
+            let (x, y) = (lerp(from.0, to.0), lerp(from.1, to.1));
+            self.mouse_at(frame + step, x, y).set_pressed(button, true);
+        }
+        let release = frame + over_frames + 1;
+        self.mouse_at(release, to.0, to.1)
+            .set_pressed(button, false);
+        release + 1
+    }
+
+    /// Moves the pointer along `path` from `frame`, keeping the pressed buttons,
+    /// and returns the frame after the path.
+    pub fn move_along(&mut self, frame: usize, path: &MousePath) -> usize {
+        let positions = path.positions();
+        for (step, &(x, y)) in positions.iter().enumerate() {
+            self.mouse_at(frame + step, x, y);
+        }
+        frame + positions.len()
+    }
+}
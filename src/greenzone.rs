@@ -0,0 +1,105 @@
+//! Module that defines the frames with verified savestates.
+//!
+//! Editor frontends keep savestates of played frames, called the greenzone, and can persist
+//! which frames were verified across sessions. The greenzone is stored in the `greenzone.txt`
+//! entry, one `frame hash` line per frame with the state hash in hexadecimal.
+
+use core::ops::RangeBounds;
+use std::collections::BTreeMap;
+
+use crate::{
+    movie::LibTASMovie,
+    section::{InvalidSectionError, MovieSection},
+};
+
+/// The verified frames of a movie and the hashes of their states.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Greenzone(pub BTreeMap<u64, u64>);
+
+impl Greenzone {
+    /// Records that the state at `frame` was verified with the hash `hash`.
+    pub fn insert(&mut self, frame: u64, hash: u64) {
+        self.0.insert(frame, hash);
+    }
+
+    /// Returns whether the state at `frame` is verified.
+    pub fn is_verified(&self, frame: u64) -> bool {
+        self.0.contains_key(&frame)
+    }
+
+    /// Returns the hash of the state at `frame`, if verified.
+    pub fn hash_of(&self, frame: u64) -> Option<u64> {
+        self.0.get(&frame).copied()
+    }
+
+    /// Returns the last verified frame at or before `frame`, from which playback can resume.
+    pub fn last_verified_before(&self, frame: u64) -> Option<u64> {
+        self.0.range(..=frame).next_back().map(|(&frame, _)| frame)
+    }
+
+    /// Removes the frames in `range`, returning the number of removed frames.
+    pub fn invalidate(&mut self, range: impl RangeBounds<u64>) -> usize {
+        let frames: Vec<u64> = self.0.range(range).map(|(&frame, _)| frame).collect();
+        for frame in &frames {
+            self.0.remove(frame);
+        }
+        frames.len()
+    }
+
+    /// Removes the states after an edit of the inputs of `frame`.
+    ///
+    /// The state at `frame` is made before its inputs are applied, so it stays verified.
+    pub fn invalidate_after_edit(&mut self, frame: u64) -> usize {
+        self.invalidate(frame + 1..)
+    }
+}
+
+impl MovieSection for Greenzone {
+    fn name() -> &'static str {
+        "greenzone.txt"
+    }
+
+    fn parse(data: &[u8]) -> Result<Self, InvalidSectionError> {
+        let Ok(s) = core::str::from_utf8(data) else {
+            return Err(InvalidSectionError("not UTF-8".to_owned()));
+        };
+
+        let mut greenzone = Self::default();
+        for line in s.lines().filter(|line| !line.is_empty()) {
+            let entry = line.split_once(' ').and_then(|(frame, hash)| {
+                Some((frame.parse().ok()?, u64::from_str_radix(hash, 16).ok()?))
+            });
+            let Some((frame, hash)) = entry else {
+                return Err(InvalidSectionError(line.to_owned()));
+            };
+            greenzone.insert(frame, hash);
+        }
+        Ok(greenzone)
+    }
+
+    fn serialize(&self) -> Vec<u8> {
+        let mut data = String::new();
+        for (frame, hash) in &self.0 {
+            data += &format!("{frame} {hash:016x}\n");
+        }
+        data.into_bytes()
+    }
+}
+
+impl LibTASMovie {
+    /// Returns the greenzone of the movie.
+    pub fn greenzone(&self) -> Option<&Greenzone> {
+        self.sections.get::<Greenzone>()
+    }
+
+    /// Returns the greenzone of the movie mutably, adding an empty one if missing.
+    pub fn greenzone_mut(&mut self) -> &mut Greenzone {
+        self.sections.get_or_insert_default()
+    }
+
+    /// Returns whether the state at `frame` is verified in the greenzone.
+    pub fn is_frame_verified(&self, frame: u64) -> bool {
+        self.greenzone()
+            .is_some_and(|greenzone| greenzone.is_verified(frame))
+    }
+}
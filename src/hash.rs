@@ -0,0 +1,126 @@
+//! Module that defines and computes the MD5 hash of a game executable.
+
+use core::{fmt::Display, str::FromStr};
+use std::{
+    fs::File,
+    io::{ErrorKind, Read},
+    path::Path,
+};
+
+use md5::{Digest as _, Md5};
+
+use crate::{catalog::hex, config::GeneralConfig};
+
+/// The size of the chunks read while hashing.
+const CHUNK_SIZE: usize = 1 << 16;
+
+/// An error while parsing an MD5 hash, containing the string that caused the error.
+#[derive(Debug)]
+pub struct InvalidMd5Error(pub String);
+
+/// The [MD5 hash](https://en.wikipedia.org/wiki/MD5) of a file.
+///
+/// Parsed from 32 hexadecimal digits in any case, and displayed in lowercase like libTAS
+/// writes it, so hashes written in different cases compare equal.
+///
+/// # Example
+/// ```
+/// use libtas_movie::hash::Md5Hash;
+/// let md5: Md5Hash = "C9B4F1B544725CB0D9D784C35232A52D".parse().unwrap();
+/// assert_eq!(md5.to_string(), "c9b4f1b544725cb0d9d784c35232a52d");
+/// assert_eq!(md5, "c9b4f1b544725cb0d9d784c35232a52d");
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Md5Hash(pub [u8; 16]);
+
+impl FromStr for Md5Hash {
+    type Err = InvalidMd5Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || InvalidMd5Error(s.to_owned());
+        if s.len() != 32 || !s.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return Err(error());
+        }
+        let mut bytes = [0; 16];
+        for (byte, digits) in bytes.iter_mut().zip(s.as_bytes().chunks(2)) {
+            let digits = core::str::from_utf8(digits).map_err(|_| error())?;
+            *byte = u8::from_str_radix(digits, 16).map_err(|_| error())?;
+        }
+        Ok(Self(bytes))
+    }
+}
+
+impl Display for Md5Hash {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&hex(&self.0))
+    }
+}
+
+/// Compares with a hash written as hexadecimal digits, ignoring case.
+/// A string that is not a hash is never equal.
+impl PartialEq<str> for Md5Hash {
+    fn eq(&self, other: &str) -> bool {
+        other.parse::<Self>().is_ok_and(|other| other == *self)
+    }
+}
+
+impl PartialEq<&str> for Md5Hash {
+    fn eq(&self, other: &&str) -> bool {
+        *self == **other
+    }
+}
+
+/// Returns the MD5 hash of the contents of `reader`, read in chunks.
+/// `progress` is called with the number of bytes read so far after each chunk.
+pub fn md5_of_reader<R: Read>(
+    mut reader: R,
+    mut progress: impl FnMut(u64),
+) -> std::io::Result<Md5Hash> {
+    let mut hasher = Md5::new();
+    let mut buffer = vec![0; CHUNK_SIZE];
+    let mut read = 0;
+    loop {
+        let len = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(len) => len,
+            Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+        hasher.update(&buffer[..len]);
+        read += len as u64;
+        progress(read);
+    }
+    Ok(Md5Hash(hasher.finalize().into()))
+}
+
+/// Returns the MD5 hash of the file at `path`, without reading it into memory at once.
+///
+/// # Example
+/// ```
+/// use libtas_movie::hash::md5_of_file;
+/// let md5 = md5_of_file("tests/movies/221769_Trapped_5_config.ini").unwrap();
+/// assert_eq!(md5.to_string().len(), 32);
+/// ```
+pub fn md5_of_file<P: AsRef<Path>>(path: P) -> std::io::Result<Md5Hash> {
+    md5_of_file_with_progress(path, |_, _| {})
+}
+
+/// Returns the MD5 hash of the file at `path`, like [`md5_of_file`].
+/// `progress` is called with the number of bytes read so far and the size of the file
+/// after each chunk, e.g. to show a progress bar for a large game.
+pub fn md5_of_file_with_progress<P: AsRef<Path>>(
+    path: P,
+    mut progress: impl FnMut(u64, u64),
+) -> std::io::Result<Md5Hash> {
+    let file = File::open(path)?;
+    let size = file.metadata()?.len();
+    md5_of_reader(file, |read| progress(read, size))
+}
+
+impl GeneralConfig {
+    /// Sets `md5` to the MD5 hash of the game executable at `path`.
+    pub fn set_md5_from_file<P: AsRef<Path>>(&mut self, path: P) -> std::io::Result<()> {
+        self.md5 = Some(md5_of_file(path)?);
+        Ok(())
+    }
+}
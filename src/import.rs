@@ -0,0 +1,183 @@
+//! Module that builds inputs from recorded input events.
+//!
+//! Events with timestamps, e.g. parsed from `evtest` output with [`parse_evtest`],
+//! are quantized onto frames with [`quantize`], as a rough starting point for a TAS.
+
+use core::time::Duration;
+
+use crate::{
+    inputs::{Inputs, MouseButton},
+    quantize::{InputEvent, Quantizer},
+    time::Framerate,
+};
+
+/// An error while parsing events, containing the line that caused the error.
+#[derive(Debug)]
+pub struct InvalidEventError(pub String);
+
+/// A change of an input device.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventKind {
+    /// A key is pressed, by its KeySym value.
+    KeyPress(u32),
+    /// A key is released, by its KeySym value.
+    KeyRelease(u32),
+    /// A mouse button from 1 to 5 is pressed.
+    ButtonPress(u8),
+    /// A mouse button from 1 to 5 is released.
+    ButtonRelease(u8),
+    /// The pointer moves to a position.
+    Motion { x: i32, y: i32 },
+    /// The pointer moves by a distance.
+    RelativeMotion { dx: i32, dy: i32 },
+}
+
+/// An event at a time from the start of the recording.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Event {
+    /// The time of the event.
+    pub time: Duration,
+    /// The change.
+    pub kind: EventKind,
+}
+
+/// Returns the KeySym of a common Linux key code, as written by `evtest`.
+fn evdev_keysym(code: u16) -> Option<u32> {
+    const ROWS: [(u16, &[u8]); 4] = [
+        (2, b"1234567890-="),
+        (16, b"qwertyuiop[]"),
+        (30, b"asdfghjkl;'`"),
+        (43, b"\\zxcvbnm,./"),
+    ];
+    for (start, chars) in ROWS {
+        if let Some(&c) = chars.get(usize::from(code.wrapping_sub(start))) {
+            return Some(c.into());
+        }
+    }
+    Some(match code {
+        1 => 0xff1b,
+        14 => 0xff08,
+        15 => 0xff09,
+        28 => 0xff0d,
+        29 => 0xffe3,
+        42 => 0xffe1,
+        54 => 0xffe2,
+        56 => 0xffe9,
+        57 => 0x20,
+        59..=68 => 0xffbe + u32::from(code - 59),
+        87 => 0xffc8,
+        88 => 0xffc9,
+        97 => 0xffe4,
+        100 => 0xffea,
+        102 => 0xff50,
+        103 => 0xff52,
+        104 => 0xff55,
+        105 => 0xff51,
+        106 => 0xff53,
+        107 => 0xff57,
+        108 => 0xff54,
+        109 => 0xff56,
+        110 => 0xff63,
+        111 => 0xffff,
+        _ => return None,
+    })
+}
+
+/// Parses the output of `evtest`, with times relative to the first event:
+/// ```text
+/// Event: time 1700000000.250000, type 1 (EV_KEY), code 30 (KEY_A), value 1
+/// ```
+/// Key and button events (`EV_KEY`) and relative motions (`EV_REL`) are supported.
+/// Other lines, other events, auto-repeats, and key codes without a known KeySym are skipped.
+pub fn parse_evtest(text: &str) -> Result<Vec<Event>, InvalidEventError> {
+    let mut events = vec![];
+    let mut start = None;
+    for line in text.lines() {
+        let Some(fields) = line.strip_prefix("Event: time ") else {
+            continue;
+        };
+        if fields.contains("SYN_") {
+            continue;
+        }
+        let invalid = || InvalidEventError(line.to_owned());
+
+        let mut fields = fields.split(", ");
+        let time = fields.next().ok_or_else(invalid)?;
+        let (secs, micros) = time.split_once('.').ok_or_else(invalid)?;
+        let time = Duration::from_secs(secs.parse().map_err(|_| invalid())?)
+            + Duration::from_micros(micros.parse().map_err(|_| invalid())?);
+        let mut number = |prefix: &str| {
+            fields
+                .next()
+                .and_then(|field| field.strip_prefix(prefix))
+                .and_then(|field| field.split(' ').next())
+                .and_then(|number| number.parse::<i32>().ok())
+                .ok_or_else(invalid)
+        };
+        let kind = number("type ")?;
+        if kind != 1 && kind != 2 {
+            continue;
+        }
+        let (code, value) = (number("code ")?, number("value ")?);
+
+        let kind = match (kind, code, value) {
+            // buttons
+            (1, 272..=274, 0 | 1) => {
+                let button = [1, 3, 2][(code - 272) as usize];
+                if value == 1 {
+                    EventKind::ButtonPress(button)
+                } else {
+                    EventKind::ButtonRelease(button)
+                }
+            }
+            (1, _, 0 | 1) => {
+                let Some(keysym) = u16::try_from(code).ok().and_then(evdev_keysym) else {
+                    continue;
+                };
+                if value == 1 {
+                    EventKind::KeyPress(keysym)
+                } else {
+                    EventKind::KeyRelease(keysym)
+                }
+            }
+            (2, 0, dx) => EventKind::RelativeMotion { dx, dy: 0 },
+            (2, 1, dy) => EventKind::RelativeMotion { dx: 0, dy },
+            _ => continue,
+        };
+        let start = *start.get_or_insert(time);
+        events.push(Event {
+            time: time.saturating_sub(start),
+            kind,
+        });
+    }
+    Ok(events)
+}
+
+/// Quantizes `events`, sorted by time, onto frames at `framerate` with a [`Quantizer`].
+///
+/// A key or a button is held on a frame if it is held at any time during the frame,
+/// so that presses shorter than a frame are kept. The pointer takes its last position
+/// in each frame, and relative motions are summed per frame into relative mouse inputs.
+/// Frames have a mouse input if there is any pointer or button event.
+pub fn quantize(events: &[Event], framerate: Framerate) -> Inputs {
+    let button = |button: u8| {
+        MouseButton::ALL
+            .get(usize::from(button).wrapping_sub(1))
+            .copied()
+    };
+    let events: Vec<_> = events
+        .iter()
+        .filter_map(|event| {
+            let kind = match event.kind {
+                EventKind::KeyPress(key) => InputEvent::KeyPress(key),
+                EventKind::KeyRelease(key) => InputEvent::KeyRelease(key),
+                EventKind::ButtonPress(n) => InputEvent::ButtonPress(button(n)?),
+                EventKind::ButtonRelease(n) => InputEvent::ButtonRelease(button(n)?),
+                EventKind::Motion { x, y } => InputEvent::Motion { x, y },
+                EventKind::RelativeMotion { dx, dy } => InputEvent::RelativeMotion { dx, dy },
+            };
+            Some((event.time, kind))
+        })
+        .collect();
+    Quantizer::new(framerate).quantize(&events)
+}
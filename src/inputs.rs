@@ -1,6 +1,11 @@
 //! Module that defines an input sequence.
 
-use core::{fmt::Display, str::FromStr};
+use core::{fmt::Display, iter::once, ops::Range, str::FromStr};
+use std::io::BufRead;
+
+use memchr::memchr_iter;
+
+use crate::time::Framerate;
 
 /// An error while parsing inputs, containing the type and the string that caused the error.
 #[derive(Debug)]
@@ -8,6 +13,8 @@ pub enum InvalidInputsError {
     Line(String),
     Keyboard(String),
     Mouse(String),
+    Controller(String),
+    Framerate(String),
 }
 
 /// A keyboard input in a frame.
@@ -19,9 +26,16 @@ pub enum InvalidInputsError {
 ///
 /// For example, `K7a:ff53` means that the keys `0x7a (z)` and `0xff53 (right)`
 /// were pressed (or held down) on that frame.
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+///
+/// The alternate format `{:#}` names the keys with [`keysym_name`], such as `K z:Right`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
 pub struct KeyboardInput(pub Vec<u32>);
 
+impl KeyboardInput {
+    /// The maximum number of keys that libTAS handles per frame.
+    pub const MAX_KEYS: usize = 32;
+}
+
 impl FromStr for KeyboardInput {
     type Err = InvalidInputsError;
 
@@ -29,14 +43,11 @@ impl FromStr for KeyboardInput {
         let Some(s) = s.strip_prefix('K') else {
             return Err(InvalidInputsError::Keyboard(s.to_owned()));
         };
-        let Ok(keys) = s
-            .split(':')
-            .map(|s| u32::from_str_radix(s, 16))
-            .collect::<Result<Vec<u32>, _>>()
-        else {
-            return Err(InvalidInputsError::Keyboard(s.to_owned()));
-        };
-        Ok(Self(keys))
+        let mut keys = vec![];
+        match parse_keys(s, |key| keys.push(key)) {
+            Some(()) => Ok(Self(keys)),
+            None => Err(InvalidInputsError::Keyboard(s.to_owned())),
+        }
     }
 }
 
@@ -44,17 +55,131 @@ impl Display for KeyboardInput {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "K")?;
         for (idx, key) in self.0.iter().enumerate() {
-            if idx != 0 {
-                write!(f, ":")?;
+            match (idx, f.alternate()) {
+                (0, true) => write!(f, " {}", keysym_name(*key))?,
+                (0, false) => write!(f, "{key:x}")?,
+                (_, true) => write!(f, ":{}", keysym_name(*key))?,
+                (_, false) => write!(f, ":{key:x}")?,
             }
-            write!(f, "{key:x}")?;
         }
         Ok(())
     }
 }
 
+/// Splits `s` at each `separator`, which must be an ASCII character.
+///
+/// This is faster than [`str::split`], as `memchr` searches many bytes at once.
+fn split_ascii(s: &str, separator: u8) -> impl Iterator<Item = &str> {
+    let mut start = 0;
+    memchr_iter(separator, s.as_bytes())
+        .chain(once(s.len()))
+        .map(move |end| {
+            // splitting at an ASCII character keeps UTF-8 boundaries
+            let part = &s[start..end];
+            start = end + 1;
+            part
+        })
+}
+
+/// Parses a non-empty hexadecimal number that fits in a `u32`.
+fn parse_hex(digits: &[u8]) -> Option<u32> {
+    let significant = match digits.iter().position(|&digit| digit != b'0') {
+        Some(start) => &digits[start..],
+        None => &digits[digits.len()..],
+    };
+    if digits.is_empty() || significant.len() > 8 {
+        return None;
+    }
+    significant.iter().try_fold(0, |value, &digit| {
+        let nibble = match digit {
+            b'0'..=b'9' => digit - b'0',
+            b'a'..=b'f' => digit - b'a' + 10,
+            b'A'..=b'F' => digit - b'A' + 10,
+            _ => return None,
+        };
+        Some(value << 4 | u32::from(nibble))
+    })
+}
+
+/// Calls `f` with each key of `keys`, hexadecimal numbers separated by `:`.
+/// Returns `None` at the first invalid key.
+fn parse_keys(keys: &str, mut f: impl FnMut(u32)) -> Option<()> {
+    for key in split_ascii(keys, b':') {
+        f(parse_hex(key.as_bytes())?);
+    }
+    Some(())
+}
+
+/// Common KeySyms with their names as in `XKeysymToString`,
+/// except printable ASCII keys and function keys.
+const KEYSYM_NAMES: [(u32, &str); 21] = [
+    (0x20, "space"),
+    (0xff08, "BackSpace"),
+    (0xff09, "Tab"),
+    (0xff0d, "Return"),
+    (0xff1b, "Escape"),
+    (0xff50, "Home"),
+    (0xff51, "Left"),
+    (0xff52, "Up"),
+    (0xff53, "Right"),
+    (0xff54, "Down"),
+    (0xff55, "Prior"),
+    (0xff56, "Next"),
+    (0xff57, "End"),
+    (0xff63, "Insert"),
+    (0xffe1, "Shift_L"),
+    (0xffe2, "Shift_R"),
+    (0xffe3, "Control_L"),
+    (0xffe4, "Control_R"),
+    (0xffe9, "Alt_L"),
+    (0xffea, "Alt_R"),
+    (0xffff, "Delete"),
+];
+
+/// Returns the name of a common KeySym as in `XKeysymToString`, the character of a
+/// printable ASCII key, or the value in hexadecimal such as `0x1008ff13` otherwise.
+pub fn keysym_name(keysym: u32) -> String {
+    match keysym {
+        0x21..=0x7e => char::from(keysym as u8).to_string(),
+        0xffbe..=0xffc9 => format!("F{}", keysym - 0xffbe + 1),
+        _ => match KEYSYM_NAMES.iter().find(|(value, _)| *value == keysym) {
+            Some((_, name)) => (*name).to_owned(),
+            None => format!("{keysym:#x}"),
+        },
+    }
+}
+
+/// Returns the KeySym named `name`, the inverse of [`keysym_name`].
+///
+/// # Example
+/// ```
+/// use libtas_movie::inputs::keysym_from_name;
+/// assert_eq!(keysym_from_name("z"), Some(0x7a));
+/// assert_eq!(keysym_from_name("Right"), Some(0xff53));
+/// assert_eq!(keysym_from_name("F12"), Some(0xffc9));
+/// assert_eq!(keysym_from_name("0x1008ff13"), Some(0x1008ff13));
+/// ```
+pub fn keysym_from_name(name: &str) -> Option<u32> {
+    if let [c @ 0x21..=0x7e] = name.as_bytes() {
+        return Some(u32::from(*c));
+    }
+    if let Some(hex) = name.strip_prefix("0x") {
+        return parse_hex(hex.as_bytes());
+    }
+    if let Some(number) = name.strip_prefix('F').and_then(|n| n.parse::<u32>().ok())
+        && (1..=12).contains(&number)
+        && !name.starts_with("F0")
+    {
+        return Some(0xffbe + number - 1);
+    }
+    KEYSYM_NAMES
+        .iter()
+        .find(|(_, known)| *known == name)
+        .map(|(value, _)| *value)
+}
+
 /// The reference mode of a mouse input.
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 pub enum ReferenceMode {
     /// Absolute coordinates.
     #[default]
@@ -98,7 +223,9 @@ impl Display for ReferenceMode {
 ///
 /// For example, `M166:270:A:1....:0` means that the absolute coordinate `(166, 270)`
 /// was clicked (or held down) with the left mouse button on that frame.
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+///
+/// The alternate format `{:#}` labels the fields, such as `M (166,270) abs LMB`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 pub struct MouseInput {
     /// X-coordinate of the pointer (can be negative).
     pub xpos: i32,
@@ -118,6 +245,68 @@ pub struct MouseInput {
     pub button5: bool,
 }
 
+/// A button of the mouse.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MouseButton {
+    /// Button 1.
+    Left,
+    /// Button 2.
+    Middle,
+    /// Button 3.
+    Right,
+    /// Button 4.
+    Button4,
+    /// Button 5.
+    Button5,
+}
+
+impl MouseButton {
+    /// All buttons, from button 1 to button 5.
+    pub const ALL: [Self; 5] = [
+        Self::Left,
+        Self::Middle,
+        Self::Right,
+        Self::Button4,
+        Self::Button5,
+    ];
+
+    /// Returns the short label of the button, such as `LMB` for the left button.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Left => "LMB",
+            Self::Middle => "MMB",
+            Self::Right => "RMB",
+            Self::Button4 => "MB4",
+            Self::Button5 => "MB5",
+        }
+    }
+}
+
+impl MouseInput {
+    /// Returns whether `button` is pressed.
+    pub fn is_pressed(&self, button: MouseButton) -> bool {
+        match button {
+            MouseButton::Left => self.left_click,
+            MouseButton::Middle => self.middle_click,
+            MouseButton::Right => self.right_click,
+            MouseButton::Button4 => self.button4,
+            MouseButton::Button5 => self.button5,
+        }
+    }
+
+    /// Sets whether `button` is pressed.
+    pub fn set_pressed(&mut self, button: MouseButton, pressed: bool) {
+        let state = match button {
+            MouseButton::Left => &mut self.left_click,
+            MouseButton::Middle => &mut self.middle_click,
+            MouseButton::Right => &mut self.right_click,
+            MouseButton::Button4 => &mut self.button4,
+            MouseButton::Button5 => &mut self.button5,
+        };
+        *state = pressed;
+    }
+}
+
 impl FromStr for MouseInput {
     type Err = InvalidInputsError;
 
@@ -125,7 +314,7 @@ impl FromStr for MouseInput {
         let Some(s) = s.strip_prefix('M') else {
             return Err(InvalidInputsError::Mouse(s.to_owned()));
         };
-        let mut tokens = s.split(':');
+        let mut tokens = split_ascii(s, b':');
 
         let Some(xpos) = tokens.next() else {
             return Err(InvalidInputsError::Mouse(s.to_owned()));
@@ -173,6 +362,19 @@ impl FromStr for MouseInput {
 
 impl Display for MouseInput {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if f.alternate() {
+            let mode = match self.reference_mode {
+                ReferenceMode::Absolute => "abs",
+                ReferenceMode::Relative => "rel",
+            };
+            write!(f, "M ({},{}) {mode}", self.xpos, self.ypos)?;
+            for button in MouseButton::ALL {
+                if self.is_pressed(button) {
+                    write!(f, " {}", button.label())?;
+                }
+            }
+            return Ok(());
+        }
         write!(
             f,
             "M{}:{}:{}:{}{}{}{}{}:0",
@@ -188,23 +390,109 @@ impl Display for MouseInput {
     }
 }
 
+/// Parses a framerate section of the form `Tnum:den`.
+fn parse_framerate(s: &str) -> Result<Framerate, InvalidInputsError> {
+    let framerate = s
+        .strip_prefix('T')
+        .and_then(|s| s.split_once(':'))
+        .and_then(|(num, den)| Framerate::new(num.parse().ok()?, den.parse().ok()?));
+    framerate.ok_or_else(|| InvalidInputsError::Framerate(s.to_owned()))
+}
+
+/// An inclusive rectangle of pointer coordinates.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Rect {
+    /// The smallest X-coordinate.
+    pub left: i32,
+    /// The smallest Y-coordinate.
+    pub top: i32,
+    /// The largest X-coordinate.
+    pub right: i32,
+    /// The largest Y-coordinate.
+    pub bottom: i32,
+}
+
+impl Rect {
+    /// Returns whether `(x, y)` is in the rectangle.
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        (self.left..=self.right).contains(&x) && (self.top..=self.bottom).contains(&y)
+    }
+}
+
+/// A controller input in a frame.
+/// The state of the controller is not parsed yet, and is kept as written.
+///
+/// # Syntax
+/// `ControllerInput` starts with `C`, followed by the controller number from 1 and the state.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ControllerInput {
+    /// The controller number, from 1.
+    pub number: u8,
+    /// The state of the controller as written after the number.
+    pub state: String,
+}
+
+impl FromStr for ControllerInput {
+    type Err = InvalidInputsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let number = s
+            .strip_prefix('C')
+            .and_then(|s| s.chars().next())
+            .and_then(|c| c.to_digit(10))
+            .filter(|&number| number != 0);
+        let Some(number) = number else {
+            return Err(InvalidInputsError::Controller(s.to_owned()));
+        };
+        Ok(Self {
+            number: number as u8,
+            state: s[2..].to_owned(),
+        })
+    }
+}
+
+impl Display for ControllerInput {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "C{}{}", self.number, self.state)
+    }
+}
+
 /// An input in a frame.
-/// Controllers, flags, and variable framerates are not implemented yet.
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+/// Flags are not implemented yet, and are kept verbatim in [`unknown`](Self::unknown).
+///
+/// The alternate format `{:#}` is meant for logs and debugging, with named keys
+/// and labeled mouse fields, such as `|K z:Right|M (166,270) abs LMB|`.
+/// It cannot be parsed back.
+///
+/// # Example
+/// ```
+/// use libtas_movie::inputs::Input;
+/// let input: Input = "|K7a:ff53|M166:270:A:1....:0|".parse().unwrap();
+/// assert_eq!(format!("{input:#}"), "|K z:Right|M (166,270) abs LMB|");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
 pub struct Input {
     /// Keyboard input.
     pub keyboard: Option<KeyboardInput>,
     /// Mouse input.
     pub mouse: Option<MouseInput>,
-    pub controllers: (), // TODO
-    pub flags: (),       // TODO
-    pub framerate: (),   // TODO
+    /// Controller inputs in the order they are written.
+    pub controllers: Vec<ControllerInput>,
+    pub flags: (), // TODO
+    /// Sections that are not modeled, such as flags or sections of future versions of libTAS,
+    /// kept verbatim in order so that saving does not lose them.
+    /// They are written after the controller inputs.
+    pub unknown: Vec<String>,
+    /// Framerate of this frame, overriding the framerate in the config.
+    /// Used when [`variable_framerate`](crate::config::GeneralConfig::variable_framerate) is on.
+    pub framerate: Option<Framerate>,
 }
 
-impl FromStr for Input {
-    type Err = InvalidInputsError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+impl Input {
+    /// Parses a line of an input frame, keeping unknown sections in [`Input::unknown`].
+    /// Unknown sections are also pushed into `ignored` if given,
+    /// in which case empty sections are pushed there instead of failing.
+    fn parse(s: &str, mut ignored: Option<&mut Vec<String>>) -> Result<Self, InvalidInputsError> {
         if s == "|" {
             return Ok(Self::default());
         }
@@ -217,48 +505,167 @@ impl FromStr for Input {
         };
 
         let mut input = Self::default();
-        for section in line.split('|') {
+        for section in split_ascii(line, b'|') {
             match section.chars().next() {
                 Some('K') => {
-                    input.keyboard = Some(section.parse()?);
+                    let mut keys = vec![];
+                    if parse_keys(&section[1..], |key| keys.push(key)).is_none() {
+                        return Err(InvalidInputsError::Keyboard(section[1..].to_owned()));
+                    }
+                    input.keyboard = Some(KeyboardInput(keys));
                 }
                 Some('M') => {
                     input.mouse = Some(section.parse()?);
                 }
                 Some('C') => {
-                    // TODO
-                }
-                Some('F') => {
-                    // TODO
+                    input.controllers.push(section.parse()?);
                 }
                 Some('T') => {
-                    // TODO
+                    input.framerate = Some(parse_framerate(section)?);
                 }
-                _ => {
-                    return Err(InvalidInputsError::Line(line.to_owned()));
+                first => {
+                    match ignored.as_deref_mut() {
+                        Some(ignored) => ignored.push(section.to_owned()),
+                        None if first.is_none() => {
+                            return Err(InvalidInputsError::Line(line.to_owned()));
+                        }
+                        None => {}
+                    }
+                    if first.is_some() {
+                        input.unknown.push(section.to_owned());
+                    }
                 }
             }
         }
         Ok(input)
     }
+
+    /// Returns whether the frame restarts the game, with the `R` flag of its `F` section.
+    pub fn restarts(&self) -> bool {
+        self.unknown.iter().any(|section| {
+            section
+                .strip_prefix('F')
+                .is_some_and(|flags| flags.contains('R'))
+        })
+    }
+
+    /// Sets or clears the restart flag in the `F` section of [`Input::unknown`],
+    /// adding the section if needed. A section left without flags is removed.
+    pub fn set_restart(&mut self, restart: bool) {
+        if restart == self.restarts() {
+            return;
+        }
+        if restart {
+            match self
+                .unknown
+                .iter_mut()
+                .find(|section| section.starts_with('F'))
+            {
+                Some(section) => section.push('R'),
+                None => self.unknown.push("FR".to_owned()),
+            }
+        } else {
+            for section in &mut self.unknown {
+                if let Some(flags) = section.strip_prefix('F') {
+                    *section = format!("F{}", flags.replace('R', ""));
+                }
+            }
+            self.unknown.retain(|section| section != "F");
+        }
+    }
+}
+
+impl FromStr for Input {
+    type Err = InvalidInputsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s, None)
+    }
 }
 
 impl Display for Input {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "|")?;
         if let Some(keyboard) = &self.keyboard {
-            write!(f, "{keyboard}|")?;
+            if f.alternate() {
+                write!(f, "{keyboard:#}|")?;
+            } else {
+                write!(f, "{keyboard}|")?;
+            }
         }
         if let Some(mouse) = &self.mouse {
-            write!(f, "{mouse}|")?;
+            if f.alternate() {
+                write!(f, "{mouse:#}|")?;
+            } else {
+                write!(f, "{mouse}|")?;
+            }
+        }
+        for controller in &self.controllers {
+            write!(f, "{controller}|")?;
+        }
+        for section in &self.unknown {
+            write!(f, "{section}|")?;
+        }
+        if let Some(framerate) = &self.framerate {
+            write!(f, "T{}:{}|", framerate.num, framerate.den)?;
         }
         Ok(())
     }
 }
 
 /// A sequence of [`Input`]s, one per frame.
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
-pub struct Inputs(pub Vec<Input>);
+///
+/// The lines of the `inputs` file that are not input frames, such as comments,
+/// are kept with the index of the frame they precede, and written back at the same positions.
+/// Frames are inserted and removed with [`Inputs::splice_frames`] to keep them in place.
+///
+/// The alternate format `{:#}` writes each frame in the alternate format of [`Input`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Inputs(pub Vec<Input>, pub(crate) Vec<(usize, String)>);
+
+impl From<Vec<Input>> for Inputs {
+    fn from(inputs: Vec<Input>) -> Self {
+        Self(inputs, vec![])
+    }
+}
+
+impl Inputs {
+    /// Returns the lines that are not input frames, with the index of the frame they precede, in order.
+    pub fn other_lines(&self) -> &[(usize, String)] {
+        &self.1
+    }
+
+    /// Inserts a line that is not an input frame before `frame`, after the other lines there.
+    pub fn insert_line(&mut self, frame: usize, line: String) {
+        let index = self.1.partition_point(|&(at, _)| at <= frame);
+        self.1.insert(index, (frame, line));
+    }
+
+    /// Replaces the frames in `range` with `frames`, and returns the removed frames.
+    ///
+    /// The other lines move with the frames they precede,
+    /// and the lines preceding removed frames move before the inserted frames.
+    ///
+    /// # Panics
+    /// Panics if `range` is out of bounds.
+    pub fn splice_frames(
+        &mut self,
+        range: Range<usize>,
+        frames: impl IntoIterator<Item = Input>,
+    ) -> Vec<Input> {
+        let len = self.0.len();
+        let removed: Vec<Input> = self.0.splice(range.clone(), frames).collect();
+        let inserted = self.0.len() + removed.len() - len;
+        for (frame, _) in &mut self.1 {
+            if *frame >= range.end {
+                *frame = *frame - removed.len() + inserted;
+            } else if *frame > range.start {
+                *frame = range.start;
+            }
+        }
+        removed
+    }
+}
 
 impl core::ops::Index<usize> for Inputs {
     type Output = Input;
@@ -272,24 +679,331 @@ impl FromStr for Inputs {
     type Err = InvalidInputsError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut inputs = vec![];
+        Self::parse(s, None)
+    }
+}
+
+impl Inputs {
+    /// Parses inputs like [`Inputs::from_str`], but skips empty sections instead of failing.
+    /// Also returns the unknown sections, including flags, with their frame.
+    /// Unknown sections are kept in [`Input::unknown`], and empty sections are dropped.
+    pub fn parse_lenient(s: &str) -> Result<(Self, Vec<(usize, String)>), InvalidInputsError> {
+        let mut ignored = vec![];
+        let inputs = Self::parse(s, Some(&mut ignored))?;
+        Ok((inputs, ignored))
+    }
+
+    /// Parses inputs, pushing unknown and empty sections with their frame into `ignored`
+    /// instead of failing on empty sections if given.
+    fn parse(
+        s: &str,
+        mut ignored: Option<&mut Vec<(usize, String)>>,
+    ) -> Result<Self, InvalidInputsError> {
+        // an empty file has no lines, and other files end with a newline
+        let s = s.strip_suffix('\n').unwrap_or(s);
+        let mut inputs = Vec::with_capacity(memchr_iter(b'\n', s.as_bytes()).count() + 1);
+        let mut other_lines = vec![];
+        let mut sections = vec![];
 
-        for line in s.split('\n') {
+        for line in split_ascii(s, b'\n').filter(|_| !s.is_empty()) {
             // "each line that starts with the character `|` is an input frame."
             if !line.starts_with('|') {
+                other_lines.push((inputs.len(), line.to_owned()));
                 continue;
             }
-            inputs.push(line.parse::<Input>()?);
+            match ignored.as_deref_mut() {
+                Some(ignored) => {
+                    inputs.push(Input::parse(line, Some(&mut sections))?);
+                    let frame = inputs.len() - 1;
+                    ignored.extend(sections.drain(..).map(|section| (frame, section)));
+                }
+                None => inputs.push(line.parse::<Input>()?),
+            }
         }
-        Ok(Self(inputs))
+        Ok(Self(inputs, other_lines))
     }
 }
 
 impl Display for Inputs {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        for input in &self.0 {
-            writeln!(f, "{input}")?;
+        let mut other_lines = self.1.iter().peekable();
+        for (frame, input) in self.0.iter().enumerate() {
+            while let Some((_, line)) = other_lines.next_if(|(at, _)| *at <= frame) {
+                writeln!(f, "{line}")?;
+            }
+            if f.alternate() {
+                writeln!(f, "{input:#}")?;
+            } else {
+                writeln!(f, "{input}")?;
+            }
+        }
+        for (_, line) in other_lines {
+            writeln!(f, "{line}")?;
         }
         Ok(())
     }
 }
+
+/// An input frame borrowing its sections from the text of the `inputs` file.
+///
+/// The line is validated when parsed, so the accessors do not fail.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct InputRef<'a>(&'a str);
+
+impl<'a> InputRef<'a> {
+    /// Parses and validates a line of an input frame without copying it.
+    /// Unknown sections, such as flags, are accepted like in [`Input::from_str`].
+    pub fn parse(s: &'a str) -> Result<Self, InvalidInputsError> {
+        if s == "|" {
+            return Ok(Self::default());
+        }
+
+        let Some(line) = s.strip_prefix('|') else {
+            return Err(InvalidInputsError::Line(s.to_owned()));
+        };
+        let Some(line) = line.strip_suffix('|') else {
+            return Err(InvalidInputsError::Line(line.to_owned()));
+        };
+
+        for section in split_ascii(line, b'|') {
+            match section.chars().next() {
+                Some('K') => {
+                    if parse_keys(&section[1..], |_| {}).is_none() {
+                        return Err(InvalidInputsError::Keyboard(section[1..].to_owned()));
+                    }
+                }
+                Some('M') => {
+                    section.parse::<MouseInput>()?;
+                }
+                Some('C') => {
+                    let number = section[1..].bytes().next();
+                    if !matches!(number, Some(b'1'..=b'9')) {
+                        return Err(InvalidInputsError::Controller(section.to_owned()));
+                    }
+                }
+                Some('T') => {
+                    parse_framerate(section)?;
+                }
+                Some(_) => {}
+                None => {
+                    return Err(InvalidInputsError::Line(line.to_owned()));
+                }
+            }
+        }
+        Ok(Self(line))
+    }
+
+    /// Returns the sections of the frame, without the separators.
+    pub fn sections(self) -> impl Iterator<Item = &'a str> {
+        split_ascii(self.0, b'|').filter(|section| !section.is_empty())
+    }
+
+    /// Returns the keyboard section as written after `K`, if any.
+    pub fn keyboard(self) -> Option<&'a str> {
+        self.sections()
+            .find_map(|section| section.strip_prefix('K'))
+    }
+
+    /// Returns the pressed keys.
+    pub fn keys(self) -> impl Iterator<Item = u32> {
+        self.keyboard()
+            .into_iter()
+            .flat_map(|keys| split_ascii(keys, b':'))
+            .filter_map(|key| parse_hex(key.as_bytes()))
+    }
+
+    /// Returns the mouse input, if any.
+    pub fn mouse(self) -> Option<MouseInput> {
+        self.sections()
+            .find(|section| section.starts_with('M'))
+            .and_then(|section| section.parse().ok())
+    }
+
+    /// Returns the controller number and the state as written of each controller input.
+    pub fn controllers(self) -> impl Iterator<Item = (u8, &'a str)> {
+        self.sections()
+            .filter_map(|section| section.strip_prefix('C'))
+            .map(|section| (section.as_bytes()[0] - b'0', &section[1..]))
+    }
+
+    /// Returns the framerate of the frame, if any.
+    pub fn framerate(self) -> Option<Framerate> {
+        self.sections()
+            .find(|section| section.starts_with('T'))
+            .and_then(|section| parse_framerate(section).ok())
+    }
+
+    /// Returns the sections that are not modeled, such as flags, as written.
+    pub fn unknown(self) -> impl Iterator<Item = &'a str> {
+        self.sections()
+            .filter(|section| !matches!(section.as_bytes()[0], b'K' | b'M' | b'C' | b'T'))
+    }
+
+    /// Returns the owned input.
+    pub fn to_input(self) -> Input {
+        Input {
+            keyboard: self
+                .keyboard()
+                .map(|_| KeyboardInput(self.keys().collect())),
+            mouse: self.mouse(),
+            controllers: self
+                .controllers()
+                .map(|(number, state)| ControllerInput {
+                    number,
+                    state: state.to_owned(),
+                })
+                .collect(),
+            flags: (),
+            unknown: self.unknown().map(str::to_owned).collect(),
+            framerate: self.framerate(),
+        }
+    }
+}
+
+/// A sequence of [`InputRef`]s borrowing the text of the `inputs` file, one per frame.
+///
+/// This avoids allocating for each frame in read-only passes over big movies,
+/// and can be converted into [`Inputs`] when needed.
+/// The second field holds the lines that are not input frames, like in [`Inputs`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InputsRef<'a>(pub Vec<InputRef<'a>>, pub Vec<(usize, &'a str)>);
+
+impl<'a> InputsRef<'a> {
+    /// Parses the text of the `inputs` file without copying it.
+    pub fn parse(s: &'a str) -> Result<Self, InvalidInputsError> {
+        // an empty file has no lines, and other files end with a newline
+        let s = s.strip_suffix('\n').unwrap_or(s);
+        let mut inputs = Vec::with_capacity(memchr_iter(b'\n', s.as_bytes()).count() + 1);
+        let mut other_lines = vec![];
+
+        for line in split_ascii(s, b'\n').filter(|_| !s.is_empty()) {
+            // "each line that starts with the character `|` is an input frame."
+            if line.starts_with('|') {
+                inputs.push(InputRef::parse(line)?);
+            } else {
+                other_lines.push((inputs.len(), line));
+            }
+        }
+        Ok(Self(inputs, other_lines))
+    }
+
+    /// Returns the owned inputs.
+    pub fn to_inputs(&self) -> Inputs {
+        Inputs(
+            self.0.iter().map(|input| input.to_input()).collect(),
+            self.1
+                .iter()
+                .map(|&(frame, line)| (frame, line.to_owned()))
+                .collect(),
+        )
+    }
+}
+
+impl From<InputsRef<'_>> for Inputs {
+    fn from(inputs: InputsRef<'_>) -> Self {
+        inputs.to_inputs()
+    }
+}
+
+/// An error while reading inputs from a reader.
+#[derive(Debug)]
+pub enum ReadInputsError {
+    /// An error occurred while reading.
+    Io(std::io::Error),
+    /// A line is incorrect.
+    Invalid(InvalidInputsError),
+}
+
+/// Callbacks for [`parse_inputs_events`], all of which do nothing by default.
+///
+/// `frame` is the index of the frame being parsed.
+pub trait InputsVisitor {
+    /// Called at the start of each frame.
+    fn frame_start(&mut self, _frame: usize) {}
+    /// Called for each key in the keyboard input, in order.
+    fn key(&mut self, _frame: usize, _key: u32) {}
+    /// Called for the mouse input.
+    fn mouse(&mut self, _frame: usize, _mouse: &MouseInput) {}
+    /// Called for the framerate of the frame.
+    fn framerate(&mut self, _frame: usize, _framerate: Framerate) {}
+    /// Called for each section that is not parsed yet, such as controllers, flags,
+    /// and unknown sections.
+    fn other_section(&mut self, _frame: usize, _section: &str) {}
+    /// Called for each line that is not an input frame, before the frame `frame`.
+    fn other_line(&mut self, _frame: usize, _line: &str) {}
+    /// Called at the end of each frame.
+    fn frame_end(&mut self, _frame: usize) {}
+}
+
+/// Parses the `inputs` file from `reader`, calling `visitor` for each frame and section
+/// without storing the inputs. Returns the number of frames.
+///
+/// This is useful for a single pass over an enormous movie.
+pub fn parse_inputs_events<R: BufRead>(
+    mut reader: R,
+    visitor: &mut impl InputsVisitor,
+) -> Result<usize, ReadInputsError> {
+    let mut frame = 0;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        match reader.read_line(&mut line) {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(err) => return Err(ReadInputsError::Io(err)),
+        }
+        let line = line.strip_suffix('\n').unwrap_or(&line);
+
+        // "each line that starts with the character `|` is an input frame."
+        if !line.starts_with('|') {
+            visitor.other_line(frame, line);
+            continue;
+        }
+        visitor.frame_start(frame);
+        visit_input(line, frame, visitor).map_err(ReadInputsError::Invalid)?;
+        visitor.frame_end(frame);
+        frame += 1;
+    }
+    Ok(frame)
+}
+
+/// Parses a line of an input frame, calling `visitor` for each section.
+fn visit_input(
+    s: &str,
+    frame: usize,
+    visitor: &mut impl InputsVisitor,
+) -> Result<(), InvalidInputsError> {
+    if s == "|" {
+        return Ok(());
+    }
+
+    let Some(line) = s.strip_prefix('|') else {
+        return Err(InvalidInputsError::Line(s.to_owned()));
+    };
+    let Some(line) = line.strip_suffix('|') else {
+        return Err(InvalidInputsError::Line(line.to_owned()));
+    };
+
+    for section in split_ascii(line, b'|') {
+        match section.chars().next() {
+            Some('K') => {
+                if parse_keys(&section[1..], |key| visitor.key(frame, key)).is_none() {
+                    return Err(InvalidInputsError::Keyboard(section[1..].to_owned()));
+                }
+            }
+            Some('M') => {
+                visitor.mouse(frame, &section.parse()?);
+            }
+            Some('T') => {
+                visitor.framerate(frame, parse_framerate(section)?);
+            }
+            Some(_) => {
+                visitor.other_section(frame, section);
+            }
+            None => {
+                return Err(InvalidInputsError::Line(line.to_owned()));
+            }
+        }
+    }
+    Ok(())
+}
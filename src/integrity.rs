@@ -0,0 +1,107 @@
+//! Module that defines the integrity manifest of a movie archive.
+//!
+//! The manifest is the `checksums` entry, written with [`SaveOptions::checksums`]
+//! and checked with [`LoadOptions::verify_integrity`].
+//!
+//! [`SaveOptions::checksums`]: crate::movie::SaveOptions::checksums
+//! [`LoadOptions::verify_integrity`]: crate::movie::LoadOptions::verify_integrity
+
+use core::{fmt::Display, str::FromStr};
+
+use sha2::{Digest as _, Sha256};
+
+use crate::catalog::hex;
+
+/// An error while parsing a manifest, containing the line that caused the error.
+#[derive(Debug)]
+pub struct InvalidChecksumsError(pub String);
+
+/// A difference between an archive and its manifest.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IntegrityError {
+    /// The entry does not match its checksum.
+    Mismatch(String),
+    /// The entry is listed in the manifest, but not in the archive.
+    Missing(String),
+    /// The entry is in the archive, but not listed in the manifest.
+    Unlisted(String),
+}
+
+/// The SHA-256 hash of each entry of an archive, in the format of `sha256sum`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Checksums(pub Vec<(String, String)>);
+
+impl Checksums {
+    /// The file name of the manifest in the archive.
+    pub const NAME: &str = "checksums";
+
+    /// Returns the checksums of `entries`, given as names and contents.
+    pub fn of_entries<'a>(entries: impl IntoIterator<Item = (&'a str, &'a [u8])>) -> Self {
+        let mut checksums = Self::default();
+        for (name, data) in entries {
+            checksums.push(name, data);
+        }
+        checksums
+    }
+
+    /// Appends the checksum of the entry `name` with contents `data`.
+    pub fn push(&mut self, name: &str, data: &[u8]) {
+        self.0.push((name.to_owned(), hex(&Sha256::digest(data))));
+    }
+
+    /// Returns the differences between `actual`, the checksums of the entries of an archive,
+    /// and the manifest, in the order of `actual` followed by missing entries.
+    pub fn verify(&self, actual: &Self) -> Vec<IntegrityError> {
+        let mut errors: Vec<_> = actual
+            .0
+            .iter()
+            .filter_map(|(name, hash)| match self.hash_of(name) {
+                None => Some(IntegrityError::Unlisted(name.clone())),
+                Some(expected) if expected != hash => Some(IntegrityError::Mismatch(name.clone())),
+                Some(_) => None,
+            })
+            .collect();
+        errors.extend(
+            self.0
+                .iter()
+                .filter(|(name, _)| actual.hash_of(name).is_none())
+                .map(|(name, _)| IntegrityError::Missing(name.clone())),
+        );
+        errors
+    }
+
+    /// Returns the checksum of the entry `name`.
+    pub fn hash_of(&self, name: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(entry, _)| entry == name)
+            .map(|(_, hash)| hash.as_str())
+    }
+}
+
+impl FromStr for Checksums {
+    type Err = InvalidChecksumsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.lines()
+            .map(|line| match line.split_once("  ") {
+                Some((hash, name))
+                    if hash.len() == 64 && hash.bytes().all(|b| b.is_ascii_hexdigit()) =>
+                {
+                    Ok((name.to_owned(), hash.to_ascii_lowercase()))
+                }
+                _ => Err(InvalidChecksumsError(line.to_owned())),
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+impl Display for Checksums {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (name, hash) in &self.0 {
+            writeln!(f, "{hash}  {name}")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+//! Module that defines interned inputs, which share identical frames.
+//!
+//! Most movies repeat a handful of distinct frames.
+//! Interning stores each distinct [`Input`] once in a [`FrameTable`],
+//! and represents frames as [`FrameId`]s that compare in constant time.
+
+use std::collections::HashMap;
+
+use crate::inputs::{Input, Inputs};
+
+/// A handle to a distinct input of a [`FrameTable`].
+///
+/// Two handles from the same table are equal if and only if their inputs are equal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FrameId(u32);
+
+impl FrameId {
+    /// Returns the index of the input in its table, in order of first appearance.
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// The distinct inputs of one or more interned sequences.
+#[derive(Clone, Debug, Default)]
+pub struct FrameTable {
+    /// The distinct inputs, indexed by [`FrameId`].
+    inputs: Vec<Input>,
+    /// The handle of each distinct input.
+    ids: HashMap<Input, FrameId>,
+}
+
+impl FrameTable {
+    /// Returns an empty table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the handle of `input`, adding it to the table if it is new.
+    pub fn intern(&mut self, input: &Input) -> FrameId {
+        if let Some(&id) = self.ids.get(input) {
+            return id;
+        }
+        let id = FrameId(self.inputs.len() as u32);
+        self.inputs.push(input.clone());
+        self.ids.insert(input.clone(), id);
+        id
+    }
+
+    /// Returns the input of `id`.
+    ///
+    /// # Panics
+    /// Panics if `id` comes from another table.
+    pub fn get(&self, id: FrameId) -> &Input {
+        &self.inputs[id.index()]
+    }
+
+    /// Returns the distinct inputs in order of first appearance.
+    pub fn inputs(&self) -> &[Input] {
+        &self.inputs
+    }
+
+    /// Returns the number of distinct inputs.
+    pub fn len(&self) -> usize {
+        self.inputs.len()
+    }
+
+    /// Returns whether the table has no input.
+    pub fn is_empty(&self) -> bool {
+        self.inputs.is_empty()
+    }
+
+    /// Interns each frame of `inputs`.
+    pub fn intern_inputs(&mut self, inputs: &Inputs) -> InternedInputs {
+        InternedInputs(
+            inputs.0.iter().map(|input| self.intern(input)).collect(),
+            inputs.1.clone(),
+        )
+    }
+
+    /// Returns the inputs of the frames of `interned`, which must come from this table.
+    pub fn resolve(&self, interned: &InternedInputs) -> Inputs {
+        Inputs(
+            interned.0.iter().map(|&id| self.get(id).clone()).collect(),
+            interned.1.clone(),
+        )
+    }
+}
+
+/// A sequence of [`FrameId`]s, one per frame, whose inputs are held by a [`FrameTable`].
+///
+/// The second field holds the lines that are not input frames, like in [`Inputs`].
+/// Sequences interned with the same table can be compared frame by frame in constant time.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InternedInputs(pub Vec<FrameId>, pub Vec<(usize, String)>);
+
+impl InternedInputs {
+    /// Returns the number of times each distinct input is used, indexed by [`FrameId::index`].
+    pub fn counts(&self, table: &FrameTable) -> Vec<usize> {
+        let mut counts = vec![0; table.len()];
+        for id in &self.0 {
+            counts[id.index()] += 1;
+        }
+        counts
+    }
+}
@@ -0,0 +1,477 @@
+//! Module that converts inputs to [JSON Lines](https://jsonlines.org/), one object per frame.
+//!
+//! Frames are written one by one, so that large movies can be piped into `jq`
+//! or other stream processors without building a single JSON document in memory:
+//! ```text
+//! {"frame": 3, "keys": [122, 65363], "mouse": {"x": 166, "y": 270, "mode": "absolute", "buttons": [1]}}
+//! ```
+//! Absent parts of a frame are omitted: `keys` is a list of KeySyms, `mouse` has the
+//! pressed buttons from 1 to 5, `controllers` is a list of objects with the `number` and
+//! `state` of each controller, `unknown` is a list of unknown sections, and `framerate`
+//! is an object with `num` and `den`.
+//!
+//! [`Inputs::from_jsonl`] and [`JsonlReader`] read such frames back, e.g. frame streams
+//! generated by bots or solvers. The `frame` field is optional, but must count up from 0
+//! when given.
+
+use core::{fmt::Write as _, iter::Peekable, str::Chars};
+use std::io::{self, BufRead, Write};
+
+use crate::{
+    catalog::json_string,
+    inputs::{
+        ControllerInput, Input, Inputs, KeyboardInput, MouseButton, MouseInput, ReferenceMode,
+    },
+    time::Framerate,
+};
+
+/// An error while reading JSON Lines.
+#[derive(Debug)]
+pub enum JsonlError {
+    /// An error occurred while reading.
+    Io(io::Error),
+    /// A line is not a valid frame.
+    Invalid {
+        /// The line number, from 1.
+        line: usize,
+        /// The reason.
+        message: String,
+    },
+}
+
+/// Returns the JSON object of `input`, the input of `frame`, without a newline.
+fn frame_to_json(frame: usize, input: &Input) -> String {
+    let mut json = format!("{{\"frame\": {frame}");
+    if let Some(keyboard) = &input.keyboard {
+        let keys: Vec<_> = keyboard.0.iter().map(u32::to_string).collect();
+        let _ = write!(json, ", \"keys\": [{}]", keys.join(", "));
+    }
+    if let Some(mouse) = &input.mouse {
+        let mode = match mouse.reference_mode {
+            ReferenceMode::Absolute => "absolute",
+            ReferenceMode::Relative => "relative",
+        };
+        let buttons: Vec<_> = (1..)
+            .zip(MouseButton::ALL)
+            .filter(|&(_, button)| mouse.is_pressed(button))
+            .map(|(number, _): (u8, _)| number.to_string())
+            .collect();
+        let _ = write!(
+            json,
+            ", \"mouse\": {{\"x\": {}, \"y\": {}, \"mode\": \"{mode}\", \"buttons\": [{}]}}",
+            mouse.xpos,
+            mouse.ypos,
+            buttons.join(", ")
+        );
+    }
+    if !input.controllers.is_empty() {
+        let controllers: Vec<_> = input
+            .controllers
+            .iter()
+            .map(|controller| {
+                format!(
+                    "{{\"number\": {}, \"state\": {}}}",
+                    controller.number,
+                    json_string(&controller.state)
+                )
+            })
+            .collect();
+        let _ = write!(json, ", \"controllers\": [{}]", controllers.join(", "));
+    }
+    if !input.unknown.is_empty() {
+        let unknown: Vec<_> = input.unknown.iter().map(|s| json_string(s)).collect();
+        let _ = write!(json, ", \"unknown\": [{}]", unknown.join(", "));
+    }
+    if let Some(framerate) = &input.framerate {
+        let _ = write!(
+            json,
+            ", \"framerate\": {{\"num\": {}, \"den\": {}}}",
+            framerate.num, framerate.den
+        );
+    }
+    json.push('}');
+    json
+}
+
+impl Inputs {
+    /// Writes the frames to `writer` as JSON Lines, one object per frame.
+    ///
+    /// The writer is not buffered, so wrap it in a [`BufWriter`](std::io::BufWriter) if needed.
+    pub fn to_jsonl<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        for (frame, input) in self.0.iter().enumerate() {
+            writeln!(writer, "{}", frame_to_json(frame, input))?;
+        }
+        Ok(())
+    }
+}
+
+/// A parsed JSON value. Numbers are kept as written.
+#[derive(Clone, Debug, PartialEq)]
+enum Json {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<Self>),
+    Object(Vec<(String, Self)>),
+}
+
+/// The maximum nesting of arrays and objects, far above that of a frame,
+/// so that deeply nested lines cannot overflow the stack.
+const MAX_DEPTH: usize = 64;
+
+/// A parser of a single JSON value.
+struct Parser<'a>(Peekable<Chars<'a>>);
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self
+            .0
+            .next_if(|c| matches!(c, ' ' | '\t' | '\r' | '\n'))
+            .is_some()
+        {}
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_whitespace();
+        match self.0.next() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err(format!("expected `{expected}`, found `{c}`")),
+            None => Err(format!("expected `{expected}`, found the end of the line")),
+        }
+    }
+
+    fn literal(&mut self, literal: &str, value: Json) -> Result<Json, String> {
+        for expected in literal.chars() {
+            if self.0.next() != Some(expected) {
+                return Err(format!("invalid literal, expected `{literal}`"));
+            }
+        }
+        Ok(value)
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let mut string = String::new();
+        loop {
+            match self.0.next().ok_or("unterminated string")? {
+                '"' => return Ok(string),
+                '\\' => {
+                    let c = match self.0.next().ok_or("unterminated string")? {
+                        c @ ('"' | '\\' | '/') => c,
+                        'b' => '\u{8}',
+                        'f' => '\u{c}',
+                        'n' => '\n',
+                        'r' => '\r',
+                        't' => '\t',
+                        'u' => self.unicode_escape()?,
+                        c => return Err(format!("invalid escape `\\{c}`")),
+                    };
+                    string.push(c);
+                }
+                c => string.push(c),
+            }
+        }
+    }
+
+    /// Parses the 4 hex digits of a `\u` escape.
+    fn hex_escape(&mut self) -> Result<u32, String> {
+        let hex: String = (0..4).filter_map(|_| self.0.next()).collect();
+        u32::from_str_radix(&hex, 16)
+            .ok()
+            .filter(|_| hex.len() == 4)
+            .ok_or_else(|| format!("invalid escape `\\u{hex}`"))
+    }
+
+    /// Parses a `\u` escape after the `u`, with the low surrogate of a surrogate pair.
+    fn unicode_escape(&mut self) -> Result<char, String> {
+        let high = self.hex_escape()?;
+        let code = if (0xd800..0xdc00).contains(&high) {
+            if self.0.next() != Some('\\') || self.0.next() != Some('u') {
+                return Err(format!("unpaired surrogate `\\u{high:04x}`"));
+            }
+            let low = self.hex_escape()?;
+            if !(0xdc00..0xe000).contains(&low) {
+                return Err(format!(
+                    "invalid surrogate pair `\\u{high:04x}\\u{low:04x}`"
+                ));
+            }
+            0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+        } else {
+            high
+        };
+        char::from_u32(code).ok_or_else(|| format!("unpaired surrogate `\\u{code:04x}`"))
+    }
+
+    /// Parses a value nested in `depth` arrays or objects.
+    fn value(&mut self, depth: usize) -> Result<Json, String> {
+        if depth > MAX_DEPTH {
+            return Err(format!("values are nested deeper than {MAX_DEPTH} levels"));
+        }
+        self.skip_whitespace();
+        match self.0.peek().copied() {
+            None => Err("expected a value, found the end of the line".to_owned()),
+            Some('n') => self.literal("null", Json::Null),
+            Some('t') => self.literal("true", Json::Bool(true)),
+            Some('f') => self.literal("false", Json::Bool(false)),
+            Some('"') => self.string().map(Json::String),
+            Some('[') => {
+                self.0.next();
+                let mut values = vec![];
+                self.skip_whitespace();
+                if self.0.next_if_eq(&']').is_none() {
+                    loop {
+                        values.push(self.value(depth + 1)?);
+                        self.skip_whitespace();
+                        match self.0.next() {
+                            Some(',') => {}
+                            Some(']') => break,
+                            _ => return Err("expected `,` or `]` in an array".to_owned()),
+                        }
+                    }
+                }
+                Ok(Json::Array(values))
+            }
+            Some('{') => {
+                self.0.next();
+                let mut fields = vec![];
+                self.skip_whitespace();
+                if self.0.next_if_eq(&'}').is_none() {
+                    loop {
+                        let key = self.string()?;
+                        self.expect(':')?;
+                        fields.push((key, self.value(depth + 1)?));
+                        self.skip_whitespace();
+                        match self.0.next() {
+                            Some(',') => {}
+                            Some('}') => break,
+                            _ => return Err("expected `,` or `}` in an object".to_owned()),
+                        }
+                    }
+                }
+                Ok(Json::Object(fields))
+            }
+            Some(c) if c == '-' || c.is_ascii_digit() => {
+                let mut number = String::new();
+                while let Some(c) = self
+                    .0
+                    .next_if(|&c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+                {
+                    number.push(c);
+                }
+                Ok(Json::Number(number))
+            }
+            Some(c) => Err(format!("unexpected `{c}`")),
+        }
+    }
+}
+
+/// Parses `text` as a single JSON value.
+fn parse_json(text: &str) -> Result<Json, String> {
+    let mut parser = Parser(text.chars().peekable());
+    let value = parser.value(0)?;
+    parser.skip_whitespace();
+    match parser.0.next() {
+        None => Ok(value),
+        Some(c) => Err(format!("unexpected `{c}` after the value")),
+    }
+}
+
+impl Json {
+    /// Returns the integer value, or an error naming `what`.
+    fn integer<T: core::str::FromStr>(&self, what: &str) -> Result<T, String> {
+        match self {
+            Self::Number(number) => number.parse().ok(),
+            _ => None,
+        }
+        .ok_or_else(|| format!("invalid {what}"))
+    }
+
+    /// Returns the elements of the array, or an error naming `what`.
+    fn array(&self, what: &str) -> Result<&[Self], String> {
+        match self {
+            Self::Array(values) => Ok(values),
+            _ => Err(format!("{what} is not an array")),
+        }
+    }
+
+    /// Returns the fields of the object, or an error naming `what`.
+    fn object(&self, what: &str) -> Result<&[(String, Self)], String> {
+        match self {
+            Self::Object(fields) => Ok(fields),
+            _ => Err(format!("{what} is not an object")),
+        }
+    }
+
+    /// Returns the string, or an error naming `what`.
+    fn string(&self, what: &str) -> Result<&str, String> {
+        match self {
+            Self::String(string) => Ok(string),
+            _ => Err(format!("{what} is not a string")),
+        }
+    }
+}
+
+/// Returns the field `key` of `fields`, or an error if it is missing.
+fn field<'a>(fields: &'a [(String, Json)], key: &str, what: &str) -> Result<&'a Json, String> {
+    fields
+        .iter()
+        .find(|(name, _)| name == key)
+        .map(|(_, value)| value)
+        .ok_or_else(|| format!("missing `{key}` in {what}"))
+}
+
+/// Parses the JSON object of a frame, returning its `frame` field if given, and its input.
+fn frame_from_json(text: &str) -> Result<(Option<usize>, Input), String> {
+    let json = parse_json(text)?;
+    let mut frame = None;
+    let mut input = Input::default();
+    for (key, value) in json.object("the frame")? {
+        match key.as_str() {
+            "frame" => frame = Some(value.integer("frame")?),
+            "keys" => {
+                let keys = value
+                    .array("keys")?
+                    .iter()
+                    .map(|key| key.integer("key"))
+                    .collect::<Result<Vec<u32>, _>>()?;
+                input.keyboard = (!keys.is_empty()).then_some(KeyboardInput(keys));
+            }
+            "mouse" => {
+                let fields = value.object("mouse")?;
+                let reference_mode = match field(fields, "mode", "mouse")?.string("mode")? {
+                    "absolute" => ReferenceMode::Absolute,
+                    "relative" => ReferenceMode::Relative,
+                    mode => return Err(format!("invalid mode `{mode}`")),
+                };
+                let mut mouse = MouseInput {
+                    xpos: field(fields, "x", "mouse")?.integer("x")?,
+                    ypos: field(fields, "y", "mouse")?.integer("y")?,
+                    reference_mode,
+                    ..MouseInput::default()
+                };
+                if let Ok(buttons) = field(fields, "buttons", "mouse") {
+                    for button in buttons.array("buttons")? {
+                        let button = button
+                            .integer::<usize>("button")
+                            .ok()
+                            .and_then(|number| MouseButton::ALL.get(number.wrapping_sub(1)))
+                            .ok_or("buttons must be from 1 to 5")?;
+                        mouse.set_pressed(*button, true);
+                    }
+                }
+                input.mouse = Some(mouse);
+            }
+            "controllers" => {
+                for controller in value.array("controllers")? {
+                    let fields = controller.object("controller")?;
+                    input.controllers.push(ControllerInput {
+                        number: field(fields, "number", "controller")?
+                            .integer("controller number")?,
+                        state: field(fields, "state", "controller")?
+                            .string("state")?
+                            .to_owned(),
+                    });
+                }
+            }
+            "unknown" => {
+                for section in value.array("unknown")? {
+                    input
+                        .unknown
+                        .push(section.string("unknown section")?.to_owned());
+                }
+            }
+            "framerate" => {
+                let fields = value.object("framerate")?;
+                input.framerate = Some(Framerate {
+                    num: field(fields, "num", "framerate")?.integer("framerate")?,
+                    den: field(fields, "den", "framerate")?.integer("framerate")?,
+                });
+            }
+            key => return Err(format!("unknown field `{key}`")),
+        }
+    }
+
+    // the frame must be writable as a line of inputs
+    if !input
+        .to_string()
+        .parse::<Input>()
+        .is_ok_and(|parsed| parsed == input)
+    {
+        return Err("the frame cannot be written as inputs".to_owned());
+    }
+    Ok((frame, input))
+}
+
+/// An iterator over the frames of JSON Lines, read one line at a time.
+///
+/// Blank lines are skipped. Iteration stops after the first error.
+#[derive(Debug)]
+pub struct JsonlReader<R> {
+    lines: io::Lines<R>,
+    /// The number of lines read.
+    line: usize,
+    /// The number of frames read.
+    frames: usize,
+    failed: bool,
+}
+
+impl<R: BufRead> JsonlReader<R> {
+    /// Returns a reader of the frames in `reader`.
+    pub fn new(reader: R) -> Self {
+        Self {
+            lines: reader.lines(),
+            line: 0,
+            frames: 0,
+            failed: false,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for JsonlReader<R> {
+    type Item = Result<Input, JsonlError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let result = loop {
+            let line = match self.lines.next()? {
+                Ok(line) => line,
+                Err(err) => break Err(JsonlError::Io(err)),
+            };
+            self.line += 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let invalid = |message| JsonlError::Invalid {
+                line: self.line,
+                message,
+            };
+            break match frame_from_json(&line) {
+                Ok((Some(frame), _)) if frame != self.frames => Err(invalid(format!(
+                    "expected frame {}, found frame {frame}",
+                    self.frames
+                ))),
+                Ok((_, input)) => {
+                    self.frames += 1;
+                    Ok(input)
+                }
+                Err(message) => Err(invalid(message)),
+            };
+        };
+        self.failed = result.is_err();
+        Some(result)
+    }
+}
+
+impl Inputs {
+    /// Reads frames written as JSON Lines by [`Inputs::to_jsonl`] from `reader`.
+    ///
+    /// Use [`JsonlReader`] to process frames as they are read.
+    pub fn from_jsonl<R: BufRead>(reader: R) -> Result<Self, JsonlError> {
+        JsonlReader::new(reader)
+            .collect::<Result<Vec<_>, _>>()
+            .map(Self::from)
+    }
+}
@@ -3,6 +3,56 @@
 //! # Resources
 //! - [libTAS - Moviefile format](https://clementgallet.github.io/libTAS/guides/format/)
 
+pub mod branch;
+pub mod cache;
+pub mod catalog;
+pub mod comment;
 pub mod config;
+pub mod consistency;
+pub mod diff;
+pub mod edit;
+pub mod editor;
+pub mod export;
+pub mod fingerprint;
+pub mod format;
+pub mod generate;
+pub mod gesture;
+pub mod greenzone;
+pub mod hash;
+pub mod import;
 pub mod inputs;
+pub mod integrity;
+pub mod intern;
+pub mod jsonl;
+pub mod lint;
+#[cfg(feature = "lua")]
+pub mod lua;
+pub mod memory;
 pub mod movie;
+pub mod pass;
+pub mod pattern;
+pub mod playback;
+pub mod pretty;
+pub mod quantize;
+pub mod record;
+pub mod render;
+pub mod repair;
+pub mod replay;
+pub mod resample;
+pub mod savestate;
+#[cfg(feature = "scripting")]
+pub mod scripting;
+pub mod search;
+pub mod section;
+pub mod segment;
+pub mod splice;
+pub mod stats;
+pub mod summary;
+pub mod table;
+#[cfg(feature = "testkit")]
+pub mod testkit;
+pub mod time;
+pub mod version;
+pub mod warning;
+#[cfg(feature = "xtest")]
+pub mod xtest;
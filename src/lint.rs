@@ -0,0 +1,397 @@
+//! Module that checks movies against configurable rules of TAS hygiene.
+//!
+//! A [`Linter`] runs [`LintRule`]s, each with a [`Severity`] that can be configured per rule,
+//! e.g. to fail CI on denied lints. Other crates can add rules by implementing [`LintRule`].
+//!
+//! # Configuration
+//! [`Linter::configure`] reads one `rule = severity` per line, where the severity is
+//! `allow`, `warn`, or `deny`. Empty lines and lines starting with `#` or `;` are ignored.
+//!
+//! # Example
+//! ```
+//! use libtas_movie::{lint::{Linter, Severity}, testkit::sample_movie};
+//!
+//! let mut linter = Linter::builtin();
+//! linter.configure("trailing-blank-frames = deny\nshort-press = allow").unwrap();
+//! let lints = linter.run(&sample_movie(5, 60));
+//! assert_eq!(lints.len(), 1);
+//! assert_eq!(lints[0].rule, "trailing-blank-frames");
+//! assert_eq!(lints[0].severity, Severity::Deny);
+//! assert_eq!(lints[0].frames, 4..5);
+//! ```
+
+use core::{fmt::Display, ops::Range, str::FromStr};
+
+use crate::{
+    inputs::{Input, Rect, ReferenceMode, keysym_name},
+    movie::LibTASMovie,
+};
+
+/// An error while parsing a lint configuration, containing the line that caused the error.
+#[derive(Debug)]
+pub struct InvalidLintConfigError(pub String);
+
+/// How a lint is reported.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The rule is not run.
+    Allow,
+    /// The lint is reported.
+    Warn,
+    /// The lint is reported as an error.
+    Deny,
+}
+
+impl FromStr for Severity {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "allow" => Ok(Self::Allow),
+            "warn" => Ok(Self::Warn),
+            "deny" => Ok(Self::Deny),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Allow => write!(f, "allow"),
+            Self::Warn => write!(f, "warn"),
+            Self::Deny => write!(f, "deny"),
+        }
+    }
+}
+
+/// A problem found by a rule in a range of frames.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Finding {
+    /// The frames of the problem.
+    pub frames: Range<usize>,
+    /// The description of the problem.
+    pub message: String,
+}
+
+/// A rule checking a movie.
+pub trait LintRule {
+    /// The unique name of the rule in kebab case, such as `trailing-blank-frames`.
+    fn name(&self) -> &'static str;
+
+    /// The severity of the rule until configured.
+    fn default_severity(&self) -> Severity {
+        Severity::Warn
+    }
+
+    /// Returns the problems found in `movie`, in order.
+    fn check(&self, movie: &LibTASMovie) -> Vec<Finding>;
+}
+
+/// A problem reported by a [`Linter`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Lint {
+    /// The name of the rule.
+    pub rule: &'static str,
+    /// The configured severity of the rule.
+    pub severity: Severity,
+    /// The frames of the problem.
+    pub frames: Range<usize>,
+    /// The description of the problem.
+    pub message: String,
+}
+
+impl Display for Lint {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let Range { start, end } = self.frames;
+        write!(f, "{}[{}]: ", self.severity, self.rule)?;
+        if end == start + 1 {
+            write!(f, "frame {start}: ")?;
+        } else {
+            write!(f, "frames {start}..{end}: ")?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+/// A set of rules with their severities.
+#[derive(Default)]
+pub struct Linter(Vec<(Box<dyn LintRule>, Severity)>);
+
+impl core::fmt::Debug for Linter {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_map()
+            .entries(
+                self.0
+                    .iter()
+                    .map(|(rule, severity)| (rule.name(), severity)),
+            )
+            .finish()
+    }
+}
+
+impl Linter {
+    /// Returns a linter without rules.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a linter with the rules defined by this crate at their default severities:
+    /// [`TrailingBlankFrames`], [`OppositeDirections`], [`ClickOutOfBounds`],
+    /// and [`ShortPress`].
+    pub fn builtin() -> Self {
+        let mut linter = Self::new();
+        linter.register(TrailingBlankFrames);
+        linter.register(OppositeDirections);
+        linter.register(ClickOutOfBounds::default());
+        linter.register(ShortPress::default());
+        linter
+    }
+
+    /// Registers `rule` at its default severity, replacing any rule with the same name.
+    pub fn register<R: LintRule + 'static>(&mut self, rule: R) -> &mut Self {
+        self.0.retain(|(other, _)| other.name() != rule.name());
+        let severity = rule.default_severity();
+        self.0.push((Box::new(rule), severity));
+        self
+    }
+
+    /// Returns the registered rules with their severities, in order of registration.
+    pub fn rules(&self) -> impl Iterator<Item = (&dyn LintRule, Severity)> {
+        self.0.iter().map(|(rule, severity)| (&**rule, *severity))
+    }
+
+    /// Returns the severity of the rule `name`, or `None` if there is no such rule.
+    pub fn severity(&self, name: &str) -> Option<Severity> {
+        self.rules()
+            .find_map(|(rule, severity)| (rule.name() == name).then_some(severity))
+    }
+
+    /// Sets the severity of the rule `name`. Returns `false` if there is no such rule.
+    pub fn set_severity(&mut self, name: &str, severity: Severity) -> bool {
+        match self.0.iter_mut().find(|(rule, _)| rule.name() == name) {
+            Some((_, old)) => {
+                *old = severity;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Sets the severities written in `text`, in the format described in the
+    /// [module](self) documentation.
+    ///
+    /// Fails at the first line with an unknown rule or severity,
+    /// after setting the severities of the previous lines.
+    pub fn configure(&mut self, text: &str) -> Result<(), InvalidLintConfigError> {
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+            let error = || InvalidLintConfigError(line.to_owned());
+            let (name, severity) = line.split_once('=').ok_or_else(error)?;
+            let severity = severity.trim().parse().map_err(|()| error())?;
+            if !self.set_severity(name.trim(), severity) {
+                return Err(error());
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs the rules that are not allowed on `movie`,
+    /// returning the lints in order of registration of their rules.
+    pub fn run(&self, movie: &LibTASMovie) -> Vec<Lint> {
+        self.rules()
+            .filter(|(_, severity)| *severity != Severity::Allow)
+            .flat_map(|(rule, severity)| {
+                rule.check(movie).into_iter().map(move |finding| Lint {
+                    rule: rule.name(),
+                    severity,
+                    frames: finding.frames,
+                    message: finding.message,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Groups increasing `frames` into ranges of consecutive frames.
+fn consecutive(frames: impl IntoIterator<Item = usize>) -> Vec<Range<usize>> {
+    let mut ranges: Vec<Range<usize>> = vec![];
+    for frame in frames {
+        match ranges.last_mut() {
+            Some(range) if range.end == frame => range.end += 1,
+            _ => ranges.push(frame..frame + 1),
+        }
+    }
+    ranges
+}
+
+/// Returns whether `input` has no key, mouse, controller, or unknown section.
+fn is_blank(input: &Input) -> bool {
+    input
+        .keyboard
+        .as_ref()
+        .is_none_or(|keyboard| keyboard.0.is_empty())
+        && input.mouse.is_none()
+        && input.controllers.is_empty()
+        && input.unknown.is_empty()
+}
+
+/// Reports the blank frames at the end of the movie, which only lengthen it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TrailingBlankFrames;
+
+impl LintRule for TrailingBlankFrames {
+    fn name(&self) -> &'static str {
+        "trailing-blank-frames"
+    }
+
+    fn check(&self, movie: &LibTASMovie) -> Vec<Finding> {
+        let inputs = &movie.inputs.0;
+        let blank = inputs.iter().rev().take_while(|input| is_blank(input));
+        let start = inputs.len() - blank.count();
+        if start == inputs.len() {
+            return vec![];
+        }
+        vec![Finding {
+            frames: start..inputs.len(),
+            message: format!("{} blank frame(s) at the end", inputs.len() - start),
+        }]
+    }
+}
+
+/// Reports frames holding opposite arrow keys, Left with Right or Up with Down,
+/// which games often resolve inconsistently.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OppositeDirections;
+
+impl LintRule for OppositeDirections {
+    fn name(&self) -> &'static str {
+        "opposite-directions"
+    }
+
+    fn check(&self, movie: &LibTASMovie) -> Vec<Finding> {
+        // Left with Right, and Up with Down
+        [(0xff51, 0xff53), (0xff52, 0xff54)]
+            .into_iter()
+            .flat_map(|(a, b)| {
+                let frames = movie.inputs.0.iter().enumerate().filter(|(_, input)| {
+                    input
+                        .keyboard
+                        .as_ref()
+                        .is_some_and(|keyboard| keyboard.0.contains(&a) && keyboard.0.contains(&b))
+                });
+                consecutive(frames.map(|(frame, _)| frame))
+                    .into_iter()
+                    .map(move |frames| Finding {
+                        frames,
+                        message: format!("{} and {} held together", keysym_name(a), keysym_name(b)),
+                    })
+            })
+            .collect()
+    }
+}
+
+/// Reports clicks at absolute coordinates outside of `bounds`, such as the game window.
+///
+/// Without bounds, clicks are expected on the screen recorded in the config,
+/// see [`GeneralConfig::screen_rect`](crate::config::GeneralConfig::screen_rect).
+/// Nothing is reported if the resolution is not recorded either.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ClickOutOfBounds {
+    /// The coordinates where clicks are expected, or `None` for the screen.
+    pub bounds: Option<Rect>,
+}
+
+impl LintRule for ClickOutOfBounds {
+    fn name(&self) -> &'static str {
+        "click-out-of-bounds"
+    }
+
+    fn check(&self, movie: &LibTASMovie) -> Vec<Finding> {
+        let Some(bounds) = self.bounds.or(movie.config.general.screen_rect()) else {
+            return vec![];
+        };
+        let frames = movie.inputs.0.iter().enumerate().filter(|(_, input)| {
+            input.mouse.is_some_and(|mouse| {
+                mouse.reference_mode == ReferenceMode::Absolute
+                    && (mouse.left_click
+                        || mouse.middle_click
+                        || mouse.right_click
+                        || mouse.button4
+                        || mouse.button5)
+                    && !bounds.contains(mouse.xpos, mouse.ypos)
+            })
+        });
+        consecutive(frames.map(|(frame, _)| frame))
+            .into_iter()
+            .map(|frames| Finding {
+                frames,
+                message: format!(
+                    "click outside of ({},{})-({},{})",
+                    bounds.left, bounds.top, bounds.right, bounds.bottom
+                ),
+            })
+            .collect()
+    }
+}
+
+/// Reports keys pressed for fewer than `min_frames` frames,
+/// which games polling inputs less often than every frame may miss.
+///
+/// Presses still held on the last frame are not reported.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ShortPress {
+    /// The minimum number of frames of a press.
+    pub min_frames: usize,
+}
+
+impl Default for ShortPress {
+    fn default() -> Self {
+        Self { min_frames: 2 }
+    }
+}
+
+impl LintRule for ShortPress {
+    fn name(&self) -> &'static str {
+        "short-press"
+    }
+
+    fn check(&self, movie: &LibTASMovie) -> Vec<Finding> {
+        let mut findings = vec![];
+        // held keys with the frame they were pressed
+        let mut held: Vec<(u32, usize)> = vec![];
+        for (frame, input) in movie.inputs.0.iter().enumerate() {
+            let keys = input
+                .keyboard
+                .as_ref()
+                .map_or(&[][..], |keyboard| &keyboard.0);
+            held.retain(|&(key, start)| {
+                if keys.contains(&key) {
+                    return true;
+                }
+                if frame - start < self.min_frames {
+                    findings.push(Finding {
+                        frames: start..frame,
+                        message: format!(
+                            "{} pressed for {} frame(s), fewer than {}",
+                            keysym_name(key),
+                            frame - start,
+                            self.min_frames
+                        ),
+                    });
+                }
+                false
+            });
+            for &key in keys {
+                if !held.iter().any(|&(held, _)| held == key) {
+                    held.push((key, frame));
+                }
+            }
+        }
+        findings.sort_by_key(|finding| finding.frames.start);
+        findings
+    }
+}
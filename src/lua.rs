@@ -0,0 +1,369 @@
+//! Module that runs Lua scripts written for libTAS against a movie, offline.
+//!
+//! This module is only available with the `lua` feature.
+//!
+//! Scripts use the same functions as [the Lua API of libTAS](https://clementgallet.github.io/libTAS/guides/lua/),
+//! operating on the frame being played:
+//! - `input.clear()`,
+//! - `input.setKey(keysym, state)` and `input.getKey(keysym)`,
+//! - `input.setMouseCoords(x, y, mode)` and `input.getMouseCoords()`,
+//!   where `mode` is 0 for absolute and 1 for relative coordinates,
+//! - `input.setMouseButtons(button, state)` and `input.getMouseButtons(button)`,
+//!   where `button` is 0 for the left button up to 4 for button 5,
+//! - `movie.currentFrame()`, `movie.frameCount()`, `movie.status()`, `movie.time()`,
+//!   `movie.rerecords()`, `movie.isDraw()`, `movie.getMarker()`, and `movie.setMarker(text)`,
+//! - `callback.onStartup(f)`, `callback.onInput(f)`, `callback.onFrame(f)`,
+//!   and `callback.onPaint(f)`.
+//!
+//! The script runs once on frame 0, then the startup callbacks run, then for each frame
+//! the input callbacks run before the frame and the frame callbacks after it.
+//! Paint callbacks never run, since nothing is drawn.
+//! Controller functions are not provided, since controller states are kept as written.
+//!
+//! # Example
+//! ```
+//! use libtas_movie::{lua::run_lua, testkit::sample_movie};
+//!
+//! let mut movie = sample_movie(10, 60);
+//! let script = r#"
+//!     callback.onInput(function()
+//!         input.setKey(0x20, movie.currentFrame() % 2)
+//!     end)
+//! "#;
+//! run_lua(&mut movie, script).unwrap();
+//! assert!(movie.inputs.0[5].keyboard.as_ref().unwrap().0.contains(&0x20));
+//! ```
+
+use core::cell::RefCell;
+use std::rc::Rc;
+
+use mlua::{Function, Lua, RegistryKey, Table, Value};
+
+use crate::{
+    editor::Marker,
+    inputs::{Input, KeyboardInput, MouseButton, MouseInput, ReferenceMode},
+    movie::LibTASMovie,
+};
+
+/// An error while running a Lua script, containing the message of Lua.
+#[derive(Debug)]
+pub struct LuaError(pub String);
+
+impl From<mlua::Error> for LuaError {
+    fn from(err: mlua::Error) -> Self {
+        Self(err.to_string())
+    }
+}
+
+/// The value of `movie.status()` during playback.
+const STATUS_PLAYING: i64 = 2;
+
+/// The state shared by the functions of a script.
+#[derive(Default)]
+struct State {
+    /// The movie being played.
+    movie: LibTASMovie,
+    /// The frame being played.
+    frame: usize,
+    /// The callbacks registered by the script, indexed by [`Callback`].
+    callbacks: [Vec<RegistryKey>; 3],
+}
+
+/// A kind of callback that runs offline.
+#[derive(Clone, Copy)]
+enum Callback {
+    Startup,
+    Input,
+    Frame,
+}
+
+type Shared = Rc<RefCell<State>>;
+
+/// Calls `f` with the input on the frame being played.
+fn with_input<T>(state: &Shared, f: impl FnOnce(&mut Input) -> T) -> mlua::Result<T> {
+    let mut state = state.borrow_mut();
+    let frame = state.frame;
+    match state.movie.inputs.0.get_mut(frame) {
+        Some(input) => Ok(f(input)),
+        None => Err(mlua::Error::RuntimeError(format!(
+            "no input on frame {frame}"
+        ))),
+    }
+}
+
+/// Returns whether a libTAS state argument is set, either a non-zero number or `true`.
+fn is_set(value: &Value) -> bool {
+    match *value {
+        Value::Boolean(state) => state,
+        Value::Integer(state) => state != 0,
+        Value::Number(state) => state != 0.0,
+        _ => false,
+    }
+}
+
+/// Returns the mouse button with libTAS index `index`.
+fn mouse_button(index: i64) -> mlua::Result<MouseButton> {
+    usize::try_from(index)
+        .ok()
+        .and_then(|index| MouseButton::ALL.get(index).copied())
+        .ok_or_else(|| mlua::Error::RuntimeError(format!("invalid mouse button {index}")))
+}
+
+/// Creates the `input` table.
+fn input_table<'lua>(lua: &'lua Lua, state: &Shared) -> mlua::Result<Table<'lua>> {
+    let table = lua.create_table()?;
+
+    let shared = Rc::clone(state);
+    table.set(
+        "clear",
+        lua.create_function(move |_, ()| {
+            with_input(&shared, |input| {
+                input.keyboard = None;
+                if let Some(mouse) = &mut input.mouse {
+                    for button in MouseButton::ALL {
+                        mouse.set_pressed(button, false);
+                    }
+                }
+            })
+        })?,
+    )?;
+
+    let shared = Rc::clone(state);
+    table.set(
+        "setKey",
+        lua.create_function(move |_, (keysym, state): (u32, Value)| {
+            let pressed = is_set(&state);
+            with_input(&shared, |input| {
+                let keyboard = input.keyboard.get_or_insert_with(KeyboardInput::default);
+                let held = keyboard.0.contains(&keysym);
+                if pressed && !held {
+                    keyboard.0.push(keysym);
+                } else if !pressed && held {
+                    keyboard.0.retain(|&key| key != keysym);
+                }
+            })
+        })?,
+    )?;
+
+    let shared = Rc::clone(state);
+    table.set(
+        "getKey",
+        lua.create_function(move |_, keysym: u32| {
+            with_input(&shared, |input| {
+                let held = input
+                    .keyboard
+                    .as_ref()
+                    .is_some_and(|keyboard| keyboard.0.contains(&keysym));
+                i64::from(held)
+            })
+        })?,
+    )?;
+
+    let shared = Rc::clone(state);
+    table.set(
+        "setMouseCoords",
+        lua.create_function(move |_, (x, y, mode): (i32, i32, Option<i64>)| {
+            let reference_mode = match mode.unwrap_or(0) {
+                0 => ReferenceMode::Absolute,
+                _ => ReferenceMode::Relative,
+            };
+            with_input(&shared, |input| {
+                let mouse = input.mouse.get_or_insert_with(MouseInput::default);
+                mouse.xpos = x;
+                mouse.ypos = y;
+                mouse.reference_mode = reference_mode;
+            })
+        })?,
+    )?;
+
+    let shared = Rc::clone(state);
+    table.set(
+        "getMouseCoords",
+        lua.create_function(move |_, ()| {
+            with_input(&shared, |input| {
+                let mouse = input.mouse.unwrap_or_default();
+                let mode = match mouse.reference_mode {
+                    ReferenceMode::Absolute => 0,
+                    ReferenceMode::Relative => 1,
+                };
+                (mouse.xpos, mouse.ypos, mode)
+            })
+        })?,
+    )?;
+
+    let shared = Rc::clone(state);
+    table.set(
+        "setMouseButtons",
+        lua.create_function(move |_, (button, state): (i64, Value)| {
+            let button = mouse_button(button)?;
+            let pressed = is_set(&state);
+            with_input(&shared, |input| {
+                let mouse = input.mouse.get_or_insert_with(MouseInput::default);
+                mouse.set_pressed(button, pressed);
+            })
+        })?,
+    )?;
+
+    let shared = Rc::clone(state);
+    table.set(
+        "getMouseButtons",
+        lua.create_function(move |_, button: i64| {
+            let button = mouse_button(button)?;
+            with_input(&shared, |input| {
+                i64::from(input.mouse.is_some_and(|mouse| mouse.is_pressed(button)))
+            })
+        })?,
+    )?;
+
+    Ok(table)
+}
+
+/// Creates the `movie` table.
+fn movie_table<'lua>(lua: &'lua Lua, state: &Shared) -> mlua::Result<Table<'lua>> {
+    let table = lua.create_table()?;
+
+    let shared = Rc::clone(state);
+    table.set(
+        "currentFrame",
+        lua.create_function(move |_, ()| Ok(shared.borrow().frame as i64))?,
+    )?;
+
+    let shared = Rc::clone(state);
+    table.set(
+        "frameCount",
+        lua.create_function(move |_, ()| Ok(shared.borrow().movie.inputs.0.len() as i64))?,
+    )?;
+
+    table.set("status", lua.create_function(|_, ()| Ok(STATUS_PLAYING))?)?;
+
+    let shared = Rc::clone(state);
+    table.set(
+        "time",
+        lua.create_function(move |_, ()| {
+            let state = shared.borrow();
+            let time = state.movie.timeline().time_of_frame(state.frame);
+            Ok((time.as_secs() as i64, i64::from(time.subsec_nanos())))
+        })?,
+    )?;
+
+    let shared = Rc::clone(state);
+    table.set(
+        "rerecords",
+        lua.create_function(move |_, ()| {
+            Ok(shared.borrow().movie.config.general.rerecord_count as i64)
+        })?,
+    )?;
+
+    table.set("isDraw", lua.create_function(|_, ()| Ok(true))?)?;
+
+    let shared = Rc::clone(state);
+    table.set(
+        "getMarker",
+        lua.create_function(move |_, ()| {
+            let state = shared.borrow();
+            let marker = state.movie.editor.marker_at(state.frame as u64);
+            Ok(marker.map_or_else(String::new, |marker| marker.text.clone()))
+        })?,
+    )?;
+
+    let shared = Rc::clone(state);
+    table.set(
+        "setMarker",
+        lua.create_function(move |_, text: Option<String>| {
+            let mut state = shared.borrow_mut();
+            let frame = state.frame as u64;
+            let markers = &mut state.movie.editor.markers;
+            markers.retain(|marker| marker.frame != frame);
+            if let Some(text) = text.filter(|text| !text.is_empty()) {
+                markers.push(Marker { frame, text });
+            }
+            Ok(())
+        })?,
+    )?;
+
+    Ok(table)
+}
+
+/// Creates the `callback` table.
+fn callback_table<'lua>(lua: &'lua Lua, state: &Shared) -> mlua::Result<Table<'lua>> {
+    let table = lua.create_table()?;
+    let kinds = [
+        ("onStartup", Some(Callback::Startup)),
+        ("onInput", Some(Callback::Input)),
+        ("onFrame", Some(Callback::Frame)),
+        ("onPaint", None),
+    ];
+    for (name, kind) in kinds {
+        let shared = Rc::clone(state);
+        table.set(
+            name,
+            lua.create_function(move |lua, f: Function<'_>| {
+                if let Some(kind) = kind {
+                    let key = lua.create_registry_value(f)?;
+                    shared.borrow_mut().callbacks[kind as usize].push(key);
+                }
+                Ok(())
+            })?,
+        )?;
+    }
+    Ok(table)
+}
+
+/// Calls the callbacks of kind `kind`, in order of registration.
+fn call(lua: &Lua, state: &Shared, kind: Callback) -> mlua::Result<()> {
+    let functions = state.borrow().callbacks[kind as usize]
+        .iter()
+        .map(|key| lua.registry_value::<Function<'_>>(key))
+        .collect::<mlua::Result<Vec<_>>>()?;
+    for function in functions {
+        function.call::<_, ()>(())?;
+    }
+    Ok(())
+}
+
+/// Runs `script` on the frames of `movie`, applying its edits to `movie`.
+///
+/// On error, the edits made before the error are kept.
+pub fn run_lua(movie: &mut LibTASMovie, script: &str) -> Result<(), LuaError> {
+    let frames = movie.inputs.0.len();
+    run_lua_for(movie, script, frames)
+}
+
+/// Runs `script` on `frames` frames of `movie`, applying its edits to `movie`.
+///
+/// Empty frames are appended to `movie` if it is shorter than `frames`, so that scripts can
+/// generate inputs. On error, the edits made before the error are kept.
+pub fn run_lua_for(movie: &mut LibTASMovie, script: &str, frames: usize) -> Result<(), LuaError> {
+    let len = movie.inputs.0.len();
+    if frames > len {
+        movie.splice_inputs(len..len, vec![Input::default(); frames - len]);
+    }
+
+    let state = Rc::new(RefCell::new(State {
+        movie: core::mem::take(movie),
+        ..State::default()
+    }));
+    let result = run(&state, script, frames);
+
+    let mut state = state.borrow_mut();
+    *movie = core::mem::take(&mut state.movie);
+    result.map_err(LuaError::from)
+}
+
+/// Runs `script` and its callbacks on `frames` frames.
+fn run(state: &Shared, script: &str, frames: usize) -> mlua::Result<()> {
+    let lua = Lua::new();
+    let globals = lua.globals();
+    globals.set("input", input_table(&lua, state)?)?;
+    globals.set("movie", movie_table(&lua, state)?)?;
+    globals.set("callback", callback_table(&lua, state)?)?;
+
+    lua.load(script).exec()?;
+    call(&lua, state, Callback::Startup)?;
+    for frame in 0..frames {
+        state.borrow_mut().frame = frame;
+        call(&lua, state, Callback::Input)?;
+        call(&lua, state, Callback::Frame)?;
+    }
+    Ok(())
+}
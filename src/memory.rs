@@ -0,0 +1,163 @@
+//! Module that estimates the heap memory used by movies.
+
+use core::mem::size_of;
+
+use crate::{
+    config::RawSection,
+    inputs::{ControllerInput, Input, Inputs},
+    intern::{FrameId, FrameTable, InternedInputs},
+    movie::{LibTASMovie, RawEntry},
+};
+
+/// The estimated heap memory used by a movie in bytes, by part.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoryFootprint {
+    /// The frames of the inputs, without the keys.
+    pub frames: usize,
+    /// The keys of the keyboard inputs.
+    pub keys: usize,
+    /// The original file and the movie as loaded, kept in fidelity mode.
+    pub raw: usize,
+    /// The config, annotations, editor information and custom sections.
+    pub other: usize,
+}
+
+impl MemoryFootprint {
+    /// Returns the estimated heap memory used in bytes.
+    pub fn total(&self) -> usize {
+        self.frames + self.keys + self.raw + self.other
+    }
+}
+
+/// Returns the heap memory used by the strings of `sections`.
+fn raw_sections_footprint(sections: &[RawSection]) -> usize {
+    size_of_val(sections)
+        + sections
+            .iter()
+            .map(|section| {
+                section.name.capacity()
+                    + section.entries.capacity() * size_of::<(String, String)>()
+                    + section
+                        .entries
+                        .iter()
+                        .map(|(key, value)| key.capacity() + value.capacity())
+                        .sum::<usize>()
+            })
+            .sum::<usize>()
+}
+
+/// Returns the heap memory used by the lines that are not input frames.
+fn other_lines_footprint(lines: &Vec<(usize, String)>) -> usize {
+    lines.capacity() * size_of::<(usize, String)>()
+        + lines.iter().map(|(_, line)| line.capacity()).sum::<usize>()
+}
+
+/// Returns the heap memory used by the contents of `inputs`, split into frames and keys.
+fn frames_footprint<'a>(inputs: impl IntoIterator<Item = &'a Input>) -> (usize, usize) {
+    let mut frames = 0;
+    let mut keys = 0;
+    for input in inputs {
+        if let Some(keyboard) = &input.keyboard {
+            keys += keyboard.0.capacity() * size_of::<u32>();
+        }
+        frames += input.controllers.capacity() * size_of::<ControllerInput>()
+            + input
+                .controllers
+                .iter()
+                .map(|controller| controller.state.capacity())
+                .sum::<usize>();
+    }
+    (frames, keys)
+}
+
+impl Inputs {
+    /// Returns the estimated heap memory used by the frames and the keys, in bytes.
+    fn footprint_parts(&self) -> (usize, usize) {
+        let (frames, keys) = frames_footprint(&self.0);
+        let frames =
+            frames + self.0.capacity() * size_of::<Input>() + other_lines_footprint(&self.1);
+        (frames, keys)
+    }
+
+    /// Returns the estimated heap memory used by the inputs in bytes.
+    pub fn memory_footprint(&self) -> usize {
+        let (frames, keys) = self.footprint_parts();
+        frames + keys
+    }
+}
+
+impl FrameTable {
+    /// Returns the estimated heap memory used by the table in bytes.
+    ///
+    /// Each distinct input is stored twice, in the table and in its index.
+    pub fn memory_footprint(&self) -> usize {
+        let (frames, keys) = frames_footprint(self.inputs());
+        2 * (frames + keys + size_of_val(self.inputs())) + self.len() * size_of::<FrameId>()
+    }
+}
+
+impl InternedInputs {
+    /// Returns the estimated heap memory used by the frames in bytes, without their table.
+    pub fn memory_footprint(&self) -> usize {
+        self.0.capacity() * size_of::<FrameId>() + other_lines_footprint(&self.1)
+    }
+}
+
+impl LibTASMovie {
+    /// Returns the estimated heap memory used by the movie.
+    ///
+    /// Custom sections are estimated by the size of their serialized contents.
+    pub fn memory_footprint(&self) -> MemoryFootprint {
+        let (frames, keys) = self.inputs.footprint_parts();
+
+        let raw = self.raw.as_ref().map_or(0, |raw| {
+            raw.archive.capacity()
+                + raw.entries.capacity() * size_of::<RawEntry>()
+                + raw
+                    .entries
+                    .iter()
+                    .map(|entry| entry.name.capacity() + entry.data.capacity())
+                    .sum::<usize>()
+        }) + self.raw_inputs.as_ref().map_or(0, |raw| raw.text().len());
+
+        let config = &self.config;
+        let general = &config.general;
+        let editor = &self.editor;
+        let other = general.authors.capacity()
+            + general.game_name.capacity()
+            + raw_sections_footprint(&config.extra)
+            + self.annotations.capacity()
+            + editor
+                .markers
+                .iter()
+                .map(|marker| size_of_val(marker) + marker.text.capacity())
+                .sum::<usize>()
+            + editor
+                .columns
+                .iter()
+                .map(|column| {
+                    size_of_val(column)
+                        + column.input.capacity()
+                        + column.name.capacity()
+                        + column
+                            .extra
+                            .iter()
+                            .map(|(key, value)| key.capacity() + value.capacity())
+                            .sum::<usize>()
+                })
+                .sum::<usize>()
+            + raw_sections_footprint(&editor.extra)
+            + self
+                .sections
+                .entries()
+                .map(|(_, data)| data.len())
+                .sum::<usize>();
+
+        MemoryFootprint {
+            frames,
+            keys,
+            raw,
+            other,
+        }
+    }
+}
@@ -1,35 +1,302 @@
 //! Module that defines a movie.
 
-use core::str::FromStr as _;
+use core::{
+    hash::{Hash as _, Hasher as _},
+    ops::Range,
+    str::{FromStr as _, Utf8Error},
+};
 
 use crate::{
-    config::{Config, InvalidConfigError},
+    config::{Config, ConfigRef, InvalidConfigError},
+    editor::{Editor, InvalidEditorError},
     inputs::{Inputs, InvalidInputsError},
+    integrity::{Checksums, IntegrityError, InvalidChecksumsError},
+    section::{InvalidSectionError, SectionRegistry, Sections},
+    warning::{Warning, Warnings},
+};
+use std::{
+    borrow::Cow,
+    fs::File,
+    hash::DefaultHasher,
+    io::{Cursor, Read, Write as _},
+    path::{Path, PathBuf},
 };
-use std::{fs::File, io::Read as _, path::Path};
 
-use flate2::{Compression, read::GzDecoder, write::GzEncoder};
-use tar::{Archive, Builder, Header};
+use flate2::{Compression, bufread, read::MultiGzDecoder, write::GzEncoder};
+use tar::{Archive, Builder, Entry, Header};
 
 /// An error while loading a movie file.
 #[derive(Debug)]
 pub enum LoadError {
     /// An error occurred while opening a file.
     FileError(std::io::Error),
-    /// The file is not a `tar.gz` archive.
-    InvalidArchive,
-    /// An extra file is in the archive.
-    ExtraEntry,
-    /// A file is missing in the archive.
-    InsufficientEntry,
+    /// The file is not a `tar.gz` archive, or is corrupted.
+    ///
+    /// The kind of `source` tells the cause apart, such as
+    /// [`UnexpectedEof`](std::io::ErrorKind::UnexpectedEof) for a truncated file,
+    /// or [`InvalidData`](std::io::ErrorKind::InvalidData) for a file that is not gzip
+    /// or whose checksum is wrong.
+    InvalidArchive {
+        /// The index of the entry that failed to be read.
+        entry: usize,
+        /// The gzip or tar error.
+        source: std::io::Error,
+    },
+    /// A file that is neither a movie file nor a registered custom section is in the archive.
+    UnexpectedEntry {
+        /// The path of the file in the archive.
+        path: PathBuf,
+    },
+    /// A movie file is missing in the archive.
+    MissingEntry {
+        /// The name of the missing file.
+        name: &'static str,
+    },
+    /// A file in the archive is incorrect.
+    InvalidEntry {
+        /// The name of the file.
+        name: String,
+        /// Why the file is incorrect.
+        source: EntryError,
+    },
+    /// The entries do not match the integrity manifest.
+    Integrity(Vec<IntegrityError>),
+    /// The movie has warnings while loading in strict mode.
+    Strict(Warnings),
+}
+
+/// An error in the contents of a file in a movie archive.
+#[derive(Debug)]
+pub enum EntryError {
+    /// The file is not valid UTF-8.
+    Utf8(Utf8Error),
     /// `Config` is incorrect.
-    InvalidConfig(InvalidConfigError),
+    Config(InvalidConfigError),
     /// `Inputs` is incorrect.
-    InvalidInputs(InvalidInputsError),
+    Inputs(InvalidInputsError),
+    /// `Editor` is incorrect.
+    Editor(InvalidEditorError),
+    /// A registered custom section is incorrect.
+    Section(InvalidSectionError),
+    /// The integrity manifest is incorrect.
+    Checksums(InvalidChecksumsError),
 }
 
-/// A libTAS movie.
+impl LoadError {
+    /// Returns an [`LoadError::InvalidArchive`] while reading the entry `entry`.
+    fn invalid_archive(entry: usize, source: std::io::Error) -> Self {
+        Self::InvalidArchive { entry, source }
+    }
+
+    /// Returns an [`LoadError::InvalidEntry`] of the file `name`.
+    pub(crate) fn invalid_entry(name: &str, source: EntryError) -> Self {
+        Self::InvalidEntry {
+            name: name.to_owned(),
+            source,
+        }
+    }
+}
+
+/// The files of a movie archive, other than custom sections.
+const MOVIE_ENTRIES: [&str; 4] = ["config.ini", "inputs", "annotations.txt", "editor.ini"];
+
+/// An entry of a movie file, retained when loading in fidelity mode.
+#[derive(Clone, Debug, Default)]
+pub struct RawEntry {
+    /// The file name of the entry.
+    pub name: String,
+    /// The original contents of the entry.
+    pub data: Vec<u8>,
+    /// The permissions of the entry.
+    pub mode: u32,
+    /// The modification time of the entry, in seconds since the Unix epoch.
+    pub mtime: u64,
+    /// The owner user id of the entry.
+    pub uid: u64,
+    /// The owner group id of the entry.
+    pub gid: u64,
+    /// The owner user name of the entry.
+    pub username: Option<String>,
+    /// The owner group name of the entry.
+    pub groupname: Option<String>,
+}
+
+/// The original bytes of a movie file, retained when loading in fidelity mode.
+///
+/// See [`LoadOptions::fidelity`].
+#[derive(Clone, Debug, Default)]
+pub struct RawMovie {
+    /// The whole `.ltm` file.
+    pub archive: Vec<u8>,
+    /// The entries in archive order.
+    pub entries: Vec<RawEntry>,
+    /// The hashes of the movie as loaded, to tell which entries are modified
+    /// without parsing them again.
+    loaded: Option<LoadedHashes>,
+}
+
+/// The hashes of the parts of a movie as loaded, compared with the current movie when saving.
+#[derive(Clone, Copy, Debug)]
+struct LoadedHashes {
+    config: u64,
+    inputs: u64,
+    editor: u64,
+}
+
+impl LoadedHashes {
+    /// Returns the hashes of the parts of `movie`.
+    fn of(movie: &LibTASMovie) -> Self {
+        Self {
+            config: text_hash(&movie.config.to_string()),
+            inputs: inputs_hash(&movie.inputs),
+            editor: text_hash(&movie.editor.to_string()),
+        }
+    }
+}
+
+impl RawMovie {
+    /// Returns the range of the gzip member of each entry in the archive,
+    /// or `None` unless each entry is compressed in its own member, as saved by this crate.
+    fn entry_members(&self) -> Option<Vec<Range<usize>>> {
+        let mut members = Vec::with_capacity(self.entries.len());
+        let mut start = 0;
+        for entry in &self.entries {
+            // the header and the contents padded to blocks
+            let size = 512 + entry.data.len().next_multiple_of(512) as u64;
+            let mut decoder = bufread::GzDecoder::new(self.archive.get(start..)?);
+            let read = std::io::copy(&mut (&mut decoder).take(size + 1), &mut std::io::sink());
+            if read.ok()? != size {
+                return None;
+            }
+            let end = self.archive.len() - decoder.get_ref().len();
+            members.push(start..end);
+            start = end;
+        }
+        Some(members)
+    }
+
+    /// Returns the original contents of the entry `name`.
+    pub fn entry(&self, name: &str) -> Option<&[u8]> {
+        self.raw_entry(name).map(|entry| entry.data.as_slice())
+    }
+
+    /// Returns the entry `name` with its header.
+    pub fn raw_entry(&self, name: &str) -> Option<&RawEntry> {
+        self.entries.iter().find(|entry| entry.name == name)
+    }
+
+    /// Returns the metadata of the movie, borrowing its strings from the entries.
+    ///
+    /// Fails if `config.ini` or `annotations.txt` is missing or invalid.
+    pub fn metadata(&self) -> Result<MovieMetadata<'_>, LoadError> {
+        let text = |name: &'static str| {
+            let Some(data) = self.entry(name) else {
+                return Err(LoadError::MissingEntry { name });
+            };
+            core::str::from_utf8(data)
+                .map_err(|err| LoadError::invalid_entry(name, EntryError::Utf8(err)))
+        };
+        let config = ConfigRef::parse(text("config.ini")?)
+            .map_err(|err| LoadError::invalid_entry("config.ini", EntryError::Config(err)))?;
+        Ok(MovieMetadata {
+            config,
+            annotations: Cow::Borrowed(text("annotations.txt")?),
+        })
+    }
+}
+
+/// The decompressed text of the `inputs` file, retained with [`LoadOptions::raw_inputs`].
+///
+/// Unlike [`RawMovie`], only the inputs are retained, without another copy of the parsed form:
+/// edits are noticed by a hash of the inputs as loaded.
+#[derive(Clone, Debug, Default)]
+pub struct RawInputs {
+    /// The original text.
+    text: String,
+    /// The hash of the inputs parsed from `text`.
+    hash: u64,
+}
+
+/// A line where the `inputs` file differs from its inputs written again.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineDifference {
+    /// The index of the line.
+    pub line: usize,
+    /// The original line, or `None` if the original text ends before it.
+    pub original: Option<String>,
+    /// The line written again, or `None` if the written text ends before it.
+    pub serialized: Option<String>,
+}
+
+/// Returns the hash of `inputs`, to tell whether they were edited.
+fn inputs_hash(inputs: &Inputs) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    inputs.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Returns the hash of `text`, the serialized form of a part of a movie.
+fn text_hash(text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl RawInputs {
+    /// Retains `text`, the text that `inputs` were parsed from.
+    pub fn new(text: String, inputs: &Inputs) -> Self {
+        Self {
+            text,
+            hash: inputs_hash(inputs),
+        }
+    }
+
+    /// Returns the original text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns whether `inputs` differ from the inputs parsed from the original text.
+    pub fn is_modified(&self, inputs: &Inputs) -> bool {
+        inputs_hash(inputs) != self.hash
+    }
+
+    /// Returns the first line where the original text differs from its inputs written again,
+    /// or `None` if they are identical or the text cannot be parsed.
+    ///
+    /// This tells which parts of the format are not written back exactly,
+    /// regardless of later edits.
+    pub fn roundtrip_difference(&self) -> Option<LineDifference> {
+        let (inputs, _) = Inputs::parse_lenient(&self.text).ok()?;
+        let serialized = inputs.to_string();
+        let (mut original, mut serialized) = (self.text.split('\n'), serialized.split('\n'));
+        (0..)
+            .map(|_| (original.next(), serialized.next()))
+            .take_while(|lines| *lines != (None, None))
+            .enumerate()
+            .find(|(_, (original, serialized))| original != serialized)
+            .map(|(line, (original, serialized))| LineDifference {
+                line,
+                original: original.map(str::to_owned),
+                serialized: serialized.map(str::to_owned),
+            })
+    }
+}
+
+/// The metadata of a movie, borrowed from a [`RawMovie`].
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MovieMetadata<'a> {
+    /// The metadata strings of `config.ini`.
+    pub config: ConfigRef<'a>,
+    /// Annotations corresponding to `annotations.txt`.
+    pub annotations: Cow<'a, str>,
+}
+
+/// A libTAS movie.
+///
+/// Two movies are equal if their contents are equal,
+/// regardless of [`LibTASMovie::raw`] and [`LibTASMovie::raw_inputs`].
+#[derive(Clone, Debug, Default)]
 pub struct LibTASMovie {
     /// Config corresponding to `config.ini`.
     pub config: Config,
@@ -37,10 +304,32 @@ pub struct LibTASMovie {
     pub inputs: Inputs,
     /// Annotations corresponding to `annotations.txt`.
     pub annotations: String,
-    /// TAS editor information corresponding to `editor.ini` (TODO).
-    pub editor: String,
+    /// TAS editor information corresponding to `editor.ini`.
+    pub editor: Editor,
+    /// Custom sections corresponding to other files in the archive.
+    pub sections: Sections,
+    /// The frame of the power-on movie at which the movie begins, if it begins from a savestate.
+    /// It is not stored in the movie file.
+    pub anchor: Option<u64>,
+    /// The original bytes of the movie file, if loaded in fidelity mode.
+    pub raw: Option<RawMovie>,
+    /// The original text of `inputs`, if loaded with [`LoadOptions::raw_inputs`].
+    pub raw_inputs: Option<RawInputs>,
+}
+
+impl PartialEq for LibTASMovie {
+    fn eq(&self, other: &Self) -> bool {
+        self.config == other.config
+            && self.inputs == other.inputs
+            && self.annotations == other.annotations
+            && self.editor == other.editor
+            && self.sections == other.sections
+            && self.anchor == other.anchor
+    }
 }
 
+impl Eq for LibTASMovie {}
+
 impl LibTASMovie {
     pub(crate) fn load_config(&mut self, string: &str) -> Result<(), InvalidConfigError> {
         match Config::from_str(string) {
@@ -52,122 +341,581 @@ impl LibTASMovie {
         }
     }
 
-    pub(crate) fn load_inputs(&mut self, string: &str) -> Result<(), InvalidInputsError> {
+    /// Loads the inputs, returning the unknown sections with their frame.
+    ///
+    /// Inputs are parsed strictly: use [`Inputs::parse_lenient`] to recover malformed inputs.
+    pub(crate) fn load_inputs(
+        &mut self,
+        string: &str,
+    ) -> Result<Vec<(usize, String)>, InvalidInputsError> {
         match Inputs::from_str(string) {
             Ok(inputs) => {
+                let unknown = inputs
+                    .0
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(frame, input)| {
+                        input
+                            .unknown
+                            .iter()
+                            .map(move |section| (frame, section.clone()))
+                    })
+                    .collect();
                 self.inputs = inputs;
-                Ok(())
+                Ok(unknown)
             }
             Err(err) => Err(err),
         }
     }
 
+    /// Returns whether the inputs differ from the retained [`LibTASMovie::raw_inputs`].
+    ///
+    /// The inputs are modified if their original text is not retained.
+    pub fn is_inputs_modified(&self) -> bool {
+        self.raw_inputs
+            .as_ref()
+            .is_none_or(|raw| raw.is_modified(&self.inputs))
+    }
+
+    /// Returns the original text of `inputs` if the inputs were not edited since loading.
+    /// Otherwise, drops the retained text, which is outdated, and returns `None`.
+    pub fn original_inputs(&mut self) -> Option<&str> {
+        if self.is_inputs_modified() {
+            self.raw_inputs = None;
+        }
+        self.raw_inputs.as_ref().map(RawInputs::text)
+    }
+
     pub(crate) fn load_annotations(&mut self, string: &str) {
         string.clone_into(&mut self.annotations);
     }
 
-    pub(crate) fn load_editor(&mut self, string: &str) {
-        string.clone_into(&mut self.editor);
+    pub(crate) fn load_editor(&mut self, string: &str) -> Result<(), InvalidEditorError> {
+        match Editor::from_str(string) {
+            Ok(editor) => {
+                self.editor = editor;
+                Ok(())
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Returns whether the entry `name` differs from the file it was loaded from.
+    ///
+    /// All entries are modified if the movie was not loaded in fidelity mode.
+    /// This compares hashes of the current movie and of the movie as loaded,
+    /// so that the original bytes of unmodified entries are written back when saving.
+    pub fn is_entry_modified(&self, name: &str) -> bool {
+        let Some(raw) = &self.raw else {
+            return true;
+        };
+        let (Some(loaded), Some(data)) = (&raw.loaded, raw.entry(name)) else {
+            return true;
+        };
+        match name {
+            "config.ini" => loaded.config != text_hash(&self.config.to_string()),
+            "inputs" => loaded.inputs != inputs_hash(&self.inputs),
+            "annotations.txt" => data != self.annotations.as_bytes(),
+            "editor.ini" => loaded.editor != text_hash(&self.editor.to_string()),
+            _ => !self
+                .sections
+                .entries()
+                .any(|(entry, current)| entry == name && current == data),
+        }
+    }
+
+    /// Serializes the entry `name`, which is a movie file or a section.
+    fn serialize_entry(&self, name: &str) -> Vec<u8> {
+        match name {
+            "config.ini" => self.config.to_string().into_bytes(),
+            "inputs" => match &self.raw_inputs {
+                Some(raw) if !raw.is_modified(&self.inputs) => raw.text.clone().into_bytes(),
+                _ => self.inputs.to_string().into_bytes(),
+            },
+            "annotations.txt" => self.annotations.as_bytes().to_vec(),
+            "editor.ini" => self.editor.to_string().into_bytes(),
+            _ => self
+                .sections
+                .entries()
+                .find_map(|(entry, data)| (entry == name).then_some(data))
+                .unwrap_or_default(),
+        }
     }
 
     /// Saves the TAS into a byte sequence representing the `.ltm` file.
+    ///
+    /// If the movie was loaded in fidelity mode, unchanged entries are written
+    /// with their original bytes without being serialized again,
+    /// and an entirely unchanged movie is written exactly as the original file.
+    /// Each entry is compressed in its own gzip member, so that the members of
+    /// unchanged entries are reused when a movie saved this way is saved again.
+    /// Likewise, unchanged inputs retained in [`LibTASMovie::raw_inputs`] are written as is.
     pub fn compress(&self) -> std::io::Result<Vec<u8>> {
-        let bytes = vec![];
-        let enc = GzEncoder::new(bytes, Compression::default());
-        let mut tar = Builder::new(enc);
+        SaveOptions::default().compress(self)
+    }
+
+    /// Saves the TAS into `path`.
+    pub fn save_to_path<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        SaveOptions::default().save_to_path(self, path)
+    }
+}
+
+/// A gzip member of a saved archive.
+enum Member<'a> {
+    /// The original compressed bytes of an unmodified entry.
+    Reused(&'a [u8]),
+    /// A range of the tar to compress.
+    Compressed(Range<usize>),
+}
+
+/// Options for saving a movie.
+#[derive(Clone, Debug, Default)]
+pub struct SaveOptions {
+    /// Whether to write the integrity manifest [`Checksums`] of the other entries.
+    pub checksums: bool,
+}
+
+impl SaveOptions {
+    /// Saves `movie` with these options into a byte sequence representing the `.ltm` file.
+    ///
+    /// See [`LibTASMovie::compress`].
+    pub fn compress(&self, movie: &LibTASMovie) -> std::io::Result<Vec<u8>> {
+        let mut names: Vec<&str> = MOVIE_ENTRIES.to_vec();
+        names.extend(movie.sections.entries().map(|(name, _)| name));
+
+        if let Some(raw) = &movie.raw {
+            if !self.checksums
+                && raw.entries.len() == names.len()
+                && names.iter().all(|name| !movie.is_entry_modified(name))
+            {
+                return Ok(raw.archive.clone());
+            }
+
+            // keep the original order, followed by entries that were not in the original archive
+            let mut ordered: Vec<_> = raw
+                .entries
+                .iter()
+                .filter_map(|entry| names.iter().find(|name| **name == entry.name).copied())
+                .collect();
+            ordered.extend(names.iter().filter(|name| raw.entry(name).is_none()));
+            names = ordered;
+        }
 
+        // reuse the original bytes of unmodified entries
+        let entries: Vec<(&str, Cow<'_, [u8]>)> = names
+            .into_iter()
+            .map(
+                |name| match movie.raw.as_ref().and_then(|raw| raw.entry(name)) {
+                    Some(data) if !movie.is_entry_modified(name) => (name, Cow::Borrowed(data)),
+                    _ => (name, Cow::Owned(movie.serialize_entry(name))),
+                },
+            )
+            .collect();
+        let mut entries: Vec<(&str, &[u8])> = entries
+            .iter()
+            .map(|(name, data)| (*name, &**data))
+            .collect();
+
+        let checksums = self
+            .checksums
+            .then(|| Checksums::of_entries(entries.iter().copied()).to_string());
+        if let Some(checksums) = &checksums {
+            entries.push((Checksums::NAME, checksums.as_bytes()));
+        }
+
+        // and their original compressed bytes, if the archive has a gzip member per entry
+        let original_members = movie
+            .raw
+            .as_ref()
+            .and_then(RawMovie::entry_members)
+            .unwrap_or_default();
+        let reused_member = |name: &str| {
+            let raw = movie.raw.as_ref()?;
+            let index = raw.entries.iter().position(|entry| entry.name == name)?;
+            let member = original_members.get(index)?;
+            (!movie.is_entry_modified(name)).then(|| &raw.archive[member.clone()])
+        };
+
+        // the entries that are compressed again are written into a tar
+        // whose slices become gzip members
+        let mut tar = Builder::new(vec![]);
+        let mut members = vec![];
         let mut header = Header::new_gnu();
-        for (file_name, data) in [
-            ("config.ini", &self.config.to_string()),
-            ("inputs", &self.inputs.to_string()),
-            ("annotations.txt", &self.annotations),
-            ("editor.ini", &self.editor),
-        ] {
+        for (file_name, data) in entries {
+            if let Some(member) = reused_member(file_name) {
+                members.push(Member::Reused(member));
+                continue;
+            }
+            // reuse the header of the original entry
+            let raw_entry = movie.raw.as_ref().and_then(|raw| raw.raw_entry(file_name));
             header.set_path(file_name)?;
             header.set_size(data.len() as u64);
-            header.set_mode(0o644);
+            header.set_mode(raw_entry.map_or(0o644, |entry| entry.mode));
+            header.set_mtime(raw_entry.map_or(0, |entry| entry.mtime));
+            header.set_uid(raw_entry.map_or(0, |entry| entry.uid));
+            header.set_gid(raw_entry.map_or(0, |entry| entry.gid));
+            header.set_username(
+                raw_entry
+                    .and_then(|entry| entry.username.as_deref())
+                    .unwrap_or_default(),
+            )?;
+            header.set_groupname(
+                raw_entry
+                    .and_then(|entry| entry.groupname.as_deref())
+                    .unwrap_or_default(),
+            )?;
             header.set_cksum();
-            tar.append(&header, data.as_bytes())?;
+            let start = tar.get_ref().len();
+            tar.append(&header, data)?;
+            members.push(Member::Compressed(start..tar.get_ref().len()));
         }
+        let end = tar.get_ref().len();
+        let tar = tar.into_inner()?;
+        members.push(Member::Compressed(end..tar.len()));
 
-        let enc = tar.into_inner()?;
-        enc.finish()
+        let mut bytes = vec![];
+        for member in members {
+            match member {
+                Member::Reused(member) => bytes.extend_from_slice(member),
+                Member::Compressed(range) => {
+                    let mut enc = GzEncoder::new(bytes, Compression::default());
+                    enc.write_all(&tar[range])?;
+                    bytes = enc.finish()?;
+                }
+            }
+        }
+        Ok(bytes)
     }
 
-    /// Saves the TAS into `path`.
-    pub fn save_to_path<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
-        let data = self.compress()?;
+    /// Saves `movie` with these options into `path`.
+    pub fn save_to_path<P: AsRef<Path>>(
+        &self,
+        movie: &LibTASMovie,
+        path: P,
+    ) -> std::io::Result<()> {
+        let data = self.compress(movie)?;
         std::fs::write(path, data)
     }
 }
 
-/// Loads a movie file in `path`.
-///
-/// # Example
-/// ```ignore
-/// use libtas_movie::load::load_movie;
-/// let movie = load_movie("path/to/tas.ltm").unwrap();
-/// ```
-pub fn load_movie<P: AsRef<Path>>(path: P) -> Result<LibTASMovie, LoadError> {
-    // open the movie file as .tar.gz
-    let mut archive = match File::open(path) {
-        Ok(file) => Archive::new(GzDecoder::new(file)),
-        Err(err) => {
-            return Err(LoadError::FileError(err));
+/// Options for loading a movie.
+#[derive(Clone, Debug, Default)]
+pub struct LoadOptions {
+    /// Whether to retain the original bytes of the file in [`LibTASMovie::raw`],
+    /// so that loading and immediately saving a movie produces an identical file.
+    pub fidelity: bool,
+    /// Custom sections to parse into [`LibTASMovie::sections`], none by default.
+    /// Other unknown files in the archive are rejected with [`LoadError::UnexpectedEntry`].
+    pub sections: SectionRegistry,
+    /// Whether to check the entries against the integrity manifest [`Checksums`] if present,
+    /// failing with [`LoadError::Integrity`] if they differ.
+    pub verify_integrity: bool,
+    /// Whether to fail with [`LoadError::Strict`] if there is any [`Warning`],
+    /// such as unknown config keys, unknown input sections, and unmodeled editor keys.
+    ///
+    /// This is useful in CI to notice format changes of libTAS that this crate does not handle.
+    pub strict: bool,
+    /// Whether to retain the decompressed text of `inputs` in [`LibTASMovie::raw_inputs`],
+    /// so that unedited inputs are saved byte for byte, at a lower cost than [`fidelity`](Self::fidelity).
+    pub raw_inputs: bool,
+}
+
+impl LoadOptions {
+    /// Loads a movie file in `path` with these options.
+    pub fn load<P: AsRef<Path>>(&self, path: P) -> Result<LibTASMovie, LoadError> {
+        match File::open(path) {
+            Ok(file) => self.load_from_reader(file),
+            Err(err) => Err(LoadError::FileError(err)),
         }
-    };
+    }
 
-    let entries = match archive.entries() {
-        Ok(entries) => entries,
-        Err(err) => {
-            return Err(LoadError::FileError(err));
+    /// Loads a movie from `reader` with these options.
+    pub fn load_from_reader<R: Read>(&self, reader: R) -> Result<LibTASMovie, LoadError> {
+        self.load_from_reader_with_warnings(reader)
+            .map(|(movie, _)| movie)
+    }
+
+    /// Loads a movie file in `path` with these options, also returning the warnings.
+    pub fn load_with_warnings<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> Result<(LibTASMovie, Warnings), LoadError> {
+        match File::open(path) {
+            Ok(file) => self.load_from_reader_with_warnings(file),
+            Err(err) => Err(LoadError::FileError(err)),
+        }
+    }
+
+    /// Loads a movie from `reader` with these options, also returning the warnings.
+    pub fn load_from_reader_with_warnings<R: Read>(
+        &self,
+        mut reader: R,
+    ) -> Result<(LibTASMovie, Warnings), LoadError> {
+        let mut warnings = Warnings::default();
+        if !self.fidelity {
+            let movie = self.load_archive(reader, None, &mut warnings)?;
+            return Ok((movie, warnings));
         }
-    };
 
-    let mut movie = LibTASMovie::default();
-    let mut loaded = [false, false, false, false];
-    for entry in entries {
-        let Ok(mut entry) = entry else {
-            return Err(LoadError::InvalidArchive);
+        let mut archive = vec![];
+        if let Err(err) = reader.read_to_end(&mut archive) {
+            return Err(LoadError::FileError(err));
+        }
+        let mut raw = RawMovie {
+            archive,
+            entries: vec![],
+            loaded: None,
         };
+        let mut movie = self.load_archive(
+            raw.archive.as_slice(),
+            Some(&mut raw.entries),
+            &mut warnings,
+        )?;
+        raw.loaded = Some(LoadedHashes::of(&movie));
+        movie.raw = Some(raw);
+        Ok((movie, warnings))
+    }
+
+    /// Loads a movie from `reader`, pushing each entry with its header into `raw_entries` if given.
+    fn load_archive<R: Read>(
+        &self,
+        reader: R,
+        mut raw_entries: Option<&mut Vec<RawEntry>>,
+        warnings: &mut Warnings,
+    ) -> Result<LibTASMovie, LoadError> {
+        // open the movie file as .tar.gz
+        let mut archive = open_archive(reader)?;
 
-        let mut string = String::new();
-        let Ok(_) = entry.read_to_string(&mut string) else {
-            return Err(LoadError::InvalidArchive);
+        let entries = match archive.entries() {
+            Ok(entries) => entries,
+            Err(err) => {
+                return Err(LoadError::FileError(err));
+            }
         };
 
-        match entry.path() {
-            Ok(path) if path.as_os_str() == "config.ini" => {
-                loaded[0] = true;
-                if let Err(err) = movie.load_config(&string) {
-                    return Err(LoadError::InvalidConfig(err));
-                }
+        let mut movie = LibTASMovie::default();
+        let mut loaded = [false, false, false, false];
+        let mut checksums = None;
+        let mut actual = Checksums::default();
+        let mut names: Vec<String> = vec![];
+        for (entry_index, entry) in entries.enumerate() {
+            let entry = entry.map_err(|err| LoadError::invalid_archive(entry_index, err))?;
+            let (path, raw) = read_entry(entry, entry_index)?;
+            let RawEntry { name, data, .. } = &raw;
+            if names.contains(name) {
+                warnings.0.push(Warning::DuplicateEntry(name.clone()));
+            } else {
+                names.push(name.clone());
             }
-            Ok(path) if path.as_os_str() == "inputs" => {
-                loaded[1] = true;
-                if let Err(err) = movie.load_inputs(&string) {
-                    return Err(LoadError::InvalidInputs(err));
+
+            if name == Checksums::NAME {
+                let string = core::str::from_utf8(data)
+                    .map_err(|err| LoadError::invalid_entry(name, EntryError::Utf8(err)))?;
+                match Checksums::from_str(string) {
+                    Ok(parsed) => checksums = Some(parsed),
+                    Err(err) => {
+                        return Err(LoadError::invalid_entry(name, EntryError::Checksums(err)));
+                    }
                 }
+                continue;
             }
-            Ok(path) if path.as_os_str() == "annotations.txt" => {
-                loaded[2] = true;
-                movie.load_annotations(&string);
+            if self.verify_integrity {
+                actual.push(name, data);
             }
-            Ok(path) if path.as_os_str() == "editor.ini" => {
-                loaded[3] = true;
-                movie.load_editor(&string);
+
+            let index = MOVIE_ENTRIES.iter().position(|entry| *entry == name);
+            match index {
+                Some(index) => {
+                    let string = core::str::from_utf8(data)
+                        .map_err(|err| LoadError::invalid_entry(name, EntryError::Utf8(err)))?;
+                    loaded[index] = true;
+                    let result = match index {
+                        0 => movie.load_config(string).map_err(EntryError::Config),
+                        1 => movie
+                            .load_inputs(string)
+                            .map(|ignored| {
+                                warnings
+                                    .0
+                                    .extend(ignored.into_iter().map(|(frame, section)| {
+                                        Warning::IgnoredInputSection { frame, section }
+                                    }));
+                                if self.raw_inputs {
+                                    movie.raw_inputs =
+                                        Some(RawInputs::new(string.to_owned(), &movie.inputs));
+                                }
+                            })
+                            .map_err(EntryError::Inputs),
+                        2 => {
+                            movie.load_annotations(string);
+                            Ok(())
+                        }
+                        _ => movie.load_editor(string).map_err(EntryError::Editor),
+                    };
+                    if let Err(err) = result {
+                        return Err(LoadError::invalid_entry(name, err));
+                    }
+                }
+                None => match movie.sections.load(&self.sections, name, data) {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        return Err(LoadError::UnexpectedEntry { path });
+                    }
+                    Err(err) => {
+                        return Err(LoadError::invalid_entry(name, EntryError::Section(err)));
+                    }
+                },
             }
-            Ok(_path) => {
-                return Err(LoadError::ExtraEntry);
+
+            if let Some(raw_entries) = raw_entries.as_deref_mut() {
+                raw_entries.push(raw);
             }
-            _ => {
-                return Err(LoadError::InvalidArchive);
+        }
+        if let Some(index) = loaded.iter().position(|loaded| !loaded) {
+            return Err(LoadError::MissingEntry {
+                name: MOVIE_ENTRIES[index],
+            });
+        }
+        if let Some(checksums) = checksums.filter(|_| self.verify_integrity) {
+            let errors = checksums.verify(&actual);
+            if !errors.is_empty() {
+                return Err(LoadError::Integrity(errors));
             }
         }
+        warnings.check_movie(&movie);
+        if self.strict && !warnings.is_empty() {
+            return Err(LoadError::Strict(core::mem::take(warnings)));
+        }
+
+        Ok(movie)
     }
-    if loaded.as_slice() != [true, true, true, true] {
-        return Err(LoadError::InsufficientEntry);
+}
+
+/// Reads the contents and the header of `entry`, the `index`-th entry of an archive.
+fn read_entry<R: Read>(
+    mut entry: Entry<'_, R>,
+    index: usize,
+) -> Result<(PathBuf, RawEntry), LoadError> {
+    let header = entry.header();
+    let (mode, mtime) = (header.mode().unwrap_or(0o644), header.mtime().unwrap_or(0));
+    let (uid, gid) = (header.uid().unwrap_or(0), header.gid().unwrap_or(0));
+    let username = header.username().ok().flatten().map(str::to_owned);
+    let groupname = header.groupname().ok().flatten().map(str::to_owned);
+
+    let mut data = vec![];
+    entry
+        .read_to_end(&mut data)
+        .map_err(|err| LoadError::invalid_archive(index, err))?;
+    let path = entry
+        .path()
+        .map_err(|err| LoadError::invalid_archive(index, err))?
+        .into_owned();
+    let raw = RawEntry {
+        name: path.to_string_lossy().into_owned(),
+        data,
+        mode,
+        mtime,
+        uid,
+        gid,
+        username,
+        groupname,
+    };
+    Ok((path, raw))
+}
+
+/// The magic bytes at the start of a gzip file.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Opens `reader` as a `tar.gz` archive, failing if it does not start as a gzip file.
+///
+/// The gzip members of the archive are decompressed as a single stream.
+fn open_archive<R: Read>(mut reader: R) -> Result<Archive<impl Read>, LoadError> {
+    let mut magic = [0; 2];
+    if reader.read_exact(&mut magic).is_err() || magic != GZIP_MAGIC {
+        return Err(LoadError::invalid_archive(
+            0,
+            std::io::Error::new(std::io::ErrorKind::InvalidData, "not a gzip file"),
+        ));
     }
+    Ok(Archive::new(MultiGzDecoder::new(
+        Cursor::new(magic).chain(reader),
+    )))
+}
 
-    Ok(movie)
+/// Loads a movie file in `path`.
+///
+/// # Example
+/// ```ignore
+/// use libtas_movie::load::load_movie;
+/// let movie = load_movie("path/to/tas.ltm").unwrap();
+/// ```
+pub fn load_movie<P: AsRef<Path>>(path: P) -> Result<LibTASMovie, LoadError> {
+    LoadOptions::default().load(path)
+}
+
+/// Loads a movie from `reader`, which yields the contents of a `.ltm` file.
+pub fn load_movie_from_reader<R: Read>(reader: R) -> Result<LibTASMovie, LoadError> {
+    LoadOptions::default().load_from_reader(reader)
+}
+
+/// Reads the entries of a movie from `reader` without parsing them.
+///
+/// Use [`RawMovie::metadata`] to read the metadata without copying the strings.
+pub fn read_raw_movie<R: Read>(mut reader: R) -> Result<RawMovie, LoadError> {
+    let mut archive = vec![];
+    if let Err(err) = reader.read_to_end(&mut archive) {
+        return Err(LoadError::FileError(err));
+    }
+    let mut raw_entries = vec![];
+    let mut tar = open_archive(archive.as_slice())?;
+    let entries = match tar.entries() {
+        Ok(entries) => entries,
+        Err(err) => return Err(LoadError::FileError(err)),
+    };
+    for (entry_index, entry) in entries.enumerate() {
+        let entry = entry.map_err(|err| LoadError::invalid_archive(entry_index, err))?;
+        raw_entries.push(read_entry(entry, entry_index)?.1);
+    }
+    drop(tar);
+    Ok(RawMovie {
+        archive,
+        entries: raw_entries,
+        loaded: None,
+    })
+}
+
+/// Loads only the config of a movie file in `path`, without parsing the other entries.
+pub fn load_config<P: AsRef<Path>>(path: P) -> Result<Config, LoadError> {
+    match File::open(path) {
+        Ok(file) => load_config_from_reader(file),
+        Err(err) => Err(LoadError::FileError(err)),
+    }
+}
+
+/// Loads only the config of a movie from `reader`, without parsing the other entries.
+pub fn load_config_from_reader<R: Read>(reader: R) -> Result<Config, LoadError> {
+    let mut archive = open_archive(reader)?;
+    let entries = match archive.entries() {
+        Ok(entries) => entries,
+        Err(err) => return Err(LoadError::FileError(err)),
+    };
+
+    for (entry_index, entry) in entries.enumerate() {
+        let mut entry = entry.map_err(|err| LoadError::invalid_archive(entry_index, err))?;
+        if !entry
+            .path()
+            .is_ok_and(|path| path.as_os_str() == "config.ini")
+        {
+            continue;
+        }
+
+        let mut data = vec![];
+        entry
+            .read_to_end(&mut data)
+            .map_err(|err| LoadError::invalid_archive(entry_index, err))?;
+        let string = core::str::from_utf8(&data)
+            .map_err(|err| LoadError::invalid_entry("config.ini", EntryError::Utf8(err)))?;
+        return Config::from_str(string)
+            .map_err(|err| LoadError::invalid_entry("config.ini", EntryError::Config(err)));
+    }
+    Err(LoadError::MissingEntry { name: "config.ini" })
 }
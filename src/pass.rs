@@ -0,0 +1,316 @@
+//! Module that defines transformations over the frames of a movie.
+
+use crate::inputs::{Input, Inputs, KeyboardInput, Rect, ReferenceMode};
+
+/// A transformation applied to each frame in order.
+///
+/// Passes can be composed with [`Pipeline`] and applied with [`Inputs::apply_pass`].
+pub trait FramePass {
+    /// Transforms `input`, the input of the frame `frame`.
+    fn apply(&mut self, frame: usize, input: &mut Input);
+}
+
+impl<P: FramePass + ?Sized> FramePass for &mut P {
+    fn apply(&mut self, frame: usize, input: &mut Input) {
+        (**self).apply(frame, input);
+    }
+}
+
+impl<P: FramePass + ?Sized> FramePass for Box<P> {
+    fn apply(&mut self, frame: usize, input: &mut Input) {
+        (**self).apply(frame, input);
+    }
+}
+
+impl Inputs {
+    /// Applies `pass` to each frame in order.
+    pub fn apply_pass(&mut self, mut pass: impl FramePass) {
+        for (frame, input) in self.0.iter_mut().enumerate() {
+            pass.apply(frame, input);
+        }
+    }
+}
+
+/// A sequence of passes applied one after another on each frame.
+#[derive(Default)]
+pub struct Pipeline(pub Vec<Box<dyn FramePass>>);
+
+impl Pipeline {
+    /// Returns an empty pipeline.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends `pass` to the pipeline.
+    pub fn then(mut self, pass: impl FramePass + 'static) -> Self {
+        self.0.push(Box::new(pass));
+        self
+    }
+}
+
+impl FramePass for Pipeline {
+    fn apply(&mut self, frame: usize, input: &mut Input) {
+        for pass in &mut self.0 {
+            pass.apply(frame, input);
+        }
+    }
+}
+
+/// Sorts and deduplicates keys, and removes empty keyboard inputs.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Normalize;
+
+impl FramePass for Normalize {
+    fn apply(&mut self, _frame: usize, input: &mut Input) {
+        if let Some(KeyboardInput(keys)) = &mut input.keyboard {
+            keys.sort_unstable();
+            keys.dedup();
+            if keys.is_empty() {
+                input.keyboard = None;
+            }
+        }
+    }
+}
+
+/// Replaces each key `from` with `to` for each pair `(from, to)`.
+///
+/// Keys are replaced simultaneously, so the pairs `(a, b)` and `(b, a)` swap the two keys.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RemapKeys(pub Vec<(u32, u32)>);
+
+impl FramePass for RemapKeys {
+    fn apply(&mut self, _frame: usize, input: &mut Input) {
+        if let Some(KeyboardInput(keys)) = &mut input.keyboard {
+            for key in keys {
+                if let Some(&(_, to)) = self.0.iter().find(|(from, _)| from == key) {
+                    *key = to;
+                }
+            }
+        }
+    }
+}
+
+/// Clamps absolute pointer coordinates into a rectangle.
+/// Relative coordinates are left as is.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ClampMouse(pub Rect);
+
+impl FramePass for ClampMouse {
+    fn apply(&mut self, _frame: usize, input: &mut Input) {
+        let Self(rect) = self;
+        if let Some(mouse) = &mut input.mouse
+            && mouse.reference_mode == ReferenceMode::Absolute
+        {
+            mouse.xpos = mouse.xpos.clamp(rect.left, rect.right);
+            mouse.ypos = mouse.ypos.clamp(rect.top, rect.bottom);
+        }
+    }
+}
+
+impl Inputs {
+    /// Clamps absolute pointer coordinates into `rect` with [`ClampMouse`],
+    /// e.g. after scaling coordinates or changing the resolution.
+    /// Returns the frames whose coordinates were clamped, in order.
+    pub fn clamp_mouse(&mut self, rect: Rect) -> Vec<usize> {
+        let mut pass = ClampMouse(rect);
+        let mut clamped = vec![];
+        for (frame, input) in self.0.iter_mut().enumerate() {
+            let before = input.mouse.map(|mouse| (mouse.xpos, mouse.ypos));
+            pass.apply(frame, input);
+            if input.mouse.map(|mouse| (mouse.xpos, mouse.ypos)) != before {
+                clamped.push(frame);
+            }
+        }
+        clamped
+    }
+}
+
+/// Converts relative pointer coordinates to absolute ones by accumulating them
+/// from a position, the pointer position before the current frame.
+/// Absolute coordinates are left as is and move the position.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ToAbsolute(pub (i32, i32));
+
+impl FramePass for ToAbsolute {
+    fn apply(&mut self, _frame: usize, input: &mut Input) {
+        let Self((x, y)) = self;
+        if let Some(mouse) = &mut input.mouse {
+            if mouse.reference_mode == ReferenceMode::Relative {
+                mouse.xpos = x.wrapping_add(mouse.xpos);
+                mouse.ypos = y.wrapping_add(mouse.ypos);
+                mouse.reference_mode = ReferenceMode::Absolute;
+            }
+            (*x, *y) = (mouse.xpos, mouse.ypos);
+        }
+    }
+}
+
+/// Converts absolute pointer coordinates to relative ones by differencing them
+/// from a position, the pointer position before the current frame.
+/// Relative coordinates are left as is and move the position.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ToRelative(pub (i32, i32));
+
+impl FramePass for ToRelative {
+    fn apply(&mut self, _frame: usize, input: &mut Input) {
+        let Self((x, y)) = self;
+        if let Some(mouse) = &mut input.mouse {
+            if mouse.reference_mode == ReferenceMode::Absolute {
+                let (xpos, ypos) = (mouse.xpos, mouse.ypos);
+                mouse.xpos = xpos.wrapping_sub(*x);
+                mouse.ypos = ypos.wrapping_sub(*y);
+                mouse.reference_mode = ReferenceMode::Relative;
+                (*x, *y) = (xpos, ypos);
+            } else {
+                *x = x.wrapping_add(mouse.xpos);
+                *y = y.wrapping_add(mouse.ypos);
+            }
+        }
+    }
+}
+
+impl Inputs {
+    /// Converts all pointer coordinates to absolute ones with [`ToAbsolute`],
+    /// where `start_pos` is the pointer position before the first frame.
+    pub fn to_absolute(&mut self, start_pos: (i32, i32)) {
+        self.apply_pass(ToAbsolute(start_pos));
+    }
+
+    /// Converts all pointer coordinates to relative ones with [`ToRelative`],
+    /// and returns the pointer position before the first frame,
+    /// such that [`Inputs::to_absolute`] with it restores the coordinates.
+    ///
+    /// The start position is deduced from the first absolute coordinates
+    /// and the relative ones before them, or is `(0, 0)` without absolute coordinates.
+    pub fn to_relative(&mut self) -> (i32, i32) {
+        let (mut dx, mut dy) = (0i32, 0i32);
+        let mut start_pos = (0, 0);
+        for mouse in self.0.iter().filter_map(|input| input.mouse.as_ref()) {
+            match mouse.reference_mode {
+                ReferenceMode::Absolute => {
+                    start_pos = (mouse.xpos.wrapping_sub(dx), mouse.ypos.wrapping_sub(dy));
+                    break;
+                }
+                ReferenceMode::Relative => {
+                    dx = dx.wrapping_add(mouse.xpos);
+                    dy = dy.wrapping_add(mouse.ypos);
+                }
+            }
+        }
+        self.apply_pass(ToRelative(start_pos));
+        start_pos
+    }
+}
+
+/// Returns a pseudorandom number mixed from `value` with SplitMix64.
+pub(crate) fn splitmix64(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Applies seeded random perturbations, to test how sensitive a sync is to tiny variations:
+/// key presses are shifted one frame earlier or later, and absolute pointer coordinates
+/// are moved by one pixel along each axis.
+///
+/// Each perturbation only depends on the seed, the frame and the key or axis,
+/// so the same seed always gives the same perturbations.
+/// A press is only shifted later if the key is held for more than one frame,
+/// and only shifted earlier if the key is not held two frames before,
+/// so that no press is lost, and releases are kept.
+/// Keys are perturbed from the inputs given to [`Jitter::new`], which the pass should be applied to.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Jitter {
+    /// The seed of the perturbations.
+    pub seed: u64,
+    /// The probability that each press is shifted, half earlier and half later.
+    pub press_shift: f64,
+    /// The probability that each absolute coordinate is moved, half by -1 and half by +1.
+    pub mouse_jitter: f64,
+    /// The original keys of each frame.
+    keys: Vec<Vec<u32>>,
+}
+
+impl Jitter {
+    /// Returns a pass perturbing `inputs` with `seed`,
+    /// with a probability of 0.5 for each press and coordinate.
+    pub fn new(inputs: &Inputs, seed: u64) -> Self {
+        Self {
+            seed,
+            press_shift: 0.5,
+            mouse_jitter: 0.5,
+            keys: inputs
+                .0
+                .iter()
+                .map(|input| {
+                    input
+                        .keyboard
+                        .as_ref()
+                        .map(|keys| keys.0.clone())
+                        .unwrap_or_default()
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns a shift of -1, 0 or +1, with `probability` of not being 0.
+    fn shift(&self, frame: usize, salt: u64, probability: f64) -> i32 {
+        let hash = splitmix64(self.seed ^ splitmix64((frame as u64) ^ splitmix64(salt)));
+        let r = (hash >> 11) as f64 / (1u64 << 53) as f64;
+        if r < probability / 2.0 {
+            -1
+        } else if r < probability {
+            1
+        } else {
+            0
+        }
+    }
+
+    /// Returns whether `key` is originally pressed on `frame`.
+    fn pressed(&self, frame: Option<usize>, key: u32) -> bool {
+        frame
+            .and_then(|frame| self.keys.get(frame))
+            .is_some_and(|keys| keys.contains(&key))
+    }
+
+    /// Returns the shift of the press of `key` on `frame`, or 0 if it is not pressed then.
+    fn press_shift(&self, frame: usize, key: u32) -> i32 {
+        if !self.pressed(Some(frame), key) || self.pressed(frame.checked_sub(1), key) {
+            return 0;
+        }
+        match self.shift(frame, key.into(), self.press_shift) {
+            -1 if frame == 0 || self.pressed(frame.checked_sub(2), key) => 0,
+            1 if !self.pressed(Some(frame + 1), key) => 0,
+            shift => shift,
+        }
+    }
+}
+
+impl FramePass for Jitter {
+    fn apply(&mut self, frame: usize, input: &mut Input) {
+        if frame < self.keys.len() {
+            let mut keys = self.keys[frame].clone();
+            keys.retain(|&key| self.press_shift(frame, key) != 1);
+            if let Some(next) = self.keys.get(frame + 1) {
+                keys.extend(
+                    next.iter()
+                        .filter(|&&key| self.press_shift(frame + 1, key) == -1),
+                );
+            }
+            input.keyboard = (!keys.is_empty()).then_some(KeyboardInput(keys));
+        }
+
+        if let Some(mouse) = &mut input.mouse
+            && mouse.reference_mode == ReferenceMode::Absolute
+        {
+            let axes = 1 << 32;
+            mouse.xpos = mouse
+                .xpos
+                .wrapping_add(self.shift(frame, axes, self.mouse_jitter));
+            mouse.ypos = mouse
+                .ypos
+                .wrapping_add(self.shift(frame, axes + 1, self.mouse_jitter));
+        }
+    }
+}
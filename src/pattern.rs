@@ -0,0 +1,269 @@
+//! Module that defines patterns over the frames of a movie, with wildcards and repetition.
+//!
+//! # Syntax
+//! A pattern is a sequence of steps separated by `then`, each matching consecutive frames.
+//! A step is a `+`-separated list of conditions that must all hold on a frame,
+//! optionally followed by a repetition:
+//! - `{n}` repeats the step exactly `n` frames,
+//! - `{n..m}` repeats the step from `n` to `m` frames, both included,
+//! - `{n..}` repeats the step at least `n` frames.
+//!
+//! The conditions are:
+//! - `press(KEY)`: the key goes down on the frame,
+//! - `hold(KEY)`: the key is held on the frame,
+//! - `release(KEY)`: the key goes up on the frame,
+//! - `click` or `click(BUTTON)`: the mouse button goes down on the frame,
+//!   where `BUTTON` is `left` (default), `middle`, `right`, `4`, or `5`,
+//! - `idle`: no key or mouse button is held,
+//! - `any`: any frame.
+//!
+//! Keys are named as by [`keysym_name`](crate::inputs::keysym_name), e.g. `z`, `Right`, or `0x20`.
+//!
+//! # Example
+//! ```
+//! use libtas_movie::{pattern::Pattern, testkit::sample_movie};
+//!
+//! // frames cycle through nothing, z, Right, and z with Right
+//! let inputs = sample_movie(8, 60).inputs;
+//! let pattern: Pattern = "press(z) then any{0..3} then press(Right)".parse().unwrap();
+//! let matches = pattern.find(&inputs);
+//! assert_eq!(matches[0].frames, 1..3);
+//! assert_eq!(matches[0].steps, [1..2, 2..2, 2..3]);
+//! ```
+
+use core::{ops::Range, str::FromStr};
+use std::collections::HashSet;
+
+use crate::inputs::{Input, Inputs, MouseButton, keysym_from_name};
+
+/// An error while parsing a pattern, containing the part that caused the error.
+#[derive(Debug)]
+pub struct InvalidPatternError(pub String);
+
+/// A condition on a frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Condition {
+    /// The key goes down on the frame.
+    Press(u32),
+    /// The key is held on the frame.
+    Hold(u32),
+    /// The key goes up on the frame.
+    Release(u32),
+    /// The mouse button goes down on the frame.
+    Click(MouseButton),
+    /// No key or mouse button is held.
+    Idle,
+    /// Any frame.
+    Any,
+}
+
+impl Condition {
+    /// Returns whether the condition holds on `input`, preceded by `previous`.
+    pub fn holds(&self, input: &Input, previous: Option<&Input>) -> bool {
+        let held = |input: Option<&Input>, key: u32| {
+            input
+                .and_then(|input| input.keyboard.as_ref())
+                .is_some_and(|keyboard| keyboard.0.contains(&key))
+        };
+        let pressed = |input: Option<&Input>, button: MouseButton| {
+            input
+                .and_then(|input| input.mouse)
+                .is_some_and(|mouse| mouse.is_pressed(button))
+        };
+        match *self {
+            Self::Press(key) => held(Some(input), key) && !held(previous, key),
+            Self::Hold(key) => held(Some(input), key),
+            Self::Release(key) => !held(Some(input), key) && held(previous, key),
+            Self::Click(button) => pressed(Some(input), button) && !pressed(previous, button),
+            Self::Idle => {
+                input
+                    .keyboard
+                    .as_ref()
+                    .is_none_or(|keyboard| keyboard.0.is_empty())
+                    && !MouseButton::ALL
+                        .into_iter()
+                        .any(|button| pressed(Some(input), button))
+            }
+            Self::Any => true,
+        }
+    }
+}
+
+impl FromStr for Condition {
+    type Err = InvalidPatternError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || InvalidPatternError(s.to_owned());
+        let (name, argument) = match s.split_once('(') {
+            Some((name, rest)) => (name, Some(rest.strip_suffix(')').ok_or_else(error)?)),
+            None => (s, None),
+        };
+        let key = || argument.and_then(keysym_from_name).ok_or_else(error);
+        match name {
+            "press" => Ok(Self::Press(key()?)),
+            "hold" => Ok(Self::Hold(key()?)),
+            "release" => Ok(Self::Release(key()?)),
+            "click" => {
+                let button = match argument {
+                    None | Some("left" | "1") => MouseButton::Left,
+                    Some("middle" | "2") => MouseButton::Middle,
+                    Some("right" | "3") => MouseButton::Right,
+                    Some("4") => MouseButton::Button4,
+                    Some("5") => MouseButton::Button5,
+                    Some(_) => return Err(error()),
+                };
+                Ok(Self::Click(button))
+            }
+            "idle" if argument.is_none() => Ok(Self::Idle),
+            "any" if argument.is_none() => Ok(Self::Any),
+            _ => Err(error()),
+        }
+    }
+}
+
+/// A step of a pattern: conditions that hold on each of a repeated number of frames.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+    /// The conditions that must all hold on each frame.
+    pub conditions: Vec<Condition>,
+    /// The smallest number of frames.
+    pub min: usize,
+    /// The largest number of frames, or `None` if unbounded.
+    pub max: Option<usize>,
+}
+
+impl FromStr for Step {
+    type Err = InvalidPatternError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || InvalidPatternError(s.to_owned());
+        let (conditions, repetition) = match s.strip_suffix('}') {
+            Some(rest) => rest.rsplit_once('{').ok_or_else(error)?,
+            None => (s, "1"),
+        };
+        let (min, max) = match repetition.split_once("..") {
+            Some((min, "")) => (min, None),
+            Some((min, max)) => (min, Some(max)),
+            None => (repetition, Some(repetition)),
+        };
+        let min = min.trim().parse().map_err(|_| error())?;
+        let max = match max {
+            Some(max) => Some(max.trim().parse().map_err(|_| error())?),
+            None => None,
+        };
+        if max.is_some_and(|max| max < min) {
+            return Err(error());
+        }
+
+        let conditions = conditions
+            .split('+')
+            .map(|condition| condition.trim().parse())
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            conditions,
+            min,
+            max,
+        })
+    }
+}
+
+/// A match of a pattern.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PatternMatch {
+    /// The frames of the whole match.
+    pub frames: Range<usize>,
+    /// The frames captured by each step, in order.
+    pub steps: Vec<Range<usize>>,
+}
+
+/// A pattern over frames, parsed from the syntax described in the [module](self) documentation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pattern(pub Vec<Step>);
+
+impl FromStr for Pattern {
+    type Err = InvalidPatternError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut steps = vec![];
+        let mut step = String::new();
+        for word in s.split_whitespace() {
+            if word == "then" {
+                steps.push(step.parse()?);
+                step.clear();
+            } else {
+                step.push_str(word);
+            }
+        }
+        steps.push(step.parse()?);
+        Ok(Self(steps))
+    }
+}
+
+impl Pattern {
+    /// Returns the non-overlapping matches of the pattern in `inputs`, in order.
+    ///
+    /// Each match starts at the earliest possible frame, and repetitions match as many frames
+    /// as possible. Empty matches are skipped.
+    pub fn find(&self, inputs: &Inputs) -> Vec<PatternMatch> {
+        let mut matches = vec![];
+        let mut start = 0;
+        let mut steps = vec![];
+        let mut failed = HashSet::new();
+        while start < inputs.0.len() {
+            steps.clear();
+            match self.match_from(&inputs.0, 0, start, &mut steps, &mut failed) {
+                Some(end) if end > start => {
+                    matches.push(PatternMatch {
+                        frames: start..end,
+                        steps: steps.clone(),
+                    });
+                    start = end;
+                }
+                _ => start += 1,
+            }
+        }
+        matches
+    }
+
+    /// Matches the steps from `step` at `frame`, pushing the frames of each step into `captures`.
+    /// Returns the end of the match.
+    ///
+    /// The steps and frames that do not match are memoized in `failed`,
+    /// so that backtracking over repetitions takes polynomial time.
+    fn match_from(
+        &self,
+        inputs: &[Input],
+        step: usize,
+        frame: usize,
+        captures: &mut Vec<Range<usize>>,
+        failed: &mut HashSet<(usize, usize)>,
+    ) -> Option<usize> {
+        let Some(current) = self.0.get(step) else {
+            return Some(frame);
+        };
+        if failed.contains(&(step, frame)) {
+            return None;
+        }
+        let max = current.max.unwrap_or(usize::MAX);
+        let mut count = 0;
+        while count < max
+            && frame + count < inputs.len()
+            && current.conditions.iter().all(|condition| {
+                let at = frame + count;
+                condition.holds(&inputs[at], at.checked_sub(1).map(|at| &inputs[at]))
+            })
+        {
+            count += 1;
+        }
+
+        for count in (current.min..=count).rev() {
+            captures.push(frame..frame + count);
+            if let Some(end) = self.match_from(inputs, step + 1, frame + count, captures, failed) {
+                return Some(end);
+            }
+            captures.pop();
+        }
+        failed.insert((step, frame));
+        None
+    }
+}
@@ -0,0 +1,148 @@
+//! Module that compares a movie against the inputs logged while playing it back.
+//!
+//! # Log format
+//! Each line of a log is an input frame as written in the `inputs` file,
+//! such as `|K7a|M10:20:A:.....|`, optionally preceded by its frame number
+//! and a colon, such as `42: |K7a|`. Frames without a number follow the previous frame,
+//! starting from frame 0. Empty lines and lines starting with `#` are ignored.
+//!
+//! Logs may skip frames or stop early, e.g. when the game crashed:
+//! only the logged frames are compared.
+//!
+//! This format is this crate's own rather than one written by libTAS,
+//! so that a log is easy to print from a script or a tool playing the movie back.
+
+use core::fmt::Display;
+use std::collections::BTreeMap;
+
+use crate::inputs::{Input, Inputs};
+
+/// An error while parsing a log, containing the line that caused the error.
+#[derive(Debug)]
+pub struct InvalidLogError(pub String);
+
+/// The inputs logged during playback, as frame numbers and inputs in order.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PlaybackLog(pub Vec<(usize, Input)>);
+
+impl PlaybackLog {
+    /// Parses a log in the format described in the [module](self) documentation.
+    pub fn parse(text: &str) -> Result<Self, InvalidLogError> {
+        let mut frames = vec![];
+        let mut next: Option<usize> = Some(0);
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = || InvalidLogError(line.to_owned());
+            let (frame, input) = match line.split_once(':') {
+                Some((frame, input)) if !frame.starts_with('|') => {
+                    (frame.trim().parse().map_err(|_| error())?, input.trim())
+                }
+                _ => (next.ok_or_else(error)?, line),
+            };
+            let input = input.parse().map_err(|_| error())?;
+            frames.push((frame, input));
+            next = frame.checked_add(1);
+        }
+        Ok(Self(frames))
+    }
+
+    /// Returns the first logged frame that disagrees with `inputs`, with up to `context`
+    /// frames on each side, or `None` if every logged frame agrees.
+    ///
+    /// A logged frame beyond the end of `inputs` disagrees.
+    pub fn compare(&self, inputs: &Inputs, context: usize) -> Option<LogMismatch> {
+        let index = self
+            .0
+            .iter()
+            .position(|(frame, input)| inputs.0.get(*frame) != Some(input))?;
+        let frame = self.0[index].0;
+
+        let start = frame.saturating_sub(context);
+        let end = frame.saturating_add(context);
+        let mut window = BTreeMap::new();
+        for at in start..inputs.0.len().min(end.saturating_add(1)) {
+            window.insert(
+                at,
+                LogFrame {
+                    frame: at,
+                    movie: Some(inputs.0[at].clone()),
+                    log: None,
+                },
+            );
+        }
+        // the last logged input of each frame wins
+        for (at, input) in self.0.iter().filter(|(at, _)| (start..=end).contains(at)) {
+            window
+                .entry(*at)
+                .or_insert_with(|| LogFrame {
+                    frame: *at,
+                    movie: None,
+                    log: None,
+                })
+                .log = Some(input.clone());
+        }
+        let window = window.into_values().collect();
+        Some(LogMismatch {
+            frame,
+            context: window,
+        })
+    }
+}
+
+/// A frame of the movie and of the log.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogFrame {
+    /// The frame.
+    pub frame: usize,
+    /// The input of the movie, or `None` if the movie ends before the frame.
+    pub movie: Option<Input>,
+    /// The logged input, or `None` if the frame is not logged.
+    pub log: Option<Input>,
+}
+
+impl LogFrame {
+    /// Returns whether the frame is logged with a different input than the movie.
+    pub fn disagrees(&self) -> bool {
+        self.log.is_some() && self.log != self.movie
+    }
+}
+
+/// The first frame where a movie and its playback log disagree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogMismatch {
+    /// The first frame that disagrees.
+    pub frame: usize,
+    /// The frames around it, in order.
+    pub context: Vec<LogFrame>,
+}
+
+impl Display for LogMismatch {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        writeln!(f, "movie and log first disagree at frame {}", self.frame)?;
+        let input = |input: &Option<Input>| {
+            input
+                .as_ref()
+                .map_or_else(|| "(none)".to_owned(), Input::to_string)
+        };
+        for frame in &self.context {
+            let mark = if frame.frame == self.frame {
+                '>'
+            } else if frame.disagrees() {
+                '!'
+            } else {
+                ' '
+            };
+            writeln!(
+                f,
+                "{mark} {}: movie {} log {}",
+                frame.frame,
+                input(&frame.movie),
+                input(&frame.log),
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+//! Module that pretty-prints frames for debugging.
+//!
+//! The derived `Debug` of [`Input`] shows keys as decimal numbers in nested structs,
+//! which is hard to read when looking for a desync. [`PrettyFrame`] shows the frame
+//! with its index and start time, named keys, and decoded mouse buttons instead.
+//!
+//! # Example
+//! ```
+//! use libtas_movie::{pretty::PrettyFrame, inputs::Input};
+//! let input: Input = "|K7a:ff53|M166:270:A:1....:0|F1|".parse().unwrap();
+//! assert_eq!(
+//!     format!("{:?}", PrettyFrame::new(42, &input)),
+//!     "PrettyFrame { frame: 42, keys: [z, Right], \
+//!      mouse: Mouse { x: 166, y: 270, mode: Absolute, buttons: [LMB] }, unknown: [F1] }"
+//! );
+//! ```
+
+use core::{
+    fmt::{Debug, Display},
+    time::Duration,
+};
+
+use crate::{
+    inputs::{Input, MouseButton, MouseInput, keysym_name},
+    movie::LibTASMovie,
+};
+
+/// Writes the [`Display`] of a value as its [`Debug`], without quotes.
+struct Plain<T>(T);
+
+impl<T: Display> Debug for Plain<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+/// A mouse input with its pressed buttons by label.
+struct PrettyMouse<'a>(&'a MouseInput);
+
+impl Debug for PrettyMouse<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mouse = self.0;
+        let buttons: Vec<_> = MouseButton::ALL
+            .into_iter()
+            .filter(|&button| mouse.is_pressed(button))
+            .map(|button| Plain(button.label()))
+            .collect();
+        f.debug_struct("Mouse")
+            .field("x", &mouse.xpos)
+            .field("y", &mouse.ypos)
+            .field("mode", &mouse.reference_mode)
+            .field("buttons", &buttons)
+            .finish()
+    }
+}
+
+/// A frame whose [`Debug`] shows its index, start time, named keys, decoded mouse buttons,
+/// controllers, unknown sections such as flags, and framerate.
+///
+/// Absent parts are omitted, and `{:#?}` writes one part per line.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct PrettyFrame<'a> {
+    /// The index of the frame.
+    pub frame: usize,
+    /// The input of the frame.
+    pub input: &'a Input,
+    /// The time at which the frame starts, if known.
+    pub time: Option<Duration>,
+}
+
+impl<'a> PrettyFrame<'a> {
+    /// Wraps the input of `frame`, without its time.
+    pub fn new(frame: usize, input: &'a Input) -> Self {
+        Self {
+            frame,
+            input,
+            time: None,
+        }
+    }
+}
+
+impl Debug for PrettyFrame<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let input = self.input;
+        let mut s = f.debug_struct("PrettyFrame");
+        s.field("frame", &self.frame);
+        if let Some(time) = &self.time {
+            s.field("time", time);
+        }
+        if let Some(keyboard) = &input.keyboard {
+            let keys: Vec<_> = keyboard
+                .0
+                .iter()
+                .map(|&key| Plain(keysym_name(key)))
+                .collect();
+            s.field("keys", &keys);
+        }
+        if let Some(mouse) = &input.mouse {
+            s.field("mouse", &PrettyMouse(mouse));
+        }
+        if !input.controllers.is_empty() {
+            let controllers: Vec<_> = input.controllers.iter().map(Plain).collect();
+            s.field("controllers", &controllers);
+        }
+        if !input.unknown.is_empty() {
+            let unknown: Vec<_> = input.unknown.iter().map(Plain).collect();
+            s.field("unknown", &unknown);
+        }
+        if let Some(framerate) = &input.framerate {
+            s.field("framerate", &Plain(framerate));
+        }
+        s.finish()
+    }
+}
+
+impl LibTASMovie {
+    /// Returns the pretty-printable `frame` with its start time, or `None` if out of range.
+    pub fn pretty_frame(&self, frame: usize) -> Option<PrettyFrame<'_>> {
+        let input = self.inputs.0.get(frame)?;
+        Some(PrettyFrame {
+            frame,
+            input,
+            time: Some(self.timeline().time_of_frame(frame)),
+        })
+    }
+
+    /// Returns all pretty-printable frames with their start times, in order.
+    pub fn pretty_frames(&self) -> impl Iterator<Item = PrettyFrame<'_>> {
+        let timeline = self.timeline();
+        self.inputs
+            .0
+            .iter()
+            .enumerate()
+            .map(move |(frame, input)| PrettyFrame {
+                frame,
+                input,
+                time: Some(timeline.time_of_frame(frame)),
+            })
+    }
+}
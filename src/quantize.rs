@@ -0,0 +1,350 @@
+//! Module that quantizes timestamped input events onto frames.
+//!
+//! [`Quantizer`] is shared by the [`Recorder`](crate::record::Recorder),
+//! the `evtest` importer [`import::quantize`](crate::import::quantize),
+//! and converters from other event-based formats.
+//!
+//! # Example
+//! ```
+//! use core::time::Duration;
+//! use libtas_movie::{quantize::{InputEvent, Quantizer}, time::Framerate};
+//! let events = [
+//!     (Duration::from_millis(20), InputEvent::KeyPress(0x7a)),
+//!     (Duration::from_millis(25), InputEvent::KeyRelease(0x7a)),
+//! ];
+//! let quantizer = Quantizer::new(Framerate { num: 60, den: 1 }).min_hold(2);
+//! assert_eq!(quantizer.quantize(&events).to_string(), "|\n|K7a|\n|K7a|\n");
+//! ```
+
+use core::time::Duration;
+
+use crate::{
+    inputs::{Input, Inputs, KeyboardInput, MouseButton, MouseInput, ReferenceMode},
+    time::{Framerate, Timeline},
+};
+
+/// A change of an input device.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputEvent {
+    /// A key is pressed, by its KeySym value.
+    KeyPress(u32),
+    /// A key is released, by its KeySym value.
+    KeyRelease(u32),
+    /// A mouse button is pressed.
+    ButtonPress(MouseButton),
+    /// A mouse button is released.
+    ButtonRelease(MouseButton),
+    /// The pointer moves to a position.
+    Motion { x: i32, y: i32 },
+    /// The pointer moves by a distance.
+    RelativeMotion { dx: i32, dy: i32 },
+}
+
+/// How event times are rounded to frames.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Rounding {
+    /// An event belongs to the frame being shown at its time.
+    #[default]
+    Floor,
+    /// An event belongs to the frame whose start is the nearest to its time.
+    Nearest,
+}
+
+/// Which keys and buttons a frame holds.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Hold {
+    /// The keys and buttons held at any time during the frame,
+    /// so that a release holds the key on the frame of the release.
+    #[default]
+    During,
+    /// The keys and buttons held at the end of the frame, and those pressed during the frame,
+    /// whose release within the frame is delayed to the next frame.
+    End,
+}
+
+/// A key or a mouse button.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Control {
+    Key(u32),
+    Button(MouseButton),
+}
+
+/// A held key or button, with the frame of its press and the frame of its delayed release.
+#[derive(Clone, Debug)]
+struct Held {
+    control: Control,
+    pressed: usize,
+    release: Option<usize>,
+}
+
+/// Quantizes events onto frames at a framerate.
+///
+/// By default, a key or a button is held on a frame if it is held at any time during the frame,
+/// see [`Hold`]. Either way, presses shorter than a frame are kept,
+/// and keys and buttons are held for at least the minimum hold length.
+/// The pointer takes its last position in each frame, and relative motions are summed
+/// per frame into relative mouse inputs.
+/// Frames have a mouse input if there is any pointer or button event.
+#[derive(Clone, Debug)]
+pub struct Quantizer {
+    framerate: Framerate,
+    timeline: Timeline,
+    rounding: Rounding,
+    hold: Hold,
+    min_hold: usize,
+}
+
+impl Quantizer {
+    /// Returns a quantizer onto frames at `framerate`, rounding times down,
+    /// and holding presses during their frames for at least one frame.
+    pub fn new(framerate: Framerate) -> Self {
+        Self {
+            framerate,
+            timeline: Timeline::new(framerate, &Inputs::default()),
+            rounding: Rounding::Floor,
+            hold: Hold::During,
+            min_hold: 1,
+        }
+    }
+
+    /// Sets how event times are rounded to frames.
+    pub fn rounding(mut self, rounding: Rounding) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    /// Sets which keys and buttons a frame holds.
+    pub fn hold(mut self, hold: Hold) -> Self {
+        self.hold = hold;
+        self
+    }
+
+    /// Sets the minimum number of frames a key or a button is held for.
+    pub fn min_hold(mut self, frames: usize) -> Self {
+        self.min_hold = frames.max(1);
+        self
+    }
+
+    /// Returns the framerate of the frames.
+    pub fn framerate(&self) -> Framerate {
+        self.framerate
+    }
+
+    /// Returns the frame of an event at `time`.
+    pub fn frame_of(&self, time: Duration) -> usize {
+        match self.rounding {
+            Rounding::Floor => self.timeline.frame_at(time),
+            Rounding::Nearest => {
+                let frame = self.timeline.frame_at(time);
+                let (start, end) = (
+                    self.timeline.time_of_frame(frame),
+                    self.timeline.time_of_frame(frame + 1),
+                );
+                if end.saturating_sub(time) <= time.saturating_sub(start) {
+                    frame + 1
+                } else {
+                    frame
+                }
+            }
+        }
+    }
+
+    /// Quantizes `events`, sorted by time, up to the frame of the last event
+    /// and the end of the last minimum hold.
+    pub fn quantize(&self, events: &[(Duration, InputEvent)]) -> Inputs {
+        self.quantize_to(events, 0)
+    }
+
+    /// Quantizes `events`, sorted by time, like [`Quantizer::quantize`]
+    /// but onto at least `frames` frames.
+    pub fn quantize_to(&self, events: &[(Duration, InputEvent)], frames: usize) -> Inputs {
+        let mut state = QuantizerState::new(self.clone());
+        // all frames have the same kind of mouse input
+        state.mouse = events.iter().any(|(_, event)| {
+            !matches!(event, InputEvent::KeyPress(_) | InputEvent::KeyRelease(_))
+        });
+        state.relative = events
+            .iter()
+            .any(|(_, event)| matches!(event, InputEvent::RelativeMotion { .. }));
+        for &(time, event) in events {
+            state.push(time, event);
+        }
+        state.finish(frames)
+    }
+}
+
+/// The state of a [`Quantizer`] fed with events one at a time, in chronological order,
+/// which completes the frames before the frame of each event.
+///
+/// Frames only have a mouse input from the first pointer or button event,
+/// and relative mouse inputs from the first relative motion.
+#[derive(Clone, Debug)]
+pub(crate) struct QuantizerState {
+    quantizer: Quantizer,
+    /// The completed frames.
+    frames: Vec<Input>,
+    /// The frame of the last event.
+    last_frame: Option<usize>,
+    /// The held keys and buttons.
+    held: Vec<Held>,
+    /// The keys and buttons of the current frame.
+    controls: Vec<Control>,
+    /// The last pointer position.
+    position: (i32, i32),
+    /// The relative motion in the current frame.
+    motion: (i32, i32),
+    /// Whether frames have a mouse input.
+    mouse: bool,
+    /// Whether mouse inputs are relative.
+    relative: bool,
+}
+
+impl QuantizerState {
+    /// Returns the state of `quantizer` before any event.
+    pub(crate) fn new(quantizer: Quantizer) -> Self {
+        Self {
+            quantizer,
+            frames: vec![],
+            last_frame: None,
+            held: vec![],
+            controls: vec![],
+            position: (0, 0),
+            motion: (0, 0),
+            mouse: false,
+            relative: false,
+        }
+    }
+
+    /// Returns the quantizer.
+    pub(crate) fn quantizer(&self) -> &Quantizer {
+        &self.quantizer
+    }
+
+    /// Returns the current frame, the first frame that is not completed.
+    pub(crate) fn frame(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Applies `event` at `time`, which is not before the previous event.
+    pub(crate) fn push(&mut self, time: Duration, event: InputEvent) {
+        let frame = self.quantizer.frame_of(time);
+        while self.frames.len() < frame {
+            self.complete();
+        }
+        let frame = self.frames.len();
+        self.last_frame = Some(frame);
+
+        match event {
+            InputEvent::KeyPress(key) => self.press(Control::Key(key)),
+            InputEvent::KeyRelease(key) => self.release(Control::Key(key)),
+            InputEvent::ButtonPress(button) => {
+                self.mouse = true;
+                self.press(Control::Button(button));
+            }
+            InputEvent::ButtonRelease(button) => {
+                self.mouse = true;
+                self.release(Control::Button(button));
+            }
+            InputEvent::Motion { x, y } => {
+                self.mouse = true;
+                self.position = (x, y);
+            }
+            InputEvent::RelativeMotion { dx, dy } => {
+                self.mouse = true;
+                self.relative = true;
+                self.motion = (
+                    self.motion.0.saturating_add(dx),
+                    self.motion.1.saturating_add(dy),
+                );
+            }
+        }
+    }
+
+    /// Presses `control` on the current frame.
+    fn press(&mut self, control: Control) {
+        let frame = self.frames.len();
+        if let Some(held) = self.held.iter_mut().find(|held| held.control == control) {
+            held.release = None;
+        } else {
+            self.held.push(Held {
+                control,
+                pressed: frame,
+                release: None,
+            });
+        }
+        if !self.controls.contains(&control) {
+            self.controls.push(control);
+        }
+    }
+
+    /// Releases `control` on the current frame, or after the minimum hold length.
+    fn release(&mut self, control: Control) {
+        let frame = self.frames.len();
+        let Some(index) = self.held.iter().position(|held| held.control == control) else {
+            return;
+        };
+        let last = self.held[index].pressed + self.quantizer.min_hold - 1;
+        if frame < last {
+            self.held[index].release = Some(last);
+            return;
+        }
+        let pressed = self.held.remove(index).pressed;
+        if self.quantizer.hold == Hold::End && pressed < frame {
+            self.controls.retain(|&held| held != control);
+        }
+    }
+
+    /// Completes the current frame.
+    fn complete(&mut self) {
+        let frame = self.frames.len();
+        self.held
+            .retain(|held| held.release.is_none_or(|release| frame < release));
+
+        let keys: Vec<u32> = self
+            .controls
+            .iter()
+            .filter_map(|&control| match control {
+                Control::Key(key) => Some(key),
+                Control::Button(_) => None,
+            })
+            .collect();
+        let (xpos, ypos) = if self.relative {
+            self.motion
+        } else {
+            self.position
+        };
+        let mut mouse = MouseInput {
+            xpos,
+            ypos,
+            reference_mode: if self.relative {
+                ReferenceMode::Relative
+            } else {
+                ReferenceMode::Absolute
+            },
+            ..MouseInput::default()
+        };
+        for &control in &self.controls {
+            if let Control::Button(button) = control {
+                mouse.set_pressed(button, true);
+            }
+        }
+        self.frames.push(Input {
+            keyboard: (!keys.is_empty()).then_some(KeyboardInput(keys)),
+            mouse: self.mouse.then_some(mouse),
+            ..Input::default()
+        });
+
+        self.controls = self.held.iter().map(|held| held.control).collect();
+        self.motion = (0, 0);
+    }
+
+    /// Completes the frames up to the frame of the last event, at least `frames` frames,
+    /// and the end of the last minimum hold, and returns them.
+    pub(crate) fn finish(mut self, frames: usize) -> Inputs {
+        let frames = self.last_frame.map_or(0, |frame| frame + 1).max(frames);
+        while self.frames.len() < frames || self.held.iter().any(|held| held.release.is_some()) {
+            self.complete();
+        }
+        Inputs::from(self.frames)
+    }
+}
@@ -0,0 +1,99 @@
+//! Module that builds movies from timestamped input events.
+//!
+//! A recording frontend feeds the events it receives to a [`Recorder`], with their times
+//! since the start of the recording (e.g. from [`Instant::elapsed`](std::time::Instant::elapsed)),
+//! and finishes the recording into a movie.
+//!
+//! # Example
+//! ```
+//! use core::time::Duration;
+//! use libtas_movie::{quantize::InputEvent, record::Recorder, time::Framerate};
+//! let mut recorder = Recorder::new(Framerate { num: 60, den: 1 });
+//! recorder.record(Duration::from_millis(20), InputEvent::KeyPress(0x7a)).unwrap();
+//! recorder.record(Duration::from_millis(60), InputEvent::KeyRelease(0x7a)).unwrap();
+//! let movie = recorder.finish(Duration::from_millis(100));
+//! assert_eq!(movie.inputs.to_string(), "|\n|K7a|\n|K7a|\n|\n|\n|\n");
+//! ```
+
+use core::time::Duration;
+
+use crate::{
+    inputs::Inputs,
+    movie::LibTASMovie,
+    quantize::{Hold, InputEvent, Quantizer, QuantizerState},
+    time::{Framerate, Timeline},
+};
+
+/// An error while recording an event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecordError {
+    /// The event at `time` came after an event at the later time `last`.
+    OutOfOrder { last: Duration, time: Duration },
+}
+
+/// A builder of movies from timestamped events, quantized to frames by a [`Quantizer`].
+///
+/// The frames before the frame of the last event are completed as events are recorded.
+#[derive(Clone, Debug)]
+pub struct Recorder {
+    state: QuantizerState,
+    /// The time of the last event.
+    last: Option<Duration>,
+}
+
+impl Recorder {
+    /// Returns a recorder of frames at `framerate`.
+    ///
+    /// Each frame holds the keys and buttons held at its end, see [`Hold::End`].
+    pub fn new(framerate: Framerate) -> Self {
+        Self::with_quantizer(Quantizer::new(framerate).hold(Hold::End))
+    }
+
+    /// Returns a recorder of frames quantized by `quantizer`.
+    pub fn with_quantizer(quantizer: Quantizer) -> Self {
+        Self {
+            state: QuantizerState::new(quantizer),
+            last: None,
+        }
+    }
+
+    /// Returns the current frame, the frame of the last event.
+    pub fn frame(&self) -> usize {
+        self.state.frame()
+    }
+
+    /// Records `event` at `time` since the start of the recording.
+    ///
+    /// Events must be recorded in chronological order.
+    pub fn record(&mut self, time: Duration, event: InputEvent) -> Result<(), RecordError> {
+        if let Some(last) = self.last
+            && time < last
+        {
+            return Err(RecordError::OutOfOrder { last, time });
+        }
+        self.last = Some(time);
+        self.state.push(time, event);
+        Ok(())
+    }
+
+    /// Finishes the recording at `end` since its start, and returns the movie
+    /// of the frames starting before `end`, including the frames of the events.
+    ///
+    /// The frame count, the length, the framerate and `mouse_support` are filled in.
+    pub fn finish(self, end: Duration) -> LibTASMovie {
+        let framerate = self.state.quantizer().framerate();
+        let frames = end.checked_sub(Duration::from_nanos(1)).map_or(0, |time| {
+            Timeline::new(framerate, &Inputs::default()).frame_at(time) + 1
+        });
+        let inputs = self.state.finish(frames);
+
+        let mut movie = LibTASMovie::default();
+        let general = &mut movie.config.general;
+        general.frame_count = inputs.0.len() as u64;
+        general.set_framerate(framerate);
+        general.mouse_support = inputs.0.iter().any(|input| input.mouse.is_some());
+        movie.inputs = inputs;
+        movie.recompute_length();
+        movie
+    }
+}
@@ -0,0 +1,76 @@
+//! Module that renders inputs as text for terminals and text-based tools.
+//!
+//! [`piano_roll`] draws frames as rows of one character per key or button,
+//! which fits many frames and columns on a screen, e.g. in a TUI viewer,
+//! or as the text of a movie shown by `git diff` through a textconv driver.
+
+use core::ops::Range;
+
+use crate::{
+    export::matrix::ColumnSpec,
+    inputs::{Inputs, keysym_name},
+};
+
+/// Returns the character drawn for each column of `spec`:
+/// the first character of the name of a key, such as `z` or `R` for `Right`,
+/// and the number of a mouse button, such as `1` for the left button.
+fn column_chars(spec: &ColumnSpec) -> Vec<char> {
+    let keys = spec
+        .keys
+        .iter()
+        .map(|&key| keysym_name(key).chars().next().unwrap_or('?'));
+    let buttons = spec
+        .mouse_buttons
+        .iter()
+        .map(|&button| char::from(b'1' + button as u8));
+    keys.chain(buttons).collect()
+}
+
+/// Renders the frames in `frames` as a piano roll of the columns of `spec`:
+/// ```text
+/// frame |z1| x,y
+///     0 |..|
+///     1 |z.|
+///     2 |..|
+///     3 |z1| 166,270
+/// ```
+/// The header shows the character of each column, and released keys and buttons are `.`.
+/// The pointer coordinates follow the grid on frames with a mouse input.
+/// Frames out of bounds are skipped, and trailing spaces are trimmed.
+pub fn piano_roll(inputs: &Inputs, frames: Range<usize>, spec: &ColumnSpec) -> String {
+    let len = inputs.0.len();
+    let frames = frames.start.min(len)..frames.end.min(len);
+    let rows = inputs.0.get(frames.clone()).unwrap_or_default();
+    let chars = column_chars(spec);
+
+    let width = frames
+        .end
+        .saturating_sub(1)
+        .to_string()
+        .len()
+        .max("frame".len());
+    let mut text = format!("{:>width$} |", "frame");
+    text.extend(&chars);
+    text.push('|');
+    if rows.iter().any(|input| input.mouse.is_some()) {
+        text.push_str(" x,y");
+    }
+    text.push('\n');
+
+    for (frame, input) in frames.zip(rows) {
+        let mut line = format!("{frame:>width$} |");
+        line.extend(
+            chars
+                .iter()
+                .zip(spec.pressed(input))
+                .map(|(&glyph, pressed)| if pressed { glyph } else { '.' }),
+        );
+        line.push('|');
+        if let Some(mouse) = input.mouse {
+            line += &format!(" {},{}", mouse.xpos, mouse.ypos);
+        }
+        text += line.trim_end();
+        text.push('\n');
+    }
+    text
+}
@@ -0,0 +1,109 @@
+//! Module that repairs common inconsistencies of a movie.
+
+use core::time::Duration;
+
+use crate::movie::LibTASMovie;
+
+/// Options choosing the repairs of [`LibTASMovie::repair`]. All repairs are on by default.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RepairOptions {
+    /// Whether to set `frame_count` to the number of frames.
+    pub frame_count: bool,
+    /// Whether to carry `length_nsec` over one second into `length_sec`,
+    /// and to set the length to the duration of the frames.
+    pub length: bool,
+    /// Whether to end non-empty annotations with a newline.
+    pub trailing_newline: bool,
+    /// Whether to remove repeated keys within a frame.
+    pub duplicate_keys: bool,
+}
+
+impl Default for RepairOptions {
+    fn default() -> Self {
+        Self {
+            frame_count: true,
+            length: true,
+            trailing_newline: true,
+            duplicate_keys: true,
+        }
+    }
+}
+
+/// A repair applied by [`LibTASMovie::repair`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Repair {
+    /// `frame_count` was changed from `old` to the number of frames, `new`.
+    FrameCount { old: u64, new: u64 },
+    /// `length_nsec` was `nsec`, over one second, and was carried into `length_sec`.
+    NsecOverflow { nsec: u64 },
+    /// The length was changed from `old` to the duration of the frames, `new`.
+    Length { old: Duration, new: Duration },
+    /// A newline was appended to the annotations.
+    TrailingNewline,
+    /// Repeated keys were removed from a frame.
+    DuplicateKeys { frame: usize, keys: Vec<u32> },
+}
+
+impl LibTASMovie {
+    /// Repairs the inconsistencies chosen by `options`, returning the applied repairs in order.
+    pub fn repair(&mut self, options: &RepairOptions) -> Vec<Repair> {
+        let mut repairs = vec![];
+
+        if options.duplicate_keys {
+            for (frame, input) in self.inputs.0.iter_mut().enumerate() {
+                let Some(keyboard) = &mut input.keyboard else {
+                    continue;
+                };
+                let mut seen = vec![];
+                let mut removed = vec![];
+                keyboard.0.retain(|&key| {
+                    if seen.contains(&key) {
+                        removed.push(key);
+                        false
+                    } else {
+                        seen.push(key);
+                        true
+                    }
+                });
+                if !removed.is_empty() {
+                    repairs.push(Repair::DuplicateKeys {
+                        frame,
+                        keys: removed,
+                    });
+                }
+            }
+        }
+
+        if options.frame_count {
+            let (old, new) = (self.config.general.frame_count, self.inputs.0.len() as u64);
+            if old != new {
+                self.config.general.frame_count = new;
+                repairs.push(Repair::FrameCount { old, new });
+            }
+        }
+
+        if options.length {
+            let length = &mut self.config.general.length;
+            if !length.is_normalized() {
+                let nsec = length.nsec;
+                *length = length.normalized();
+                repairs.push(Repair::NsecOverflow { nsec });
+            }
+            let old = self.duration();
+            if self.recompute_length() {
+                let new = self.duration();
+                repairs.push(Repair::Length { old, new });
+            }
+        }
+
+        if options.trailing_newline
+            && !self.annotations.is_empty()
+            && !self.annotations.ends_with('\n')
+        {
+            self.annotations.push('\n');
+            repairs.push(Repair::TrailingNewline);
+        }
+
+        repairs
+    }
+}
@@ -0,0 +1,133 @@
+//! Module that feeds the inputs of a movie to other systems at the pace of the movie.
+//!
+//! A [`Player`] is either driven by the caller's clock with [`Player::advance`],
+//! e.g. once per iteration of a game loop, or plays in real time with [`Player::run`].
+
+use core::time::Duration;
+use std::time::Instant;
+
+use crate::{inputs::Input, movie::LibTASMovie, time::Timeline};
+
+/// A receiver of the inputs of each frame.
+pub trait InputSink {
+    /// Receives `input`, the input of the frame `idx`.
+    fn frame(&mut self, idx: usize, input: &Input);
+}
+
+impl<S: InputSink + ?Sized> InputSink for &mut S {
+    fn frame(&mut self, idx: usize, input: &Input) {
+        (**self).frame(idx, input);
+    }
+}
+
+impl InputSink for Vec<(usize, Input)> {
+    fn frame(&mut self, idx: usize, input: &Input) {
+        self.push((idx, input.clone()));
+    }
+}
+
+/// A playback position in a movie, paced by its framerate including per-frame framerates.
+#[derive(Clone, Debug)]
+pub struct Player<'a> {
+    inputs: &'a [Input],
+    timeline: Timeline,
+    position: usize,
+    time: Duration,
+    paused: bool,
+}
+
+impl<'a> Player<'a> {
+    /// Returns a player at the first frame of `movie`.
+    pub fn new(movie: &'a LibTASMovie) -> Self {
+        Self {
+            inputs: &movie.inputs.0,
+            timeline: movie.timeline(),
+            position: 0,
+            time: Duration::ZERO,
+            paused: false,
+        }
+    }
+
+    /// Returns the next frame to be fed.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the playback time.
+    pub fn time(&self) -> Duration {
+        self.time
+    }
+
+    /// Returns whether every frame was fed.
+    pub fn is_finished(&self) -> bool {
+        self.position >= self.inputs.len()
+    }
+
+    /// Returns whether the player is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Pauses the player, so that [`Player::advance`] does nothing.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Resumes the player.
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// Moves to the start of `frame`, which is fed next. Frames past the end finish the playback.
+    pub fn seek(&mut self, frame: usize) {
+        self.position = frame.min(self.inputs.len());
+        self.time = self.timeline.time_of_frame(self.position);
+    }
+
+    /// Feeds the next frame to `sink` and moves the playback time to its start,
+    /// whether or not the player is paused. Returns `false` if the playback is finished.
+    pub fn step(&mut self, sink: &mut impl InputSink) -> bool {
+        let Some(input) = self.inputs.get(self.position) else {
+            return false;
+        };
+        self.time = self.time.max(self.timeline.time_of_frame(self.position));
+        sink.frame(self.position, input);
+        self.position += 1;
+        true
+    }
+
+    /// Advances the playback time by `elapsed` and feeds the frames starting until then.
+    /// Returns the number of fed frames, which is zero while paused.
+    pub fn advance(&mut self, elapsed: Duration, sink: &mut impl InputSink) -> usize {
+        if self.paused {
+            return 0;
+        }
+        self.time += elapsed;
+        let mut fed = 0;
+        while !self.is_finished() && self.timeline.time_of_frame(self.position) <= self.time {
+            let time = self.time;
+            self.step(sink);
+            self.time = time;
+            fed += 1;
+        }
+        fed
+    }
+
+    /// Plays the remaining frames in real time, sleeping until each frame starts.
+    /// Blocks until the playback is finished, regardless of pausing.
+    pub fn run(&mut self, sink: &mut impl InputSink) {
+        let start = Instant::now();
+        let offset = self.time;
+        while !self.is_finished() {
+            let due = self
+                .timeline
+                .time_of_frame(self.position)
+                .saturating_sub(offset);
+            let now = start.elapsed();
+            if due > now {
+                std::thread::sleep(due - now);
+            }
+            self.step(sink);
+        }
+    }
+}
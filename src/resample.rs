@@ -0,0 +1,225 @@
+//! Module that converts movies to another framerate.
+
+use core::time::Duration;
+
+use crate::{
+    comment::FrameComments,
+    greenzone::Greenzone,
+    inputs::{Input, Inputs, KeyboardInput, MouseInput, ReferenceMode},
+    movie::LibTASMovie,
+    time::{Framerate, Timeline},
+};
+
+/// How frames are filled when resampling to a higher framerate.
+///
+/// When resampling to a lower framerate, each frame merges the frames starting during it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ResampleStrategy {
+    /// Each input is held for its whole duration, e.g. each frame is duplicated from 30 to 60 fps.
+    #[default]
+    Duplicate,
+    /// Each input is only on the first frame of its duration, followed by empty frames
+    /// keeping the pointer position, e.g. for games reacting to presses.
+    Spread,
+}
+
+/// Merges `inputs` into one frame: keys and buttons held in any frame are held,
+/// absolute pointers take the last position, and relative motions are summed.
+/// A relative motion after an absolute position moves that position.
+/// Each controller takes its last state, the flags of all frames are set,
+/// other unknown sections are kept once, and the framerate is the last one.
+fn merge(inputs: &[Input]) -> Input {
+    let mut merged = Input::default();
+    for input in inputs {
+        for controller in &input.controllers {
+            match merged
+                .controllers
+                .iter_mut()
+                .find(|merged| merged.number == controller.number)
+            {
+                Some(merged) => merged.state.clone_from(&controller.state),
+                None => merged.controllers.push(controller.clone()),
+            }
+        }
+        for section in &input.unknown {
+            let merged_flags = merged
+                .unknown
+                .iter()
+                .position(|merged| merged.starts_with('F'));
+            match (section.strip_prefix('F'), merged_flags) {
+                (Some(flags), Some(index)) => {
+                    let merged = &mut merged.unknown[index];
+                    for flag in flags.chars() {
+                        if !merged[1..].contains(flag) {
+                            merged.push(flag);
+                        }
+                    }
+                }
+                _ if merged.unknown.contains(section) => {}
+                _ => merged.unknown.push(section.clone()),
+            }
+        }
+        if input.framerate.is_some() {
+            merged.framerate = input.framerate;
+        }
+        if let Some(keyboard) = &input.keyboard {
+            let keys = &mut merged.keyboard.get_or_insert_with(KeyboardInput::default).0;
+            for &key in &keyboard.0 {
+                if !keys.contains(&key) {
+                    keys.push(key);
+                }
+            }
+        }
+        if let Some(mouse) = input.mouse {
+            merged.mouse = Some(match merged.mouse {
+                Some(old) => MouseInput {
+                    xpos: if mouse.reference_mode == ReferenceMode::Relative {
+                        old.xpos.saturating_add(mouse.xpos)
+                    } else {
+                        mouse.xpos
+                    },
+                    ypos: if mouse.reference_mode == ReferenceMode::Relative {
+                        old.ypos.saturating_add(mouse.ypos)
+                    } else {
+                        mouse.ypos
+                    },
+                    reference_mode: if mouse.reference_mode == ReferenceMode::Relative {
+                        old.reference_mode
+                    } else {
+                        ReferenceMode::Absolute
+                    },
+                    left_click: old.left_click || mouse.left_click,
+                    middle_click: old.middle_click || mouse.middle_click,
+                    right_click: old.right_click || mouse.right_click,
+                    button4: old.button4 || mouse.button4,
+                    button5: old.button5 || mouse.button5,
+                },
+                None => mouse,
+            });
+        }
+    }
+    merged
+}
+
+/// Returns the frame at `framerate` during which `time` is.
+fn frame_at(framerate: Framerate, time: Duration) -> u64 {
+    let frame =
+        time.as_nanos() * u128::from(framerate.num) / (u128::from(framerate.den) * 1_000_000_000);
+    frame as u64
+}
+
+impl LibTASMovie {
+    /// Converts the movie to the constant framerate `framerate` using `strategy`,
+    /// keeping its duration up to a frame. Per-frame framerates are removed.
+    ///
+    /// The config framerate, `frame_count`, and the length are updated,
+    /// and markers, comments, and lines of the `inputs` file that are not frames
+    /// are moved to the frame at the same time.
+    /// The greenzone is removed, as its savestates no longer match.
+    /// Does nothing if `framerate` is zero.
+    pub fn resample(&mut self, framerate: Framerate, strategy: ResampleStrategy) {
+        if framerate.num == 0 || framerate.den == 0 {
+            return;
+        }
+        let timeline = self.timeline();
+        let end = timeline.time_of_frame(timeline.len());
+        let mut frames = frame_at(framerate, end);
+        if framerate.duration_of(frames) < end {
+            frames += 1;
+        }
+
+        let source = &self.inputs.0;
+        let mut inputs = Vec::with_capacity(frames as usize);
+        let mut next = 0;
+        for frame in 0..frames {
+            let frame_end = framerate.duration_of(frame + 1);
+            let first = next;
+            while next < source.len() && timeline.time_of_frame(next) < frame_end {
+                next += 1;
+            }
+            let mut input = if first < next {
+                merge(&source[first..next])
+            } else {
+                // a source frame lasting over several frames
+                let held = &source[next.saturating_sub(1)];
+                match strategy {
+                    // relative motions and flags already happened on the first frame
+                    ResampleStrategy::Duplicate => Input {
+                        mouse: held.mouse.map(|mouse| match mouse.reference_mode {
+                            ReferenceMode::Absolute => mouse,
+                            ReferenceMode::Relative => MouseInput {
+                                xpos: 0,
+                                ypos: 0,
+                                ..mouse
+                            },
+                        }),
+                        unknown: held
+                            .unknown
+                            .iter()
+                            .filter(|section| !section.starts_with('F'))
+                            .cloned()
+                            .collect(),
+                        ..held.clone()
+                    },
+                    ResampleStrategy::Spread => Input {
+                        mouse: held
+                            .mouse
+                            .filter(|mouse| mouse.reference_mode == ReferenceMode::Absolute)
+                            .map(|mouse| MouseInput {
+                                xpos: mouse.xpos,
+                                ypos: mouse.ypos,
+                                ..MouseInput::default()
+                            }),
+                        ..Input::default()
+                    },
+                }
+            };
+            input.framerate = None;
+            inputs.push(input);
+        }
+
+        let remap = |frame: u64, timeline: &Timeline| {
+            frame_at(framerate, timeline.time_of_frame(frame as usize))
+                .min(frames.saturating_sub(1))
+        };
+        for marker in &mut self.editor.markers {
+            marker.frame = remap(marker.frame, &timeline);
+        }
+        if let Some(comments) = self.sections.get_mut::<FrameComments>() {
+            let old = core::mem::take(&mut comments.0);
+            for (frame, text) in old {
+                let frame = remap(frame, &timeline);
+                match comments.0.get_mut(&frame) {
+                    Some(merged) => {
+                        merged.push('\n');
+                        merged.push_str(&text);
+                    }
+                    None => {
+                        comments.0.insert(frame, text);
+                    }
+                }
+            }
+        }
+        self.sections.remove::<Greenzone>();
+        let lines = core::mem::take(&mut self.inputs.1)
+            .into_iter()
+            .map(|(frame, line)| {
+                let frame = if frame < timeline.len() {
+                    remap(frame as u64, &timeline) as usize
+                } else {
+                    frames as usize
+                };
+                (frame, line)
+            })
+            .collect();
+
+        let general = &mut self.config.general;
+        let was_complete = general.savestate_frame_count == general.frame_count;
+        general.set_framerate(framerate);
+        self.inputs = Inputs(inputs, lines);
+        self.sync_length();
+        if was_complete {
+            self.config.general.savestate_frame_count = self.config.general.frame_count;
+        }
+    }
+}
@@ -0,0 +1,86 @@
+//! Module that defines movies beginning from a savestate.
+//!
+//! libTAS movies always begin at power-on. A savestate-anchored movie instead contains
+//! the inputs after a frame of another (power-on) movie, and is played by loading a savestate
+//! made at that frame first. The anchor is kept in [`LibTASMovie::anchor`] while editing,
+//! and is not stored in the movie file, which libTAS would not understand.
+
+use core::ops::Range;
+
+use crate::movie::LibTASMovie;
+
+impl LibTASMovie {
+    /// Returns whether the movie begins from a savestate instead of power-on.
+    pub fn is_savestate_anchored(&self) -> bool {
+        self.anchor.is_some()
+    }
+
+    /// Returns the frame of the power-on movie at which the movie begins,
+    /// or `None` if the movie begins at power-on.
+    pub fn anchor_frame(&self) -> Option<u64> {
+        self.anchor
+    }
+
+    /// Returns a savestate-anchored movie containing the frames in `range`,
+    /// to be played from a savestate at `range.start`.
+    ///
+    /// Frame counts, the length, markers, and lines of the `inputs` file that are not frames
+    /// are adjusted to the new movie.
+    /// Returns `None` if the range is out of bounds.
+    pub fn to_savestate_anchored(&self, range: Range<usize>) -> Option<Self> {
+        self.inputs.0.get(range.clone())?;
+        let base = self.anchor_frame().unwrap_or(0);
+
+        let mut movie = self.clone();
+        movie.raw = None;
+        // lines before the first frame and after the last frame belong to the range
+        movie
+            .inputs
+            .1
+            .retain(|(frame, _)| (range.start..=range.end).contains(frame));
+        let len = movie.inputs.0.len();
+        movie.inputs.splice_frames(range.end..len, []);
+        movie.inputs.splice_frames(0..range.start, []);
+        movie
+            .editor
+            .markers
+            .retain(|marker| range.contains(&(marker.frame as usize)));
+        for marker in &mut movie.editor.markers {
+            marker.frame -= range.start as u64;
+        }
+        movie.anchor = Some(base + range.start as u64);
+        movie.sync_length();
+        movie.config.general.savestate_frame_count = movie.config.general.frame_count;
+        Some(movie)
+    }
+
+    /// Returns the power-on movie made by playing the first frames of `power_on` up to the anchor,
+    /// followed by this movie.
+    ///
+    /// Returns `None` if this movie is not savestate-anchored,
+    /// or if `power_on` is shorter than the anchor frame.
+    pub fn to_power_on(&self, power_on: &Self) -> Option<Self> {
+        let anchor = self.anchor_frame()? as usize;
+        let prefix = power_on.inputs.0.get(..anchor)?;
+
+        let mut movie = self.clone();
+        movie.raw = None;
+        movie.anchor = None;
+        movie.inputs.splice_frames(0..0, prefix.iter().cloned());
+        for marker in &mut movie.editor.markers {
+            marker.frame += anchor as u64;
+        }
+        let mut markers: Vec<_> = power_on
+            .editor
+            .markers
+            .iter()
+            .filter(|marker| (marker.frame as usize) < anchor)
+            .cloned()
+            .collect();
+        markers.append(&mut movie.editor.markers);
+        movie.editor.markers = markers;
+        movie.sync_length();
+        movie.config.general.savestate_frame_count = movie.config.general.frame_count;
+        Some(movie)
+    }
+}
@@ -0,0 +1,267 @@
+//! Module that runs [rhai](https://rhai.rs/) scripts to transform movies.
+//!
+//! This module is only available with the `scripting` feature.
+//!
+//! Scripts see the movie as the variable `movie`, with these properties and methods:
+//! - `movie.game_name`, `movie.authors`, and `movie.rerecord_count`, which can be assigned,
+//! - `movie.frame_count`,
+//! - `movie.inputs.len()`,
+//! - `movie.inputs.keys(frame)`, the KeySyms held on `frame`,
+//! - `movie.inputs.is_pressed(frame, key)`,
+//! - `movie.inputs.set_key(frame, key, pressed)`,
+//! - `movie.inputs.clear(frame)`, which releases all keys and mouse buttons,
+//! - `movie.inputs.insert(frame, count)` and `movie.inputs.remove(frame, count)`,
+//!   which insert empty frames and remove frames,
+//! - `movie.inputs.mouse_x(frame)` and `movie.inputs.mouse_y(frame)`,
+//!   which are `()` without mouse input,
+//! - `movie.inputs.set_mouse(frame, x, y)`.
+//!
+//! Keys are KeySym values or names such as `"z"` or `"Right"`.
+//! The function `keysym(name)` returns the value of a named key.
+//!
+//! # Example
+//! ```
+//! use libtas_movie::{scripting::run_script, testkit::sample_movie};
+//!
+//! let mut movie = sample_movie(10, 60);
+//! let script = r#"
+//!     for frame in 0..movie.inputs.len() {
+//!         movie.inputs.set_key(frame, "space", frame % 2 == 0);
+//!     }
+//! "#;
+//! run_script(&mut movie, script).unwrap();
+//! assert!(movie.inputs.0[4].keyboard.as_ref().unwrap().0.contains(&0x20));
+//! ```
+
+use core::cell::RefCell;
+use std::rc::Rc;
+
+use rhai::{Dynamic, Engine, EvalAltResult, INT, ImmutableString, Position, Scope};
+
+use crate::{
+    inputs::{Input, KeyboardInput, MouseInput, keysym_from_name},
+    movie::LibTASMovie,
+};
+
+/// An error while running a script, containing the message of rhai.
+#[derive(Debug)]
+pub struct ScriptError(pub String);
+
+/// The result of a function called by a script.
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+/// The movie seen by a script.
+#[derive(Clone)]
+struct ScriptMovie(Rc<RefCell<LibTASMovie>>);
+
+/// The inputs of the movie seen by a script, sharing the movie.
+#[derive(Clone)]
+struct ScriptInputs(Rc<RefCell<LibTASMovie>>);
+
+/// Returns an error of a script with `message`.
+fn script_error<T>(message: String) -> ScriptResult<T> {
+    Err(Box::new(EvalAltResult::ErrorRuntime(
+        message.into(),
+        Position::NONE,
+    )))
+}
+
+/// Returns the KeySym of `key`, either a number or a name.
+fn key_value(key: &Dynamic) -> ScriptResult<u32> {
+    if let Some(key) = key.clone().try_cast::<INT>() {
+        return match u32::try_from(key) {
+            Ok(key) => Ok(key),
+            Err(_) => script_error(format!("invalid key {key}")),
+        };
+    }
+    if let Some(name) = key.clone().try_cast::<ImmutableString>() {
+        return match keysym_from_name(&name) {
+            Some(key) => Ok(key),
+            None => script_error(format!("unknown key {name}")),
+        };
+    }
+    script_error(format!("invalid key {key}"))
+}
+
+impl ScriptInputs {
+    /// Calls `f` with the input on `frame`, failing if `frame` is out of bounds.
+    fn with_input<T>(&mut self, frame: INT, f: impl FnOnce(&mut Input) -> T) -> ScriptResult<T> {
+        let mut movie = self.0.borrow_mut();
+        let len = movie.inputs.0.len();
+        match usize::try_from(frame)
+            .ok()
+            .and_then(|frame| movie.inputs.0.get_mut(frame))
+        {
+            Some(input) => Ok(f(input)),
+            None => script_error(format!("frame {frame} out of bounds (0..{len})")),
+        }
+    }
+
+    /// Replaces `removed` frames at `frame` with `inserted` empty frames.
+    fn splice(&mut self, frame: INT, removed: INT, inserted: INT) -> ScriptResult<()> {
+        let (Ok(frame), Ok(removed), Ok(inserted)) = (
+            usize::try_from(frame),
+            usize::try_from(removed),
+            usize::try_from(inserted),
+        ) else {
+            return script_error("negative frame or count".to_owned());
+        };
+        let mut movie = self.0.borrow_mut();
+        let len = movie.inputs.0.len();
+        let range = frame..frame.saturating_add(removed);
+        match movie.splice_inputs(range, vec![Input::default(); inserted]) {
+            Some(_) => Ok(()),
+            None => script_error(format!("frames out of bounds (0..{len})")),
+        }
+    }
+}
+
+/// Returns the engine with the movie model registered.
+fn engine() -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .register_type_with_name::<ScriptMovie>("Movie")
+        .register_get("inputs", |movie: &mut ScriptMovie| {
+            ScriptInputs(Rc::clone(&movie.0))
+        })
+        .register_get("frame_count", |movie: &mut ScriptMovie| {
+            movie.0.borrow().config.general.frame_count as INT
+        })
+        .register_get_set(
+            "game_name",
+            |movie: &mut ScriptMovie| movie.0.borrow().config.general.game_name.clone(),
+            |movie: &mut ScriptMovie, name: String| {
+                movie.0.borrow_mut().config.general.game_name = name;
+            },
+        )
+        .register_get_set(
+            "authors",
+            |movie: &mut ScriptMovie| movie.0.borrow().config.general.authors.clone(),
+            |movie: &mut ScriptMovie, authors: String| {
+                movie.0.borrow_mut().config.general.authors = authors;
+            },
+        )
+        .register_get_set(
+            "rerecord_count",
+            |movie: &mut ScriptMovie| movie.0.borrow().config.general.rerecord_count as INT,
+            |movie: &mut ScriptMovie, count: INT| {
+                movie.0.borrow_mut().config.general.rerecord_count = count.max(0) as u64;
+            },
+        );
+
+    engine
+        .register_type_with_name::<ScriptInputs>("Inputs")
+        .register_fn("len", |inputs: &mut ScriptInputs| {
+            inputs.0.borrow().inputs.0.len() as INT
+        })
+        .register_fn("keys", |inputs: &mut ScriptInputs, frame: INT| {
+            inputs.with_input(frame, |input| {
+                let keys = input
+                    .keyboard
+                    .as_ref()
+                    .map_or(&[][..], |keyboard| &keyboard.0);
+                keys.iter()
+                    .map(|&key| Dynamic::from(INT::from(key)))
+                    .collect::<rhai::Array>()
+            })
+        })
+        .register_fn(
+            "is_pressed",
+            |inputs: &mut ScriptInputs, frame: INT, key: Dynamic| {
+                let key = key_value(&key)?;
+                inputs.with_input(frame, |input| {
+                    input
+                        .keyboard
+                        .as_ref()
+                        .is_some_and(|keyboard| keyboard.0.contains(&key))
+                })
+            },
+        )
+        .register_fn(
+            "set_key",
+            |inputs: &mut ScriptInputs, frame: INT, key: Dynamic, pressed: bool| {
+                let key = key_value(&key)?;
+                inputs.with_input(frame, |input| {
+                    let keyboard = input.keyboard.get_or_insert_with(KeyboardInput::default);
+                    let held = keyboard.0.contains(&key);
+                    if pressed && !held {
+                        keyboard.0.push(key);
+                    } else if !pressed && held {
+                        keyboard.0.retain(|&other| other != key);
+                    }
+                })
+            },
+        )
+        .register_fn("clear", |inputs: &mut ScriptInputs, frame: INT| {
+            inputs.with_input(frame, |input| {
+                input.keyboard = None;
+                if let Some(mouse) = &mut input.mouse {
+                    *mouse = MouseInput {
+                        xpos: mouse.xpos,
+                        ypos: mouse.ypos,
+                        reference_mode: mouse.reference_mode,
+                        ..MouseInput::default()
+                    };
+                }
+            })
+        })
+        .register_fn(
+            "insert",
+            |inputs: &mut ScriptInputs, frame: INT, count: INT| inputs.splice(frame, 0, count),
+        )
+        .register_fn(
+            "remove",
+            |inputs: &mut ScriptInputs, frame: INT, count: INT| inputs.splice(frame, count, 0),
+        )
+        .register_fn("mouse_x", |inputs: &mut ScriptInputs, frame: INT| {
+            inputs.with_input(frame, |input| {
+                input
+                    .mouse
+                    .map_or(Dynamic::UNIT, |mouse| Dynamic::from(INT::from(mouse.xpos)))
+            })
+        })
+        .register_fn("mouse_y", |inputs: &mut ScriptInputs, frame: INT| {
+            inputs.with_input(frame, |input| {
+                input
+                    .mouse
+                    .map_or(Dynamic::UNIT, |mouse| Dynamic::from(INT::from(mouse.ypos)))
+            })
+        })
+        .register_fn(
+            "set_mouse",
+            |inputs: &mut ScriptInputs, frame: INT, x: INT, y: INT| {
+                let (Ok(x), Ok(y)) = (i32::try_from(x), i32::try_from(y)) else {
+                    return script_error(format!("invalid coordinates {x}, {y}"));
+                };
+                inputs.with_input(frame, |input| {
+                    let mouse = input.mouse.get_or_insert_with(MouseInput::default);
+                    mouse.xpos = x;
+                    mouse.ypos = y;
+                })
+            },
+        );
+
+    engine.register_fn("keysym", |name: &str| match keysym_from_name(name) {
+        Some(key) => Ok(INT::from(key)),
+        None => script_error(format!("unknown key {name}")),
+    });
+    engine
+}
+
+/// Runs `script` with `movie` as the variable `movie`, applying its edits to `movie`.
+///
+/// On error, the edits made before the error are kept.
+pub fn run_script(movie: &mut LibTASMovie, script: &str) -> Result<(), ScriptError> {
+    let shared = Rc::new(RefCell::new(core::mem::take(movie)));
+    let mut scope = Scope::new();
+    scope.push("movie", ScriptMovie(Rc::clone(&shared)));
+    let result = engine().run_with_scope(&mut scope, script);
+    drop(scope);
+
+    *movie = match Rc::try_unwrap(shared) {
+        Ok(edited) => edited.into_inner(),
+        // the script leaked a reference, e.g. in a returned value
+        Err(shared) => shared.borrow().clone(),
+    };
+    result.map_err(|err| ScriptError(err.to_string()))
+}
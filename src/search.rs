@@ -0,0 +1,73 @@
+//! Module that searches inputs for sequences of frames.
+
+use crate::inputs::{Input, Inputs};
+
+/// Options of how frames are compared in [`Inputs::find_sequence`].
+///
+/// Framerates are never compared. Keys are compared regardless of their order,
+/// and a frame without keyboard input matches a frame with no keys.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MatchOptions {
+    /// Whether to ignore mouse inputs.
+    pub ignore_mouse: bool,
+    /// Whether frames may press keys that are not in the sequence.
+    pub ignore_extra_keys: bool,
+}
+
+impl MatchOptions {
+    /// Returns whether `input` matches the frame `pattern` of a sequence.
+    pub fn matches(&self, input: &Input, pattern: &Input) -> bool {
+        let keys = |input: &Input| {
+            let mut keys = input
+                .keyboard
+                .as_ref()
+                .map(|keyboard| keyboard.0.clone())
+                .unwrap_or_default();
+            keys.sort_unstable();
+            keys.dedup();
+            keys
+        };
+        let (keys, pattern_keys) = (keys(input), keys(pattern));
+        let keys_match = if self.ignore_extra_keys {
+            pattern_keys
+                .iter()
+                .all(|key| keys.binary_search(key).is_ok())
+        } else {
+            keys == pattern_keys
+        };
+        keys_match
+            && (self.ignore_mouse || input.mouse == pattern.mouse)
+            && input.controllers == pattern.controllers
+    }
+}
+
+impl Inputs {
+    /// Returns the first frame of every occurrence of `sequence`, including overlapping ones.
+    /// Returns no occurrence if `sequence` is empty.
+    ///
+    /// # Example
+    /// ```
+    /// use libtas_movie::{search::MatchOptions, testkit::sample_movie};
+    ///
+    /// // frames cycle through nothing, z, Right, and z with Right
+    /// let inputs = sample_movie(10, 60).inputs;
+    /// let sequence = &inputs.0[1..3];
+    /// assert_eq!(inputs.find_sequence(sequence, MatchOptions::default()), [1, 5]);
+    /// ```
+    pub fn find_sequence(&self, sequence: &[Input], options: MatchOptions) -> Vec<usize> {
+        if sequence.is_empty() {
+            return vec![];
+        }
+        self.0
+            .windows(sequence.len())
+            .enumerate()
+            .filter(|(_, window)| {
+                window
+                    .iter()
+                    .zip(sequence)
+                    .all(|(input, pattern)| options.matches(input, pattern))
+            })
+            .map(|(start, _)| start)
+            .collect()
+    }
+}
@@ -0,0 +1,220 @@
+//! Module that defines custom entries of a movie archive.
+//!
+//! Applications can attach their own files to a movie by implementing [`MovieSection`],
+//! registering it in a [`SectionRegistry`] when loading, and inserting it into [`Sections`].
+
+use core::{any::Any, fmt::Debug};
+
+use crate::{branch::Branches, comment::FrameComments, greenzone::Greenzone};
+
+/// An error while parsing a custom section, containing the reason of the error.
+#[derive(Debug)]
+pub struct InvalidSectionError(pub String);
+
+/// A custom file in a movie archive, parsed into a typed value.
+///
+/// # Example
+/// ```
+/// use libtas_movie::section::{InvalidSectionError, MovieSection};
+///
+/// #[derive(Clone, Debug, PartialEq)]
+/// struct RouteNotes(String);
+///
+/// impl MovieSection for RouteNotes {
+///     fn name() -> &'static str {
+///         "route.txt"
+///     }
+///
+///     fn parse(data: &[u8]) -> Result<Self, InvalidSectionError> {
+///         match String::from_utf8(data.to_vec()) {
+///             Ok(string) => Ok(Self(string)),
+///             Err(err) => Err(InvalidSectionError(err.to_string())),
+///         }
+///     }
+///
+///     fn serialize(&self) -> Vec<u8> {
+///         self.0.as_bytes().to_vec()
+///     }
+/// }
+/// ```
+pub trait MovieSection: Clone + Debug + PartialEq + 'static {
+    /// The file name of the entry in the archive.
+    fn name() -> &'static str;
+    /// Parses the contents of the entry.
+    fn parse(data: &[u8]) -> Result<Self, InvalidSectionError>;
+    /// Serializes into the contents of the entry.
+    fn serialize(&self) -> Vec<u8>;
+}
+
+/// An object-safe version of [`MovieSection`], used to store sections of different types.
+trait DynSection: Debug {
+    fn name(&self) -> &'static str;
+    fn serialize(&self) -> Vec<u8>;
+    fn clone_box(&self) -> Box<dyn DynSection>;
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn eq_dyn(&self, other: &dyn DynSection) -> bool;
+}
+
+impl<T: MovieSection> DynSection for T {
+    fn name(&self) -> &'static str {
+        T::name()
+    }
+
+    fn serialize(&self) -> Vec<u8> {
+        MovieSection::serialize(self)
+    }
+
+    fn clone_box(&self) -> Box<dyn DynSection> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn eq_dyn(&self, other: &dyn DynSection) -> bool {
+        other.as_any().downcast_ref::<T>() == Some(self)
+    }
+}
+
+/// Custom sections attached to a movie, at most one per type.
+#[derive(Debug, Default)]
+pub struct Sections(Vec<Box<dyn DynSection>>);
+
+impl Clone for Sections {
+    fn clone(&self) -> Self {
+        Self(self.0.iter().map(|section| section.clone_box()).collect())
+    }
+}
+
+impl PartialEq for Sections {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.len() == other.0.len()
+            && self
+                .0
+                .iter()
+                .all(|section| other.0.iter().any(|other| section.eq_dyn(other.as_ref())))
+    }
+}
+
+impl Eq for Sections {}
+
+impl Sections {
+    /// Returns the section of type `T`.
+    pub fn get<T: MovieSection>(&self) -> Option<&T> {
+        self.0
+            .iter()
+            .find_map(|section| section.as_any().downcast_ref::<T>())
+    }
+
+    /// Returns the section of type `T` mutably.
+    pub fn get_mut<T: MovieSection>(&mut self) -> Option<&mut T> {
+        self.0
+            .iter_mut()
+            .find_map(|section| section.as_any_mut().downcast_mut::<T>())
+    }
+
+    /// Returns the section of type `T` mutably, inserting the default value if missing.
+    pub fn get_or_insert_default<T: MovieSection + Default>(&mut self) -> &mut T {
+        let index = match self.0.iter().position(|section| section.as_any().is::<T>()) {
+            Some(index) => index,
+            None => {
+                self.0.push(Box::new(T::default()));
+                self.0.len() - 1
+            }
+        };
+        self.0[index]
+            .as_any_mut()
+            .downcast_mut()
+            .unwrap_or_else(|| unreachable!())
+    }
+
+    /// Inserts `section`, returning the previous section of the same type.
+    pub fn insert<T: MovieSection>(&mut self, section: T) -> Option<T> {
+        let old = self.remove::<T>();
+        self.0.push(Box::new(section));
+        old
+    }
+
+    /// Removes and returns the section of type `T`.
+    pub fn remove<T: MovieSection>(&mut self) -> Option<T> {
+        let index = self
+            .0
+            .iter()
+            .position(|section| section.as_any().is::<T>())?;
+        let section = self.0.remove(index);
+        section.as_any().downcast_ref::<T>().cloned()
+    }
+
+    /// Returns whether there is no section.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the file name and the serialized contents of each section.
+    pub fn entries(&self) -> impl Iterator<Item = (&'static str, Vec<u8>)> + '_ {
+        self.0
+            .iter()
+            .map(|section| (section.name(), section.serialize()))
+    }
+
+    /// Parses `data` as the entry `name` and inserts it, if the entry is registered.
+    /// Returns `Ok(false)` if the entry is not registered.
+    pub(crate) fn load(
+        &mut self,
+        registry: &SectionRegistry,
+        name: &str,
+        data: &[u8],
+    ) -> Result<bool, InvalidSectionError> {
+        let Some((_, parse)) = registry.0.iter().find(|(entry, _)| *entry == name) else {
+            return Ok(false);
+        };
+        let section = parse(data)?;
+        self.0.retain(|old| old.name() != section.name());
+        self.0.push(section);
+        Ok(true)
+    }
+}
+
+/// A parser of a registered section.
+type SectionParser = fn(&[u8]) -> Result<Box<dyn DynSection>, InvalidSectionError>;
+
+/// The set of custom sections recognized while loading a movie.
+#[derive(Clone, Debug, Default)]
+pub struct SectionRegistry(Vec<(&'static str, SectionParser)>);
+
+impl SectionRegistry {
+    /// Returns an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a registry of the sections defined by this crate,
+    /// such as [`Branches`], [`Greenzone`], and [`FrameComments`].
+    pub fn builtin() -> Self {
+        let mut registry = Self::new();
+        registry.register::<Branches>();
+        registry.register::<Greenzone>();
+        registry.register::<FrameComments>();
+        registry
+    }
+
+    /// Registers the section type `T`, replacing any section with the same file name.
+    pub fn register<T: MovieSection>(&mut self) -> &mut Self {
+        self.0.retain(|(name, _)| *name != T::name());
+        self.0.push((T::name(), |data| {
+            T::parse(data).map(|section| Box::new(section) as Box<dyn DynSection>)
+        }));
+        self
+    }
+
+    /// Returns whether the entry `name` is registered.
+    pub fn contains(&self, name: &str) -> bool {
+        self.0.iter().any(|(entry, _)| *entry == name)
+    }
+}
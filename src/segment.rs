@@ -0,0 +1,107 @@
+//! Module that defines segments of a movie, labelled ranges of frames.
+
+use core::{ops::Range, slice::Iter};
+
+use crate::{editor::Editor, inputs::Inputs};
+
+/// A labelled range of frames.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Segment {
+    /// The frames of the segment.
+    pub frames: Range<usize>,
+    /// The label of the segment.
+    pub label: String,
+}
+
+/// Segments of a movie, sorted by their first frame.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Segments(pub Vec<Segment>);
+
+impl Segments {
+    /// Splits `inputs` at the markers of `editor`, each segment labelled by the text of its marker
+    /// and lasting until the next marker or the end of the inputs.
+    ///
+    /// Frames before the first marker form a segment with an empty label.
+    /// Of markers on the same frame, the first one is used, and markers past the end are ignored.
+    pub fn from_markers(editor: &Editor, inputs: &Inputs) -> Self {
+        let len = inputs.0.len();
+        let mut starts: Vec<(usize, &str)> = vec![];
+        for marker in editor.sorted_markers() {
+            let frame = usize::try_from(marker.frame).unwrap_or(usize::MAX);
+            if frame < len && starts.last().is_none_or(|&(last, _)| last != frame) {
+                starts.push((frame, &marker.text));
+            }
+        }
+        if starts.first().is_none_or(|&(first, _)| first != 0) && len != 0 {
+            starts.insert(0, (0, ""));
+        }
+
+        let ends = starts.iter().skip(1).map(|&(start, _)| start).chain([len]);
+        Self(
+            starts
+                .iter()
+                .zip(ends)
+                .map(|(&(start, label), end)| Segment {
+                    frames: start..end,
+                    label: label.to_owned(),
+                })
+                .collect(),
+        )
+    }
+
+    /// Splits `len` frames into windows of `window` frames, labelled by their frame ranges.
+    /// The last window may be shorter. A `window` of 0 is treated as 1.
+    pub fn windows(len: usize, window: usize) -> Self {
+        let window = window.max(1);
+        Self(
+            (0..len)
+                .step_by(window)
+                .map(|start| {
+                    let end = (start + window).min(len);
+                    Segment {
+                        frames: start..end,
+                        label: format!("{start}-{}", end - 1),
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    /// Returns the segment containing `frame`.
+    pub fn segment_at(&self, frame: usize) -> Option<&Segment> {
+        let index = self
+            .0
+            .partition_point(|segment| segment.frames.start <= frame);
+        let segment = self.0.get(index.checked_sub(1)?)?;
+        segment.frames.contains(&frame).then_some(segment)
+    }
+
+    /// Returns the segment labelled `label`.
+    pub fn segment(&self, label: &str) -> Option<&Segment> {
+        self.0.iter().find(|segment| segment.label == label)
+    }
+
+    /// Returns the segments in order.
+    pub fn iter(&self) -> Iter<'_, Segment> {
+        self.0.iter()
+    }
+
+    /// Returns the number of segments.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns whether there is no segment.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl<'a> IntoIterator for &'a Segments {
+    type Item = &'a Segment;
+    type IntoIter = Iter<'a, Segment>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
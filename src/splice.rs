@@ -0,0 +1,276 @@
+//! Module that defines insertion and deletion of frames.
+
+use core::ops::Range;
+
+use crate::{
+    comment::FrameComments,
+    editor::Marker,
+    greenzone::Greenzone,
+    inputs::{Input, Inputs},
+    movie::LibTASMovie,
+    time::{Framerate, gcd},
+};
+
+/// How the timing of a movie changes when frames are replaced.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SpliceTiming {
+    /// Frames keep their framerate, so the frames after the edit move in time.
+    #[default]
+    Shift,
+    /// The frames replacing the range are given framerates so that they last as long as
+    /// the removed frames, and the frames after the edit keep their time.
+    ///
+    /// When frames are only inserted or only deleted, the time is shared with the frame
+    /// before the edit, or the frame after it at the start of the movie.
+    /// This writes per-frame framerates and turns on `variable_framerate`.
+    Preserve,
+}
+
+/// Options of [`Inputs::append_from`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AppendOptions {
+    /// The framerate of the frames without their own framerate in the inputs appended to.
+    pub framerate: Framerate,
+    /// The framerate of the frames without their own framerate in the appended inputs.
+    pub other_framerate: Framerate,
+    /// Whether the game restarts at the seam, e.g. when the appended inputs start at power-on
+    /// rather than where these inputs end.
+    pub restart: bool,
+}
+
+impl Inputs {
+    /// Appends the frames of `other`, and the lines that are not frames, after these inputs.
+    ///
+    /// Appended frames without their own framerate keep `options.other_framerate` as their
+    /// framerate if it differs from `options.framerate`, so that they last as long as in
+    /// `other`. Returns whether such per-frame framerates were written, in which case
+    /// `variable_framerate` must be turned on for libTAS to read them.
+    ///
+    /// The first appended frame restarts the game if `options.restart` is set,
+    /// and otherwise keeps its own restart flag. The flags of the other frames are kept.
+    pub fn append_from(&mut self, other: &Self, options: AppendOptions) -> bool {
+        let offset = self.0.len();
+        let carry = options.framerate != options.other_framerate;
+        let mut written = false;
+        self.splice_frames(
+            offset..offset,
+            other.0.iter().enumerate().map(|(frame, input)| {
+                let mut input = input.clone();
+                if carry && input.framerate.is_none() {
+                    input.framerate = Some(options.other_framerate);
+                    written = true;
+                }
+                if frame == 0 && options.restart {
+                    input.set_restart(true);
+                }
+                input
+            }),
+        );
+        for (frame, line) in &other.1 {
+            self.insert_line(frame + offset, line.clone());
+        }
+        written
+    }
+}
+
+impl LibTASMovie {
+    /// Appends the frames of `other` after the frames of this movie,
+    /// with [`Inputs::append_from`], updating the frame count and the length.
+    ///
+    /// The markers and comments of `other` are moved after the frames of this movie.
+    /// The rerecord counts are added, and the input devices of both movies are supported.
+    /// A movie saved at its end stays saved at its end.
+    /// The game does not restart at the seam, so that segments from [`LibTASMovie::extract`]
+    /// are stitched back together.
+    pub fn append(&mut self, other: &Self) {
+        let offset = self.inputs.0.len() as u64;
+        let general = &self.config.general;
+        let was_complete = general.savestate_frame_count == general.frame_count;
+        let options = AppendOptions {
+            framerate: self.framerate(),
+            other_framerate: other.framerate(),
+            restart: false,
+        };
+        if self.inputs.append_from(&other.inputs, options) {
+            self.config.general.variable_framerate = true;
+        }
+        self.config.general.variable_framerate |= other.config.general.variable_framerate;
+
+        self.editor
+            .markers
+            .extend(other.editor.markers.iter().map(|marker| Marker {
+                frame: marker.frame + offset,
+                text: marker.text.clone(),
+            }));
+        if let Some(other_comments) = other.sections.get::<FrameComments>() {
+            let comments = self.sections.get_or_insert_default::<FrameComments>();
+            for (frame, text) in &other_comments.0 {
+                comments.0.insert(frame + offset, text.clone());
+            }
+        }
+
+        let general = &mut self.config.general;
+        let other_general = &other.config.general;
+        general.rerecord_count = general
+            .rerecord_count
+            .saturating_add(other_general.rerecord_count);
+        general.mouse_support |= other_general.mouse_support;
+        general.nb_controllers = general.nb_controllers.max(other_general.nb_controllers);
+        self.sync_length();
+        if was_complete {
+            self.config.general.savestate_frame_count = self.config.general.frame_count;
+        }
+    }
+
+    /// Returns a new movie with only the frames in `range`, e.g. to share a segment,
+    /// or `None` if the range is out of bounds.
+    ///
+    /// The frame count and the length are updated, and the movie is saved at its end.
+    /// Markers, comments, and lines of the `inputs` file that are not frames are kept
+    /// within the range. The rerecord count is reset, since the rerecords of this movie
+    /// cannot be attributed to a range, and the greenzone is dropped.
+    pub fn extract(&self, range: Range<usize>) -> Option<Self> {
+        self.inputs.0.get(range.clone())?;
+        let mut movie = self.clone();
+        movie.raw = None;
+        movie.sections.remove::<Greenzone>();
+        let (start, end) = (range.start as u64, range.end as u64);
+        movie
+            .editor
+            .markers
+            .retain(|marker| (start..end).contains(&marker.frame));
+        // lines before the first frame and after the last frame belong to the range
+        movie
+            .inputs
+            .1
+            .retain(|(frame, _)| (range.start..=range.end).contains(frame));
+
+        let len = movie.inputs.0.len();
+        movie.splice_inputs(range.end..len, vec![]);
+        movie.splice_inputs(0..range.start, vec![]);
+        movie.config.general.rerecord_count = 0;
+        movie.config.general.savestate_frame_count = movie.config.general.frame_count;
+        Some(movie)
+    }
+
+    /// Replaces the frames in `range` with `inputs`, re-indexing the comments and the markers,
+    /// and updating the frame count and the length.
+    /// Returns the removed frames, or `None` without editing if the range is out of bounds.
+    ///
+    /// Comments of deleted frames are removed, and markers of deleted frames are moved
+    /// to the frame after the edit, as are lines of the `inputs` file that are not frames.
+    pub fn splice_inputs(&mut self, range: Range<usize>, inputs: Vec<Input>) -> Option<Vec<Input>> {
+        self.splice_inputs_with(range, inputs, SpliceTiming::Shift)
+    }
+
+    /// Replaces the frames in `range` with `inputs` like [`LibTASMovie::splice_inputs`],
+    /// with the timing `timing`.
+    pub fn splice_inputs_with(
+        &mut self,
+        range: Range<usize>,
+        mut inputs: Vec<Input>,
+        timing: SpliceTiming,
+    ) -> Option<Vec<Input>> {
+        self.inputs.0.get(range.clone())?;
+        let (at, removed_len, inserted_len) = (range.start, range.len(), inputs.len());
+
+        if timing == SpliceTiming::Preserve {
+            self.preserve_time(range.clone(), &mut inputs);
+        }
+
+        let removed = self.inputs.splice_frames(range, inputs);
+
+        let (at, removed_len, inserted_len) = (at as u64, removed_len as u64, inserted_len as u64);
+        if let Some(comments) = self.sections.get_mut::<FrameComments>() {
+            comments.splice(at, removed_len, inserted_len);
+        }
+        for marker in &mut self.editor.markers {
+            if marker.frame >= at + removed_len {
+                marker.frame = marker.frame - removed_len + inserted_len;
+            } else if marker.frame >= at + inserted_len {
+                marker.frame = at + inserted_len;
+            }
+        }
+        self.sync_length();
+        Some(removed)
+    }
+
+    /// Sets the framerates of `inputs` so that they last as long as the frames in `range`.
+    /// The neighboring frame shares the time if either is empty.
+    fn preserve_time(&mut self, range: Range<usize>, inputs: &mut [Input]) {
+        let default = self.config.general.framerate();
+        let framerate_of = |input: &Input| input.framerate.unwrap_or(default);
+
+        // the frames sharing the duration, with the duration in seconds as a fraction
+        let mut duration = (0u128, 1u128);
+        for input in &self.inputs.0[range.clone()] {
+            let Some(sum) = add_frame(duration, framerate_of(input)) else {
+                return;
+            };
+            duration = sum;
+        }
+        if inputs.is_empty() && range.is_empty() {
+            return;
+        }
+
+        let mut neighbor = None;
+        if inputs.is_empty() || range.is_empty() {
+            let index = if range.start > 0 {
+                range.start - 1
+            } else {
+                range.end
+            };
+            let Some(sum) = self
+                .inputs
+                .0
+                .get(index)
+                .and_then(|input| add_frame(duration, framerate_of(input)))
+            else {
+                return;
+            };
+            duration = sum;
+            neighbor = Some(index);
+        }
+        let (num, den) = duration;
+        if num == 0 {
+            return;
+        }
+
+        let count = inputs.len() as u128 + u128::from(neighbor.is_some());
+        let Some(fr_num) = count.checked_mul(den) else {
+            return;
+        };
+        let fr_den = num;
+        let gcd = gcd(fr_num, fr_den).max(1);
+        let (Ok(fr_num), Ok(fr_den)) = (u64::try_from(fr_num / gcd), u64::try_from(fr_den / gcd))
+        else {
+            return;
+        };
+        let framerate = Framerate {
+            num: fr_num,
+            den: fr_den,
+        };
+        for input in inputs {
+            input.framerate = Some(framerate);
+        }
+        if let Some(index) = neighbor {
+            self.inputs.0[index].framerate = Some(framerate);
+        }
+        self.config.general.variable_framerate = true;
+    }
+}
+
+/// Adds the duration in seconds of a frame at `framerate` to the fraction `num/den`,
+/// or returns `None` for a zero framerate or on overflow.
+fn add_frame((num, den): (u128, u128), framerate: Framerate) -> Option<(u128, u128)> {
+    if framerate.num == 0 || framerate.den == 0 {
+        return None;
+    }
+    let (term, term_den) = (u128::from(framerate.den), u128::from(framerate.num));
+    let lcm = (den / gcd(den, term_den)).checked_mul(term_den)?;
+    let num = num
+        .checked_mul(lcm / den)?
+        .checked_add(term.checked_mul(lcm / term_den)?)?;
+    let gcd = gcd(num, lcm).max(1);
+    Some((num / gcd, lcm / gcd))
+}
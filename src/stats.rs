@@ -0,0 +1,234 @@
+//! Module that computes statistics of inputs for visualization.
+
+use core::{fmt::Write as _, time::Duration};
+use std::collections::BTreeMap;
+
+use crate::{
+    inputs::{Input, Inputs, MouseButton, MouseInput, ReferenceMode},
+    segment::Segments,
+    time::Timeline,
+};
+
+/// A bucket of a [`Heatmap`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HeatmapCell {
+    /// The smallest X-coordinate of the bucket.
+    pub x: i32,
+    /// The smallest Y-coordinate of the bucket.
+    pub y: i32,
+    /// The number of frames whose pointer is in the bucket.
+    pub hovers: u64,
+    /// The number of button presses in the bucket, counted when a button goes down.
+    pub clicks: u64,
+}
+
+/// A 2D histogram of pointer positions, in square buckets.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Heatmap {
+    /// The width and height of the buckets in pixels.
+    pub bucket_size: u32,
+    /// The buckets with at least one hover, sorted by Y then X.
+    pub cells: Vec<HeatmapCell>,
+}
+
+impl Heatmap {
+    /// Returns the cells as CSV with a header row.
+    pub fn to_csv(&self) -> String {
+        let mut csv = "x,y,hovers,clicks\n".to_owned();
+        for cell in &self.cells {
+            let _ = writeln!(csv, "{},{},{},{}", cell.x, cell.y, cell.hovers, cell.clicks);
+        }
+        csv
+    }
+
+    /// Returns the heatmap as a JSON object.
+    pub fn to_json(&self) -> String {
+        let mut json = format!("{{\"bucket_size\": {}, \"cells\": [", self.bucket_size);
+        for (index, cell) in self.cells.iter().enumerate() {
+            if index != 0 {
+                json.push(',');
+            }
+            let _ = write!(
+                json,
+                "\n  {{\"x\": {}, \"y\": {}, \"hovers\": {}, \"clicks\": {}}}",
+                cell.x, cell.y, cell.hovers, cell.clicks
+            );
+        }
+        json.push_str("\n]}\n");
+        json
+    }
+}
+
+/// Returns the histogram of the pointer positions of `inputs` in buckets of `bucket_size` pixels.
+///
+/// Only frames with absolute coordinates are counted.
+/// A `bucket_size` of 0 is treated as 1.
+pub fn mouse_heatmap(inputs: &Inputs, bucket_size: u32) -> Heatmap {
+    let bucket_size = bucket_size.max(1);
+    let size = i64::from(bucket_size);
+    let mut cells = BTreeMap::<(i64, i64), (u64, u64)>::new();
+    let mut previous: Option<MouseInput> = None;
+    for input in &inputs.0 {
+        let Some(mouse) = input.mouse else {
+            previous = None;
+            continue;
+        };
+        let pressed = MouseButton::ALL
+            .iter()
+            .filter(|&&button| {
+                mouse.is_pressed(button)
+                    && !previous.is_some_and(|previous| previous.is_pressed(button))
+            })
+            .count() as u64;
+        previous = Some(mouse);
+        if mouse.reference_mode != ReferenceMode::Absolute {
+            continue;
+        }
+
+        let bucket = (
+            i64::from(mouse.ypos).div_euclid(size),
+            i64::from(mouse.xpos).div_euclid(size),
+        );
+        let (hovers, clicks) = cells.entry(bucket).or_default();
+        *hovers += 1;
+        *clicks += pressed;
+    }
+
+    let cells = cells
+        .into_iter()
+        .map(|((y, x), (hovers, clicks))| HeatmapCell {
+            x: (x * size) as i32,
+            y: (y * size) as i32,
+            hovers,
+            clicks,
+        })
+        .collect();
+    Heatmap { bucket_size, cells }
+}
+
+/// The key presses of a segment.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct KeyHistogram {
+    /// The label of the segment.
+    pub label: String,
+    /// The number of frames of the segment.
+    pub frames: usize,
+    /// The number of presses of each key by KeySym, counted when the key goes down.
+    pub presses: BTreeMap<u32, usize>,
+}
+
+impl KeyHistogram {
+    /// Returns the number of presses of all keys.
+    pub fn total(&self) -> usize {
+        self.presses.values().sum()
+    }
+}
+
+/// Returns the key presses of `inputs` in each of `segments`, in order.
+///
+/// A key held since before a segment is not counted as pressed in the segment.
+/// Frames beyond the end of `inputs` are ignored.
+pub fn key_histogram_by_segment(inputs: &Inputs, segments: &Segments) -> Vec<KeyHistogram> {
+    let keys = |frame: usize| held_keys(inputs.0.get(frame));
+    segments
+        .iter()
+        .map(|segment| {
+            let frames =
+                segment.frames.start.min(inputs.0.len())..segment.frames.end.min(inputs.0.len());
+            let mut presses = BTreeMap::new();
+            for frame in frames.clone() {
+                let previous = frame.checked_sub(1).map_or(&[][..], keys);
+                for key in keys(frame).iter().filter(|key| !previous.contains(key)) {
+                    *presses.entry(*key).or_default() += 1;
+                }
+            }
+            KeyHistogram {
+                label: segment.label.clone(),
+                frames: frames.len(),
+                presses,
+            }
+        })
+        .collect()
+}
+
+/// The actions in a window of an APM series.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ApmSample {
+    /// The start of the window.
+    pub start: Duration,
+    /// The number of key and mouse button presses in the window.
+    pub actions: usize,
+    /// The actions per minute in the window.
+    pub apm: f64,
+}
+
+/// Returns the keys pressed in `input`, if any.
+fn held_keys(input: Option<&Input>) -> &[u32] {
+    input
+        .and_then(|input| input.keyboard.as_ref())
+        .map_or(&[], |keyboard| &keyboard.0)
+}
+
+/// Returns the number of keys and mouse buttons of `input` that are not pressed in `previous`.
+fn count_presses(input: &Input, previous: Option<&Input>) -> usize {
+    let pressed = |input: Option<&Input>, button| {
+        input
+            .and_then(|input| input.mouse)
+            .is_some_and(|mouse| mouse.is_pressed(button))
+    };
+    let previous_keys = held_keys(previous);
+    let keys = held_keys(Some(input))
+        .iter()
+        .filter(|key| !previous_keys.contains(key))
+        .count();
+    let buttons = MouseButton::ALL
+        .into_iter()
+        .filter(|&button| pressed(Some(input), button) && !pressed(previous, button))
+        .count();
+    keys + buttons
+}
+
+/// The shortest window of [`apm_series`], so that short windows do not
+/// allocate a sample per few nanoseconds of the movie.
+pub const MIN_APM_WINDOW: Duration = Duration::from_millis(100);
+
+/// Returns the actions per minute of `inputs` in consecutive windows of `window`,
+/// timed with `timeline`. The last window may extend past the end of the movie.
+///
+/// Actions are presses of keys and mouse buttons.
+/// Returns no sample if `window` is zero, and windows shorter than
+/// [`MIN_APM_WINDOW`] are lengthened to it.
+pub fn apm_series(inputs: &Inputs, window: Duration, timeline: &Timeline) -> Vec<ApmSample> {
+    if window.is_zero() {
+        return vec![];
+    }
+    let window = window.max(MIN_APM_WINDOW);
+    let nanos = window.as_nanos();
+    let end = timeline.time_of_frame(inputs.0.len());
+    let windows = end.as_nanos().div_ceil(nanos) as usize;
+    let mut actions = vec![0; windows];
+    for (frame, input) in inputs.0.iter().enumerate() {
+        let presses = count_presses(input, frame.checked_sub(1).map(|frame| &inputs.0[frame]));
+        if presses != 0 {
+            let index = timeline.time_of_frame(frame).as_nanos() / nanos;
+            actions[index as usize] += presses;
+        }
+    }
+
+    let minutes = window.as_secs_f64() / 60.0;
+    actions
+        .into_iter()
+        .enumerate()
+        .map_while(|(index, actions)| {
+            let start = nanos.checked_mul(index as u128)?;
+            Some(ApmSample {
+                start: Duration::new(
+                    u64::try_from(start / 1_000_000_000).ok()?,
+                    (start % 1_000_000_000) as u32,
+                ),
+                actions,
+                apm: actions as f64 / minutes,
+            })
+        })
+        .collect()
+}
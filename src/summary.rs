@@ -0,0 +1,58 @@
+//! Module that generates summaries of movies for submissions.
+
+use crate::movie::LibTASMovie;
+
+/// The markup of a submission summary.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SummaryFormat {
+    /// A Markdown list.
+    #[default]
+    Markdown,
+    /// A BBCode list.
+    BBCode,
+}
+
+impl LibTASMovie {
+    /// Returns the metadata block of a [TASVideos](https://tasvideos.org/) submission:
+    /// game, length, frames, rerecords, authors, libTAS version, and MD5.
+    pub fn submission_summary(&self, format: SummaryFormat) -> String {
+        let general = &self.config.general;
+        let items = [
+            ("Game", general.game_name.clone()),
+            ("Length", self.duration_summary().to_string()),
+            ("Frames", general.frame_count.to_string()),
+            ("Rerecords", general.rerecord_count.to_string()),
+            ("Authors", general.authors.clone()),
+            (
+                "libTAS version",
+                format!(
+                    "{}.{}.{}",
+                    general.libtas_major_version,
+                    general.libtas_minor_version,
+                    general.libtas_patch_version
+                ),
+            ),
+            (
+                "MD5",
+                general.md5.map_or_else(String::new, |md5| md5.to_string()),
+            ),
+        ];
+
+        let mut summary = String::new();
+        match format {
+            SummaryFormat::Markdown => {
+                for (name, value) in items {
+                    summary += &format!("- **{name}:** {value}\n");
+                }
+            }
+            SummaryFormat::BBCode => {
+                summary += "[list]\n";
+                for (name, value) in items {
+                    summary += &format!("[*][b]{name}:[/b] {value}\n");
+                }
+                summary += "[/list]\n";
+            }
+        }
+        summary
+    }
+}
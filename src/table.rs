@@ -0,0 +1,363 @@
+//! Module that defines a grid view of inputs for input editors.
+//!
+//! [`InputTableModel`] exposes inputs as rows of frames and columns of keys and buttons,
+//! so GUI frontends can bind a grid view without deriving the columns themselves.
+
+use core::ops::Range;
+
+use crate::inputs::{Input, Inputs, KeyboardInput, MouseInput, keysym_name};
+
+/// A column of an input table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Column {
+    /// A key, by its KeySym value.
+    Key(u32),
+    /// The X-coordinate of the pointer.
+    MouseX,
+    /// The Y-coordinate of the pointer.
+    MouseY,
+    /// A mouse button, from 1 to 5.
+    MouseButton(u8),
+}
+
+impl Column {
+    /// Returns the columns used by `inputs`: the mouse columns if any frame has a mouse input,
+    /// then the keys in order of first appearance.
+    pub fn for_inputs(inputs: &Inputs) -> Vec<Self> {
+        let mut columns = vec![];
+        let mut mouse = false;
+        let mut buttons = [false; 5];
+        for input in &inputs.0 {
+            if let Some(keyboard) = &input.keyboard {
+                for &key in &keyboard.0 {
+                    if !columns.contains(&Self::Key(key)) {
+                        columns.push(Self::Key(key));
+                    }
+                }
+            }
+            if let Some(mouse_input) = &input.mouse {
+                mouse = true;
+                for (used, button) in buttons.iter_mut().zip(1..) {
+                    *used |= mouse_button(mouse_input, button).is_some_and(|pressed| pressed);
+                }
+            }
+        }
+
+        let mut mouse_columns = vec![];
+        if mouse {
+            mouse_columns.extend([Self::MouseX, Self::MouseY]);
+            for (used, button) in buttons.into_iter().zip(1..) {
+                if used {
+                    mouse_columns.push(Self::MouseButton(button));
+                }
+            }
+        }
+        mouse_columns.extend(columns);
+        mouse_columns
+    }
+
+    /// Returns the short header of the column used in text, e.g. `z`, `Right`, `x`, or `LMB`.
+    pub fn short_label(&self) -> String {
+        match self {
+            Self::Key(key) => keysym_name(*key),
+            Self::MouseX => "x".to_owned(),
+            Self::MouseY => "y".to_owned(),
+            Self::MouseButton(1) => "LMB".to_owned(),
+            Self::MouseButton(2) => "MMB".to_owned(),
+            Self::MouseButton(3) => "RMB".to_owned(),
+            Self::MouseButton(button) => format!("MB{button}"),
+        }
+    }
+
+    /// Returns the header of the column, e.g. `z`, `Right`, `Mouse X coord`, or `Mouse button 1`.
+    pub fn label(&self) -> String {
+        match self {
+            Self::Key(key) => keysym_name(*key),
+            Self::MouseX => "Mouse X coord".to_owned(),
+            Self::MouseY => "Mouse Y coord".to_owned(),
+            Self::MouseButton(button) => format!("Mouse button {button}"),
+        }
+    }
+}
+
+/// Returns the state of the mouse button `button`, or `None` if there is no such button.
+fn mouse_button(mouse: &MouseInput, button: u8) -> Option<bool> {
+    match button {
+        1 => Some(mouse.left_click),
+        2 => Some(mouse.middle_click),
+        3 => Some(mouse.right_click),
+        4 => Some(mouse.button4),
+        5 => Some(mouse.button5),
+        _ => None,
+    }
+}
+
+/// Returns the state of the mouse button `button` mutably, or `None` if there is no such button.
+fn mouse_button_mut(mouse: &mut MouseInput, button: u8) -> Option<&mut bool> {
+    match button {
+        1 => Some(&mut mouse.left_click),
+        2 => Some(&mut mouse.middle_click),
+        3 => Some(&mut mouse.right_click),
+        4 => Some(&mut mouse.button4),
+        5 => Some(&mut mouse.button5),
+        _ => None,
+    }
+}
+
+/// The value of a cell of an input table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cell {
+    /// Whether a key or a button is pressed.
+    Pressed(bool),
+    /// A pointer coordinate.
+    Coordinate(i32),
+}
+
+/// A table of frames by columns, editable cell by cell.
+///
+/// Rows are frames, and columns are indexes into [`InputTableModel::columns`].
+pub trait InputTableModel {
+    /// Returns the columns of the table.
+    fn columns(&self) -> &[Column];
+    /// Returns the number of rows.
+    fn row_count(&self) -> usize;
+    /// Returns the cell at `row` and `column`, or `None` if out of bounds.
+    fn cell(&self, row: usize, column: usize) -> Option<Cell>;
+    /// Sets the cell at `row` and `column`.
+    /// Returns `false` without editing if out of bounds or if `cell` does not fit the column.
+    fn set_cell(&mut self, row: usize, column: usize, cell: Cell) -> bool;
+    /// Inserts `count` empty rows at `at`. Returns `false` without editing if out of bounds.
+    fn insert_rows(&mut self, at: usize, count: usize) -> bool;
+    /// Removes the rows in `rows`. Returns `false` without editing if out of bounds.
+    fn remove_rows(&mut self, rows: Range<usize>) -> bool;
+}
+
+/// An [`InputTableModel`] over [`Inputs`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InputTable {
+    inputs: Inputs,
+    columns: Vec<Column>,
+}
+
+impl InputTable {
+    /// Returns a table of `inputs` with the columns they use.
+    pub fn new(inputs: Inputs) -> Self {
+        let columns = Column::for_inputs(&inputs);
+        Self { inputs, columns }
+    }
+
+    /// Returns the inputs of the table.
+    pub fn inputs(&self) -> &Inputs {
+        &self.inputs
+    }
+
+    /// Returns the inputs of the table, consuming it.
+    pub fn into_inputs(self) -> Inputs {
+        self.inputs
+    }
+
+    /// Appends `column` if it is not in the table yet, and returns its index.
+    pub fn add_column(&mut self, column: Column) -> usize {
+        match self.columns.iter().position(|&old| old == column) {
+            Some(index) => index,
+            None => {
+                self.columns.push(column);
+                self.columns.len() - 1
+            }
+        }
+    }
+}
+
+impl InputTableModel for InputTable {
+    fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    fn row_count(&self) -> usize {
+        self.inputs.0.len()
+    }
+
+    fn cell(&self, row: usize, column: usize) -> Option<Cell> {
+        let input = self.inputs.0.get(row)?;
+        let mouse = input.mouse.unwrap_or_default();
+        Some(match *self.columns.get(column)? {
+            Column::Key(key) => Cell::Pressed(
+                input
+                    .keyboard
+                    .as_ref()
+                    .is_some_and(|keyboard| keyboard.0.contains(&key)),
+            ),
+            Column::MouseX => Cell::Coordinate(mouse.xpos),
+            Column::MouseY => Cell::Coordinate(mouse.ypos),
+            Column::MouseButton(button) => Cell::Pressed(mouse_button(&mouse, button)?),
+        })
+    }
+
+    fn set_cell(&mut self, row: usize, column: usize, cell: Cell) -> bool {
+        let (Some(input), Some(&column)) = (self.inputs.0.get_mut(row), self.columns.get(column))
+        else {
+            return false;
+        };
+        match (column, cell) {
+            (Column::Key(key), Cell::Pressed(pressed)) => {
+                let mut keys = input
+                    .keyboard
+                    .take()
+                    .map(|keyboard| keyboard.0)
+                    .unwrap_or_default();
+                keys.retain(|&old| old != key);
+                if pressed {
+                    keys.push(key);
+                }
+                input.keyboard = (!keys.is_empty()).then_some(KeyboardInput(keys));
+            }
+            (Column::MouseX, Cell::Coordinate(xpos)) => {
+                input.mouse.get_or_insert_default().xpos = xpos;
+            }
+            (Column::MouseY, Cell::Coordinate(ypos)) => {
+                input.mouse.get_or_insert_default().ypos = ypos;
+            }
+            (Column::MouseButton(button), Cell::Pressed(pressed)) => {
+                let mut mouse = input.mouse.unwrap_or_default();
+                let Some(state) = mouse_button_mut(&mut mouse, button) else {
+                    return false;
+                };
+                *state = pressed;
+                input.mouse = Some(mouse);
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    fn insert_rows(&mut self, at: usize, count: usize) -> bool {
+        if at > self.inputs.0.len() {
+            return false;
+        }
+        self.inputs
+            .splice_frames(at..at, core::iter::repeat_n(Input::default(), count));
+        true
+    }
+
+    fn remove_rows(&mut self, rows: Range<usize>) -> bool {
+        if self.inputs.0.get(rows.clone()).is_none() {
+            return false;
+        }
+        self.inputs.splice_frames(rows, []);
+        true
+    }
+}
+
+/// Returns the text of the cell of `column` in `input`: the short label if pressed,
+/// the coordinate if there is a mouse input, or an empty string otherwise.
+fn cell_text(input: &Input, column: Column) -> String {
+    let mouse = input.mouse.as_ref();
+    match column {
+        Column::Key(key) => {
+            let pressed = input
+                .keyboard
+                .as_ref()
+                .is_some_and(|keyboard| keyboard.0.contains(&key));
+            if pressed {
+                column.short_label()
+            } else {
+                String::new()
+            }
+        }
+        Column::MouseX => mouse
+            .map(|mouse| mouse.xpos.to_string())
+            .unwrap_or_default(),
+        Column::MouseY => mouse
+            .map(|mouse| mouse.ypos.to_string())
+            .unwrap_or_default(),
+        Column::MouseButton(button) => {
+            if mouse.and_then(|mouse| mouse_button(mouse, button)) == Some(true) {
+                column.short_label()
+            } else {
+                String::new()
+            }
+        }
+    }
+}
+
+/// Renders the frames in `frames` as fixed-width rows of `columns`, like the input editor:
+/// ```text
+/// frame | z | Right | LMB | x,y
+///     0 |   |       |     |
+///     1 | z | Right | LMB | 166,270
+/// ```
+/// [`Column::MouseX`] directly followed by [`Column::MouseY`] is rendered as one `x,y` column.
+/// Frames out of bounds are skipped, and trailing spaces are trimmed.
+pub fn render_text(inputs: &Inputs, columns: &[Column], frames: Range<usize>) -> String {
+    let rows = inputs
+        .0
+        .get(frames.start.min(inputs.0.len())..frames.end.min(inputs.0.len()));
+    let rows = rows.unwrap_or_default();
+
+    // group the columns, merging x and y
+    let mut groups: Vec<&[Column]> = vec![];
+    let mut rest = columns;
+    while !rest.is_empty() {
+        let len = if rest.starts_with(&[Column::MouseX, Column::MouseY]) {
+            2
+        } else {
+            1
+        };
+        let (group, tail) = rest.split_at(len);
+        groups.push(group);
+        rest = tail;
+    }
+
+    let mut table = vec![];
+    let header: Vec<String> = groups
+        .iter()
+        .map(|group| {
+            let labels: Vec<String> = group.iter().map(Column::short_label).collect();
+            labels.join(",")
+        })
+        .collect();
+    table.push(("frame".to_owned(), header));
+    for (index, input) in rows.iter().enumerate() {
+        let cells = groups
+            .iter()
+            .map(|group| {
+                let texts: Vec<String> = group
+                    .iter()
+                    .map(|&column| cell_text(input, column))
+                    .collect();
+                if texts.iter().all(String::is_empty) {
+                    String::new()
+                } else {
+                    texts.join(",")
+                }
+            })
+            .collect();
+        table.push(((frames.start + index).to_string(), cells));
+    }
+
+    let frame_width = table
+        .iter()
+        .map(|(frame, _)| frame.len())
+        .max()
+        .unwrap_or(0);
+    let widths: Vec<usize> = (0..groups.len())
+        .map(|group| {
+            table
+                .iter()
+                .map(|(_, cells)| cells[group].len())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let mut text = String::new();
+    for (frame, cells) in &table {
+        let mut line = format!("{frame:>frame_width$}");
+        for (cell, width) in cells.iter().zip(&widths) {
+            line += &format!(" | {cell:<width$}");
+        }
+        text += line.trim_end();
+        text.push('\n');
+    }
+    text
+}
@@ -0,0 +1,158 @@
+//! Module that provides sample movies for writing tests without `.ltm` fixtures.
+//!
+//! This module is only available with the `testkit` feature.
+
+use crate::{
+    config::Config,
+    inputs::{Input, Inputs, KeyboardInput, MouseInput, ReferenceMode},
+    movie::LibTASMovie,
+    time::Framerate,
+};
+
+use flate2::{Compression, write::GzEncoder};
+use tar::{Builder, Header};
+
+/// A kind of archive that fails to load.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BadArchive {
+    /// Bytes that are not a `tar.gz` archive at all.
+    NotGzip,
+    /// A valid archive without `inputs`.
+    MissingEntry,
+    /// A valid movie with an additional unknown file.
+    ExtraEntry,
+    /// A movie whose `config.ini` cannot be parsed.
+    InvalidConfig,
+    /// A movie whose `inputs` cannot be parsed.
+    InvalidInputs,
+}
+
+impl BadArchive {
+    /// All kinds of bad archives.
+    pub const ALL: [Self; 5] = [
+        Self::NotGzip,
+        Self::MissingEntry,
+        Self::ExtraEntry,
+        Self::InvalidConfig,
+        Self::InvalidInputs,
+    ];
+
+    /// Returns the bytes of the archive.
+    pub fn bytes(self) -> Vec<u8> {
+        let config = Config::default().to_string();
+        match self {
+            Self::NotGzip => b"this is not a movie\n".to_vec(),
+            Self::MissingEntry => archive(&[
+                ("config.ini", config.as_bytes()),
+                ("annotations.txt", b""),
+                ("editor.ini", b""),
+            ]),
+            Self::ExtraEntry => archive(&[
+                ("config.ini", config.as_bytes()),
+                ("inputs", b"|\n"),
+                ("annotations.txt", b""),
+                ("editor.ini", b""),
+                ("extra.txt", b""),
+            ]),
+            Self::InvalidConfig => archive(&[
+                (
+                    "config.ini",
+                    b"[General]\nframe_count=many\n\n[mainthread_timetrack]\n",
+                ),
+                ("inputs", b"|\n"),
+                ("annotations.txt", b""),
+                ("editor.ini", b""),
+            ]),
+            Self::InvalidInputs => archive(&[
+                ("config.ini", config.as_bytes()),
+                ("inputs", b"|Kzz|\n"),
+                ("annotations.txt", b""),
+                ("editor.ini", b""),
+            ]),
+        }
+    }
+}
+
+/// Packs `entries` into a `tar.gz` archive.
+pub fn archive(entries: &[(&str, &[u8])]) -> Vec<u8> {
+    let enc = GzEncoder::new(vec![], Compression::default());
+    let mut tar = Builder::new(enc);
+
+    let mut header = Header::new_gnu();
+    for (file_name, data) in entries {
+        header.set_path(file_name).unwrap();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        tar.append(&header, *data).unwrap();
+    }
+
+    tar.into_inner().unwrap().finish().unwrap()
+}
+
+/// Returns a movie of `inputs` at `fps` frames per second,
+/// with `frame_count` and the movie length filled in.
+fn movie_with_inputs(inputs: Vec<Input>, fps: u64) -> LibTASMovie {
+    let mut movie = LibTASMovie::default();
+    let frames = inputs.len() as u64;
+
+    let general = &mut movie.config.general;
+    let framerate = Framerate { num: fps, den: 1 };
+    let length = framerate.duration_of(frames);
+    general.frame_count = frames;
+    general.savestate_frame_count = frames;
+    general.set_framerate(framerate);
+    general.length = length.into();
+
+    movie.inputs = Inputs::from(inputs);
+    movie
+}
+
+/// Returns a keyboard-only movie with `frames` frames at `fps` frames per second.
+///
+/// The inputs cycle through a few patterns: nothing, `z`, `Right`, and `z` with `Right`.
+pub fn sample_movie(frames: usize, fps: u64) -> LibTASMovie {
+    let inputs = (0..frames)
+        .map(|frame| {
+            let keyboard = match frame % 4 {
+                0 => None,
+                1 => Some(KeyboardInput(vec![0x7a])),
+                2 => Some(KeyboardInput(vec![0xff53])),
+                _ => Some(KeyboardInput(vec![0x7a, 0xff53])),
+            };
+            Input {
+                keyboard,
+                ..Input::default()
+            }
+        })
+        .collect();
+
+    let mut movie = movie_with_inputs(inputs, fps);
+    movie.config.general.mouse_support = false;
+    movie
+}
+
+/// Returns a movie whose pointer follows `path` in absolute coordinates,
+/// one point per frame at `fps` frames per second.
+///
+/// The left button is pressed on the last frame.
+pub fn movie_with_mouse_path(path: &[(i32, i32)], fps: u64) -> LibTASMovie {
+    let inputs = path
+        .iter()
+        .enumerate()
+        .map(|(frame, &(xpos, ypos))| Input {
+            mouse: Some(MouseInput {
+                xpos,
+                ypos,
+                reference_mode: ReferenceMode::Absolute,
+                left_click: frame + 1 == path.len(),
+                ..MouseInput::default()
+            }),
+            ..Input::default()
+        })
+        .collect();
+
+    let mut movie = movie_with_inputs(inputs, fps);
+    movie.config.general.mouse_support = true;
+    movie
+}
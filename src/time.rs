@@ -0,0 +1,473 @@
+//! Module that defines framerates and the timing of movies.
+
+use core::{
+    cmp::Ordering,
+    fmt::Display,
+    hash::{Hash, Hasher},
+    ops::{Add, Sub},
+    time::Duration,
+};
+
+use crate::{inputs::Inputs, movie::LibTASMovie};
+
+/// A framerate expressed as the exact fraction `num/den` frames per second.
+///
+/// Framerates are compared by value, so `120/2` equals `60/1`.
+#[derive(Clone, Copy, Debug)]
+pub struct Framerate {
+    /// Numerator of the framerate.
+    pub num: u64,
+    /// Denominator of the framerate.
+    pub den: u64,
+}
+
+impl Framerate {
+    /// Returns the framerate `num/den`, or `None` if either is zero.
+    pub fn new(num: u64, den: u64) -> Option<Self> {
+        (num != 0 && den != 0).then_some(Self { num, den })
+    }
+
+    /// Returns the framerate of `fps` frames per second.
+    pub fn from_fps(fps: u64) -> Option<Self> {
+        Self::new(fps, 1)
+    }
+
+    /// Returns the same framerate with `num` and `den` coprime.
+    pub fn reduced(self) -> Self {
+        let gcd = gcd(self.num.into(), self.den.into()).max(1) as u64;
+        Self {
+            num: self.num / gcd,
+            den: self.den / gcd,
+        }
+    }
+
+    /// Returns the framerate as frames per second.
+    pub fn as_f64(self) -> f64 {
+        self.num as f64 / self.den as f64
+    }
+
+    /// Returns the duration of a frame, rounded up to nanoseconds.
+    pub fn frame_duration(self) -> Duration {
+        self.duration_of(1)
+    }
+
+    /// Returns the duration of `frames` frames, rounded up to nanoseconds
+    /// so that a frame starting at the returned time is not counted as already elapsed.
+    ///
+    /// Rounding down would make the start time of a frame fall just before the exact
+    /// boundary, where [`Timeline::frame_at`] (which rounds down) sees the previous frame,
+    /// e.g. frame 1 at 60000/1001 fps would start at 16 683 333 ns, still in frame 0.
+    pub fn duration_of(self, frames: u64) -> Duration {
+        if self.num == 0 {
+            return Duration::ZERO;
+        }
+        let nanos = (u128::from(frames) * u128::from(self.den) * 1_000_000_000)
+            .div_ceil(u128::from(self.num));
+        Duration::new(
+            (nanos / 1_000_000_000) as u64,
+            (nanos % 1_000_000_000) as u32,
+        )
+    }
+}
+
+impl Default for Framerate {
+    fn default() -> Self {
+        Self { num: 60, den: 1 }
+    }
+}
+
+impl PartialEq for Framerate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Framerate {}
+
+impl Hash for Framerate {
+    /// Hashes the reduced fraction, so that equal framerates have equal hashes.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let reduced = self.reduced();
+        (reduced.num, reduced.den).hash(state);
+    }
+}
+
+impl PartialOrd for Framerate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Framerate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let lhs = u128::from(self.num) * u128::from(other.den);
+        let rhs = u128::from(other.num) * u128::from(self.den);
+        lhs.cmp(&rhs)
+    }
+}
+
+impl Display for Framerate {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if self.den == 1 {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+/// A time written in the config as seconds and nanoseconds, such as `length_sec` and
+/// `length_nsec`.
+///
+/// `nsec` should be less than a second, but hand-edited configs may carry more.
+/// Arithmetic and conversions normalize the result, carrying whole seconds out of `nsec`.
+///
+/// # Example
+/// ```
+/// use core::time::Duration;
+/// use libtas_movie::time::TimeSpec;
+/// let time = TimeSpec::new(1, 1_500_000_000);
+/// assert_eq!(time.normalized(), TimeSpec::new(2, 500_000_000));
+/// assert_eq!(time + TimeSpec::new(0, 600_000_000), TimeSpec::new(3, 100_000_000));
+/// assert_eq!(Duration::from(time), Duration::from_millis(2500));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TimeSpec {
+    /// The integer part of the time in seconds.
+    pub sec: u64,
+    /// The fractional part of the time in seconds, expressed as nanoseconds.
+    pub nsec: u64,
+}
+
+impl TimeSpec {
+    /// The number of nanoseconds in a second.
+    pub const NANOS_PER_SEC: u32 = 1_000_000_000;
+
+    /// Returns the time `sec` seconds and `nsec` nanoseconds, as written.
+    pub const fn new(sec: u64, nsec: u64) -> Self {
+        Self { sec, nsec }
+    }
+
+    /// Returns whether `nsec` is less than a second.
+    pub const fn is_normalized(self) -> bool {
+        self.nsec < Self::NANOS_PER_SEC as u64
+    }
+
+    /// Returns the same time with `nsec` less than a second,
+    /// saturating `sec` if it overflows.
+    pub const fn normalized(self) -> Self {
+        Self {
+            sec: self
+                .sec
+                .saturating_add(self.nsec / Self::NANOS_PER_SEC as u64),
+            nsec: self.nsec % Self::NANOS_PER_SEC as u64,
+        }
+    }
+
+    /// Returns the normalized sum of both times, or `None` if it overflows.
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        Duration::from(self)
+            .checked_add(Duration::from(other))
+            .map(Self::from)
+    }
+
+    /// Returns the normalized difference of both times,
+    /// or `None` if `other` is later than `self` or if it overflows.
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        Duration::from(self)
+            .checked_sub(Duration::from(other))
+            .map(Self::from)
+    }
+}
+
+impl From<Duration> for TimeSpec {
+    fn from(duration: Duration) -> Self {
+        Self::new(duration.as_secs(), duration.subsec_nanos().into())
+    }
+}
+
+impl From<TimeSpec> for Duration {
+    /// Converts the time, saturating at [`Duration::MAX`].
+    fn from(time: TimeSpec) -> Self {
+        Self::from_secs(time.sec).saturating_add(Self::from_nanos(time.nsec))
+    }
+}
+
+impl Add for TimeSpec {
+    type Output = Self;
+
+    /// Adds both times. Panics if the sum overflows, like [`Duration`].
+    fn add(self, other: Self) -> Self {
+        self.checked_add(other)
+            .unwrap_or_else(|| panic!("overflow when adding times"))
+    }
+}
+
+impl Sub for TimeSpec {
+    type Output = Self;
+
+    /// Subtracts `other` from `self`. Panics if `other` is later, like [`Duration`].
+    fn sub(self, other: Self) -> Self {
+        self.checked_sub(other)
+            .unwrap_or_else(|| panic!("overflow when subtracting times"))
+    }
+}
+
+impl Display for TimeSpec {
+    /// Writes the time in seconds, e.g. `22.800000000`.
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let time = self.normalized();
+        write!(f, "{}.{:09}", time.sec, time.nsec)
+    }
+}
+
+/// A run of consecutive frames with the same framerate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Segment {
+    /// The first frame of the run.
+    start_frame: usize,
+    /// The time at which the first frame starts.
+    start_time: Duration,
+    /// The framerate of the run.
+    framerate: Framerate,
+}
+
+impl Segment {
+    /// Returns the time at which `frame` starts, assuming the run continues up to `frame`.
+    fn time_of_frame(&self, frame: usize) -> Duration {
+        self.start_time
+            + self
+                .framerate
+                .duration_of((frame - self.start_frame) as u64)
+    }
+
+    /// Returns the frame at `time` in the run, assuming the run continues up to `time`.
+    fn frame_at(&self, time: Duration) -> usize {
+        if self.framerate.den == 0 {
+            return self.start_frame;
+        }
+        let elapsed = (time - self.start_time).as_nanos();
+        let frames = elapsed * u128::from(self.framerate.num)
+            / (u128::from(self.framerate.den) * 1_000_000_000);
+        self.start_frame + frames as usize
+    }
+}
+
+/// The start time of each frame of a movie, taking per-frame framerates into account.
+///
+/// Frames without a framerate use the default framerate of the movie.
+/// Times past the last frame are extrapolated with the framerate of the last frame.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Timeline {
+    /// Runs of frames, sorted by their first frame. Never empty.
+    segments: Vec<Segment>,
+    /// The number of frames.
+    len: usize,
+}
+
+impl Timeline {
+    /// Builds the timeline of `inputs`, with `default` as the framerate of frames
+    /// without their own framerate.
+    pub fn new(default: Framerate, inputs: &Inputs) -> Self {
+        let mut segments: Vec<Segment> = vec![Segment {
+            start_frame: 0,
+            start_time: Duration::ZERO,
+            framerate: inputs
+                .0
+                .first()
+                .and_then(|input| input.framerate)
+                .unwrap_or(default),
+        }];
+        for (frame, input) in inputs.0.iter().enumerate().skip(1) {
+            let framerate = input.framerate.unwrap_or(default);
+            let last = segments[segments.len() - 1];
+            if framerate.num != last.framerate.num || framerate.den != last.framerate.den {
+                segments.push(Segment {
+                    start_frame: frame,
+                    start_time: last.time_of_frame(frame),
+                    framerate,
+                });
+            }
+        }
+        Self {
+            segments,
+            len: inputs.0.len(),
+        }
+    }
+
+    /// Returns the number of frames.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether there is no frame.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the time at which `frame` starts.
+    /// `time_of_frame(len())` is the end of the last frame.
+    pub fn time_of_frame(&self, frame: usize) -> Duration {
+        let index = self
+            .segments
+            .partition_point(|segment| segment.start_frame <= frame);
+        self.segments[index - 1].time_of_frame(frame)
+    }
+
+    /// Returns the total duration of the frames, rounded up to nanoseconds
+    /// like [`Framerate::duration_of`].
+    ///
+    /// Unlike [`Timeline::time_of_frame`], which rounds the start of each run of frames,
+    /// the exact durations of all frames are summed with integer arithmetic before rounding
+    /// once, so the result does not drift with the number of framerate changes.
+    pub fn total_duration(&self) -> Duration {
+        self.exact_total_nanos().map_or_else(
+            || self.time_of_frame(self.len),
+            |nanos| {
+                Duration::new(
+                    (nanos / 1_000_000_000) as u64,
+                    (nanos % 1_000_000_000) as u32,
+                )
+            },
+        )
+    }
+
+    /// Returns the total duration of the frames in nanoseconds, rounded up,
+    /// or `None` if the exact sum overflows.
+    fn exact_total_nanos(&self) -> Option<u128> {
+        // the sum as the fraction `num/den` of nanoseconds
+        let (mut num, mut den) = (0u128, 1u128);
+        for (index, segment) in self.segments.iter().enumerate() {
+            let end = self
+                .segments
+                .get(index + 1)
+                .map_or(self.len, |next| next.start_frame);
+            let frames = end.saturating_sub(segment.start_frame) as u128;
+            let framerate = segment.framerate;
+            if framerate.num == 0 || frames == 0 {
+                continue;
+            }
+            let term = frames
+                .checked_mul(framerate.den.into())?
+                .checked_mul(1_000_000_000)?;
+            let term_den = u128::from(framerate.num);
+            let lcm = (den / gcd(den, term_den)).checked_mul(term_den)?;
+            num = num
+                .checked_mul(lcm / den)?
+                .checked_add(term.checked_mul(lcm / term_den)?)?;
+            den = lcm;
+            let gcd = gcd(num, den).max(1);
+            (num, den) = (num / gcd, den / gcd);
+        }
+        Some(num.div_ceil(den))
+    }
+
+    /// Returns the frame being shown at `time`.
+    pub fn frame_at(&self, time: Duration) -> usize {
+        let index = self
+            .segments
+            .partition_point(|segment| segment.start_time <= time);
+        self.segments[index - 1].frame_at(time)
+    }
+}
+
+impl LibTASMovie {
+    /// Returns the timeline of the movie.
+    pub fn timeline(&self) -> Timeline {
+        Timeline::new(self.framerate(), &self.inputs)
+    }
+
+    /// Returns the default framerate of the movie, as recorded in the config.
+    /// Frames may override it with their own framerate.
+    pub fn framerate(&self) -> Framerate {
+        self.config.general.framerate()
+    }
+
+    /// Returns the default framerate of the movie as frames per second.
+    pub fn fps_f64(&self) -> f64 {
+        self.framerate().as_f64()
+    }
+
+    /// Returns the length of the movie, as recorded in the config.
+    pub fn duration(&self) -> Duration {
+        self.config.general.length.into()
+    }
+
+    /// Sets the length of the movie recorded in the config.
+    pub fn set_duration(&mut self, duration: Duration) {
+        self.config.general.length = duration.into();
+    }
+
+    /// Updates `frame_count` and the length of the movie from the inputs.
+    pub(crate) fn sync_length(&mut self) {
+        let timeline = self.timeline();
+        self.config.general.frame_count = timeline.len() as u64;
+        self.set_duration(timeline.total_duration());
+    }
+
+    /// Sets the length of the movie recorded in the config to the total duration of the frames,
+    /// taking per-frame framerates into account. Returns whether the length changed.
+    ///
+    /// # Example
+    /// ```
+    /// use core::time::Duration;
+    /// use libtas_movie::testkit::sample_movie;
+    /// let mut movie = sample_movie(90, 60);
+    /// movie.set_duration(Duration::ZERO);
+    /// assert!(movie.recompute_length());
+    /// assert_eq!(movie.duration(), Duration::from_millis(1500));
+    /// ```
+    pub fn recompute_length(&mut self) -> bool {
+        let length = self.timeline().total_duration();
+        let changed = self.duration() != length;
+        self.set_duration(length);
+        changed
+    }
+
+    /// Returns a summary of the length of the movie, displayed like `22.800s (456 frames @ 20 fps)`.
+    pub fn duration_summary(&self) -> DurationSummary {
+        DurationSummary {
+            duration: self.duration(),
+            frames: self.config.general.frame_count,
+            framerate: self.config.general.framerate(),
+        }
+    }
+}
+
+/// A summary of the length of a movie, displayed like `22.800s (456 frames @ 20 fps)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DurationSummary {
+    /// The length of the movie.
+    pub duration: Duration,
+    /// The number of frames.
+    pub frames: u64,
+    /// The framerate of the movie.
+    pub framerate: Framerate,
+}
+
+impl Display for DurationSummary {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{}.{:03}s ({} frames @ ",
+            self.duration.as_secs(),
+            self.duration.subsec_millis(),
+            self.frames,
+        )?;
+        if self.framerate.den == 1 {
+            write!(f, "{}", self.framerate.num)?;
+        } else {
+            let fps = format!("{:.3}", self.framerate.as_f64());
+            write!(f, "{}", fps.trim_end_matches('0').trim_end_matches('.'))?;
+        }
+        write!(f, " fps)")
+    }
+}
+
+/// Returns the greatest common divisor of `a` and `b`.
+pub(crate) fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
@@ -0,0 +1,225 @@
+//! Module that defines libTAS versions and migrations of movies between them.
+
+use core::{fmt::Display, str::FromStr};
+
+use crate::{config::GeneralConfig, inputs::ReferenceMode, movie::LibTASMovie};
+
+/// A version of libTAS, `major.minor.patch`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version {
+    /// The major version (`a` in `a.b.c`).
+    pub major: u32,
+    /// The minor version (`b` in `a.b.c`).
+    pub minor: u32,
+    /// The patch version (`c` in `a.b.c`).
+    pub patch: u32,
+}
+
+impl Version {
+    /// Returns the version `major.minor.patch`.
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+}
+
+impl FromStr for Version {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split('.').map(|part| part.parse::<u32>());
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(Ok(major)), Some(Ok(minor)), Some(Ok(patch)), None) => {
+                Ok(Self::new(major, minor, patch))
+            }
+            _ => Err(()),
+        }
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl GeneralConfig {
+    /// Returns the version of libTAS that wrote the movie.
+    pub fn libtas_version(&self) -> Version {
+        Version::new(
+            self.libtas_major_version,
+            self.libtas_minor_version,
+            self.libtas_patch_version,
+        )
+    }
+
+    /// Sets the version of libTAS that wrote the movie.
+    pub fn set_libtas_version(&mut self, version: Version) {
+        self.libtas_major_version = version.major;
+        self.libtas_minor_version = version.minor;
+        self.libtas_patch_version = version.patch;
+    }
+}
+
+/// A change of the movie format introduced in a version of libTAS.
+#[derive(Clone, Copy, Debug)]
+pub struct Migration {
+    /// The version that introduced the change.
+    pub version: Version,
+    /// A short description of the change.
+    pub description: &'static str,
+    /// Rewrites a movie written before `version` into the new format.
+    pub upgrade: fn(&mut LibTASMovie),
+    /// Rewrites a movie into the format before `version`.
+    /// This may lose information that older versions cannot express.
+    pub downgrade: fn(&mut LibTASMovie),
+}
+
+impl LibTASMovie {
+    /// Rewrites the movie for libTAS `version` using `migrations`, which must be sorted by version,
+    /// and returns the descriptions of the applied migrations.
+    ///
+    /// This crate does not ship migrations: callers provide the format changes they rely on,
+    /// with the versions that introduced them.
+    ///
+    /// Upgrading applies each migration newer than the current version up to `version` in order,
+    /// and downgrading reverts each migration newer than `version` in reverse order.
+    pub fn migrate_with(
+        &mut self,
+        version: Version,
+        migrations: &[Migration],
+    ) -> Vec<&'static str> {
+        let current = self.config.general.libtas_version();
+        let mut applied = vec![];
+        if current < version {
+            for migration in migrations {
+                if current < migration.version && migration.version <= version {
+                    (migration.upgrade)(self);
+                    applied.push(migration.description);
+                }
+            }
+        } else {
+            for migration in migrations.iter().rev() {
+                if version < migration.version && migration.version <= current {
+                    (migration.downgrade)(self);
+                    applied.push(migration.description);
+                }
+            }
+        }
+        self.config.general.set_libtas_version(version);
+        applied
+    }
+}
+
+/// A feature of the movie format that requires a minimum version of libTAS.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Feature {
+    /// Per-frame framerates or the `variable_framerate` flag.
+    VariableFramerate,
+    /// Mouse inputs in [`ReferenceMode::Relative`].
+    RelativeMouse,
+    /// A config key not modeled by this crate, written by a newer libTAS than the target.
+    /// Contains the group and the key.
+    UnknownConfigKey(String, String),
+}
+
+/// A feature used by a movie that the target version does not support.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompatIssue {
+    /// The unsupported feature.
+    pub feature: Feature,
+    /// The minimum version supporting the feature: the version that introduced it,
+    /// or the version that wrote the movie for unknown config keys.
+    pub required: Version,
+    /// The first frame using the feature, if it is used by inputs.
+    pub first_frame: Option<usize>,
+}
+
+/// The result of [`LibTASMovie::compatible_with`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompatReport {
+    /// The version checked against.
+    pub target: Version,
+    /// Features the target version does not support.
+    pub issues: Vec<CompatIssue>,
+}
+
+impl CompatReport {
+    /// Returns whether the movie can be shared with the target version.
+    pub fn is_compatible(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl LibTASMovie {
+    /// Lists the features used by the movie that libTAS `target` does not support.
+    ///
+    /// `introduced` gives the version that introduced each feature,
+    /// and features missing from it are not checked.
+    pub fn compatible_with(
+        &self,
+        target: Version,
+        introduced: &[(Feature, Version)],
+    ) -> CompatReport {
+        let introduced = |feature: Feature| {
+            introduced
+                .iter()
+                .find(|(known, _)| *known == feature)
+                .map(|&(_, version)| version)
+        };
+        let mut issues = vec![];
+
+        if let Some(required) = introduced(Feature::VariableFramerate)
+            && target < required
+        {
+            let first_frame = self
+                .inputs
+                .0
+                .iter()
+                .position(|input| input.framerate.is_some());
+            if first_frame.is_some() || self.config.general.variable_framerate {
+                issues.push(CompatIssue {
+                    feature: Feature::VariableFramerate,
+                    required,
+                    first_frame,
+                });
+            }
+        }
+
+        if let Some(required) = introduced(Feature::RelativeMouse)
+            && target < required
+        {
+            let first_frame = self.inputs.0.iter().position(|input| {
+                input
+                    .mouse
+                    .is_some_and(|mouse| mouse.reference_mode == ReferenceMode::Relative)
+            });
+            if first_frame.is_some() {
+                issues.push(CompatIssue {
+                    feature: Feature::RelativeMouse,
+                    required,
+                    first_frame,
+                });
+            }
+        }
+
+        // keys this crate does not know can only be attributed to the version that wrote them
+        let written = self.config.general.libtas_version();
+        if target < written {
+            for section in &self.config.extra {
+                for (key, _) in &section.entries {
+                    issues.push(CompatIssue {
+                        feature: Feature::UnknownConfigKey(section.name.clone(), key.clone()),
+                        required: written,
+                        first_frame: None,
+                    });
+                }
+            }
+        }
+
+        CompatReport { target, issues }
+    }
+}
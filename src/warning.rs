@@ -0,0 +1,131 @@
+//! Module that defines recoverable oddities found while loading a movie.
+
+use crate::movie::LibTASMovie;
+
+/// The largest valid X11 KeySym.
+pub const MAX_KEYSYM: u32 = 0x1fff_ffff;
+
+/// A recoverable oddity found while loading a movie.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Warning {
+    /// A config key that is not modeled, kept in [`Config::extra`](crate::config::Config::extra).
+    UnknownConfigKey {
+        /// The group of the key.
+        section: String,
+        /// The key.
+        key: String,
+    },
+    /// An `md5` value that is not an MD5 hash, which is kept verbatim in
+    /// [`Config::extra`](crate::config::Config::extra) while
+    /// [`GeneralConfig::md5`](crate::config::GeneralConfig::md5) is `None`.
+    InvalidMd5(String),
+    /// An editor key that is not modeled, kept in [`Editor`](crate::editor::Editor)
+    /// as an extra key of a column or an extra group.
+    /// Groups only holding an empty array are not reported.
+    UnknownEditorKey {
+        /// The group of the key.
+        group: String,
+        /// The key.
+        key: String,
+    },
+    /// An input section that is not modeled, which is kept verbatim in
+    /// [`Input::unknown`](crate::inputs::Input::unknown), or an empty section, which is dropped.
+    IgnoredInputSection {
+        /// The frame of the section.
+        frame: usize,
+        /// The section, such as `F1`.
+        section: String,
+    },
+    /// An archive entry that appears more than once, where the last one is used.
+    DuplicateEntry(String),
+    /// A key above [`MAX_KEYSYM`].
+    KeysymOutOfRange {
+        /// The frame of the key.
+        frame: usize,
+        /// The key.
+        key: u32,
+    },
+    /// `frame_count` in the config differs from the number of frames,
+    /// which makes libTAS refuse to play the movie.
+    FrameCountMismatch {
+        /// `frame_count` in the config.
+        config: u64,
+        /// The number of frames.
+        inputs: usize,
+    },
+}
+
+/// The warnings found while loading a movie, in the order they were found.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Warnings(pub Vec<Warning>);
+
+impl Warnings {
+    /// Returns whether there is no warning.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Pushes the warnings about the contents of `movie`: unknown config and editor keys,
+    /// a malformed MD5 hash, keys out of range, and a stale frame count.
+    pub(crate) fn check_movie(&mut self, movie: &LibTASMovie) {
+        for section in &movie.config.extra {
+            let general = section.name == "General";
+            self.0.extend(section.entries.iter().map(|(key, value)| {
+                if general && key == "md5" {
+                    Warning::InvalidMd5(value.clone())
+                } else {
+                    Warning::UnknownConfigKey {
+                        section: section.name.clone(),
+                        key: key.clone(),
+                    }
+                }
+            }));
+        }
+        for column in &movie.editor.columns {
+            self.0.extend(
+                column
+                    .extra
+                    .iter()
+                    .map(|(key, _)| Warning::UnknownEditorKey {
+                        group: "input_names".to_owned(),
+                        key: key.clone(),
+                    }),
+            );
+        }
+        for group in &movie.editor.extra {
+            if let [(key, value)] = group.entries.as_slice()
+                && key == "size"
+                && value == "0"
+            {
+                continue;
+            }
+            self.0.extend(
+                group
+                    .entries
+                    .iter()
+                    .map(|(key, _)| Warning::UnknownEditorKey {
+                        group: group.name.clone(),
+                        key: key.clone(),
+                    }),
+            );
+        }
+        for (frame, input) in movie.inputs.0.iter().enumerate() {
+            let Some(keyboard) = &input.keyboard else {
+                continue;
+            };
+            self.0.extend(
+                keyboard
+                    .0
+                    .iter()
+                    .filter(|&&key| key > MAX_KEYSYM)
+                    .map(|&key| Warning::KeysymOutOfRange { frame, key }),
+            );
+        }
+        if !movie.is_frame_count_consistent() {
+            self.0.push(Warning::FrameCountMismatch {
+                config: movie.config.general.frame_count,
+                inputs: movie.frame_count(),
+            });
+        }
+    }
+}
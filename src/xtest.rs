@@ -0,0 +1,192 @@
+//! Module that replays inputs as real X11 events through the XTest extension.
+//!
+//! This is meant for sanity-checking a movie against a build of a game running outside libTAS.
+//! Playback is **not deterministic**: the game runs in real time, so the same events may
+//! land on different frames. Use it with [`Player::run`](crate::replay::Player::run).
+//!
+//! This module is only available with the `xtest` feature.
+
+use x11rb::{
+    connection::Connection as _,
+    errors::{ConnectError, ConnectionError, ReplyError},
+    protocol::{
+        xproto::{
+            BUTTON_PRESS_EVENT, BUTTON_RELEASE_EVENT, ConnectionExt as _, KEY_PRESS_EVENT,
+            KEY_RELEASE_EVENT, MOTION_NOTIFY_EVENT, Window,
+        },
+        xtest::ConnectionExt as _,
+    },
+    rust_connection::RustConnection,
+};
+
+use crate::{
+    inputs::{Input, MouseInput, ReferenceMode},
+    replay::InputSink,
+};
+
+/// An error while replaying inputs through XTest.
+#[derive(Debug)]
+pub enum XTestError {
+    /// Connecting to the X server failed.
+    Connect(ConnectError),
+    /// The connection to the X server failed.
+    Connection(ConnectionError),
+    /// The X server returned an error.
+    Reply(ReplyError),
+    /// No keycode produces the KeySym.
+    UnmappedKey(u32),
+}
+
+impl From<ConnectionError> for XTestError {
+    fn from(err: ConnectionError) -> Self {
+        Self::Connection(err)
+    }
+}
+
+impl From<ReplyError> for XTestError {
+    fn from(err: ReplyError) -> Self {
+        Self::Reply(err)
+    }
+}
+
+/// An [`InputSink`] sending each frame to the X server as fake key, button, and motion events.
+///
+/// Only changes are sent: keys and buttons are pressed when they start being held
+/// and released when they stop. Errors stop the replay and are kept in [`XTestSink::error`].
+#[derive(Debug)]
+pub struct XTestSink {
+    conn: RustConnection,
+    root: Window,
+    min_keycode: u8,
+    keysyms_per_keycode: usize,
+    keysyms: Vec<u32>,
+    keys: Vec<u32>,
+    buttons: [bool; 5],
+    error: Option<XTestError>,
+}
+
+impl XTestSink {
+    /// Connects to the display in `DISPLAY`.
+    pub fn connect() -> Result<Self, XTestError> {
+        Self::connect_to(None)
+    }
+
+    /// Connects to the display `display`, such as `:0`, or to `DISPLAY` if `None`.
+    pub fn connect_to(display: Option<&str>) -> Result<Self, XTestError> {
+        let (conn, screen) = x11rb::connect(display).map_err(XTestError::Connect)?;
+        let setup = conn.setup();
+        let root = setup.roots[screen].root;
+        let (min_keycode, max_keycode) = (setup.min_keycode, setup.max_keycode);
+        let mapping = conn
+            .get_keyboard_mapping(min_keycode, max_keycode - min_keycode + 1)?
+            .reply()?;
+        Ok(Self {
+            conn,
+            root,
+            min_keycode,
+            keysyms_per_keycode: mapping.keysyms_per_keycode.into(),
+            keysyms: mapping.keysyms,
+            keys: vec![],
+            buttons: [false; 5],
+            error: None,
+        })
+    }
+
+    /// Returns the error that stopped the replay.
+    pub fn error(&self) -> Option<&XTestError> {
+        self.error.as_ref()
+    }
+
+    /// Returns the keycode producing `keysym`.
+    fn keycode(&self, keysym: u32) -> Result<u8, XTestError> {
+        let index = self
+            .keysyms
+            .iter()
+            .position(|&sym| sym == keysym)
+            .ok_or(XTestError::UnmappedKey(keysym))?;
+        Ok(self.min_keycode + (index / self.keysyms_per_keycode.max(1)) as u8)
+    }
+
+    /// Sends a fake event.
+    fn fake(&self, kind: u8, detail: u8, x: i16, y: i16) -> Result<(), XTestError> {
+        self.conn
+            .xtest_fake_input(kind, detail, 0, self.root, x, y, 0)?;
+        Ok(())
+    }
+
+    /// Sends the events turning the held keys and buttons into those of `input`.
+    pub fn send(&mut self, input: &Input) -> Result<(), XTestError> {
+        let keys = input
+            .keyboard
+            .as_ref()
+            .map(|keyboard| keyboard.0.clone())
+            .unwrap_or_default();
+        for &key in self.keys.iter().filter(|key| !keys.contains(key)) {
+            self.fake(KEY_RELEASE_EVENT, self.keycode(key)?, 0, 0)?;
+        }
+        for &key in keys.iter().filter(|key| !self.keys.contains(key)) {
+            self.fake(KEY_PRESS_EVENT, self.keycode(key)?, 0, 0)?;
+        }
+        self.keys = keys;
+
+        if let Some(mouse) = input.mouse {
+            self.send_mouse(&mouse)?;
+        }
+        self.conn.flush()?;
+        Ok(())
+    }
+
+    /// Sends the pointer motion and the button changes of `mouse`.
+    fn send_mouse(&mut self, mouse: &MouseInput) -> Result<(), XTestError> {
+        let clamp = |value: i32| value.clamp(i16::MIN.into(), i16::MAX.into()) as i16;
+        let (x, y) = (clamp(mouse.xpos), clamp(mouse.ypos));
+        match mouse.reference_mode {
+            ReferenceMode::Absolute => self.fake(MOTION_NOTIFY_EVENT, 0, x, y)?,
+            ReferenceMode::Relative if (x, y) != (0, 0) => {
+                self.fake(MOTION_NOTIFY_EVENT, 1, x, y)?;
+            }
+            ReferenceMode::Relative => {}
+        }
+
+        let buttons = [
+            mouse.left_click,
+            mouse.middle_click,
+            mouse.right_click,
+            mouse.button4,
+            mouse.button5,
+        ];
+        for (index, (&held, &pressed)) in self.buttons.iter().zip(&buttons).enumerate() {
+            if held != pressed {
+                let kind = if pressed {
+                    BUTTON_PRESS_EVENT
+                } else {
+                    BUTTON_RELEASE_EVENT
+                };
+                self.fake(kind, index as u8 + 1, 0, 0)?;
+            }
+        }
+        self.buttons = buttons;
+        Ok(())
+    }
+
+    /// Releases all held keys and buttons.
+    pub fn release_all(&mut self) -> Result<(), XTestError> {
+        self.send(&Input {
+            mouse: self.buttons.contains(&true).then(|| MouseInput {
+                reference_mode: ReferenceMode::Relative,
+                ..MouseInput::default()
+            }),
+            ..Input::default()
+        })
+    }
+}
+
+impl InputSink for XTestSink {
+    fn frame(&mut self, _idx: usize, input: &Input) {
+        if self.error.is_none()
+            && let Err(err) = self.send(input)
+        {
+            self.error = Some(err);
+        }
+    }
+}
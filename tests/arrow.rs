@@ -0,0 +1,81 @@
+#![cfg(feature = "arrow")]
+
+use arrow_array::{Array as _, BooleanArray, Int32Array, Int64Array, StringArray};
+use libtas_movie::{
+    export::{arrow::record_batch, matrix::ColumnSpec},
+    testkit::{movie_with_mouse_path, sample_movie},
+};
+
+#[test]
+fn test_record_batch() {
+    let mut movie = sample_movie(4, 10);
+    movie.inputs.0[3].mouse = movie_with_mouse_path(&[(0, 0), (5, 6)], 10).inputs.0[1].mouse;
+    movie.inputs.0[2].unknown = vec!["F1".to_owned()];
+    let spec = ColumnSpec::for_inputs(&movie.inputs);
+    let batch = record_batch(&movie, &spec).unwrap();
+
+    let names: Vec<_> = batch
+        .schema()
+        .fields()
+        .iter()
+        .map(|field| field.name().clone())
+        .collect();
+    assert_eq!(
+        names,
+        [
+            "frame",
+            "time_ns",
+            "key_z",
+            "key_Right",
+            "button_LMB",
+            "x",
+            "y",
+            "flags"
+        ]
+    );
+    assert_eq!(batch.num_rows(), 4);
+
+    let column = |name: &str| batch.column_by_name(name).unwrap().clone();
+    let time = column("time_ns");
+    let time = time.as_any().downcast_ref::<Int64Array>().unwrap();
+    assert_eq!(time.value(3), 300_000_000);
+    let z = column("key_z");
+    let z = z.as_any().downcast_ref::<BooleanArray>().unwrap();
+    assert_eq!(
+        z.iter().collect::<Vec<_>>(),
+        [Some(false), Some(true), Some(false), Some(true)]
+    );
+    let x = column("x");
+    let x = x.as_any().downcast_ref::<Int32Array>().unwrap();
+    assert_eq!(x.iter().collect::<Vec<_>>(), [None, None, None, Some(5)]);
+    let flags = column("flags");
+    let flags = flags.as_any().downcast_ref::<StringArray>().unwrap();
+    assert_eq!(flags.null_count(), 3);
+    assert_eq!(flags.value(2), "F1");
+}
+
+#[cfg(feature = "parquet")]
+#[test]
+fn test_parquet() {
+    use libtas_movie::format::FormatRegistry;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    let movie = sample_movie(100, 60);
+    let registry = FormatRegistry::builtin();
+    let parquet = registry.get("parquet").unwrap().export(&movie).unwrap();
+    assert!(parquet.starts_with(b"PAR1"));
+
+    let path = std::env::temp_dir().join(format!("libtas-movie-{}.parquet", std::process::id()));
+    std::fs::write(&path, &parquet).unwrap();
+    let file = std::fs::File::open(&path).unwrap();
+    let batches = ParquetRecordBatchReaderBuilder::try_new(file)
+        .unwrap()
+        .build()
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let spec = ColumnSpec::for_inputs(&movie.inputs);
+    assert_eq!(batches, [record_batch(&movie, &spec).unwrap()]);
+}
@@ -0,0 +1,72 @@
+use libtas_movie::{
+    branch::Branches,
+    diff::diff_inputs,
+    inputs::{Input, Inputs, KeyboardInput},
+    movie::LoadOptions,
+    section::{MovieSection as _, SectionRegistry},
+    testkit::sample_movie,
+};
+
+/// Returns options loading the sections of this crate.
+fn builtin_options() -> LoadOptions {
+    LoadOptions {
+        sections: SectionRegistry::builtin(),
+        ..LoadOptions::default()
+    }
+}
+
+#[test]
+fn test_branches() {
+    let mut movie = sample_movie(8, 60);
+    assert!(movie.branches().is_none());
+    assert!(movie.create_branch("main"));
+    assert!(!movie.create_branch("bad\nname"));
+
+    movie.inputs.0.truncate(6);
+    movie.inputs.0[2] = Input::default();
+    assert!(movie.create_branch("alt"));
+    assert_eq!(
+        movie.branches().unwrap().names().collect::<Vec<_>>(),
+        ["main", "alt"]
+    );
+    assert_eq!(movie.diff_branches("main", "alt"), Some(vec![2..3, 6..8]));
+    assert_eq!(movie.diff_branches("main", "none"), None);
+
+    let alt = movie.switch_branch("main").unwrap();
+    assert_eq!(alt.0.len(), 6);
+    assert_eq!(movie.inputs.0.len(), 8);
+    assert_eq!(movie.config.general.frame_count, 8);
+    assert!(movie.switch_branch("none").is_none());
+
+    let loaded = builtin_options()
+        .load_from_reader(movie.compress().unwrap().as_slice())
+        .unwrap();
+    assert_eq!(loaded, movie);
+    assert_eq!(loaded.branches().unwrap().get("alt").unwrap().inputs, alt);
+
+    assert_eq!(movie.remove_branch("main").unwrap().name, "main");
+    assert!(movie.remove_branch("main").is_none());
+}
+
+#[test]
+fn test_branches_format() {
+    let branches = Branches::parse(b"[a b]\n|K7a|\n|\n[empty]\n").unwrap();
+    assert_eq!(branches.0.len(), 2);
+    assert_eq!(branches.0[0].name, "a b");
+    assert_eq!(
+        branches.0[0].inputs.0[0].keyboard,
+        Some(KeyboardInput(vec![0x7a]))
+    );
+    assert_eq!(branches.0[1].inputs, Inputs::default());
+    assert_eq!(branches.serialize(), b"[a b]\n|K7a|\n|\n[empty]\n");
+
+    assert!(Branches::parse(b"|K7a|\n").is_err());
+}
+
+#[test]
+fn test_diff_inputs() {
+    let a = sample_movie(4, 60).inputs;
+    assert!(diff_inputs(&a, &a).is_empty());
+    let ranges = diff_inputs(&a, &Inputs::default());
+    assert_eq!((ranges.len(), ranges[0].clone()), (1, 0..4));
+}
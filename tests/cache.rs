@@ -0,0 +1,81 @@
+use std::fs;
+
+use libtas_movie::{
+    cache::{CacheError, cache_path, decode_cache, encode_cache, file_hash, load_movie_cached},
+    comment::FrameComments,
+    inputs::{ControllerInput, Input},
+    movie::load_movie,
+    section::SectionRegistry,
+    testkit::{movie_with_mouse_path, sample_movie},
+    time::Framerate,
+};
+
+#[test]
+fn test_cache_roundtrip() {
+    let mut movie = load_movie("tests/movies/221769_Trapped_5.ltm").unwrap();
+    movie.inputs.insert_line(3, "# comment".to_owned());
+    movie.inputs.0[4].unknown.push("F1".to_owned());
+    movie
+        .sections
+        .get_or_insert_default::<FrameComments>()
+        .0
+        .insert(2, "jump".to_owned());
+    let hash = [7; 32];
+    let cache = encode_cache(&movie, &hash);
+    let decoded = decode_cache(&cache, &hash, &SectionRegistry::builtin()).unwrap();
+    assert_eq!(decoded, movie);
+
+    assert!(matches!(
+        decode_cache(&cache, &[0; 32], &SectionRegistry::builtin()),
+        Err(CacheError::Stale)
+    ));
+    assert!(matches!(
+        decode_cache(
+            &cache[..cache.len() - 1],
+            &hash,
+            &SectionRegistry::builtin()
+        ),
+        Err(CacheError::Invalid)
+    ));
+    assert!(matches!(
+        decode_cache(b"not a cache", &hash, &SectionRegistry::builtin()),
+        Err(CacheError::Invalid)
+    ));
+}
+
+#[test]
+fn test_cache_many_frames() {
+    let path = (0..10_000).map(|x| (x % 640, x / 640)).collect::<Vec<_>>();
+    let mut movie = movie_with_mouse_path(&path, 60);
+    movie.inputs.0[5000].controllers = vec![ControllerInput {
+        number: 2,
+        state: "a:b".to_owned(),
+    }];
+    movie.inputs.0[6000].framerate = Some(Framerate { num: 120, den: 2 });
+    movie.inputs.0[6001].framerate = Some(Framerate { num: 60, den: 1 });
+    movie.inputs.0[9999] = Input::default();
+    let cache = encode_cache(&movie, &[0; 32]);
+    let decoded = decode_cache(&cache, &[0; 32], &SectionRegistry::new()).unwrap();
+    assert_eq!(decoded.inputs.to_string(), movie.inputs.to_string());
+}
+
+#[test]
+fn test_load_movie_cached() {
+    let path = format!("{}/cached.ltm", env!("CARGO_TARGET_TMPDIR"));
+    let cache = cache_path(&path);
+    assert_eq!(cache.to_str(), Some(&*format!("{path}.cache")));
+    let _ = fs::remove_file(&cache);
+
+    let movie = sample_movie(100, 60);
+    movie.save_to_path(&path).unwrap();
+    assert_eq!(load_movie_cached(&path).unwrap(), movie);
+    let data = fs::read(&cache).unwrap();
+    let hash = file_hash(&fs::read(&path).unwrap());
+    assert!(decode_cache(&data, &hash, &SectionRegistry::builtin()).is_ok());
+    assert_eq!(load_movie_cached(&path).unwrap(), movie);
+
+    // a modified movie invalidates the cache
+    let movie = sample_movie(50, 30);
+    movie.save_to_path(&path).unwrap();
+    assert_eq!(load_movie_cached(&path).unwrap(), movie);
+}
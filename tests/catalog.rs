@@ -0,0 +1,148 @@
+use core::time::Duration;
+use std::{borrow::Cow, fs, path::PathBuf};
+
+use libtas_movie::{
+    catalog::{Catalog, CatalogEntry},
+    inputs::KeyboardInput,
+    movie::{LoadError, load_config, load_movie, read_raw_movie},
+    testkit::{BadArchive, archive, sample_movie},
+    version::Version,
+};
+
+#[test]
+fn test_load_config() {
+    let config = load_config("tests/movies/221769_Trapped_5.ltm").unwrap();
+    assert_eq!(config.general.frame_count, 456);
+    assert!(matches!(
+        load_config("tests/invalid_movies/not_movie.txt"),
+        Err(LoadError::InvalidArchive { .. })
+    ));
+}
+
+#[test]
+fn test_metadata() {
+    let data = fs::read("tests/movies/221769_Trapped_5.ltm").unwrap();
+    let raw = read_raw_movie(data.as_slice()).unwrap();
+    assert_eq!(raw.archive, data);
+    let metadata = raw.metadata().unwrap();
+    assert!(matches!(metadata.config.authors, Cow::Borrowed("synabler")));
+    assert_eq!(metadata.config.game_name, "ruffle");
+    assert_eq!(
+        metadata.config.get_raw("General", "frame_count"),
+        Some("456")
+    );
+    assert_eq!(metadata.config.get_raw("General", "missing"), None);
+
+    let movie = load_movie("tests/movies/221769_Trapped_5.ltm").unwrap();
+    assert_eq!(metadata.config.md5, movie.config.general.md5);
+    assert_eq!(metadata.annotations, movie.annotations);
+    assert_eq!(metadata.config.to_config().unwrap(), movie.config);
+
+    let raw = read_raw_movie(BadArchive::MissingEntry.bytes().as_slice()).unwrap();
+    assert!(raw.metadata().is_ok());
+    assert!(matches!(
+        read_raw_movie(BadArchive::NotGzip.bytes().as_slice()),
+        Err(LoadError::InvalidArchive { .. })
+    ));
+}
+
+#[test]
+fn test_catalog() {
+    let catalog = Catalog::scan("tests").unwrap();
+    assert!(catalog.errors.is_empty());
+    assert_eq!(
+        catalog.entries,
+        [CatalogEntry {
+            path: PathBuf::from("tests/movies/221769_Trapped_5.ltm"),
+            game_name: "ruffle".to_owned(),
+            authors: "synabler".to_owned(),
+            frame_count: 456,
+            length: Duration::from_millis(22800),
+            md5: "c9b4f1b544725cb0d9d784c35232a52d".to_owned(),
+            libtas_version: Version::new(1, 4, 7),
+            file_hash: "e85a55ac20c730000790e2a0b56f590cd4b8cfdd9d8f65e9c7898d3afdbd47f0"
+                .to_owned(),
+        }]
+    );
+
+    assert_eq!(
+        catalog.to_csv(),
+        "path,game_name,authors,frame_count,length,md5,libtas_version,file_hash\n\
+         tests/movies/221769_Trapped_5.ltm,ruffle,synabler,456,22.800000000,\
+         c9b4f1b544725cb0d9d784c35232a52d,1.4.7,\
+         e85a55ac20c730000790e2a0b56f590cd4b8cfdd9d8f65e9c7898d3afdbd47f0\n"
+    );
+    assert!(catalog.to_json().contains(
+        "\"game_name\": \"ruffle\", \"authors\": \"synabler\", \"frame_count\": 456, \
+         \"length\": 22.800000000,"
+    ));
+}
+
+#[test]
+fn test_catalog_entry_errors() {
+    let mut entry = CatalogEntry::from_path("tests/movies/221769_Trapped_5.ltm").unwrap();
+    entry.authors = "a, \"b\"".to_owned();
+    let catalog = Catalog {
+        entries: vec![entry],
+        errors: vec![],
+    };
+    assert!(catalog.to_csv().contains(",\"a, \"\"b\"\"\","));
+    assert!(catalog.to_json().contains("\"authors\": \"a, \\\"b\\\"\""));
+
+    for bad in [BadArchive::NotGzip, BadArchive::InvalidConfig] {
+        assert!(CatalogEntry::from_bytes("bad.ltm", &bad.bytes()).is_err());
+    }
+    // only the config is read
+    assert!(CatalogEntry::from_bytes("extra.ltm", &BadArchive::ExtraEntry.bytes()).is_ok());
+}
+
+#[test]
+fn test_content_hash() {
+    let movie = sample_movie(8, 60);
+    let hash = movie.content_hash();
+    assert_eq!(hash.len(), 64);
+
+    let mut reordered = movie.clone();
+    reordered.inputs.0[3].keyboard = Some(KeyboardInput(vec![0xff53, 0x7a]));
+    assert_ne!(reordered.compress().unwrap(), movie.compress().unwrap());
+    assert_eq!(reordered.content_hash(), hash);
+
+    let mut changed = movie.clone();
+    changed.inputs.0[0].keyboard = Some(KeyboardInput(vec![0x7a]));
+    assert_ne!(changed.content_hash(), hash);
+}
+
+#[test]
+fn test_find_duplicates() {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("catalog_duplicates");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("nested")).unwrap();
+
+    let movie = sample_movie(8, 60);
+    movie.save_to_path(dir.join("a.ltm")).unwrap();
+    let config = movie.config.to_string();
+    let inputs = movie.inputs.to_string();
+    fs::write(
+        dir.join("nested/b.ltm"),
+        archive(&[
+            ("editor.ini", b""),
+            ("annotations.txt", b""),
+            ("inputs", inputs.as_bytes()),
+            ("config.ini", config.as_bytes()),
+        ]),
+    )
+    .unwrap();
+    let mut other = movie.clone();
+    other.annotations = "other".to_owned();
+    other.save_to_path(dir.join("c.ltm")).unwrap();
+    sample_movie(9, 60).save_to_path(dir.join("d.ltm")).unwrap();
+
+    let catalog = Catalog::scan(&dir).unwrap();
+    assert_eq!(catalog.entries.len(), 4);
+    let duplicates = catalog.find_duplicates();
+    let paths: Vec<Vec<_>> = duplicates
+        .iter()
+        .map(|group| group.iter().map(|entry| entry.path.clone()).collect())
+        .collect();
+    assert_eq!(paths, [[dir.join("a.ltm"), dir.join("nested/b.ltm")]]);
+}
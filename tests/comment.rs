@@ -0,0 +1,73 @@
+use libtas_movie::{
+    comment::FrameComments,
+    edit::EditSession,
+    inputs::Input,
+    movie::LoadOptions,
+    section::{MovieSection as _, SectionRegistry},
+    testkit::sample_movie,
+};
+
+/// Returns options loading the sections of this crate.
+fn builtin_options() -> LoadOptions {
+    LoadOptions {
+        sections: SectionRegistry::builtin(),
+        ..LoadOptions::default()
+    }
+}
+
+#[test]
+fn test_comments() {
+    let mut movie = sample_movie(10, 60);
+    assert_eq!(movie.comment(2), None);
+    assert_eq!(movie.set_comment(2, "jump\nhere"), None);
+    assert_eq!(movie.set_comment(6, "land"), None);
+    assert_eq!(movie.set_comment(8, "menu"), None);
+    assert_eq!(movie.comment(2), Some("jump\nhere"));
+
+    let loaded = builtin_options()
+        .load_from_reader(movie.compress().unwrap().as_slice())
+        .unwrap();
+    assert_eq!(loaded, movie);
+
+    // delete frames 5..7, and insert one frame at 0
+    let removed = movie.splice_inputs(5..7, vec![]).unwrap();
+    assert_eq!(removed.len(), 2);
+    movie.splice_inputs(0..0, vec![Input::default()]).unwrap();
+    assert_eq!(movie.config.general.frame_count, 9);
+    assert_eq!(movie.comment(3), Some("jump\nhere"));
+    assert_eq!(movie.comment(7), Some("menu"));
+    assert_eq!(movie.sections.get::<FrameComments>().unwrap().0.len(), 2);
+    assert!(movie.splice_inputs(9..10, vec![]).is_none());
+
+    assert_eq!(movie.remove_comment(3).as_deref(), Some("jump\nhere"));
+    assert_eq!(movie.remove_comment(7).as_deref(), Some("menu"));
+    assert!(movie.sections.is_empty());
+}
+
+#[test]
+fn test_comments_format() {
+    let comments = FrameComments::parse(b"3 a\\\\b\\nc\n10 \n").unwrap();
+    assert_eq!(comments.0[&3], "a\\b\nc");
+    assert_eq!(comments.0[&10], "");
+    assert_eq!(comments.serialize(), b"3 a\\\\b\\nc\n10 \n");
+    assert!(FrameComments::parse(b"3 a\\b\n").is_err());
+    assert!(FrameComments::parse(b"x a\n").is_err());
+}
+
+#[test]
+fn test_comments_undo() {
+    let mut movie = sample_movie(10, 60);
+    movie.set_comment(4, "deleted");
+    movie.set_comment(8, "shifted");
+    let original = movie.clone();
+
+    let mut session = EditSession::new(movie);
+    session.splice(3..6, vec![Input::default()]);
+    assert_eq!(session.movie().comment(4), None);
+    assert_eq!(session.movie().comment(6), Some("shifted"));
+
+    session.set_comment(0, Some("new".to_owned()));
+    assert!(session.undo());
+    assert!(session.undo());
+    assert_eq!(*session.movie(), original);
+}
@@ -0,0 +1,272 @@
+use libtas_movie::{
+    config::{ControllerType, InputsConfig},
+    consistency::{Inconsistency, MouseSupportFix, SanitizeReport},
+    edit::EditSession,
+    inputs::{ControllerInput, Input, KeyboardInput, MouseInput, Rect, ReferenceMode},
+    movie::load_movie,
+    testkit::{movie_with_mouse_path, sample_movie},
+};
+
+fn controller_input(number: u8) -> Input {
+    format!("|C{number}0:0:0:0:0:0:...............|")
+        .parse()
+        .unwrap()
+}
+
+#[test]
+fn test_controller_input() {
+    let input = controller_input(2);
+    assert_eq!(
+        input.controllers,
+        [ControllerInput {
+            number: 2,
+            state: "0:0:0:0:0:0:...............".to_owned()
+        }]
+    );
+    assert_eq!(input.to_string(), "|C20:0:0:0:0:0:...............|");
+    assert!("|C|".parse::<Input>().is_err());
+    assert!("|C0|".parse::<Input>().is_err());
+}
+
+#[test]
+fn test_nb_controllers() {
+    let mut movie = sample_movie(4, 60);
+    assert_eq!(movie.max_controller(), 0);
+    assert!(!movie.raise_nb_controllers());
+
+    movie.inputs.0[1] = controller_input(1);
+    movie.inputs.0[3] = controller_input(3);
+    assert_eq!(movie.frames_exceeding_controllers(), [1, 3]);
+    assert!(movie.raise_nb_controllers());
+    assert_eq!(movie.config.general.nb_controllers, 3);
+    assert!(movie.frames_exceeding_controllers().is_empty());
+}
+
+#[test]
+fn test_edit_session_nb_controllers() {
+    let mut session = EditSession::new(sample_movie(4, 60));
+    session.set_input(0, controller_input(2));
+    assert_eq!(session.movie().config.general.nb_controllers, 2);
+    assert!(session.undo());
+    assert_eq!(session.movie().config.general.nb_controllers, 0);
+    assert!(session.movie().inputs.0[0].controllers.is_empty());
+
+    session.set_auto_nb_controllers(false);
+    session.set_input(0, controller_input(2));
+    assert_eq!(session.movie().config.general.nb_controllers, 0);
+    assert_eq!(session.movie().frames_exceeding_controllers(), [0]);
+
+    // only the inserted frames raise nb_controllers
+    session.set_auto_nb_controllers(true);
+    session.set_input(1, Input::default());
+    assert_eq!(session.movie().config.general.nb_controllers, 0);
+    session.set_input(1, controller_input(1));
+    assert_eq!(session.movie().config.general.nb_controllers, 1);
+}
+
+#[test]
+fn test_mouse_support() {
+    let mut movie = movie_with_mouse_path(&[(0, 0), (1, 1)], 60);
+    movie.inputs.0.push(Input::default());
+    assert!(movie.inconsistencies().is_empty());
+
+    movie.config.general.mouse_support = false;
+    assert_eq!(
+        movie.inconsistencies(),
+        [Inconsistency::UnsupportedMouse(vec![0, 1])]
+    );
+
+    let mut enabled = movie.clone();
+    assert!(enabled.fix_mouse_support(MouseSupportFix::EnableSupport));
+    assert!(enabled.config.general.mouse_support);
+    assert_eq!(enabled.inputs, movie.inputs);
+
+    assert!(movie.fix_mouse_support(MouseSupportFix::StripInputs));
+    assert!(!movie.config.general.mouse_support);
+    assert!(movie.inputs.0.iter().all(|input| input.mouse.is_none()));
+    assert!(!movie.fix_mouse_support(MouseSupportFix::StripInputs));
+    assert!(movie.inconsistencies().is_empty());
+
+    movie.inputs.0[2].mouse = Some(MouseInput::default());
+    assert_eq!(movie.frames_with_unsupported_mouse(), [2]);
+}
+
+#[test]
+fn test_inputs_config() {
+    let mut movie = sample_movie(4, 60);
+    let inputs_config = movie.config.inputs_config();
+    assert_eq!(
+        inputs_config,
+        InputsConfig {
+            keyboard_support: true,
+            mouse_support: false,
+            joystick_support: false,
+            nb_controllers: 0,
+        }
+    );
+
+    movie.config.set_inputs_config(inputs_config);
+    assert_eq!(movie.config.get_raw("General", "keyboard_support"), None);
+
+    // `joystick_support` is only written if it differs from having controllers
+    movie.config.set_inputs_config(InputsConfig {
+        nb_controllers: 2,
+        joystick_support: true,
+        ..inputs_config
+    });
+    assert_eq!(movie.config.general.joystick_support, None);
+    movie.config.set_inputs_config(InputsConfig {
+        nb_controllers: 2,
+        ..inputs_config
+    });
+    assert_eq!(movie.config.general.joystick_support, Some(false));
+    assert!(!movie.config.inputs_config().joystick_support);
+    movie.config.set_inputs_config(inputs_config);
+
+    movie.config.set_inputs_config(InputsConfig {
+        keyboard_support: false,
+        ..inputs_config
+    });
+    assert!(
+        movie
+            .config
+            .to_string()
+            .contains("\nkeyboard_support=false\n")
+    );
+    assert_eq!(
+        movie.inconsistencies(),
+        [Inconsistency::UnsupportedKeyboard(vec![1, 2, 3])]
+    );
+}
+
+#[test]
+fn test_sanitize_inputs() {
+    let mut movie = movie_with_mouse_path(&[(0, 0), (1, 1), (2, 2)], 60);
+    assert!(movie.sanitize_inputs().is_empty());
+
+    movie.config.general.mouse_support = false;
+    movie.config.general.nb_controllers = 1;
+    movie.inputs.0[0].keyboard = Some(KeyboardInput((1..=34).collect()));
+    movie.inputs.0[2] = "|C10:0:0:0:0:0:...............|C20:0:0:0:0:0:...............|"
+        .parse()
+        .unwrap();
+    let report = movie.sanitize_inputs();
+    assert_eq!(
+        report,
+        SanitizeReport {
+            keyboard_frames: vec![],
+            mouse_frames: vec![0, 1],
+            controllers: vec![(2, controller_input(2).controllers[0].clone())],
+            keys: vec![(0, 33), (0, 34)],
+        }
+    );
+    assert_eq!(
+        movie.inputs.0[0].keyboard,
+        Some(KeyboardInput((1..=32).collect()))
+    );
+    assert_eq!(movie.inputs.0[2], controller_input(1));
+    assert!(movie.inconsistencies().is_empty());
+    assert!(movie.sanitize_inputs().is_empty());
+}
+
+#[test]
+fn test_joystick_config() {
+    let mut movie = sample_movie(4, 60);
+    movie.inputs.0[1] = controller_input(1);
+    movie.inputs.0[3] = controller_input(2);
+    movie.config.general.nb_controllers = 2;
+    assert!(movie.config.general.joystick_support());
+    assert!(movie.inconsistencies().is_empty());
+
+    movie
+        .config
+        .set_raw("General", "controller_types", "xbox360, custom")
+        .unwrap();
+    assert_eq!(
+        movie.config.general.controller_types,
+        Some(vec![
+            ControllerType::Xbox360,
+            ControllerType::Other("custom".to_owned())
+        ])
+    );
+    assert_eq!(
+        movie.config.general.controller_type(2),
+        Some(&ControllerType::Other("custom".to_owned()))
+    );
+    assert_eq!(movie.config.general.controller_type(0), None);
+    assert!(movie.inconsistencies().is_empty());
+
+    movie.config.general.nb_controllers = 1;
+    movie.config.general.joystick_support = Some(false);
+    assert!(!movie.config.general.joystick_support());
+    assert!(!movie.config.inputs_config().joystick_support);
+    let string = movie.config.to_string();
+    assert!(string.contains("\ncontroller_types=xbox360, custom\n"));
+    assert!(string.contains("\njoystick_support=false\n"));
+    assert_eq!(
+        movie.inconsistencies(),
+        [
+            Inconsistency::ExceedingControllers(vec![3]),
+            Inconsistency::UnsupportedJoystick(vec![1, 3]),
+            Inconsistency::ControllerTypes {
+                types: 2,
+                nb_controllers: 1
+            },
+        ]
+    );
+
+    let report = movie.sanitize_inputs();
+    assert_eq!(
+        report.controllers,
+        [
+            (1, controller_input(1).controllers[0].clone()),
+            (3, controller_input(2).controllers[0].clone()),
+        ]
+    );
+}
+
+#[test]
+fn test_frame_count_consistency() {
+    let mut movie = load_movie("tests/movies/221769_Trapped_5.ltm").unwrap();
+    assert_eq!(movie.frame_count(), 456);
+    assert!(movie.is_frame_count_consistent());
+    assert!(movie.is_length_consistent());
+
+    movie.inputs.0.pop();
+    assert_eq!(movie.frame_count(), 455);
+    assert!(!movie.is_frame_count_consistent());
+    assert!(!movie.is_length_consistent());
+
+    movie.config.general.frame_count = 455;
+    assert!(movie.is_frame_count_consistent());
+    // the length is still that of 456 frames
+    assert!(!movie.is_length_consistent());
+    assert!(movie.recompute_length());
+    assert!(movie.is_length_consistent());
+}
+
+#[test]
+fn test_mouse_off_screen() {
+    let mut movie = movie_with_mouse_path(&[(0, 0), (640, 10), (639, 479), (-1, 5)], 60);
+    assert_eq!(movie.config.general.screen_rect(), None);
+    assert!(movie.frames_with_mouse_off_screen().is_empty());
+
+    movie.config.general.set_screen_size(640, 480);
+    assert_eq!(
+        movie.config.general.screen_rect(),
+        Some(Rect {
+            left: 0,
+            top: 0,
+            right: 639,
+            bottom: 479,
+        })
+    );
+    assert_eq!(movie.frames_with_mouse_off_screen(), [1, 3]);
+
+    // relative coordinates are offsets, not positions
+    movie.inputs.0[1].mouse.as_mut().unwrap().reference_mode = ReferenceMode::Relative;
+    assert_eq!(movie.frames_with_mouse_off_screen(), [3]);
+
+    movie.config.general.set_screen_size(0, 480);
+    assert_eq!(movie.config.general.screen_rect(), None);
+}
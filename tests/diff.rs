@@ -0,0 +1,116 @@
+use core::time::Duration;
+
+use libtas_movie::{
+    diff::{ChangeKind, ConfigChange, MovieDiff, SegmentLength, compare_segments},
+    editor::Marker,
+    movie::LibTASMovie,
+    testkit::sample_movie,
+};
+
+fn marked_movie(frames: usize, markers: &[(u64, &str)]) -> LibTASMovie {
+    let mut movie = sample_movie(frames, 60);
+    movie.editor.markers = markers
+        .iter()
+        .map(|&(frame, text)| Marker {
+            frame,
+            text: text.to_owned(),
+        })
+        .collect();
+    movie
+}
+
+#[test]
+fn test_compare_segments() {
+    let old = marked_movie(300, &[(0, "Intro"), (60, "Level 1"), (180, "Boss")]);
+    let new = marked_movie(
+        270,
+        &[(0, "Intro"), (60, "Level 1"), (150, "Skip"), (160, "Boss")],
+    );
+    let comparison = compare_segments(&old, &new);
+    let labels = comparison
+        .0
+        .iter()
+        .map(|timing| timing.label.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(labels, ["Intro", "Level 1", "Boss", "Skip"]);
+    let gains = comparison
+        .0
+        .iter()
+        .map(|timing| timing.frames_gained())
+        .collect::<Vec<_>>();
+    assert_eq!(gains, [Some(0), Some(30), Some(10), None]);
+    assert_eq!(comparison.frames_gained(), 40);
+    assert_eq!(
+        comparison.0[1].new,
+        Some(SegmentLength {
+            frames: 90,
+            duration: Duration::from_millis(1500),
+        })
+    );
+    assert_eq!(comparison.0[3].old, None);
+
+    let text = comparison.to_string();
+    let lines = text.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 6);
+    assert!(lines[2].starts_with("Level 1"));
+    assert!(lines[2].ends_with("+30"));
+    assert!(lines[2].contains("120 (00:00:02.000)"));
+    assert!(lines[4].ends_with("-"));
+    assert!(lines[5].ends_with("+40"));
+}
+
+#[test]
+fn test_movie_diff() {
+    let old = sample_movie(6, 60);
+    let mut new = old.clone();
+    new.inputs.0.truncate(4);
+    new.inputs.0[1] = "|K7b|".parse().unwrap();
+    new.config.general.rerecord_count = 5;
+    new.config.set_raw("General", "future_key", "1").unwrap();
+
+    let diff = MovieDiff::new(&old, &new);
+    assert_eq!(
+        diff.config,
+        [
+            ConfigChange {
+                section: "General".to_owned(),
+                key: "rerecord_count".to_owned(),
+                old: Some("0".to_owned()),
+                new: Some("5".to_owned()),
+            },
+            ConfigChange {
+                section: "General".to_owned(),
+                key: "future_key".to_owned(),
+                old: None,
+                new: Some("1".to_owned()),
+            },
+        ]
+    );
+    let hunks: Vec<_> = diff
+        .hunks
+        .iter()
+        .map(|hunk| (hunk.frames(), hunk.kind()))
+        .collect();
+    assert_eq!(
+        hunks,
+        [(1..2, ChangeKind::Changed), (4..6, ChangeKind::Removed)]
+    );
+
+    assert_eq!(
+        diff.to_string(),
+        "config:
+~ General.rerecord_count  0  5
++ General.future_key      -  1
+frames:
+~ 1  |K7a|  |K7b|
+- 4  |      -
+- 5  |K7a|  -
+"
+    );
+    assert_eq!(
+        diff.render(true).to_string().lines().nth(1),
+        Some("\x1b[33m~ General.rerecord_count  0  5\x1b[0m")
+    );
+    assert!(MovieDiff::new(&old, &old).is_empty());
+    assert_eq!(MovieDiff::new(&old, &old).to_string(), "no differences\n");
+}
@@ -0,0 +1,107 @@
+use libtas_movie::{
+    edit::{Edit, EditSession},
+    inputs::{Input, KeyboardInput},
+    testkit::sample_movie,
+};
+
+#[test]
+fn test_undo_redo() {
+    let original = sample_movie(8, 60);
+    let mut session = EditSession::new(original.clone());
+    assert!(!session.can_undo());
+
+    assert!(session.toggle_key(0, 0x61));
+    assert!(session.toggle_key(1, 0x7a));
+    assert_eq!(
+        session.movie().inputs.0[0].keyboard,
+        Some(KeyboardInput(vec![0x61]))
+    );
+    assert_eq!(session.movie().inputs.0[1].keyboard, None);
+    assert!(!session.toggle_key(8, 0x61));
+
+    assert!(session.splice(2..4, vec![]));
+    assert_eq!(session.movie().config.general.frame_count, 6);
+    assert!(!session.splice(7..9, vec![]));
+
+    assert!(session.undo());
+    assert_eq!(session.movie().config.general.frame_count, 8);
+    assert!(session.can_redo());
+    assert!(session.redo());
+    assert_eq!(session.movie().inputs.0.len(), 6);
+
+    while session.undo() {}
+    assert_eq!(*session.movie(), original);
+
+    // a new edit drops the redo history
+    assert!(session.redo());
+    assert!(session.set_input(0, Input::default()));
+    assert!(!session.can_redo());
+}
+
+#[test]
+fn test_transactions() {
+    let original = sample_movie(8, 60);
+    let mut session = EditSession::new(original.clone());
+
+    session.begin_transaction();
+    session.edit_config(|config| config.general.rerecord_count = 10);
+    session.splice(0..0, vec![Input::default(); 2]);
+    session.end_transaction();
+    assert_eq!(session.movie().config.general.rerecord_count, 10);
+    assert_eq!(session.movie().inputs.0.len(), 10);
+
+    assert!(session.undo());
+    assert!(!session.can_undo());
+    assert_eq!(*session.movie(), original);
+
+    // an empty edit is not recorded
+    session.edit_config(|_| {});
+    assert!(!session.can_undo());
+}
+
+#[test]
+fn test_history_limit() {
+    let mut session = EditSession::new(sample_movie(8, 60));
+    for frame in 0..8 {
+        session.toggle_key(frame, 0x61);
+    }
+    session.set_history_limit(3);
+    assert!(session.undo());
+    assert!(session.undo());
+    assert!(session.undo());
+    assert!(!session.undo());
+    assert_eq!(
+        session.movie().inputs.0[4].keyboard,
+        Some(KeyboardInput(vec![0x61]))
+    );
+}
+
+#[test]
+fn test_apply() {
+    let movie = sample_movie(8, 60);
+    let mut session = EditSession::new(movie.clone());
+
+    // splices must remove the frames of the movie
+    let splice = |at, removed: &[Input]| Edit::Splice {
+        at,
+        removed: removed.to_vec(),
+        inserted: vec![],
+    };
+    assert!(!session.apply(splice(0, &movie.inputs.0[1..3])));
+    assert!(!session.apply(splice(7, &movie.inputs.0[6..8])));
+    assert!(!session.apply(splice(usize::MAX, &movie.inputs.0[..1])));
+    assert!(!session.can_undo());
+    assert!(session.apply(splice(1, &movie.inputs.0[1..3])));
+    assert_eq!(session.movie().inputs.0.len(), 6);
+
+    // comments must replace the current comment
+    let comment = Edit::Comment {
+        frame: 0,
+        old: Some("stale".to_owned()),
+        new: None,
+    };
+    assert!(!session.apply(comment));
+    assert!(session.undo());
+    assert!(!session.can_undo());
+    assert_eq!(session.movie(), &movie);
+}
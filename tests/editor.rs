@@ -0,0 +1,97 @@
+use libtas_movie::{
+    editor::{
+        Autofire, ColumnState, Editor, EditorState, SingleInput, ViewState, editor_state_path,
+    },
+    movie::load_movie,
+};
+
+#[test]
+fn test_columns() {
+    let movie = load_movie("tests/movies/221769_Trapped_5.ltm").unwrap();
+    let columns = &movie.editor.columns;
+    assert_eq!(columns[0].name, "Mouse X coord");
+    assert_eq!(
+        columns[0].single_input(),
+        Some(SingleInput { kind: 1, which: 1 })
+    );
+    assert_eq!(
+        movie.editor.column("space").unwrap().single_input(),
+        Some(SingleInput {
+            kind: SingleInput::KEYBOARD,
+            which: 0x20
+        })
+    );
+    assert_eq!(
+        movie.editor.column("Down").unwrap().single_input(),
+        Some(SingleInput {
+            kind: SingleInput::KEYBOARD,
+            which: 0xff54
+        })
+    );
+    assert!(columns.iter().all(|column| column.extra.is_empty()));
+}
+
+#[test]
+fn test_column_state() {
+    let editor = load_movie("tests/movies/221769_Trapped_5.ltm")
+        .unwrap()
+        .editor;
+    let state = EditorState {
+        columns: vec![
+            ColumnState {
+                name: "z".to_owned(),
+                locked: true,
+                autofire: Some("110".parse().unwrap()),
+                ..ColumnState::default()
+            },
+            ColumnState {
+                name: "Up".to_owned(),
+                hidden: true,
+                ..ColumnState::default()
+            },
+        ],
+        view: Some(ViewState {
+            scroll_frame: 100,
+            selection: Some(120..130),
+        }),
+    };
+    assert!(state.column("z").unwrap().locked);
+    assert!(editor.column("z").is_some());
+
+    let state_str = state.to_string();
+    assert_eq!(
+        state_str,
+        "[columns]\n1\\autofire=110\n1\\locked=true\n1\\name=z\n2\\hidden=true\n2\\name=Up\nsize=2\n\n\
+         [view]\nscroll_frame=100\nselection_end=130\nselection_start=120\n"
+    );
+    assert_eq!(state_str.parse::<EditorState>().unwrap(), state);
+    assert_eq!(EditorState::default().to_string(), "");
+    assert_eq!(
+        editor_state_path("a/b.ltm").to_str(),
+        Some("a/b.ltm.editor")
+    );
+
+    // keys unknown to libTAS are preserved in editor.ini, but checked in the sidecar file
+    let editor: Editor = "[input_names]\n1\\locked=maybe\n1\\name=z\nsize=1\n"
+        .parse()
+        .unwrap();
+    assert_eq!(
+        editor.columns[0].extra,
+        [("locked".to_owned(), "maybe".to_owned())]
+    );
+    assert!(
+        "[columns]\n1\\locked=maybe\nsize=1\n"
+            .parse::<EditorState>()
+            .is_err()
+    );
+}
+
+#[test]
+fn test_autofire() {
+    let autofire: Autofire = "10".parse().unwrap();
+    assert!(autofire.is_pressed(0));
+    assert!(!autofire.is_pressed(3));
+    assert_eq!(autofire.to_string(), "10");
+    assert!(!Autofire::default().is_pressed(0));
+    assert!("12".parse::<Autofire>().is_err());
+}
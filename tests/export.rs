@@ -0,0 +1,174 @@
+use libtas_movie::{
+    editor::Marker,
+    export::{
+        chapters::{ffmpeg_chapters, marker_timestamps},
+        matrix::{ColumnSpec, MouseSeries},
+        script::{autohotkey_script, xdotool_script},
+        vcd::vcd,
+    },
+    inputs::MouseButton,
+    movie::LibTASMovie,
+    testkit::{movie_with_mouse_path, sample_movie},
+};
+
+fn marked_movie() -> LibTASMovie {
+    let mut movie = sample_movie(300, 60);
+    movie.editor.markers = vec![
+        Marker {
+            frame: 90,
+            text: "Boss; phase=2".to_owned(),
+        },
+        Marker {
+            frame: 0,
+            text: "Start".to_owned(),
+        },
+    ];
+    movie
+}
+
+#[test]
+fn test_ffmpeg_chapters() {
+    assert_eq!(
+        ffmpeg_chapters(&marked_movie()),
+        r";FFMETADATA1
+
+[CHAPTER]
+TIMEBASE=1/1000
+START=0
+END=1500
+title=Start
+
+[CHAPTER]
+TIMEBASE=1/1000
+START=1500
+END=5000
+title=Boss\; phase\=2
+"
+    );
+}
+
+#[test]
+fn test_marker_timestamps() {
+    assert_eq!(
+        marker_timestamps(&marked_movie()),
+        "0\t00:00:00.000\tStart\n90\t00:00:01.500\tBoss; phase=2\n"
+    );
+}
+
+#[test]
+fn test_xdotool_script() {
+    let mut movie = sample_movie(4, 10);
+    movie.inputs.0[2].keyboard.as_mut().unwrap().0.push(0x3b);
+    assert_eq!(
+        xdotool_script(&movie),
+        "#!/bin/sh
+# 0.400s (4 frames @ 10 fps)
+sleep 0.100
+xdotool keydown z
+sleep 0.100
+xdotool keyup z keydown Right keydown 0x3b
+sleep 0.100
+xdotool keyup 0x3b keydown z
+sleep 0.100
+xdotool keyup z keyup Right
+"
+    );
+}
+
+#[test]
+fn test_autohotkey_script() {
+    let movie = movie_with_mouse_path(&[(10, 20), (10, 20), (-5, 3)], 60);
+    assert_eq!(
+        autohotkey_script(&movie),
+        "; 0.050s (3 frames @ 60 fps)
+#Requires AutoHotkey v2.0
+CoordMode \"Mouse\", \"Screen\"
+MouseMove 10, 20, 0
+Sleep 33
+MouseMove -5, 3, 0
+Click \"Left Down\"
+Sleep 17
+Click \"Left Up\"
+"
+    );
+}
+
+#[test]
+fn test_to_matrix() {
+    let inputs = movie_with_mouse_path(&[(1, 2), (3, 4)], 60).inputs;
+    let spec = ColumnSpec::for_inputs(&inputs);
+    assert_eq!(
+        spec,
+        ColumnSpec {
+            keys: vec![],
+            mouse_buttons: vec![MouseButton::Left],
+        }
+    );
+    assert_eq!(spec.labels(), ["LMB"]);
+    assert_eq!(inputs.to_matrix(&spec), [[false], [true]]);
+
+    let spec = ColumnSpec {
+        keys: vec![0x7a],
+        mouse_buttons: vec![MouseButton::Right, MouseButton::Left],
+    };
+    assert_eq!(spec.len(), 3);
+    assert_eq!(spec.labels(), ["z", "RMB", "LMB"]);
+    assert_eq!(
+        inputs.to_matrix(&spec),
+        [[false, false, false], [false, false, true]]
+    );
+    assert!(inputs.to_matrix(&ColumnSpec::default())[0].is_empty());
+
+    assert_eq!(
+        inputs.to_mouse_series(),
+        MouseSeries {
+            x: vec![Some(1), Some(3)],
+            y: vec![Some(2), Some(4)],
+        }
+    );
+    let series = sample_movie(2, 60).inputs.to_mouse_series();
+    assert_eq!(series.x, [None, None]);
+}
+
+#[test]
+fn test_vcd() {
+    let mut movie = sample_movie(4, 10);
+    movie.inputs.0[3].mouse = movie_with_mouse_path(&[(0, 0), (1, 1)], 10).inputs.0[1].mouse;
+    let spec = ColumnSpec::for_inputs(&movie.inputs);
+    assert_eq!(
+        vcd(&movie, &spec),
+        "$version libtas-movie $end
+$timescale 1ns $end
+$scope module inputs $end
+$var wire 1 ! z $end
+$var wire 1 \" Right $end
+$var wire 1 # LMB $end
+$upscope $end
+$enddefinitions $end
+#0
+$dumpvars
+0!
+0\"
+0#
+$end
+#100000000
+1!
+#200000000
+0!
+1\"
+#300000000
+1!
+1#
+#400000000
+"
+    );
+
+    // signals past `~` get longer identifiers
+    let spec = ColumnSpec {
+        keys: (0..100).collect(),
+        mouse_buttons: vec![],
+    };
+    let dump = vcd(&sample_movie(1, 60), &spec);
+    assert!(dump.contains("$var wire 1 ~ ] $end\n$var wire 1 !! ^ $end\n"));
+    assert!(dump.ends_with("0&!\n$end\n#16666667\n"));
+}
@@ -0,0 +1,100 @@
+use libtas_movie::{
+    fingerprint::{Fingerprint, first_divergence},
+    inputs::{Input, KeyboardInput},
+    testkit::sample_movie,
+};
+
+#[test]
+fn test_fingerprints() {
+    let inputs = sample_movie(8, 60).inputs;
+    let fingerprints = inputs.fingerprints();
+    assert_eq!(fingerprints.len(), 8);
+    assert_eq!(fingerprints, inputs.clone().fingerprints());
+    // identical frames at different positions still differ
+    assert_ne!(fingerprints[0], fingerprints[4]);
+    assert_eq!(Fingerprint(0xab).to_string(), "00000000000000ab");
+
+    let mut edited = inputs.clone();
+    edited.0[5].keyboard = None;
+    let other = edited.fingerprints();
+    assert_eq!(fingerprints[..5], other[..5]);
+    assert!(
+        fingerprints[5..]
+            .iter()
+            .zip(&other[5..])
+            .all(|(a, b)| a != b)
+    );
+
+    let mut commented = inputs.clone();
+    commented.insert_line(3, "# comment".to_owned());
+    assert_eq!(commented.fingerprints(), fingerprints);
+}
+
+#[test]
+fn test_first_divergence() {
+    let inputs = sample_movie(100, 60).inputs;
+    let fingerprints = inputs.fingerprints();
+    assert_eq!(first_divergence(&fingerprints, &fingerprints), None);
+    assert_eq!(
+        first_divergence(&fingerprints, &fingerprints[..60]),
+        Some(60)
+    );
+    assert_eq!(first_divergence(&[], &fingerprints), Some(0));
+
+    let mut edited = inputs.clone();
+    edited.0[37].keyboard = Some(KeyboardInput(vec![0x20]));
+    assert_eq!(
+        first_divergence(&fingerprints, &edited.fingerprints()),
+        Some(37)
+    );
+}
+
+#[test]
+fn test_locate_desync() {
+    let reference = sample_movie(100, 60).inputs;
+    let mut movie = reference.clone();
+    for frame in [10, 40, 47, 53, 90] {
+        movie.0[frame] = Input {
+            keyboard: Some(KeyboardInput(vec![0x20])),
+            ..Input::default()
+        };
+    }
+
+    let report = movie.locate_desync(&reference, 50, 2);
+    assert_eq!(report.first_difference, Some(10));
+    let frames: Vec<_> = report.differences.iter().map(|d| d.frame).collect();
+    assert_eq!(frames, [47, 53]);
+    assert_eq!(
+        report.differences[0].movie.as_ref().unwrap().keyboard,
+        Some(KeyboardInput(vec![0x20]))
+    );
+    assert_eq!(
+        report.differences[0].reference,
+        Some(reference.0[47].clone())
+    );
+    assert_eq!(
+        report.to_string(),
+        "desync at frame 50, inputs first differ at frame 10\n\
+         frame 47 (-3): movie |K20| reference |K7a:ff53|\n\
+         frame 53 (+3): movie |K20| reference |K7a|\n"
+    );
+
+    let report = movie.locate_desync(&reference, 50, 1);
+    assert_eq!(report.differences[0].frame, 47);
+    let report = movie.locate_desync(&reference, 200, 10);
+    assert_eq!(report.differences.len(), 5);
+
+    let mut longer = reference.clone();
+    longer.0.push(Input::default());
+    let report = longer.locate_desync(&reference, 0, 1);
+    assert_eq!(report.first_difference, Some(100));
+    assert_eq!(report.differences[0].reference, None);
+
+    let report = reference.locate_desync(&reference, 5, 3);
+    assert_eq!(report.first_difference, None);
+    assert!(report.differences.is_empty());
+    assert_eq!(
+        report.to_string(),
+        "desync at frame 5, inputs are identical\n"
+    );
+}
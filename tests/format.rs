@@ -0,0 +1,145 @@
+use libtas_movie::{
+    format::{ConvertError, FormatRegistry, LtmFormat, MovieFormat},
+    inputs::{Input, KeyboardInput},
+    movie::{LibTASMovie, load_movie},
+    testkit::sample_movie,
+};
+
+/// A format of one line per frame, with the keys in hexadecimal.
+struct KeyLines;
+
+impl MovieFormat for KeyLines {
+    fn name(&self) -> &'static str {
+        "key-lines"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["keys", "KEYS2"]
+    }
+
+    fn detect(&self, data: &[u8]) -> bool {
+        data.starts_with(b"keys\n")
+    }
+
+    fn import(&self, data: &[u8]) -> Result<LibTASMovie, ConvertError> {
+        let text =
+            core::str::from_utf8(data).map_err(|err| ConvertError::Invalid(err.to_string()))?;
+        let mut movie = sample_movie(0, 60);
+        for line in text.lines().skip(1) {
+            let mut input = Input::default();
+            if !line.is_empty() {
+                let keys = line
+                    .split(' ')
+                    .map(|key| u32::from_str_radix(key, 16))
+                    .collect::<Result<_, _>>()
+                    .map_err(|err| ConvertError::Invalid(err.to_string()))?;
+                input.keyboard = Some(KeyboardInput(keys));
+            }
+            movie.inputs.0.push(input);
+        }
+        Ok(movie)
+    }
+
+    fn export(&self, movie: &LibTASMovie) -> Result<Vec<u8>, ConvertError> {
+        let mut text = "keys\n".to_owned();
+        for input in &movie.inputs.0 {
+            let keys = input
+                .keyboard
+                .as_ref()
+                .map_or(&[][..], |keyboard| &keyboard.0);
+            let keys: Vec<_> = keys.iter().map(|key| format!("{key:x}")).collect();
+            text.push_str(&keys.join(" "));
+            text.push('\n');
+        }
+        Ok(text.into_bytes())
+    }
+}
+
+#[test]
+fn test_detect_format() {
+    let mut registry = FormatRegistry::builtin();
+    registry.register(KeyLines);
+
+    let name = |path: &str, data: &[u8]| {
+        registry
+            .detect_format(path, data)
+            .map(|format| format.name())
+    };
+    assert_eq!(name("run.ltm", b""), Some("ltm"));
+    assert_eq!(name("run.LTM", b""), Some("ltm"));
+    assert_eq!(name("run.ahk", b""), Some("autohotkey"));
+    assert_eq!(name("run.keys2", b""), Some("key-lines"));
+    assert_eq!(name("run", b"keys\n7a\n"), Some("key-lines"));
+    assert_eq!(name("run.bin", b"\x1f\x8b\x08"), Some("ltm"));
+    assert_eq!(name("run.bin", b"unknown"), None);
+
+    assert_eq!(registry.get("xdotool").unwrap().extensions(), ["sh"]);
+    assert!(registry.get("key-lines").is_some());
+    assert!(FormatRegistry::new().get("ltm").is_none());
+}
+
+#[test]
+fn test_register_replaces() {
+    struct Shadow;
+    impl MovieFormat for Shadow {
+        fn name(&self) -> &'static str {
+            "ltm"
+        }
+        fn extensions(&self) -> &'static [&'static str] {
+            &["ltm2"]
+        }
+    }
+
+    let mut registry = FormatRegistry::builtin();
+    let count = registry.formats().count();
+    registry.register(Shadow);
+    assert_eq!(registry.formats().count(), count);
+    assert_eq!(registry.get("ltm").unwrap().extensions(), ["ltm2"]);
+    assert!(registry.for_extension("ltm").is_none());
+}
+
+#[test]
+fn test_unsupported() {
+    let registry = FormatRegistry::builtin();
+    let movie = sample_movie(4, 60);
+    assert!(matches!(
+        registry.get("xdotool").unwrap().import(b""),
+        Err(ConvertError::Unsupported { format: "xdotool" })
+    ));
+    assert!(registry.get("xdotool").unwrap().export(&movie).is_ok());
+    assert!(matches!(
+        LtmFormat.import(b"not a movie"),
+        Err(ConvertError::Load(_))
+    ));
+}
+
+#[test]
+fn test_convert() {
+    let dir = env!("CARGO_TARGET_TMPDIR");
+    let ltm = format!("{dir}/format_convert.ltm");
+    let keys = format!("{dir}/format_convert.keys");
+    let back = format!("{dir}/format_convert_back.ltm");
+
+    let mut registry = FormatRegistry::builtin();
+    registry.register(KeyLines);
+    sample_movie(8, 60).save_to_path(&ltm).unwrap();
+    registry.convert(&ltm, &keys).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&keys).unwrap(),
+        "keys\n\n7a\nff53\n7a ff53\n\n7a\nff53\n7a ff53\n"
+    );
+    registry.convert(&keys, &back).unwrap();
+    assert_eq!(
+        load_movie(&back).unwrap().inputs,
+        sample_movie(8, 60).inputs
+    );
+
+    assert!(matches!(
+        registry.convert(&ltm, format!("{dir}/format_convert.unknown")),
+        Err(ConvertError::UnknownFormat(_))
+    ));
+    assert!(matches!(
+        registry.convert(format!("{dir}/missing.ltm"), &keys),
+        Err(ConvertError::Io(_))
+    ));
+}
@@ -0,0 +1,85 @@
+use libtas_movie::{
+    generate::{GeneratorConfig, MouseGenerator, random_movie},
+    inputs::{Inputs, KeyboardInput, ReferenceMode},
+    movie::load_movie_from_reader,
+};
+
+#[test]
+fn test_random_keyboard_movie() {
+    let config = GeneratorConfig {
+        frames: 500,
+        ..GeneratorConfig::default()
+    };
+    let movie = random_movie(&config, 7);
+    assert_eq!(movie.inputs.0.len(), 500);
+    assert_eq!(movie.config.general.frame_count, 500);
+    assert!(!movie.config.general.mouse_support);
+    assert_eq!(
+        movie.duration_summary().to_string(),
+        "8.333s (500 frames @ 60 fps)"
+    );
+
+    // the same seed gives the same movie, another seed another movie
+    assert_eq!(movie, random_movie(&config, 7));
+    assert_ne!(movie.inputs, random_movie(&config, 8).inputs);
+
+    let mut pressed = 0;
+    for input in &movie.inputs.0 {
+        assert_eq!(input.mouse, None);
+        if let Some(KeyboardInput(keys)) = &input.keyboard {
+            assert!(keys.iter().all(|key| config.keys.contains(key)));
+            pressed += 1;
+        }
+    }
+    assert!(pressed > 0);
+
+    // generated inputs are valid
+    let text = movie.inputs.to_string();
+    assert_eq!(text.parse::<Inputs>().unwrap(), movie.inputs);
+}
+
+#[test]
+fn test_random_mouse_movie() {
+    let mouse = MouseGenerator::default();
+    let bounds = mouse.bounds;
+    let config = GeneratorConfig {
+        frames: 300,
+        keys: vec![],
+        mouse: Some(mouse),
+        ..GeneratorConfig::default()
+    };
+    let movie = random_movie(&config, 1);
+    assert!(movie.config.general.mouse_support);
+    assert!(movie.inputs.0.iter().all(|input| input.keyboard.is_none()));
+    let mut previous: Option<(i32, i32)> = None;
+    for input in &movie.inputs.0 {
+        let mouse = input.mouse.unwrap();
+        assert!(bounds.contains(mouse.xpos, mouse.ypos));
+        if let Some((x, y)) = previous {
+            assert!((mouse.xpos - x).abs() <= 8 && (mouse.ypos - y).abs() <= 8);
+        }
+        previous = Some((mouse.xpos, mouse.ypos));
+    }
+    assert!(
+        movie
+            .inputs
+            .0
+            .iter()
+            .any(|input| input.mouse.unwrap().left_click)
+    );
+
+    let loaded = load_movie_from_reader(movie.compress().unwrap().as_slice()).unwrap();
+    assert_eq!(loaded, movie);
+
+    // relative moves accumulate to the absolute positions
+    let config = GeneratorConfig {
+        mouse: Some(MouseGenerator {
+            reference_mode: ReferenceMode::Relative,
+            ..MouseGenerator::default()
+        }),
+        ..config
+    };
+    let mut relative = random_movie(&config, 1).inputs;
+    relative.to_absolute((319, 239));
+    assert_eq!(relative, movie.inputs);
+}
@@ -0,0 +1,147 @@
+use libtas_movie::{
+    gesture::{Curve, Easing, MousePath},
+    inputs::{Inputs, KeyboardInput, MouseButton},
+    movie::load_movie,
+    testkit::sample_movie,
+};
+
+/// Returns the frames where `button` is pressed.
+fn pressed(inputs: &Inputs, button: MouseButton) -> Vec<usize> {
+    (0..inputs.0.len())
+        .filter(|&frame| {
+            inputs.0[frame]
+                .mouse
+                .is_some_and(|mouse| mouse.is_pressed(button))
+        })
+        .collect()
+}
+
+#[test]
+fn test_click() {
+    let mut inputs = sample_movie(2, 60).inputs;
+    assert_eq!(inputs.click_at(1, 10, 20, MouseButton::Left), 3);
+    assert_eq!(inputs.0.len(), 3);
+    assert_eq!(pressed(&inputs, MouseButton::Left), [1]);
+    let mouse = inputs.0[2].mouse.unwrap();
+    assert_eq!((mouse.xpos, mouse.ypos), (10, 20));
+    assert_eq!(inputs.0[0].mouse, None);
+    // other inputs are kept
+    assert_eq!(inputs.0[1].keyboard, Some(KeyboardInput(vec![0x7a])));
+}
+
+#[test]
+fn test_double_click() {
+    let mut inputs = Inputs::default();
+    assert_eq!(inputs.double_click_at(2, 5, 5, MouseButton::Right), 6);
+    assert_eq!(inputs.0.len(), 6);
+    assert_eq!(pressed(&inputs, MouseButton::Right), [2, 4]);
+    assert!(pressed(&inputs, MouseButton::Left).is_empty());
+}
+
+#[test]
+fn test_hold_button() {
+    let mut inputs = Inputs::default();
+    inputs.click_at(0, 0, 0, MouseButton::Left);
+    assert_eq!(inputs.hold_button(1..4, MouseButton::Middle, 7, 8), 5);
+    assert_eq!(pressed(&inputs, MouseButton::Middle), [1, 2, 3]);
+    assert_eq!(pressed(&inputs, MouseButton::Left), [0]);
+    let mouse = inputs.0[1].mouse.unwrap();
+    assert_eq!((mouse.xpos, mouse.ypos), (7, 8));
+}
+
+#[test]
+fn test_drag() {
+    let mut inputs = Inputs::default();
+    let end = inputs.drag(0, (0, 0), (100, -40), 4, MouseButton::Left, Easing::Linear);
+    assert_eq!(end, 6);
+    let positions: Vec<_> = inputs
+        .0
+        .iter()
+        .map(|input| input.mouse.map(|mouse| (mouse.xpos, mouse.ypos)).unwrap())
+        .collect();
+    assert_eq!(
+        positions,
+        [
+            (0, 0),
+            (25, -10),
+            (50, -20),
+            (75, -30),
+            (100, -40),
+            (100, -40)
+        ]
+    );
+    assert_eq!(pressed(&inputs, MouseButton::Left), [0, 1, 2, 3, 4]);
+
+    let mut eased = Inputs::default();
+    eased.drag(0, (0, 0), (100, 0), 4, MouseButton::Left, Easing::EaseIn);
+    assert_eq!(eased.0[2].mouse.unwrap().xpos, 25);
+    eased.drag(0, (0, 0), (100, 0), 4, MouseButton::Left, Easing::EaseInOut);
+    assert_eq!(eased.0[2].mouse.unwrap().xpos, 50);
+    assert_eq!(eased.0[1].mouse.unwrap().xpos, 16);
+    assert_eq!(Easing::EaseOut.apply(0.5), 0.75);
+}
+
+#[test]
+fn test_drag_on_movie() {
+    let mut movie = load_movie("tests/movies/221769_Trapped_5.ltm").unwrap();
+    let frames = movie.inputs.0.len();
+    let end = movie.inputs.drag(
+        frames,
+        (166, 270),
+        (400, 300),
+        10,
+        MouseButton::Left,
+        Easing::EaseOut,
+    );
+    assert_eq!(end, frames + 12);
+    assert_eq!(movie.inputs.0.len(), frames + 12);
+
+    let reloaded: Inputs = movie.inputs.to_string().parse().unwrap();
+    assert_eq!(reloaded, movie.inputs);
+    let last = reloaded.0[frames + 11].mouse.unwrap();
+    assert_eq!((last.xpos, last.ypos, last.left_click), (400, 300, false));
+}
+
+#[test]
+fn test_mouse_path() {
+    let polyline = MousePath::new([(0, 0), (100, 0), (100, 100)], 4).curve(Curve::Polyline);
+    assert_eq!(
+        polyline.positions(),
+        [(0, 0), (50, 0), (100, 0), (100, 50), (100, 100)]
+    );
+    let eased = MousePath::new([(0, 0), (100, 0)], 2)
+        .curve(Curve::Polyline)
+        .easing(Easing::EaseIn);
+    assert_eq!(eased.positions(), [(0, 0), (25, 0), (100, 0)]);
+
+    // the Bezier curve only goes through the endpoints
+    let bezier = MousePath::new([(0, 0), (100, 100), (200, 0)], 2).curve(Curve::Bezier);
+    assert_eq!(bezier.positions(), [(0, 0), (100, 50), (200, 0)]);
+
+    // the Catmull-Rom spline goes through all waypoints, at constant speed
+    let spline = MousePath::new([(0, 0), (100, 0), (300, 0)], 3);
+    assert_eq!(spline.positions(), [(0, 0), (100, 0), (200, 0), (300, 0)]);
+    let spline = MousePath::new([(0, 0), (50, 50), (100, 0)], 10);
+    let positions = spline.positions();
+    assert_eq!(positions[0], (0, 0));
+    assert_eq!(positions[5], (50, 50));
+    assert_eq!(positions[10], (100, 0));
+
+    assert!(MousePath::new([], 10).positions().is_empty());
+    assert_eq!(MousePath::new([(3, 4)], 1).positions(), [(3, 4), (3, 4)]);
+}
+
+#[test]
+fn test_move_along() {
+    let mut inputs = Inputs::default();
+    inputs.hold_button(1..3, MouseButton::Left, 0, 0);
+    let path = MousePath::new([(0, 0), (30, 0)], 3).curve(Curve::Polyline);
+    assert_eq!(inputs.move_along(1, &path), 5);
+    let positions: Vec<_> = inputs.0[1..]
+        .iter()
+        .map(|input| input.mouse.map(|mouse| (mouse.xpos, mouse.ypos)).unwrap())
+        .collect();
+    assert_eq!(positions, [(0, 0), (10, 0), (20, 0), (30, 0)]);
+    // pressed buttons are kept
+    assert_eq!(pressed(&inputs, MouseButton::Left), [1, 2]);
+}
@@ -0,0 +1,52 @@
+use libtas_movie::{
+    greenzone::Greenzone,
+    movie::LoadOptions,
+    section::{MovieSection as _, SectionRegistry},
+    testkit::sample_movie,
+};
+
+/// Returns options loading the sections of this crate.
+fn builtin_options() -> LoadOptions {
+    LoadOptions {
+        sections: SectionRegistry::builtin(),
+        ..LoadOptions::default()
+    }
+}
+
+#[test]
+fn test_greenzone() {
+    let mut movie = sample_movie(10, 60);
+    assert!(movie.greenzone().is_none());
+    assert!(!movie.is_frame_verified(0));
+
+    let greenzone = movie.greenzone_mut();
+    for frame in 0..10 {
+        greenzone.insert(frame, frame * 0x1111);
+    }
+    assert_eq!(greenzone.hash_of(3), Some(0x3333));
+    assert_eq!(greenzone.invalidate_after_edit(5), 4);
+    assert!(greenzone.is_verified(5));
+    assert!(!greenzone.is_verified(6));
+    assert_eq!(greenzone.last_verified_before(8), Some(5));
+    assert_eq!(greenzone.invalidate(..2), 2);
+    assert_eq!(greenzone.last_verified_before(1), None);
+
+    let loaded = builtin_options()
+        .load_from_reader(movie.compress().unwrap().as_slice())
+        .unwrap();
+    assert_eq!(loaded, movie);
+    assert!(loaded.is_frame_verified(2));
+    assert!(!loaded.is_frame_verified(9));
+}
+
+#[test]
+fn test_greenzone_format() {
+    let greenzone = Greenzone::parse(b"0 00000000000000ff\n12 abc\n").unwrap();
+    assert_eq!(greenzone.hash_of(0), Some(0xff));
+    assert_eq!(greenzone.hash_of(12), Some(0xabc));
+    assert_eq!(
+        greenzone.serialize(),
+        b"0 00000000000000ff\n12 0000000000000abc\n"
+    );
+    assert!(Greenzone::parse(b"12\n").is_err());
+}
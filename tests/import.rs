@@ -0,0 +1,83 @@
+use core::time::Duration;
+
+use libtas_movie::{
+    import::{Event, EventKind, parse_evtest, quantize},
+    inputs::{KeyboardInput, ReferenceMode},
+    time::Framerate,
+};
+
+const EVTEST: &str = "\
+Input driver version is 1.0.1
+Event: time 1700000000.000000, type 4 (EV_MSC), code 4 (MSC_SCAN), value 1e
+Event: time 1700000000.000000, type 1 (EV_KEY), code 30 (KEY_A), value 1
+Event: time 1700000000.000000, -------------- SYN_REPORT ------------
+Event: time 1700000000.120000, type 1 (EV_KEY), code 30 (KEY_A), value 2
+Event: time 1700000000.150000, type 1 (EV_KEY), code 30 (KEY_A), value 0
+Event: time 1700000000.160000, type 1 (EV_KEY), code 106 (KEY_RIGHT), value 1
+Event: time 1700000000.170000, type 1 (EV_KEY), code 106 (KEY_RIGHT), value 0
+Event: time 1700000000.200000, type 1 (EV_KEY), code 272 (BTN_LEFT), value 1
+Event: time 1700000000.200000, type 2 (EV_REL), code 0 (REL_X), value -4
+Event: time 1700000000.210000, type 2 (EV_REL), code 1 (REL_Y), value 7
+Event: time 1700000000.300000, type 1 (EV_KEY), code 272 (BTN_LEFT), value 0
+Event: time 1700000000.300000, type 1 (EV_KEY), code 999 (?), value 1
+";
+
+#[test]
+fn test_parse_evtest() {
+    let events = parse_evtest(EVTEST).unwrap();
+    assert_eq!(events.len(), 8);
+    assert_eq!(
+        events[0],
+        Event {
+            time: Duration::ZERO,
+            kind: EventKind::KeyPress(0x61)
+        }
+    );
+    assert_eq!(events[2].kind, EventKind::KeyPress(0xff53));
+    assert_eq!(events[4].kind, EventKind::ButtonPress(1));
+    assert_eq!(events[5].kind, EventKind::RelativeMotion { dx: -4, dy: 0 });
+    assert_eq!(events[7].time, Duration::from_millis(300));
+
+    assert!(parse_evtest("Event: time x, type 1 (EV_KEY), code 30 (KEY_A), value 1").is_err());
+}
+
+#[test]
+fn test_quantize() {
+    let events = parse_evtest(EVTEST).unwrap();
+    let inputs = quantize(&events, Framerate::from_fps(10).unwrap());
+    assert_eq!(inputs.0.len(), 4);
+    // KEY_A is held from 0ms to 150ms, and Right is tapped within frame 1
+    assert_eq!(inputs.0[0].keyboard, Some(KeyboardInput(vec![0x61])));
+    assert_eq!(
+        inputs.0[1].keyboard,
+        Some(KeyboardInput(vec![0x61, 0xff53]))
+    );
+    assert_eq!(inputs.0[2].keyboard, None);
+
+    let mouse = inputs.0[2].mouse.unwrap();
+    assert_eq!(mouse.reference_mode, ReferenceMode::Relative);
+    assert_eq!((mouse.xpos, mouse.ypos), (-4, 7));
+    assert!(mouse.left_click);
+    assert!(inputs.0[3].mouse.unwrap().left_click);
+    assert!(!inputs.0[0].mouse.unwrap().left_click);
+}
+
+#[test]
+fn test_quantize_absolute() {
+    let at = |ms, kind| Event {
+        time: Duration::from_millis(ms),
+        kind,
+    };
+    let events = [
+        at(0, EventKind::Motion { x: 1, y: 2 }),
+        at(5, EventKind::Motion { x: 3, y: 4 }),
+        at(40, EventKind::ButtonPress(3)),
+    ];
+    let inputs = quantize(&events, Framerate::from_fps(60).unwrap());
+    assert_eq!(inputs.0.len(), 3);
+    let mouse = inputs.0[1].mouse.unwrap();
+    assert_eq!((mouse.xpos, mouse.ypos), (3, 4));
+    assert_eq!(mouse.reference_mode, ReferenceMode::Absolute);
+    assert!(inputs.0[2].mouse.unwrap().right_click);
+    assert!(quantize(&[], Framerate::default()).0.is_empty());
+}
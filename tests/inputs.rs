@@ -0,0 +1,188 @@
+use std::{fs::File, io::BufReader};
+
+use libtas_movie::{
+    inputs::{
+        Input, Inputs, InputsRef, InputsVisitor, MouseInput, ReadInputsError, parse_inputs_events,
+    },
+    movie::load_movie,
+    testkit::sample_movie,
+};
+
+/// Counts key presses and clicks.
+#[derive(Default)]
+struct Counter {
+    frames: usize,
+    keys: usize,
+    clicks: usize,
+}
+
+impl InputsVisitor for Counter {
+    fn frame_end(&mut self, _frame: usize) {
+        self.frames += 1;
+    }
+
+    fn key(&mut self, _frame: usize, _key: u32) {
+        self.keys += 1;
+    }
+
+    fn mouse(&mut self, _frame: usize, mouse: &MouseInput) {
+        if mouse.left_click {
+            self.clicks += 1;
+        }
+    }
+}
+
+#[test]
+fn test_parse_events() {
+    let file = File::open("tests/movies/221769_Trapped_5_inputs").unwrap();
+    let mut counter = Counter::default();
+    let frames = parse_inputs_events(BufReader::new(file), &mut counter).unwrap();
+
+    let movie = load_movie("tests/movies/221769_Trapped_5.ltm").unwrap();
+    let inputs = &movie.inputs.0;
+    assert_eq!(frames, inputs.len());
+    assert_eq!(counter.frames, inputs.len());
+    let keys = inputs
+        .iter()
+        .filter_map(|input| input.keyboard.as_ref())
+        .map(|keyboard| keyboard.0.len())
+        .sum::<usize>();
+    assert_eq!(counter.keys, keys);
+    let clicks = inputs
+        .iter()
+        .filter(|input| input.mouse.is_some_and(|mouse| mouse.left_click))
+        .count();
+    assert_eq!(counter.clicks, clicks);
+}
+
+#[test]
+fn test_parse_events_invalid() {
+    let mut counter = Counter::default();
+    let result = parse_inputs_events(&b"|K7a|\n|Kzz|\n"[..], &mut counter);
+    assert!(matches!(result, Err(ReadInputsError::Invalid(_))));
+    assert_eq!(counter.frames, 1);
+}
+
+#[test]
+fn test_other_lines() {
+    let text = "# route v2\n|K7a|\n|\n\n# boss\n|K20|\n# end\n";
+    let inputs: Inputs = text.parse().unwrap();
+    assert_eq!(inputs.0.len(), 3);
+    assert_eq!(
+        inputs.other_lines(),
+        [
+            (0, "# route v2".to_owned()),
+            (2, "".to_owned()),
+            (2, "# boss".to_owned()),
+            (3, "# end".to_owned()),
+        ]
+    );
+    assert_eq!(inputs.to_string(), text);
+    assert_eq!("".parse::<Inputs>().unwrap(), Inputs::default());
+
+    let mut movie = sample_movie(0, 60);
+    movie.inputs = inputs;
+    movie.splice_inputs(0..2, vec![Input::default()]).unwrap();
+    assert_eq!(
+        movie.inputs.to_string(),
+        "# route v2\n|\n\n# boss\n|K20|\n# end\n"
+    );
+}
+
+/// Collects the lines that are not frames.
+#[derive(Default)]
+struct OtherLines(Vec<(usize, String)>);
+
+impl InputsVisitor for OtherLines {
+    fn other_line(&mut self, frame: usize, line: &str) {
+        self.0.push((frame, line.to_owned()));
+    }
+}
+
+#[test]
+fn test_parse_events_other_lines() {
+    let mut other_lines = OtherLines::default();
+    parse_inputs_events(&b"# start\n|K7a|\n# end\n"[..], &mut other_lines).unwrap();
+    assert_eq!(
+        other_lines.0,
+        [(0, "# start".to_owned()), (1, "# end".to_owned())]
+    );
+}
+
+#[test]
+fn test_inputs_ref() {
+    let text = std::fs::read_to_string("tests/movies/221769_Trapped_5_inputs").unwrap();
+    let inputs = InputsRef::parse(&text).unwrap();
+    assert_eq!(inputs.to_inputs(), text.parse::<Inputs>().unwrap());
+
+    let text = "# start\n|K7a:ff53|M10:20:A:L....|C1state|T30:1|F1|\n|\n";
+    let inputs = InputsRef::parse(text).unwrap();
+    assert_eq!(inputs.1, [(0, "# start")]);
+    let input = inputs.0[0];
+    assert_eq!(input.keyboard(), Some("7a:ff53"));
+    assert_eq!(input.keys().collect::<Vec<_>>(), [0x7a, 0xff53]);
+    assert_eq!(input.mouse().map(|mouse| mouse.xpos), Some(10));
+    assert_eq!(input.controllers().collect::<Vec<_>>(), [(1, "state")]);
+    assert_eq!(input.framerate().map(|framerate| framerate.num), Some(30));
+    assert_eq!(input.unknown().collect::<Vec<_>>(), ["F1"]);
+    assert_eq!(inputs.0[1].sections().count(), 0);
+    assert_eq!(Inputs::from(inputs), text.parse::<Inputs>().unwrap());
+
+    assert!(InputsRef::parse("|Kzz|\n").is_err());
+    assert!(InputsRef::parse("|C0|\n").is_err());
+    assert!(InputsRef::parse("|K7a||\n").is_err());
+}
+
+#[test]
+fn test_unknown_sections() {
+    let input: Input = "|K7a|C1state|F1|X9:future|T30:1|".parse().unwrap();
+    assert_eq!(input.unknown, ["F1", "X9:future"]);
+    assert_eq!(input.to_string(), "|K7a|C1state|F1|X9:future|T30:1|");
+
+    // unknown sections are written after the controllers wherever they were read
+    let input: Input = "|X9|K7a|".parse().unwrap();
+    assert_eq!(input.to_string(), "|K7a|X9|");
+
+    assert!("|K7a||".parse::<Input>().is_err());
+}
+
+#[test]
+fn test_alternate_display() {
+    let text = "# start\n|K7a:ff53:1008ff13|M-3:4:R:1.3.5:0|C1state|T30:1|\n|\n";
+    let inputs: Inputs = text.parse().unwrap();
+    assert_eq!(
+        format!("{inputs:#}"),
+        "# start\n|K z:Right:0x1008ff13|M (-3,4) rel LMB RMB MB5|C1state|T30:1|\n|\n"
+    );
+    // the canonical format is unchanged
+    assert_eq!(inputs.to_string(), text);
+}
+
+/// Collects the sections that are not parsed yet.
+#[derive(Default)]
+struct OtherSections(Vec<(usize, String)>);
+
+impl InputsVisitor for OtherSections {
+    fn other_section(&mut self, frame: usize, section: &str) {
+        self.0.push((frame, section.to_owned()));
+    }
+}
+
+#[test]
+fn test_parse_events_unknown_section() {
+    let text = "|K7a|X1:2|\n|FR|\n";
+    let inputs: Inputs = text.parse().unwrap();
+    assert_eq!(inputs.0[0].unknown, ["X1:2"]);
+
+    let mut other_sections = OtherSections::default();
+    let frames = parse_inputs_events(text.as_bytes(), &mut other_sections).unwrap();
+    assert_eq!(frames, 2);
+    assert_eq!(
+        other_sections.0,
+        [(0, "X1:2".to_owned()), (1, "FR".to_owned())]
+    );
+
+    // an empty section is still invalid
+    let result = parse_inputs_events(&b"|K7a||\n"[..], &mut other_sections);
+    assert!(matches!(result, Err(ReadInputsError::Invalid(_))));
+}
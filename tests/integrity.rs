@@ -0,0 +1,77 @@
+use libtas_movie::{
+    integrity::{Checksums, IntegrityError},
+    movie::{LoadError, LoadOptions, SaveOptions, load_movie_from_reader},
+    testkit::{archive, sample_movie},
+};
+
+fn verifying() -> LoadOptions {
+    LoadOptions {
+        verify_integrity: true,
+        ..LoadOptions::default()
+    }
+}
+
+#[test]
+fn test_checksums() {
+    let checksums = Checksums::of_entries([("inputs", &b"|\n"[..]), ("annotations.txt", b"")]);
+    let text = checksums.to_string();
+    assert_eq!(
+        text,
+        "9d5244c50ced231a7bb23d76593f0bce8f8d3ff803dfe514176002739b6f75c1  inputs\n\
+         e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855  annotations.txt\n"
+    );
+    assert_eq!(text.parse::<Checksums>().unwrap(), checksums);
+    assert!("0123  inputs".parse::<Checksums>().is_err());
+}
+
+#[test]
+fn test_save_with_checksums() {
+    let movie = sample_movie(8, 60);
+    let options = SaveOptions { checksums: true };
+    let bytes = options.compress(&movie).unwrap();
+    assert_eq!(
+        verifying().load_from_reader(bytes.as_slice()).unwrap(),
+        movie
+    );
+    assert_eq!(load_movie_from_reader(bytes.as_slice()).unwrap(), movie);
+}
+
+#[test]
+fn test_verify_integrity() {
+    let movie = sample_movie(8, 60);
+    let config = movie.config.to_string();
+    let inputs = movie.inputs.to_string();
+    let checksums = Checksums::of_entries([
+        ("config.ini", config.as_bytes()),
+        ("inputs", inputs.as_bytes()),
+        ("annotations.txt", b""),
+    ])
+    .to_string();
+    let bytes = archive(&[
+        ("config.ini", config.as_bytes()),
+        ("inputs", b"|\n"),
+        ("annotations.txt", b""),
+        ("editor.ini", b""),
+        ("checksums", checksums.as_bytes()),
+    ]);
+
+    assert!(load_movie_from_reader(bytes.as_slice()).is_ok());
+    match verifying().load_from_reader(bytes.as_slice()) {
+        Err(LoadError::Integrity(errors)) => assert_eq!(
+            errors,
+            [
+                IntegrityError::Mismatch("inputs".to_owned()),
+                IntegrityError::Unlisted("editor.ini".to_owned()),
+            ]
+        ),
+        result => panic!("unexpected result: {result:?}"),
+    }
+
+    let mut checksums = Checksums::of_entries([("config.ini", config.as_bytes())]);
+    checksums.push("branches.txt", b"");
+    let actual = Checksums::of_entries([("config.ini", config.as_bytes())]);
+    assert_eq!(
+        checksums.verify(&actual),
+        [IntegrityError::Missing("branches.txt".to_owned())]
+    );
+}
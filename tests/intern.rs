@@ -0,0 +1,51 @@
+use libtas_movie::{
+    diff::{diff_inputs, diff_interned},
+    inputs::{Input, KeyboardInput},
+    intern::FrameTable,
+    movie::load_movie,
+    testkit::sample_movie,
+    time::Framerate,
+};
+
+#[test]
+fn test_intern() {
+    let movie = sample_movie(1000, 60);
+    let mut table = FrameTable::new();
+    let interned = table.intern_inputs(&movie.inputs);
+    assert_eq!(table.len(), 4);
+    assert_eq!(interned.0.len(), 1000);
+    assert_eq!(interned.0[0], interned.0[4]);
+    assert_ne!(interned.0[0], interned.0[1]);
+    assert_eq!(table.get(interned.0[0]), &Input::default());
+    assert_eq!(interned.counts(&table), [250, 250, 250, 250]);
+    assert_eq!(table.resolve(&interned), movie.inputs);
+    assert!(
+        interned.memory_footprint() + table.memory_footprint() < movie.inputs.memory_footprint()
+    );
+}
+
+#[test]
+fn test_intern_framerates() {
+    let mut table = FrameTable::new();
+    let input = |num, den| Input {
+        framerate: Some(Framerate { num, den }),
+        ..Input::default()
+    };
+    assert_eq!(table.intern(&input(60, 1)), table.intern(&input(120, 2)));
+    assert_ne!(table.intern(&input(60, 1)), table.intern(&input(30, 1)));
+}
+
+#[test]
+fn test_diff_interned() {
+    let movie = load_movie("tests/movies/221769_Trapped_5.ltm").unwrap();
+    let mut edited = movie.inputs.clone();
+    edited.0[10].keyboard = Some(KeyboardInput(vec![0x20]));
+    edited.0[11].keyboard = Some(KeyboardInput(vec![0x20]));
+    edited.0.truncate(400);
+
+    let mut table = FrameTable::new();
+    let a = table.intern_inputs(&movie.inputs);
+    let b = table.intern_inputs(&edited);
+    assert_eq!(diff_interned(&a, &b), diff_inputs(&movie.inputs, &edited));
+    assert_eq!(diff_interned(&a, &b), [10..12, 400..456]);
+}
@@ -1,8 +1,23 @@
-use std::fs::read_to_string;
+use std::{
+    fs::{read, read_to_string},
+    io::Read as _,
+    path::Path,
+};
+
+use flate2::bufread::GzDecoder;
 
 use libtas_movie::{
+    config::{AudioConfig, Config, ConfigRef, ThreadScope, TimetrackConfig},
+    editor::{Editor, Marker},
+    hash::{Md5Hash, md5_of_file, md5_of_file_with_progress, md5_of_reader},
     inputs::{KeyboardInput, ReferenceMode},
-    movie::{LoadError, load_movie},
+    movie::{
+        EntryError, LineDifference, LoadError, LoadOptions, RawMovie, load_movie,
+        load_movie_from_reader,
+    },
+    testkit::{archive, sample_movie},
+    time::TimeSpec,
+    warning::Warning,
 };
 
 #[test]
@@ -12,28 +27,25 @@ fn test_config() {
     // check config
     let general = &movie.config.general;
     assert_eq!(general.authors, "synabler");
-    assert_eq!(general.auto_restart, false);
+    assert!(!general.auto_restart);
     assert_eq!(general.frame_count, 456);
     assert_eq!(general.framerate_den, 1);
     assert_eq!(general.framerate_num, 20);
     assert_eq!(general.game_name, "ruffle");
-    assert_eq!(general.initial_monotonic_time_nsec, 0);
-    assert_eq!(general.initial_monotonic_time_sec, 1);
-    assert_eq!(general.initial_time_nsec, 0);
-    assert_eq!(general.initial_time_sec, 1);
-    assert_eq!(general.length_nsec, 800000000);
-    assert_eq!(general.length_sec, 22);
+    assert_eq!(general.initial_monotonic_time, TimeSpec::new(1, 0));
+    assert_eq!(general.initial_time, TimeSpec::new(1, 0));
+    assert_eq!(general.length, TimeSpec::new(22, 800000000));
     assert_eq!(general.libtas_major_version, 1);
     assert_eq!(general.libtas_minor_version, 4);
     assert_eq!(general.libtas_patch_version, 7);
-    assert_eq!(general.md5, "c9b4f1b544725cb0d9d784c35232a52d");
-    assert_eq!(general.mouse_support, true);
+    assert_eq!(general.md5.unwrap(), "c9b4f1b544725cb0d9d784c35232a52d");
+    assert!(general.mouse_support);
     assert_eq!(general.nb_controllers, 0);
     assert_eq!(general.rerecord_count, 101);
     assert_eq!(general.savestate_frame_count, 456);
-    assert_eq!(general.variable_framerate, false);
+    assert!(!general.variable_framerate);
 
-    let timetrack = &movie.config.mainthread_timetrack;
+    let timetrack = movie.config.mainthread_timetrack().unwrap();
     assert_eq!(timetrack.get_tick_count, -1);
     assert_eq!(timetrack.get_tick_count64, -1);
     assert_eq!(timetrack.query_performance_counter, -1);
@@ -78,6 +90,52 @@ fn test_inputs() {
     assert_eq!(movie.inputs.to_string(), config_str);
 }
 
+#[test]
+fn test_editor() {
+    let movie = load_movie("tests/movies/221769_Trapped_5.ltm").unwrap();
+    assert!(movie.editor.markers.is_empty());
+    assert_eq!(movie.editor.columns.len(), 9);
+    assert_eq!(movie.editor.extra.len(), 1);
+
+    // check Display
+    let editor_str = read_to_string("tests/movies/221769_Trapped_5_editor.ini").unwrap();
+    assert_eq!(movie.editor.to_string(), editor_str);
+
+    // markers are written in their sorted position
+    let mut editor = movie.editor;
+    editor.markers.push(Marker {
+        frame: 120,
+        text: "Level 2".to_owned(),
+    });
+    let editor_str = editor.to_string();
+    assert!(editor_str.contains(
+        "size=9\n\n[markers]\n1\\frame=120\n1\\text=Level 2\nsize=1\n\n[nondraw_frames]"
+    ));
+    assert_eq!(editor_str.parse::<Editor>().unwrap(), editor);
+
+    // keys are sorted as strings, like QSettings does
+    let mut editor = Editor::default();
+    for frame in 1..=10 {
+        editor.markers.push(Marker {
+            frame,
+            text: String::new(),
+        });
+    }
+    let editor_str = editor.to_string();
+    assert!(editor_str.starts_with("[markers]\n10\\frame=10\n10\\text=\n1\\frame=1\n"));
+    assert!(editor_str.ends_with("9\\text=\nsize=10\n"));
+    assert_eq!(
+        editor_str.parse::<Editor>().unwrap().markers,
+        editor.markers
+    );
+
+    // the markers group is only written without markers if it was read
+    editor.markers.clear();
+    assert_eq!(editor.to_string(), "");
+    let editor: Editor = "[markers]\nsize=0\n".parse().unwrap();
+    assert_eq!(editor.to_string(), "[markers]\nsize=0\n");
+}
+
 #[test]
 fn test_compress() {
     let movie = load_movie("tests/movies/221769_Trapped_5.ltm").unwrap();
@@ -88,6 +146,162 @@ fn test_compress() {
     assert_eq!(movie, reloaded);
 }
 
+/// Loading and saving a movie in fidelity mode should reproduce the original file.
+#[test]
+fn test_fidelity() {
+    let path = "tests/movies/221769_Trapped_5.ltm";
+    let options = LoadOptions {
+        fidelity: true,
+        ..LoadOptions::default()
+    };
+    let mut movie = options.load(path).unwrap();
+    assert_eq!(movie.compress().unwrap(), read(path).unwrap());
+
+    // only the modified entry is re-serialized
+    movie.annotations = "route notes".to_owned();
+    let data = movie.compress().unwrap();
+    let reloaded = options.load_from_reader(data.as_slice()).unwrap();
+    assert_eq!(reloaded.annotations, "route notes");
+    assert_eq!(reloaded, movie);
+    let raw = reloaded.raw.unwrap();
+    assert_eq!(
+        raw.entry("editor.ini"),
+        movie.raw.as_ref().unwrap().entry("editor.ini")
+    );
+    assert_eq!(load_movie_from_reader(data.as_slice()).unwrap(), movie);
+
+    // the entries keep their order and headers
+    let headers = |raw: &RawMovie| {
+        raw.entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry.name.clone(),
+                    entry.mode,
+                    entry.mtime,
+                    entry.username.clone(),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    let original = movie.raw.as_ref().unwrap();
+    assert_eq!(headers(&raw), headers(original));
+    assert_eq!(raw.entries[0].name, "inputs");
+    assert_eq!(raw.entries[0].mode, 0o664);
+    assert_eq!(raw.entries[0].username.as_deref(), Some("jh"));
+}
+
+/// Only the entries changed after loading in fidelity mode should be reported as modified.
+#[test]
+fn test_modified_entries() {
+    let path = "tests/movies/221769_Trapped_5.ltm";
+    let options = LoadOptions {
+        fidelity: true,
+        ..LoadOptions::default()
+    };
+    let mut movie = options.load(path).unwrap();
+    let names = ["config.ini", "inputs", "annotations.txt", "editor.ini"];
+    assert!(names.iter().all(|name| !movie.is_entry_modified(name)));
+
+    movie.annotations = "route notes".to_owned();
+    movie.editor.markers.clear();
+    movie
+        .editor
+        .markers
+        .clone_from(&load_movie(path).unwrap().editor.markers);
+    let modified: Vec<_> = names
+        .into_iter()
+        .filter(|name| movie.is_entry_modified(name))
+        .collect();
+    assert_eq!(modified, ["annotations.txt"]);
+
+    let data = movie.compress().unwrap();
+    let reloaded = options.load_from_reader(data.as_slice()).unwrap();
+    assert_eq!(
+        reloaded.raw.unwrap().entry("inputs"),
+        movie.raw.as_ref().unwrap().entry("inputs")
+    );
+
+    let movie = load_movie(path).unwrap();
+    assert!(names.iter().all(|name| movie.is_entry_modified(name)));
+}
+
+/// A movie saved again after loading it in fidelity mode should reuse
+/// the compressed bytes of its unmodified entries.
+#[test]
+fn test_reused_members() {
+    let options = LoadOptions {
+        fidelity: true,
+        ..LoadOptions::default()
+    };
+    let mut movie = options.load("tests/movies/221769_Trapped_5.ltm").unwrap();
+    movie.annotations = "route notes".to_owned();
+    let data = movie.compress().unwrap();
+
+    // the first entry, the inputs, is a gzip member of its own
+    let mut decoder = GzDecoder::new(data.as_slice());
+    let mut inputs = vec![];
+    decoder.read_to_end(&mut inputs).unwrap();
+    let member = data.len() - decoder.get_ref().len();
+    assert!(member < data.len());
+
+    let mut movie = options.load_from_reader(data.as_slice()).unwrap();
+    movie.annotations = "other notes".to_owned();
+    let saved = movie.compress().unwrap();
+    assert_eq!(saved[..member], data[..member]);
+    let reloaded = load_movie_from_reader(saved.as_slice()).unwrap();
+    assert_eq!(reloaded.annotations, "other notes");
+    assert_eq!(reloaded.inputs, movie.inputs);
+}
+
+/// Inputs retained as text should be saved byte for byte until they are edited.
+#[test]
+fn test_raw_inputs() {
+    let options = LoadOptions {
+        raw_inputs: true,
+        ..LoadOptions::default()
+    };
+    let config = sample_movie(2, 60).config.to_string();
+    let text = "|X9|K7a|\n|\n";
+    let bytes = archive(&[
+        ("config.ini", config.as_bytes()),
+        ("inputs", text.as_bytes()),
+        ("annotations.txt", b""),
+        ("editor.ini", b""),
+    ]);
+    let mut movie = options.load_from_reader(bytes.as_slice()).unwrap();
+    assert!(!movie.is_inputs_modified());
+    assert_eq!(movie.original_inputs(), Some(text));
+
+    // the unknown section is written after the keyboard when serialized again
+    let raw = movie.raw_inputs.as_ref().unwrap();
+    assert_eq!(
+        raw.roundtrip_difference(),
+        Some(LineDifference {
+            line: 0,
+            original: Some("|X9|K7a|".to_owned()),
+            serialized: Some("|K7a|X9|".to_owned()),
+        })
+    );
+
+    let data = movie.compress().unwrap();
+    let reloaded = options.load_from_reader(data.as_slice()).unwrap();
+    assert_eq!(reloaded.raw_inputs.unwrap().text(), text);
+
+    movie.inputs.0.pop();
+    assert!(movie.is_inputs_modified());
+    assert_eq!(movie.original_inputs(), None);
+    assert!(movie.raw_inputs.is_none());
+    let data = movie.compress().unwrap();
+    let reloaded = options.load_from_reader(data.as_slice()).unwrap();
+    assert_eq!(reloaded.raw_inputs.unwrap().text(), "|K7a|X9|\n");
+
+    let movie = load_movie("tests/movies/221769_Trapped_5.ltm").unwrap();
+    assert!(movie.is_inputs_modified());
+    let movie = options.load("tests/movies/221769_Trapped_5.ltm").unwrap();
+    assert_eq!(movie.raw_inputs.unwrap().roundtrip_difference(), None);
+}
+
 /// If a file doesn't exist, it should fail with `NotFound`.
 #[test]
 fn test_load_not_exist() {
@@ -103,7 +317,416 @@ fn test_load_not_exist() {
 #[test]
 fn test_load_not_movie() {
     match load_movie("tests/invalid_movies/not_movie.txt") {
-        Err(LoadError::InvalidArchive) => {}
+        Err(LoadError::InvalidArchive { entry, source }) => {
+            assert_eq!(entry, 0);
+            assert_eq!(source.kind(), std::io::ErrorKind::InvalidData);
+        }
         _ => panic!("should have failed to load"),
     }
 }
+
+/// If a file is truncated, it should fail with `InvalidArchive` at the truncated entry.
+#[test]
+fn test_load_truncated() {
+    let bytes = read("tests/movies/221769_Trapped_5.ltm").unwrap();
+    match load_movie_from_reader(&bytes[..bytes.len() / 2]) {
+        Err(LoadError::InvalidArchive { source, .. }) => {
+            assert_eq!(source.kind(), std::io::ErrorKind::UnexpectedEof);
+        }
+        result => panic!("loaded as {result:?}"),
+    }
+}
+
+/// If an entry is not UTF-8, it should fail with `InvalidEntry` naming the entry.
+#[test]
+fn test_load_not_utf8() {
+    let config = Config::default().to_string();
+    let bytes = archive(&[
+        ("config.ini", config.as_bytes()),
+        ("inputs", b"|\n"),
+        ("annotations.txt", b"\xff"),
+        ("editor.ini", b""),
+    ]);
+    match load_movie_from_reader(bytes.as_slice()) {
+        Err(LoadError::InvalidEntry {
+            name,
+            source: EntryError::Utf8(_),
+        }) => assert_eq!(name, "annotations.txt"),
+        result => panic!("loaded as {result:?}"),
+    }
+}
+
+/// Keys and groups that are not modeled should be accessible and round-trip.
+#[test]
+fn test_config_raw() {
+    let mut movie = load_movie("tests/movies/221769_Trapped_5.ltm").unwrap();
+    let config = &mut movie.config;
+    assert_eq!(
+        config.get_raw("General", "frame_count").as_deref(),
+        Some("456")
+    );
+    assert_eq!(config.get_raw("General", "future_key"), None);
+
+    config.set_raw("General", "frame_count", "457").unwrap();
+    assert_eq!(config.general.frame_count, 457);
+    assert!(config.set_raw("General", "frame_count", "many").is_err());
+
+    config.set_raw("General", "future_key", "1").unwrap();
+    config.set_raw("future_group", "a", "b").unwrap();
+    let string = config.to_string();
+    assert!(string.contains("framerate_num=20\nfuture_key=1\ngame_name=ruffle\n"));
+    assert!(string.ends_with("time=-1\n\n[future_group]\na=b\n"));
+
+    let reparsed: Config = string.parse().unwrap();
+    assert_eq!(&reparsed, config);
+    assert_eq!(reparsed.get_raw("future_group", "a").as_deref(), Some("b"));
+}
+
+/// Normalized configs should be written like libTAS writes them.
+#[test]
+fn test_config_normalize() {
+    let original = read_to_string("tests/movies/221769_Trapped_5_config.ini").unwrap();
+    let with_keyboard = original.replace(
+        "initial_time_sec=1\n",
+        "initial_time_sec=1\nkeyboard_support=true\n",
+    );
+    let config: Config = with_keyboard.parse().unwrap();
+    assert_eq!(config.to_string(), with_keyboard);
+    assert_eq!(config.general.keyboard_support, Some(true));
+    assert!(!config.extra.iter().any(|section| section.name == "General"));
+
+    let edited = original.replace("framerate_den=1\n", "").replace(
+        "variable_framerate=false\n",
+        "variable_framerate=false\nzoom=TRUE\nkeyboard_support=False\nzoom=2\n",
+    ) + "\n[b]\nkey=1\n\n[a]\nz=1\ny=2\n\n[empty]\n";
+    let mut config: Config = edited.parse().unwrap();
+    assert!(!config.inputs_config().keyboard_support);
+    config.normalize();
+    let string = config.to_string();
+    assert!(string.contains("initial_time_sec=1\nkeyboard_support=false\nlength_nsec="));
+    assert!(string.contains("variable_framerate=false\nzoom=true\n\n[mainthread_timetrack]"));
+    assert!(string.ends_with("time=-1\n\n[a]\ny=2\nz=1\n\n[b]\nkey=1\n"));
+
+    let mut reparsed: Config = string.parse().unwrap();
+    assert_eq!(reparsed, config);
+    reparsed.normalize();
+    assert_eq!(reparsed, config);
+}
+
+/// Values should follow the conventions of QSettings, which libTAS writes `config.ini` with.
+#[test]
+fn test_config_qsettings_values() {
+    let original = read_to_string("tests/movies/221769_Trapped_5_config.ini").unwrap();
+    let text = original
+        .replace("authors=synabler", "authors=@@synabler")
+        .replace("game_name=ruffle", "game_name=@Invalid()")
+        .replace("nb_controllers=0", "nb_controllers=@Invalid()")
+        .replace("auto_restart=false", "auto_restart=@Invalid()")
+        .replace("md5=c9b4f1b544725cb0d9d784c35232a52d", "md5=@Invalid()");
+    let config: Config = text.parse().unwrap();
+    assert_eq!(config.general.authors, "@synabler");
+    assert_eq!(config.general.game_name, "");
+    assert_eq!(config.general.nb_controllers, 0);
+    assert!(!config.general.auto_restart);
+    assert_eq!(config.general.md5, None);
+    assert_eq!(
+        config.get_raw("General", "authors").as_deref(),
+        Some("@@synabler")
+    );
+
+    let string = config.to_string();
+    assert!(string.contains("\nauthors=@@synabler\n"));
+    assert!(string.contains("\ngame_name=\n"));
+    assert!(string.contains("\nmd5=\n"));
+    let reparsed: Config = string.parse().unwrap();
+    assert_eq!(reparsed, config);
+
+    let metadata = ConfigRef::parse(&text).unwrap();
+    assert_eq!(metadata.authors, "@synabler");
+    assert_eq!(metadata.game_name, "");
+    assert_eq!(metadata.md5, None);
+}
+
+/// Strings with special characters should round-trip like QSettings writes them.
+#[test]
+fn test_config_escaping() {
+    let cases = [
+        ("plain author", "plain author"),
+        ("a, b & c", "\"a, b & c\""),
+        ("x=y", "\"x=y\""),
+        ("semi;colon", "\"semi;colon\""),
+        ("say \"hi\"", "say \\\"hi\\\""),
+        ("back\\slash", "back\\\\slash"),
+        ("two\nlines\ttab", "two\\nlines\\ttab"),
+        (" padded ", "\" padded \""),
+        ("café", "caf\\xe9"),
+        ("é1", "\\xe9\\x31"),
+        ("日本", "\\x65e5\\x672c"),
+        ("🎮", "\\xd83c\\xdfae"),
+        ("@@at", "@@@at"),
+        ("", ""),
+    ];
+    let original = read_to_string("tests/movies/221769_Trapped_5_config.ini").unwrap();
+    for (authors, written) in cases {
+        let mut config: Config = original.parse().unwrap();
+        config.general.authors = authors.to_owned();
+        config.general.game_name = authors.to_owned();
+
+        let string = config.to_string();
+        assert!(
+            string.contains(&format!("\nauthors={written}\n")),
+            "{authors:?}: {string}"
+        );
+        let reparsed: Config = string.parse().unwrap();
+        assert_eq!(reparsed.general.authors, authors);
+        assert_eq!(reparsed.general.game_name, authors);
+        assert_eq!(ConfigRef::parse(&string).unwrap().authors, authors);
+    }
+
+    // values written by hand or by other versions of QSettings
+    for (written, authors) in [
+        ("  spaced  ", "spaced"),
+        ("\"quoted\" part", "quoted part"),
+        ("\"a;b\"", "a;b"),
+        ("\\101\\x42", "AB"),
+        ("utf-8 é", "utf-8 é"),
+        ("\"@Invalid()\"", ""),
+    ] {
+        let text = original.replace("authors=synabler", &format!("authors={written}"));
+        let config: Config = text.parse().unwrap();
+        assert_eq!(config.general.authors, authors, "{written}");
+        assert_eq!(ConfigRef::parse(&text).unwrap().authors, authors);
+    }
+}
+
+#[test]
+fn test_config_game_path() {
+    let original = read_to_string("tests/movies/221769_Trapped_5_config.ini").unwrap();
+    let mut config: Config = original.parse().unwrap();
+    assert_eq!(config.general.game_path, None);
+    assert_eq!(config.general.game_args, None);
+    assert!(!config.general.set_game_dir("/games"));
+    assert_eq!(config.to_string(), original);
+
+    config
+        .general
+        .set_game_path("/home/tas/My Games/ruffle, v2/ruffle");
+    config.general.game_args = Some("--scale 2 \"Trapped 5.swf\"".to_owned());
+    let string = config.to_string();
+    assert!(string.contains(
+        "\ngame_args=--scale 2 \\\"Trapped 5.swf\\\"\ngame_name=ruffle\n\
+         game_path=\"/home/tas/My Games/ruffle, v2/ruffle\"\n"
+    ));
+    let reparsed: Config = string.parse().unwrap();
+    assert_eq!(reparsed, config);
+    assert_eq!(
+        reparsed.get_raw("General", "game_path").as_deref(),
+        Some("\"/home/tas/My Games/ruffle, v2/ruffle\"")
+    );
+
+    assert!(!config.general.rebase_game_path("/home/other", "/opt"));
+    assert!(config.general.rebase_game_path("/home/tas", "/home/runner"));
+    assert_eq!(
+        config.general.game_path(),
+        Some(Path::new("/home/runner/My Games/ruffle, v2/ruffle"))
+    );
+    assert!(config.general.set_game_dir("/games"));
+    assert_eq!(config.general.game_path.as_deref(), Some("/games/ruffle"));
+}
+
+#[test]
+fn test_config_audio() {
+    let original = read_to_string("tests/movies/221769_Trapped_5_config.ini").unwrap();
+    let mut config: Config = original.parse().unwrap();
+    assert_eq!(config.audio, None);
+    assert_eq!(config.get_raw("audio", "frequency"), None);
+
+    config.set_raw("audio", "frequency", "48000").unwrap();
+    assert_eq!(
+        config.audio,
+        Some(AudioConfig {
+            frequency: 48000,
+            ..AudioConfig::default()
+        })
+    );
+    config.set_raw("audio", "latency", "3").unwrap();
+    let string = config.to_string();
+    assert!(string.contains(
+        "variable_framerate=false\n\n\
+         [audio]\nbitdepth=16\nchannels=2\nfrequency=48000\nlatency=3\nmute=false\n\n\
+         [mainthread_timetrack]\n"
+    ));
+
+    let text = original.replace(
+        "\n\n[mainthread_timetrack]",
+        "\n\n[audio]\nbitdepth=8\nchannels=1\nfrequency=22050\nmute=true\n\n[mainthread_timetrack]",
+    );
+    let config: Config = text.parse().unwrap();
+    assert_eq!(
+        config.audio,
+        Some(AudioConfig {
+            bitdepth: 8,
+            channels: 1,
+            frequency: 22050,
+            mute: true,
+        })
+    );
+    assert_eq!(config.get_raw("audio", "mute").as_deref(), Some("true"));
+    assert!(config.extra.is_empty());
+    assert_eq!(config.to_string(), text);
+}
+
+#[test]
+fn test_config_timetracks() {
+    let original = read_to_string("tests/movies/221769_Trapped_5_config.ini").unwrap();
+    let text = format!(
+        "{original}\n[render_timetrack]\nclock=2\n\n\
+         [secondarythread_timetrack]\nGetTickCount=-1\nGetTickCount64=-1\n\
+         QueryPerformanceCounter=-1\nclock=-1\nclock_gettime_monotonic=-1\n\
+         clock_gettime_real=-1\ngettimeofday=5\nsdl_getperformancecounter=-1\n\
+         sdl_getticks=-1\nthreads=3\ntime=-1\n"
+    );
+    let mut config: Config = text.parse().unwrap();
+    assert_eq!(
+        config.timetracks.keys().collect::<Vec<_>>(),
+        [
+            &ThreadScope::Main,
+            &ThreadScope::Other("render".to_owned()),
+            &ThreadScope::Secondary,
+        ]
+    );
+    assert_eq!(config.secondarythread_timetrack().unwrap().gettimeofday, 5);
+    assert_eq!(
+        config.timetracks[&ThreadScope::Other("render".to_owned())],
+        TimetrackConfig {
+            clock: 2,
+            ..TimetrackConfig::default()
+        }
+    );
+    assert_eq!(
+        config
+            .get_raw("secondarythread_timetrack", "threads")
+            .as_deref(),
+        Some("3")
+    );
+    assert_eq!(
+        config.get_raw("render_timetrack", "time").as_deref(),
+        Some("-1")
+    );
+
+    // the render group is written with all keys
+    let string = config.to_string();
+    assert!(string.contains("[render_timetrack]\nGetTickCount=-1\n"));
+    assert!(string.ends_with("sdl_getticks=-1\nthreads=3\ntime=-1\n"));
+    assert_eq!(string.parse::<Config>().unwrap(), config);
+
+    config.timetracks.clear();
+    assert_eq!(config.mainthread_timetrack(), None);
+    config.mainthread_timetrack_mut().time = 10;
+    config
+        .set_raw("secondarythread_timetrack", "clock", "1")
+        .unwrap();
+    assert_eq!(config.secondarythread_timetrack().unwrap().clock, 1);
+    assert_eq!(config.timetracks.len(), 2);
+    assert_eq!(ThreadScope::from_section("_timetrack"), None);
+    assert_eq!(ThreadScope::Main.section(), "mainthread_timetrack");
+}
+
+#[test]
+fn test_config_screen_size() {
+    let original = read_to_string("tests/movies/221769_Trapped_5_config.ini").unwrap();
+    let mut config: Config = original.parse().unwrap();
+    assert_eq!(config.general.screen_width, None);
+    assert_eq!(config.to_string(), original);
+
+    config.general.set_screen_size(800, 600);
+    let string = config.to_string();
+    assert!(string.contains("\nscreen_height=600\nscreen_width=800\nvariable_framerate="));
+    let reparsed: Config = string.parse().unwrap();
+    assert_eq!(reparsed.general.screen_width, Some(800));
+    assert_eq!(reparsed.general.screen_height, Some(600));
+}
+
+#[test]
+fn test_config_md5() {
+    let original = read_to_string("tests/movies/221769_Trapped_5_config.ini").unwrap();
+    let text = original.replace(
+        "md5=c9b4f1b544725cb0d9d784c35232a52d",
+        "md5=C9B4F1B544725CB0D9D784C35232A52D",
+    );
+    let config: Config = text.parse().unwrap();
+    let md5 = config.general.md5.unwrap();
+    assert_eq!(md5, "c9b4f1b544725cb0d9d784c35232a52d");
+    assert_eq!(md5, "C9b4f1b544725cb0d9d784c35232a52D");
+    assert_ne!(md5, "c9b4f1b544725cb0d9d784c35232a52");
+    assert_eq!(config.to_string(), original);
+    assert_eq!(ConfigRef::parse(&text).unwrap().md5, Some(md5));
+
+    let empty: Config = original
+        .replace("md5=c9b4f1b544725cb0d9d784c35232a52d", "md5=")
+        .parse()
+        .unwrap();
+    assert_eq!(empty.general.md5, None);
+    assert!(empty.to_string().contains("\nmd5=\n"));
+    assert_eq!(
+        Config::default().get_raw("General", "md5").as_deref(),
+        Some("")
+    );
+
+    for invalid in [
+        "c9b4",
+        "g9b4f1b544725cb0d9d784c35232a52d",
+        "c9b4f1b544725cb0d9d784c35232a52d0",
+    ] {
+        assert!(invalid.parse::<Md5Hash>().is_err(), "{invalid}");
+        // a malformed hash is kept verbatim with a warning
+        let text = original.replace("c9b4f1b544725cb0d9d784c35232a52d", invalid);
+        let config: Config = text.parse().unwrap();
+        assert_eq!(config.general.md5, None);
+        assert_eq!(config.to_string(), text);
+        assert_eq!(ConfigRef::parse(&text).unwrap().md5, None);
+
+        let movie = archive(&[
+            ("config.ini", text.as_bytes()),
+            ("inputs", b""),
+            ("annotations.txt", b""),
+            ("editor.ini", b""),
+        ]);
+        let (mut movie, warnings) = LoadOptions::default()
+            .load_from_reader_with_warnings(movie.as_slice())
+            .unwrap();
+        assert!(
+            warnings
+                .0
+                .contains(&Warning::InvalidMd5(invalid.to_owned()))
+        );
+
+        // a hash set afterwards replaces it
+        movie.config.general.md5 = Some(md5);
+        assert_eq!(movie.config.to_string(), original);
+    }
+}
+
+#[test]
+fn test_md5_of_file() {
+    let path = "tests/movies/221769_Trapped_5_config.ini";
+    let data = read(path).unwrap();
+    let mut calls = vec![];
+    let md5 = md5_of_file_with_progress(path, |read, size| calls.push((read, size))).unwrap();
+    assert_eq!(md5, md5_of_file(path).unwrap());
+    assert_eq!(calls.last(), Some(&(data.len() as u64, data.len() as u64)));
+
+    // the digests of RFC 1321
+    let md5 = |data: &[u8]| md5_of_reader(data, |_| {}).unwrap().to_string();
+    assert_eq!(md5(b""), "d41d8cd98f00b204e9800998ecf8427e");
+    assert_eq!(md5(b"abc"), "900150983cd24fb0d6963f7d28e17f72");
+    let mut reads = 0;
+    let long = vec![b'a'; 200_000];
+    md5_of_reader(long.as_slice(), |_| reads += 1).unwrap();
+    assert!(reads > 1);
+
+    let mut config = Config::default();
+    config.general.set_md5_from_file(path).unwrap();
+    assert_eq!(config.general.md5, Some(md5_of_file(path).unwrap()));
+    assert!(config.general.set_md5_from_file("tests/missing").is_err());
+}
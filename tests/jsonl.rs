@@ -0,0 +1,108 @@
+use libtas_movie::{
+    format::FormatRegistry,
+    inputs::Inputs,
+    jsonl::{JsonlError, JsonlReader},
+    testkit::sample_movie,
+};
+
+#[test]
+fn test_to_jsonl() {
+    let inputs: Inputs = "|\n|K7a:ff53|M-3:4:R:1.3..:0|C1st\"ate|F1|T30:1|\n"
+        .parse()
+        .unwrap();
+    let mut jsonl = vec![];
+    inputs.to_jsonl(&mut jsonl).unwrap();
+    assert_eq!(
+        String::from_utf8(jsonl).unwrap(),
+        "{\"frame\": 0}\n\
+         {\"frame\": 1, \"keys\": [122, 65363], \
+         \"mouse\": {\"x\": -3, \"y\": 4, \"mode\": \"relative\", \"buttons\": [1, 3]}, \
+         \"controllers\": [{\"number\": 1, \"state\": \"st\\\"ate\"}], \"unknown\": [\"F1\"], \
+         \"framerate\": {\"num\": 30, \"den\": 1}}\n"
+    );
+
+    let movie = sample_movie(3, 60);
+    let format = FormatRegistry::builtin();
+    let format = format.for_extension("jsonl").unwrap();
+    assert_eq!(
+        format.export(&movie).unwrap(),
+        b"{\"frame\": 0}\n{\"frame\": 1, \"keys\": [122]}\n{\"frame\": 2, \"keys\": [65363]}\n"
+    );
+}
+
+#[test]
+fn test_from_jsonl() {
+    let text = "|\n|K7a:ff53|M-3:4:R:1.3..:0|C1st\"ate|F1|T30:1|\n|M5:6:A:.....:0|\n";
+    let inputs: Inputs = text.parse().unwrap();
+    let mut jsonl = vec![];
+    inputs.to_jsonl(&mut jsonl).unwrap();
+    assert_eq!(Inputs::from_jsonl(jsonl.as_slice()).unwrap(), inputs);
+
+    // the frame numbers are optional, and blank lines and whitespace are skipped
+    let jsonl = "{}\n\n{ \"keys\" : [122] , \"frame\":1 }\r\n{\"mouse\": {\"x\": 1, \"y\": 2, \"mode\": \"absolute\"}}\n";
+    assert_eq!(
+        Inputs::from_jsonl(jsonl.as_bytes()).unwrap().to_string(),
+        "|\n|K7a|\n|M1:2:A:.....:0|\n"
+    );
+
+    // frames are read one at a time
+    let mut reader = JsonlReader::new("{\"keys\": [122]}\n{\"keys\": \"z\"}\n{}\n".as_bytes());
+    assert!(reader.next().unwrap().is_ok());
+    let Some(Err(JsonlError::Invalid { line, message })) = reader.next() else {
+        panic!("expected an invalid line");
+    };
+    assert_eq!((line, message.as_str()), (2, "keys is not an array"));
+    assert!(reader.next().is_none());
+
+    let error = |jsonl: &str| match Inputs::from_jsonl(jsonl.as_bytes()) {
+        Err(JsonlError::Invalid { line, message }) => (line, message),
+        result => panic!("expected an invalid line, got {result:?}"),
+    };
+    assert_eq!(
+        error("{}\n{\"frame\": 2}\n"),
+        (2, "expected frame 1, found frame 2".to_owned())
+    );
+    assert_eq!(
+        error("{\"frame\": 0"),
+        (1, "expected `,` or `}` in an object".to_owned())
+    );
+    assert_eq!(error("[]"), (1, "the frame is not an object".to_owned()));
+    assert_eq!(
+        error("{\"key\": [1]}"),
+        (1, "unknown field `key`".to_owned())
+    );
+    assert_eq!(
+        error("{\"mouse\": {\"x\": 1, \"y\": 2, \"mode\": \"absolute\", \"buttons\": [6]}}"),
+        (1, "buttons must be from 1 to 5".to_owned())
+    );
+    assert_eq!(
+        error("{\"unknown\": [\"a|b\"]}"),
+        (1, "the frame cannot be written as inputs".to_owned())
+    );
+
+    // surrogate pairs are decoded, but not lone surrogates
+    let inputs = Inputs::from_jsonl("{\"unknown\": [\"\\ud83d\\ude00\"]}".as_bytes()).unwrap();
+    assert_eq!(inputs.0[0].unknown, ["\u{1f600}"]);
+    assert_eq!(
+        error("{\"unknown\": [\"\\ud83d\"]}"),
+        (1, "unpaired surrogate `\\ud83d`".to_owned())
+    );
+    assert_eq!(
+        error("{\"unknown\": [\"\\ude00\"]}"),
+        (1, "unpaired surrogate `\\ude00`".to_owned())
+    );
+
+    // deep nesting is an error rather than a stack overflow
+    let nested = "[".repeat(100_000);
+    assert_eq!(
+        error(&format!("{{\"unknown\": {nested}}}")),
+        (1, "values are nested deeper than 64 levels".to_owned())
+    );
+
+    let format = FormatRegistry::builtin();
+    let format = format.get("jsonl").unwrap();
+    let movie = format.import(b"{}\n{\"keys\": [122]}\n").unwrap();
+    assert_eq!(movie.config.general.frame_count, 2);
+    assert!(movie.duration() > core::time::Duration::ZERO);
+    assert!(format.import(b"{").is_err());
+}
@@ -0,0 +1,147 @@
+use libtas_movie::{
+    inputs::{Input, KeyboardInput, Rect},
+    lint::{
+        ClickOutOfBounds, Finding, Lint, LintRule, Linter, OppositeDirections, Severity,
+        ShortPress, TrailingBlankFrames,
+    },
+    movie::LibTASMovie,
+    testkit::{movie_with_mouse_path, sample_movie},
+};
+
+fn keys(keys: &[u32]) -> Input {
+    Input {
+        keyboard: Some(KeyboardInput(keys.to_vec())),
+        ..Input::default()
+    }
+}
+
+#[test]
+fn test_builtin_rules() {
+    let mut movie = sample_movie(0, 60);
+    movie.inputs.0 = vec![
+        keys(&[0xff51, 0xff53]),
+        keys(&[0xff51, 0xff53, 0x7a]),
+        keys(&[0x7a]),
+        keys(&[0xff52]),
+        Input::default(),
+        keys(&[0xff52, 0xff54]),
+        Input::default(),
+        Input::default(),
+    ];
+
+    assert_eq!(
+        OppositeDirections.check(&movie),
+        [
+            Finding {
+                frames: 0..2,
+                message: "Left and Right held together".to_owned(),
+            },
+            Finding {
+                frames: 5..6,
+                message: "Up and Down held together".to_owned(),
+            },
+        ]
+    );
+    assert_eq!(
+        TrailingBlankFrames.check(&movie),
+        [Finding {
+            frames: 6..8,
+            message: "2 blank frame(s) at the end".to_owned(),
+        }]
+    );
+    let frames = |findings: Vec<Finding>| {
+        findings
+            .into_iter()
+            .map(|finding| finding.frames)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        frames(ShortPress::default().check(&movie)),
+        [3..4, 5..6, 5..6]
+    );
+    assert_eq!(
+        frames(ShortPress { min_frames: 3 }.check(&movie)),
+        [0..2, 0..2, 1..3, 3..4, 5..6, 5..6]
+    );
+
+    let movie = movie_with_mouse_path(&[(-1, 0), (-1, 0), (640, 10)], 60);
+    assert!(ClickOutOfBounds::default().check(&movie).is_empty());
+    let rule = ClickOutOfBounds {
+        bounds: Some(Rect {
+            left: 0,
+            top: 0,
+            right: 639,
+            bottom: 479,
+        }),
+    };
+    let findings = rule.check(&movie);
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].frames, 2..3);
+
+    // without bounds, clicks are checked against the recorded screen
+    let mut movie = movie;
+    movie.config.general.set_screen_size(640, 480);
+    assert_eq!(ClickOutOfBounds::default().check(&movie), findings);
+}
+
+/// Reports every movie.
+struct Always;
+
+impl LintRule for Always {
+    fn name(&self) -> &'static str {
+        "always"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Deny
+    }
+
+    fn check(&self, _movie: &LibTASMovie) -> Vec<Finding> {
+        vec![Finding {
+            frames: 0..1,
+            message: "always".to_owned(),
+        }]
+    }
+}
+
+#[test]
+fn test_linter() {
+    let mut linter = Linter::builtin();
+    assert_eq!(linter.severity("short-press"), Some(Severity::Warn));
+    linter.register(Always);
+    assert_eq!(linter.severity("always"), Some(Severity::Deny));
+
+    let movie = sample_movie(5, 60);
+    let lints = linter.run(&movie);
+    assert_eq!(
+        lints.iter().map(ToString::to_string).collect::<Vec<_>>(),
+        [
+            "warn[trailing-blank-frames]: frame 4: 1 blank frame(s) at the end",
+            "warn[short-press]: frame 1: z pressed for 1 frame(s), fewer than 2",
+            "warn[short-press]: frame 3: z pressed for 1 frame(s), fewer than 2",
+            "deny[always]: frame 0: always",
+        ]
+    );
+
+    linter
+        .configure("# hygiene\nshort-press = allow\n\n trailing-blank-frames=deny \n")
+        .unwrap();
+    assert_eq!(
+        linter.run(&movie)[0],
+        Lint {
+            rule: "trailing-blank-frames",
+            severity: Severity::Deny,
+            frames: 4..5,
+            message: "1 blank frame(s) at the end".to_owned(),
+        }
+    );
+    assert_eq!(linter.run(&movie).len(), 2);
+
+    assert_eq!(
+        linter.configure("unknown = deny").unwrap_err().0,
+        "unknown = deny"
+    );
+    assert!(linter.configure("always = error").is_err());
+    assert!(linter.configure("always").is_err());
+    assert!(!linter.set_severity("unknown", Severity::Warn));
+}
@@ -0,0 +1,115 @@
+#![cfg(feature = "lua")]
+
+use libtas_movie::{
+    editor::Marker,
+    inputs::{KeyboardInput, ReferenceMode},
+    lua::{run_lua, run_lua_for},
+    testkit::{movie_with_mouse_path, sample_movie},
+};
+
+#[test]
+fn test_lua_keys() {
+    let mut movie = sample_movie(8, 60);
+    let script = r#"
+        callback.onInput(function()
+            if input.getKey(0x7a) == 1 then
+                input.setKey(0x7a, 0)
+                input.setKey(0x78, 1)
+            end
+        end)
+        callback.onInput(function()
+            if movie.currentFrame() == 2 then
+                input.clear()
+            end
+        end)
+    "#;
+    run_lua(&mut movie, script).unwrap();
+
+    let keys = |frame: usize| movie.inputs.0[frame].keyboard.clone();
+    assert_eq!(keys(1), Some(KeyboardInput(vec![0x78])));
+    assert_eq!(keys(2), None);
+    assert_eq!(keys(3), Some(KeyboardInput(vec![0xff53, 0x78])));
+    assert_eq!(keys(6), Some(KeyboardInput(vec![0xff53])));
+}
+
+#[test]
+fn test_lua_mouse() {
+    let mut movie = movie_with_mouse_path(&[(1, 2), (3, 4), (5, 6)], 60);
+    let script = r#"
+        callback.onInput(function()
+            local x, y, mode = input.getMouseCoords()
+            input.setMouseCoords(x + 10, y, 1)
+            input.setMouseButtons(2, input.getMouseButtons(0))
+        end)
+    "#;
+    run_lua(&mut movie, script).unwrap();
+
+    let mouse = movie.inputs.0[2].mouse.unwrap();
+    assert_eq!((mouse.xpos, mouse.ypos), (15, 6));
+    assert_eq!(mouse.reference_mode, ReferenceMode::Relative);
+    assert!(mouse.left_click && mouse.right_click);
+    assert!(!movie.inputs.0[0].mouse.unwrap().right_click);
+}
+
+#[test]
+fn test_lua_movie() {
+    let mut movie = sample_movie(4, 60);
+    movie.config.general.rerecord_count = 7;
+    let script = r#"
+        local frames = {}
+        callback.onStartup(function()
+            assert(movie.frameCount() == 6)
+            assert(movie.rerecords() == 7)
+            assert(movie.status() == 2)
+        end)
+        callback.onFrame(function()
+            local sec, nsec = movie.time()
+            if movie.currentFrame() == 3 then
+                assert(sec == 0 and nsec == 50000000)
+                movie.setMarker("three")
+            end
+            if movie.currentFrame() >= 4 then
+                input.setKey(0x20, true)
+            end
+        end)
+        callback.onPaint(function() error("never painted") end)
+    "#;
+    run_lua_for(&mut movie, script, 6).unwrap();
+
+    assert_eq!(movie.inputs.0.len(), 6);
+    assert_eq!(movie.config.general.frame_count, 6);
+    assert_eq!(movie.inputs.0[5].keyboard, Some(KeyboardInput(vec![0x20])));
+    assert_eq!(
+        movie.editor.markers,
+        [Marker {
+            frame: 3,
+            text: "three".to_owned()
+        }]
+    );
+}
+
+#[test]
+fn test_lua_errors() {
+    let mut empty = sample_movie(0, 60);
+    assert!(run_lua(&mut empty, "input.setKey(0x20, 1)").is_err());
+
+    let mut movie = sample_movie(4, 60);
+    for script in [
+        "input.setMouseButtons(5, 1)",
+        "callback.onInput(function() error('failed') end)",
+        "local x = ",
+    ] {
+        assert!(run_lua(&mut movie, script).is_err(), "{script}");
+    }
+
+    // edits before an error are kept
+    let script = r#"
+        callback.onInput(function()
+            input.clear()
+            if movie.currentFrame() == 2 then error("stop") end
+        end)
+    "#;
+    assert!(run_lua(&mut movie, script).is_err());
+    assert!(movie.inputs.0[1].keyboard.is_none());
+    assert!(movie.inputs.0[3].keyboard.is_some());
+}
@@ -0,0 +1,44 @@
+use core::mem::size_of;
+
+use libtas_movie::{
+    inputs::{Input, Inputs, KeyboardInput},
+    movie::{LoadOptions, load_movie},
+    testkit::sample_movie,
+};
+
+#[test]
+fn test_inputs_footprint() {
+    let mut inputs = Inputs::from(Vec::with_capacity(2));
+    assert_eq!(inputs.memory_footprint(), 2 * size_of::<Input>());
+    inputs.0.push(Input {
+        keyboard: Some(KeyboardInput(Vec::with_capacity(3))),
+        ..Input::default()
+    });
+    assert_eq!(
+        inputs.memory_footprint(),
+        2 * size_of::<Input>() + 3 * size_of::<u32>()
+    );
+}
+
+#[test]
+fn test_movie_footprint() {
+    let movie = sample_movie(100, 60);
+    let footprint = movie.memory_footprint();
+    assert!(footprint.frames >= 100 * size_of::<Input>());
+    assert!(footprint.keys >= 100 * size_of::<u32>());
+    assert_eq!(footprint.raw, 0);
+    assert_eq!(
+        footprint.frames + footprint.keys,
+        movie.inputs.memory_footprint()
+    );
+
+    let path = "tests/movies/221769_Trapped_5.ltm";
+    let plain = load_movie(path).unwrap().memory_footprint();
+    let options = LoadOptions {
+        fidelity: true,
+        ..LoadOptions::default()
+    };
+    let fidelity = options.load(path).unwrap().memory_footprint();
+    assert!(fidelity.raw > 0);
+    assert_eq!(fidelity.total(), plain.total() + fidelity.raw);
+}
@@ -0,0 +1,206 @@
+use libtas_movie::{
+    inputs::{Input, KeyboardInput, Rect, ReferenceMode},
+    pass::{ClampMouse, FramePass, Jitter, Normalize, Pipeline, RemapKeys},
+    testkit::{movie_with_mouse_path, sample_movie},
+};
+
+#[test]
+fn test_normalize() {
+    let mut movie = sample_movie(4, 60);
+    movie.inputs.0[0].keyboard = Some(KeyboardInput(vec![]));
+    movie.inputs.0[3].keyboard = Some(KeyboardInput(vec![0xff53, 0x7a, 0xff53]));
+    movie.inputs.apply_pass(Normalize);
+    assert_eq!(movie.inputs[0].keyboard, None);
+    assert_eq!(
+        movie.inputs[3].keyboard,
+        Some(KeyboardInput(vec![0x7a, 0xff53]))
+    );
+}
+
+#[test]
+fn test_pipeline() {
+    let mut movie = sample_movie(4, 60);
+    // swap z and Right, then sort
+    let pipeline = Pipeline::new()
+        .then(RemapKeys(vec![(0x7a, 0xff53), (0xff53, 0x7a)]))
+        .then(Normalize);
+    movie.inputs.apply_pass(pipeline);
+    assert_eq!(movie.inputs[1].keyboard, Some(KeyboardInput(vec![0xff53])));
+    assert_eq!(movie.inputs[2].keyboard, Some(KeyboardInput(vec![0x7a])));
+    assert_eq!(
+        movie.inputs[3].keyboard,
+        Some(KeyboardInput(vec![0x7a, 0xff53]))
+    );
+}
+
+#[test]
+fn test_clamp_mouse() {
+    let mut movie = movie_with_mouse_path(&[(-5, 10), (50, 700), (10, 10)], 60);
+    let rect = Rect {
+        left: 0,
+        top: 0,
+        right: 639,
+        bottom: 479,
+    };
+    movie.inputs.apply_pass(ClampMouse(rect));
+    let positions: Vec<_> = movie
+        .inputs
+        .0
+        .iter()
+        .map(|input| input.mouse.map(|mouse| (mouse.xpos, mouse.ypos)).unwrap())
+        .collect();
+    assert_eq!(positions, [(0, 10), (50, 479), (10, 10)]);
+}
+
+#[test]
+fn test_clamp_mouse_report() {
+    let mut movie = movie_with_mouse_path(&[(-5, 10), (50, 700), (10, 10), (640, 480)], 60);
+    let rect = Rect {
+        left: 0,
+        top: 0,
+        right: 639,
+        bottom: 479,
+    };
+    assert_eq!(movie.inputs.clamp_mouse(rect), [0, 1, 3]);
+    assert_eq!(
+        movie.inputs[3].mouse.map(|mouse| (mouse.xpos, mouse.ypos)),
+        Some((639, 479))
+    );
+    assert_eq!(movie.inputs.clamp_mouse(rect), []);
+}
+
+/// Passes can keep state and be reused.
+#[test]
+fn test_stateful_pass() {
+    struct CountKeys(usize);
+
+    impl FramePass for CountKeys {
+        fn apply(&mut self, _frame: usize, input: &mut Input) {
+            self.0 += input
+                .keyboard
+                .as_ref()
+                .map_or(0, |keyboard| keyboard.0.len());
+        }
+    }
+
+    let mut movie = sample_movie(8, 60);
+    let mut count = CountKeys(0);
+    movie.inputs.apply_pass(&mut count);
+    movie.inputs.apply_pass(&mut count);
+    assert_eq!(count.0, 16);
+}
+
+#[test]
+fn test_mouse_mode_conversion() {
+    let path = [(100, 50), (110, 50), (110, 40), (90, 60)];
+    let mut movie = movie_with_mouse_path(&path, 60);
+    let absolute = movie.inputs.clone();
+    let mouse = |inputs: &libtas_movie::inputs::Inputs| -> Vec<_> {
+        inputs
+            .0
+            .iter()
+            .map(|input| {
+                input
+                    .mouse
+                    .map(|mouse| (mouse.xpos, mouse.ypos, mouse.reference_mode))
+                    .unwrap()
+            })
+            .collect()
+    };
+
+    assert_eq!(movie.inputs.to_relative(), (100, 50));
+    assert_eq!(
+        mouse(&movie.inputs),
+        [
+            (0, 0, ReferenceMode::Relative),
+            (10, 0, ReferenceMode::Relative),
+            (0, -10, ReferenceMode::Relative),
+            (-20, 20, ReferenceMode::Relative),
+        ]
+    );
+    movie.inputs.to_absolute((100, 50));
+    assert_eq!(movie.inputs, absolute);
+
+    // the start position is deduced from relative moves before absolute coordinates
+    movie.inputs.to_relative();
+    movie.inputs.0[3].mouse.as_mut().unwrap().xpos = 5;
+    movie.inputs.0[3].mouse.as_mut().unwrap().ypos = 5;
+    movie.inputs.0[3].mouse.as_mut().unwrap().reference_mode = ReferenceMode::Absolute;
+    assert_eq!(movie.inputs.to_relative(), (-5, 15));
+    movie.inputs.to_absolute((0, 0));
+    assert_eq!(mouse(&movie.inputs)[3], (10, -10, ReferenceMode::Absolute));
+}
+
+#[test]
+fn test_jitter() {
+    let mut movie = movie_with_mouse_path(&[(10, 10); 8], 60);
+    for (frame, input) in movie.inputs.0.iter_mut().enumerate() {
+        let mut keys = vec![];
+        if (2..5).contains(&frame) {
+            keys.push(0x7a);
+        }
+        if frame == 6 {
+            keys.push(0xff53);
+        }
+        input.keyboard = (!keys.is_empty()).then_some(KeyboardInput(keys));
+    }
+    let held = |inputs: &libtas_movie::inputs::Inputs, key: u32| -> Vec<usize> {
+        (0..inputs.0.len())
+            .filter(|&frame| {
+                inputs.0[frame]
+                    .keyboard
+                    .as_ref()
+                    .is_some_and(|keys| keys.0.contains(&key))
+            })
+            .collect()
+    };
+
+    // without perturbations, the inputs are kept
+    let mut inputs = movie.inputs.clone();
+    let mut jitter = Jitter::new(&inputs, 1);
+    jitter.press_shift = 0.0;
+    jitter.mouse_jitter = 0.0;
+    inputs.apply_pass(jitter);
+    assert_eq!(inputs, movie.inputs);
+
+    let mut seen = vec![];
+    for seed in 0..32 {
+        let mut inputs = movie.inputs.clone();
+        let mut jitter = Jitter::new(&inputs, seed);
+        jitter.press_shift = 1.0;
+        inputs.apply_pass(jitter.clone());
+        // the same seed gives the same perturbations
+        let mut again = movie.inputs.clone();
+        again.apply_pass(jitter);
+        assert_eq!(inputs, again);
+
+        let z = held(&inputs, 0x7a);
+        assert!(z == [1, 2, 3, 4] || z == [3, 4], "{z:?}");
+        // a press of one frame is not shifted later
+        let right = held(&inputs, 0xff53);
+        assert!(right == [5, 6] || right == [6], "{right:?}");
+        for input in &inputs.0 {
+            let mouse = input.mouse.unwrap();
+            assert!((9..=11).contains(&mouse.xpos) && (9..=11).contains(&mouse.ypos));
+        }
+        seen.push(z.len());
+    }
+    // both shifts happen with some seed
+    assert!(seen.contains(&2) && seen.contains(&4));
+
+    // a release of one frame between two presses is kept
+    for (frame, input) in movie.inputs.0.iter_mut().enumerate() {
+        input.keyboard = [2, 4, 5]
+            .contains(&frame)
+            .then(|| KeyboardInput(vec![0x7a]));
+    }
+    for seed in 0..32 {
+        let mut inputs = movie.inputs.clone();
+        let mut jitter = Jitter::new(&inputs, seed);
+        jitter.press_shift = 1.0;
+        inputs.apply_pass(jitter);
+        let z = held(&inputs, 0x7a);
+        assert!(!z.contains(&3), "{z:?}");
+        assert!(z.ends_with(&[4, 5]) || z.ends_with(&[2, 5]), "{z:?}");
+    }
+}
@@ -0,0 +1,112 @@
+use libtas_movie::{
+    inputs::{KeyboardInput, MouseButton, keysym_from_name, keysym_name},
+    pattern::{Condition, Pattern, Step},
+    testkit::{movie_with_mouse_path, sample_movie},
+};
+
+#[test]
+fn test_keysym_from_name() {
+    for keysym in [0x20, 0x7a, 0xff53, 0xffbe, 0xffc9, 0xffe1, 0x1008ff13] {
+        assert_eq!(keysym_from_name(&keysym_name(keysym)), Some(keysym));
+    }
+    assert_eq!(keysym_from_name("F13"), None);
+    assert_eq!(keysym_from_name("F01"), None);
+    assert_eq!(keysym_from_name("Unknown"), None);
+    assert_eq!(keysym_from_name(""), None);
+}
+
+#[test]
+fn test_parse_pattern() {
+    let pattern: Pattern = "press(z) then any{0..5} then hold(Right)+click{2..}"
+        .parse()
+        .unwrap();
+    assert_eq!(
+        pattern.0,
+        [
+            Step {
+                conditions: vec![Condition::Press(0x7a)],
+                min: 1,
+                max: Some(1),
+            },
+            Step {
+                conditions: vec![Condition::Any],
+                min: 0,
+                max: Some(5),
+            },
+            Step {
+                conditions: vec![Condition::Hold(0xff53), Condition::Click(MouseButton::Left)],
+                min: 2,
+                max: None,
+            },
+        ]
+    );
+    let pattern: Pattern = "idle {3} then click(right)".parse().unwrap();
+    assert_eq!(pattern.0[0].min, 3);
+    assert_eq!(
+        pattern.0[1].conditions,
+        [Condition::Click(MouseButton::Right)]
+    );
+
+    for invalid in [
+        "",
+        "press",
+        "press(nokey)",
+        "press(z) then",
+        "any{5..2}",
+        "any{x}",
+        "click(6)",
+        "jump",
+        "idle(z)",
+    ] {
+        assert!(invalid.parse::<Pattern>().is_err(), "{invalid}");
+    }
+}
+
+#[test]
+fn test_find_pattern() {
+    // frames cycle through nothing, z, Right, and z with Right
+    let inputs = sample_movie(12, 60).inputs;
+    let pattern: Pattern = "hold(z){2..} ".parse().unwrap();
+    assert!(pattern.find(&inputs).is_empty());
+
+    let pattern: Pattern = "press(z) then release(z)".parse().unwrap();
+    let matches = pattern.find(&inputs);
+    assert_eq!(
+        matches
+            .iter()
+            .map(|found| found.frames.clone())
+            .collect::<Vec<_>>(),
+        [1..3, 3..5, 5..7, 7..9, 9..11]
+    );
+
+    let pattern: Pattern = "idle then any{0..} then idle".parse().unwrap();
+    let matches = pattern.find(&inputs);
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].frames, 0..9);
+    assert_eq!(matches[0].steps, [0..1, 1..8, 8..9]);
+
+    // empty matches are skipped
+    let pattern: Pattern = "hold(Up){0..}".parse().unwrap();
+    assert!(pattern.find(&inputs).is_empty());
+
+    // nested repetitions that never match do not backtrack exponentially
+    let inputs = sample_movie(200, 60).inputs;
+    let pattern: Pattern = format!("{} then hold(Up)", ["any{0..}"; 10].join(" then "))
+        .parse()
+        .unwrap();
+    assert!(pattern.find(&inputs).is_empty());
+}
+
+#[test]
+fn test_find_pattern_click() {
+    let mut inputs = movie_with_mouse_path(&[(0, 0); 6], 60).inputs;
+    inputs.0[2].keyboard = Some(KeyboardInput(vec![0xff53]));
+    let pattern: Pattern = "press(Right) then any{0..3} then press(Right)+click"
+        .parse()
+        .unwrap();
+    assert!(pattern.find(&inputs).is_empty());
+    inputs.0[5].keyboard = Some(KeyboardInput(vec![0xff53]));
+    let matches = pattern.find(&inputs);
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].steps, [2..3, 3..5, 5..6]);
+}
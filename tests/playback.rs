@@ -0,0 +1,70 @@
+use libtas_movie::{
+    inputs::{Input, KeyboardInput},
+    playback::PlaybackLog,
+    testkit::sample_movie,
+};
+
+#[test]
+fn test_parse_log() {
+    let log = PlaybackLog::parse("# log\n|\n|K7a|\n\n5: |Kff53|\n6:|K7a:ff53|\n|\n").unwrap();
+    let frames: Vec<_> = log.0.iter().map(|(frame, _)| *frame).collect();
+    assert_eq!(frames, [0, 1, 5, 6, 7]);
+    assert_eq!(log.0[2].1.keyboard, Some(KeyboardInput(vec![0xff53])));
+
+    for text in ["K7a", "x: |K7a|", "3: |Kzz|", "|K7a"] {
+        assert!(PlaybackLog::parse(text).is_err(), "{text}");
+    }
+    assert_eq!(PlaybackLog::parse("").unwrap(), PlaybackLog::default());
+
+    // no frame follows the last one
+    let last = format!("{}: |\n", usize::MAX);
+    assert_eq!(PlaybackLog::parse(&last).unwrap().0[0].0, usize::MAX);
+    assert!(PlaybackLog::parse(&format!("{last}|\n")).is_err());
+}
+
+#[test]
+fn test_compare_log() {
+    let inputs = sample_movie(20, 60).inputs;
+    let text: String = inputs.0[..12]
+        .iter()
+        .map(|input| format!("{input}\n"))
+        .collect();
+    let mut log = PlaybackLog::parse(&text).unwrap();
+    assert_eq!(log.compare(&inputs, 2), None);
+
+    log.0[9].1 = Input::default();
+    log.0[10].1 = Input::default();
+    let mismatch = log.compare(&inputs, 2).unwrap();
+    assert_eq!(mismatch.frame, 9);
+    let frames: Vec<_> = mismatch.context.iter().map(|frame| frame.frame).collect();
+    assert_eq!(frames, [7, 8, 9, 10, 11]);
+    assert!(mismatch.context[3].disagrees());
+    assert!(!mismatch.context[4].disagrees());
+    assert_eq!(
+        mismatch.to_string(),
+        "movie and log first disagree at frame 9\n\
+         \x20 7: movie |K7a:ff53| log |K7a:ff53|\n\
+         \x20 8: movie | log |\n\
+         > 9: movie |K7a| log |\n\
+         ! 10: movie |Kff53| log |\n\
+         \x20 11: movie |K7a:ff53| log |K7a:ff53|\n"
+    );
+}
+
+#[test]
+fn test_compare_log_bounds() {
+    let inputs = sample_movie(4, 60).inputs;
+    let log = PlaybackLog::parse("0: |\n2: |Kff53|\n5: |\n").unwrap();
+    let mismatch = log.compare(&inputs, 2).unwrap();
+    assert_eq!(mismatch.frame, 5);
+    // frames neither in the movie nor in the log are left out
+    let frames: Vec<_> = mismatch.context.iter().map(|frame| frame.frame).collect();
+    assert_eq!(frames, [3, 5]);
+    assert_eq!(mismatch.context[0].log, None);
+    assert_eq!(mismatch.context[1].movie, None);
+    assert!(mismatch.context[1].disagrees());
+
+    // skipped and missing frames are not compared
+    let log = PlaybackLog::parse("1: |K7a|\n3: |K7a:ff53|\n").unwrap();
+    assert_eq!(log.compare(&inputs, 3), None);
+}
@@ -0,0 +1,43 @@
+use core::time::Duration;
+
+use libtas_movie::{inputs::Input, pretty::PrettyFrame, testkit::sample_movie};
+
+#[test]
+fn test_pretty_frame() {
+    let movie = sample_movie(4, 20);
+    let frame = movie.pretty_frame(3).unwrap();
+    assert_eq!(frame.time, Some(Duration::from_millis(150)));
+    assert_eq!(
+        format!("{frame:?}"),
+        "PrettyFrame { frame: 3, time: 150ms, keys: [z, Right] }"
+    );
+    assert!(movie.pretty_frame(4).is_none());
+    assert_eq!(
+        movie
+            .pretty_frames()
+            .map(|frame| frame.time.unwrap())
+            .collect::<Vec<_>>(),
+        [0, 50, 100, 150].map(Duration::from_millis)
+    );
+
+    let input: Input = "|M-3:4:R:.2..5:0|C1state|T30:1|".parse().unwrap();
+    assert_eq!(
+        format!("{:#?}", PrettyFrame::new(0, &input)),
+        "PrettyFrame {
+    frame: 0,
+    mouse: Mouse {
+        x: -3,
+        y: 4,
+        mode: Relative,
+        buttons: [
+            MMB,
+            MB5,
+        ],
+    },
+    controllers: [
+        C1state,
+    ],
+    framerate: 30,
+}"
+    );
+}
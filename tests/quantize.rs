@@ -0,0 +1,109 @@
+use core::time::Duration;
+
+use libtas_movie::{
+    inputs::{KeyboardInput, MouseButton},
+    quantize::{Hold, InputEvent, Quantizer, Rounding},
+    time::Framerate,
+};
+
+fn ms(millis: u64) -> Duration {
+    Duration::from_millis(millis)
+}
+
+#[test]
+fn test_rounding() {
+    let floor = Quantizer::new(Framerate::from_fps(10).unwrap());
+    assert_eq!(floor.frame_of(ms(0)), 0);
+    assert_eq!(floor.frame_of(ms(149)), 1);
+    assert_eq!(floor.frame_of(ms(150)), 1);
+    let nearest = floor.rounding(Rounding::Nearest);
+    assert_eq!(nearest.frame_of(ms(149)), 1);
+    assert_eq!(nearest.frame_of(ms(150)), 2);
+    assert_eq!(nearest.frame_of(ms(200)), 2);
+
+    // an exact frame start at 60 fps
+    let quantizer = Quantizer::new(Framerate::from_fps(60).unwrap());
+    assert_eq!(quantizer.frame_of(ms(50)), 3);
+}
+
+#[test]
+fn test_min_hold() {
+    let events = [
+        (ms(0), InputEvent::KeyPress(0x7a)),
+        (ms(5), InputEvent::KeyRelease(0x7a)),
+        (ms(5), InputEvent::ButtonPress(MouseButton::Left)),
+        (ms(25), InputEvent::KeyPress(0x78)),
+        (ms(65), InputEvent::KeyRelease(0x78)),
+        (ms(66), InputEvent::ButtonRelease(MouseButton::Left)),
+    ];
+    let quantizer = Quantizer::new(Framerate::from_fps(100).unwrap());
+    let keys = |quantizer: &Quantizer| -> Vec<_> {
+        quantizer
+            .quantize(&events)
+            .0
+            .iter()
+            .map(|input| input.keyboard.clone().map(|KeyboardInput(keys)| keys))
+            .collect()
+    };
+    let x = Some(vec![0x78]);
+    assert_eq!(
+        keys(&quantizer),
+        [
+            Some(vec![0x7a]),
+            None,
+            x.clone(),
+            x.clone(),
+            x.clone(),
+            x.clone(),
+            x.clone()
+        ]
+    );
+    // short presses are held longer, and the inputs are extended to the end of the holds
+    let quantizer = quantizer.min_hold(3);
+    let inputs = quantizer.quantize(&events);
+    assert_eq!(
+        keys(&quantizer)[..3],
+        [Some(vec![0x7a]), Some(vec![0x7a]), Some(vec![0x7a, 0x78])]
+    );
+    assert_eq!(inputs.0.len(), 7);
+    assert!(inputs.0.iter().all(|input| input.mouse.unwrap().left_click));
+
+    // a press again during the delayed release cancels it
+    let events = [
+        (ms(0), InputEvent::KeyPress(0x7a)),
+        (ms(1), InputEvent::KeyRelease(0x7a)),
+        (ms(15), InputEvent::KeyPress(0x7a)),
+    ];
+    let inputs = quantizer.quantize_to(&events, 5);
+    assert!(inputs.0.iter().all(|input| input.keyboard.is_some()));
+    assert_eq!(inputs.0.len(), 5);
+}
+
+#[test]
+fn test_hold() {
+    let events = [
+        (ms(5), InputEvent::KeyPress(0x7a)),
+        (ms(15), InputEvent::KeyRelease(0x7a)),
+        (ms(22), InputEvent::KeyPress(0x78)),
+        (ms(24), InputEvent::KeyRelease(0x78)),
+    ];
+    let quantizer = Quantizer::new(Framerate::from_fps(100).unwrap());
+    // held during the frame of the release
+    assert_eq!(
+        quantizer.quantize(&events).to_string(),
+        "|K7a|\n|K7a|\n|K78|\n"
+    );
+    // released at the end of the frame of the release, but a tap is kept
+    let quantizer = quantizer.hold(Hold::End);
+    assert_eq!(quantizer.quantize(&events).to_string(), "|K7a|\n|\n|K78|\n");
+}
+
+#[test]
+fn test_zero_framerate() {
+    for framerate in [Framerate { num: 0, den: 1 }, Framerate { num: 60, den: 0 }] {
+        let quantizer = Quantizer::new(framerate).rounding(Rounding::Nearest);
+        assert_eq!(quantizer.frame_of(ms(20)), 1);
+        let events = [(ms(20), InputEvent::KeyPress(0x7a))];
+        assert_eq!(quantizer.quantize(&events).0.len(), 2);
+    }
+}
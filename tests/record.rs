@@ -0,0 +1,97 @@
+use core::time::Duration;
+
+use libtas_movie::{
+    inputs::MouseButton,
+    quantize::{InputEvent, Quantizer, Rounding},
+    record::{RecordError, Recorder},
+    time::Framerate,
+};
+
+fn ms(millis: u64) -> Duration {
+    Duration::from_millis(millis)
+}
+
+#[test]
+fn test_recorder() {
+    let mut recorder = Recorder::new(Framerate { num: 100, den: 1 });
+    recorder.record(ms(15), InputEvent::KeyPress(0x7a)).unwrap();
+    recorder
+        .record(ms(25), InputEvent::KeyPress(0xff53))
+        .unwrap();
+    // repeated presses are ignored
+    recorder.record(ms(26), InputEvent::KeyPress(0x7a)).unwrap();
+    recorder
+        .record(ms(35), InputEvent::KeyRelease(0x7a))
+        .unwrap();
+    assert_eq!(recorder.frame(), 3);
+    // a tap within a frame is held on that frame
+    recorder.record(ms(41), InputEvent::KeyPress(0x78)).unwrap();
+    recorder
+        .record(ms(42), InputEvent::KeyRelease(0x78))
+        .unwrap();
+    recorder
+        .record(ms(50), InputEvent::KeyRelease(0xff53))
+        .unwrap();
+    assert_eq!(
+        recorder.record(ms(49), InputEvent::KeyPress(0x7a)),
+        Err(RecordError::OutOfOrder {
+            last: ms(50),
+            time: ms(49)
+        })
+    );
+
+    let movie = recorder.finish(ms(70));
+    assert_eq!(
+        movie.inputs.to_string(),
+        "|\n|K7a|\n|K7a:ff53|\n|Kff53|\n|Kff53:78|\n|\n|\n"
+    );
+    assert_eq!(movie.config.general.frame_count, 7);
+    assert_eq!(movie.duration(), ms(70));
+    assert!(!movie.config.general.mouse_support);
+}
+
+#[test]
+fn test_recorder_mouse() {
+    let mut recorder = Recorder::new(Framerate { num: 60, den: 1 });
+    recorder
+        .record(ms(20), InputEvent::Motion { x: 5, y: 5 })
+        .unwrap();
+    recorder
+        .record(ms(30), InputEvent::Motion { x: 10, y: 20 })
+        .unwrap();
+    recorder
+        .record(ms(40), InputEvent::ButtonPress(MouseButton::Left))
+        .unwrap();
+    recorder
+        .record(ms(45), InputEvent::ButtonRelease(MouseButton::Left))
+        .unwrap();
+    recorder
+        .record(ms(50), InputEvent::ButtonPress(MouseButton::Right))
+        .unwrap();
+    // the last frame is kept even if the recording ends early
+    let movie = recorder.finish(ms(10));
+    assert_eq!(
+        movie.inputs.to_string(),
+        "|\n|M10:20:A:.....:0|\n|M10:20:A:1....:0|\n|M10:20:A:..3..:0|\n"
+    );
+    assert!(movie.config.general.mouse_support);
+    assert_eq!(
+        movie.duration_summary().to_string(),
+        "0.066s (4 frames @ 60 fps)"
+    );
+}
+
+#[test]
+fn test_recorder_quantizer() {
+    let quantizer = Quantizer::new(Framerate { num: 100, den: 1 })
+        .rounding(Rounding::Nearest)
+        .min_hold(3);
+    let mut recorder = Recorder::with_quantizer(quantizer);
+    recorder.record(ms(16), InputEvent::KeyPress(0x7a)).unwrap();
+    recorder
+        .record(ms(18), InputEvent::KeyRelease(0x7a))
+        .unwrap();
+    assert_eq!(recorder.frame(), 2);
+    let movie = recorder.finish(ms(30));
+    assert_eq!(movie.inputs.to_string(), "|\n|\n|K7a|\n|K7a|\n|K7a|\n");
+}
@@ -0,0 +1,33 @@
+use libtas_movie::{
+    export::matrix::ColumnSpec,
+    inputs::MouseButton,
+    render::piano_roll,
+    testkit::{movie_with_mouse_path, sample_movie},
+};
+
+#[test]
+fn test_piano_roll() {
+    let mut inputs = sample_movie(4, 60).inputs;
+    inputs.0[3].mouse = movie_with_mouse_path(&[(0, 0), (166, 270)], 60).inputs.0[1].mouse;
+
+    let spec = ColumnSpec::for_inputs(&inputs);
+    assert_eq!(
+        piano_roll(&inputs, 0..10, &spec),
+        "frame |zR1| x,y
+    0 |...|
+    1 |z..|
+    2 |.R.|
+    3 |zR1| 166,270
+"
+    );
+
+    let spec = ColumnSpec {
+        keys: vec![0xff53],
+        mouse_buttons: vec![MouseButton::Right],
+    };
+    assert_eq!(
+        piano_roll(&inputs, 1..3, &spec),
+        "frame |R3|\n    1 |..|\n    2 |R.|\n"
+    );
+    assert_eq!(piano_roll(&inputs, 5..9, &spec), "frame |R3|\n");
+}
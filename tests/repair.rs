@@ -0,0 +1,89 @@
+use core::time::Duration;
+
+use libtas_movie::{
+    inputs::KeyboardInput,
+    repair::{Repair, RepairOptions},
+    testkit::sample_movie,
+    time::TimeSpec,
+};
+
+#[test]
+fn test_repair_nothing() {
+    let mut movie = sample_movie(8, 60);
+    let original = movie.clone();
+    assert!(movie.repair(&RepairOptions::default()).is_empty());
+    assert_eq!(movie, original);
+}
+
+#[test]
+fn test_repair() {
+    let mut movie = sample_movie(60, 60);
+    movie.config.general.frame_count = 50;
+    movie.config.general.length = TimeSpec::new(0, 1_000_000_000);
+    movie.annotations = "route notes".to_owned();
+    movie.inputs.0[3].keyboard = Some(KeyboardInput(vec![0x7a, 0xff53, 0x7a, 0x7a]));
+
+    let repairs = movie.repair(&RepairOptions::default());
+    assert_eq!(
+        repairs,
+        [
+            Repair::DuplicateKeys {
+                frame: 3,
+                keys: vec![0x7a, 0x7a]
+            },
+            Repair::FrameCount { old: 50, new: 60 },
+            Repair::NsecOverflow {
+                nsec: 1_000_000_000
+            },
+            Repair::TrailingNewline,
+        ]
+    );
+    assert_eq!(movie, {
+        let mut expected = sample_movie(60, 60);
+        expected.annotations = "route notes\n".to_owned();
+        expected
+    });
+    assert!(movie.repair(&RepairOptions::default()).is_empty());
+}
+
+#[test]
+fn test_repair_length() {
+    let mut movie = sample_movie(30, 60);
+    movie.config.general.length.nsec = 2_500_000_000;
+    let repairs = movie.repair(&RepairOptions::default());
+    assert_eq!(
+        repairs,
+        [
+            Repair::NsecOverflow {
+                nsec: 2_500_000_000
+            },
+            Repair::Length {
+                old: Duration::from_millis(2500),
+                new: Duration::from_millis(500),
+            },
+        ]
+    );
+    assert_eq!(movie.config.general.length, TimeSpec::new(0, 500_000_000));
+}
+
+#[test]
+fn test_repair_options() {
+    let mut movie = sample_movie(8, 60);
+    movie.config.general.frame_count = 1;
+    movie.annotations = "notes".to_owned();
+    let options = RepairOptions {
+        frame_count: false,
+        ..RepairOptions::default()
+    };
+    assert_eq!(movie.repair(&options), [Repair::TrailingNewline]);
+    assert_eq!(movie.config.general.frame_count, 1);
+
+    let options = RepairOptions {
+        frame_count: false,
+        length: false,
+        trailing_newline: false,
+        duplicate_keys: false,
+    };
+    movie.annotations = "notes".to_owned();
+    assert!(movie.repair(&options).is_empty());
+}
@@ -0,0 +1,80 @@
+use core::time::Duration;
+
+use libtas_movie::{
+    inputs::Input,
+    replay::{InputSink, Player},
+    testkit::sample_movie,
+    time::Framerate,
+};
+
+#[test]
+fn test_advance() {
+    let movie = sample_movie(4, 60);
+    let mut player = Player::new(&movie);
+    let mut fed: Vec<(usize, Input)> = vec![];
+
+    assert_eq!(player.advance(Duration::ZERO, &mut fed), 1);
+    assert_eq!(player.advance(Duration::from_millis(16), &mut fed), 0);
+    assert_eq!(player.advance(Duration::from_millis(1), &mut fed), 1);
+    assert_eq!(player.position(), 2);
+
+    player.pause();
+    assert_eq!(player.advance(Duration::from_secs(1), &mut fed), 0);
+    player.resume();
+    assert_eq!(player.advance(Duration::from_secs(1), &mut fed), 2);
+    assert!(player.is_finished());
+    assert_eq!(
+        fed.iter().map(|(frame, _)| *frame).collect::<Vec<_>>(),
+        [0, 1, 2, 3]
+    );
+    assert_eq!(fed[1].1, movie.inputs.0[1]);
+}
+
+#[test]
+fn test_variable_framerate() {
+    let mut movie = sample_movie(4, 60);
+    movie.inputs.0[1].framerate = Framerate::new(10, 1);
+
+    let mut player = Player::new(&movie);
+    let mut fed = vec![];
+    player.advance(Duration::from_millis(20), &mut fed);
+    // frame 1 lasts 100ms at 10 fps
+    assert_eq!(player.advance(Duration::from_millis(90), &mut fed), 0);
+    assert_eq!(player.advance(Duration::from_millis(10), &mut fed), 1);
+    assert_eq!(player.position(), 3);
+}
+
+#[test]
+fn test_seek_and_step() {
+    struct Count(usize);
+    impl InputSink for Count {
+        fn frame(&mut self, _idx: usize, _input: &Input) {
+            self.0 += 1;
+        }
+    }
+
+    let movie = sample_movie(4, 60);
+    let mut player = Player::new(&movie);
+    let mut count = Count(0);
+
+    player.seek(3);
+    assert_eq!(player.time(), Duration::from_nanos(50_000_000));
+    player.pause();
+    assert!(player.step(&mut count));
+    assert!(!player.step(&mut count));
+    assert_eq!(count.0, 1);
+
+    player.seek(10);
+    assert!(player.is_finished());
+}
+
+#[test]
+fn test_run() {
+    let movie = sample_movie(5, 1000);
+    let mut player = Player::new(&movie);
+    player.seek(1);
+    let mut fed: Vec<(usize, Input)> = vec![];
+    player.run(&mut fed);
+    assert_eq!(fed.len(), 4);
+    assert!(player.is_finished());
+}
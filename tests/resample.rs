@@ -0,0 +1,183 @@
+use core::time::Duration;
+
+use libtas_movie::{
+    editor::Marker,
+    inputs::{Input, KeyboardInput, MouseInput, ReferenceMode},
+    movie::LibTASMovie,
+    resample::ResampleStrategy,
+    testkit::{movie_with_mouse_path, sample_movie},
+    time::Framerate,
+};
+
+fn keys(input: &Input) -> Vec<u32> {
+    input
+        .keyboard
+        .as_ref()
+        .map(|keyboard| keyboard.0.clone())
+        .unwrap_or_default()
+}
+
+#[test]
+fn test_upsample() {
+    let original = sample_movie(4, 30);
+
+    let mut movie = original.clone();
+    movie.resample(
+        Framerate::from_fps(60).unwrap(),
+        ResampleStrategy::Duplicate,
+    );
+    assert_eq!(movie.inputs.0.len(), 8);
+    for (frame, input) in movie.inputs.0.iter().enumerate() {
+        assert_eq!(*input, original.inputs.0[frame / 2]);
+    }
+    let general = &movie.config.general;
+    assert_eq!((general.framerate_num, general.frame_count), (60, 8));
+    assert_eq!(general.savestate_frame_count, 8);
+    assert_eq!(movie.duration(), original.duration());
+
+    let mut movie = original.clone();
+    movie.resample(Framerate::from_fps(60).unwrap(), ResampleStrategy::Spread);
+    assert_eq!(keys(&movie.inputs.0[2]), [0x7a]);
+    assert_eq!(movie.inputs.0[3], Input::default());
+}
+
+#[test]
+fn test_downsample() {
+    let mut movie = sample_movie(5, 60);
+    movie.editor.markers.push(Marker {
+        frame: 3,
+        text: "jump".to_owned(),
+    });
+    movie.set_comment(2, "a");
+    movie.set_comment(3, "b");
+    movie.resample(
+        Framerate::from_fps(30).unwrap(),
+        ResampleStrategy::default(),
+    );
+
+    assert_eq!(movie.inputs.0.len(), 3);
+    assert_eq!(movie.inputs.0[0].keyboard, Some(KeyboardInput(vec![0x7a])));
+    assert_eq!(keys(&movie.inputs.0[1]), [0xff53, 0x7a]);
+    assert_eq!(movie.inputs.0[2].keyboard, None);
+    assert_eq!(movie.editor.markers[0].frame, 1);
+    assert_eq!(movie.comment(1), Some("a\nb"));
+    assert_eq!(movie.duration(), Duration::from_millis(100));
+}
+
+#[test]
+fn test_resample_mouse_and_vfr() {
+    let mut movie = movie_with_mouse_path(&[(0, 0), (10, 0), (20, 0), (30, 0)], 60);
+    movie.inputs.0[0].framerate = Framerate::new(30, 1);
+    // 1/30 + 3/60 = 83.3ms
+    movie.resample(Framerate::from_fps(30).unwrap(), ResampleStrategy::Spread);
+    assert_eq!(movie.inputs.0.len(), 3);
+    assert!(movie.inputs.0.iter().all(|input| input.framerate.is_none()));
+    let positions: Vec<_> = movie
+        .inputs
+        .0
+        .iter()
+        .map(|input| input.mouse.unwrap().xpos)
+        .collect();
+    assert_eq!(positions, [0, 20, 30]);
+    assert!(movie.inputs.0[2].mouse.unwrap().left_click);
+}
+
+#[test]
+fn test_resample_relative_mouse() {
+    let mouse = |xpos, ypos, reference_mode| MouseInput {
+        xpos,
+        ypos,
+        reference_mode,
+        ..MouseInput::default()
+    };
+    let movie_with_mice = |mice: &[MouseInput], fps| {
+        let mut movie = sample_movie(mice.len(), fps);
+        for (input, &mouse) in movie.inputs.0.iter_mut().zip(mice) {
+            input.mouse = Some(mouse);
+        }
+        movie
+    };
+    let positions = |movie: &LibTASMovie| {
+        movie
+            .inputs
+            .0
+            .iter()
+            .map(|input| {
+                let mouse = input.mouse.unwrap();
+                (mouse.xpos, mouse.ypos, mouse.reference_mode)
+            })
+            .collect::<Vec<_>>()
+    };
+    use ReferenceMode::{Absolute, Relative};
+
+    // duplicated frames do not move the pointer again
+    let mut movie = movie_with_mice(&[mouse(5, -3, Relative), mouse(10, 20, Absolute)], 30);
+    movie.resample(
+        Framerate::from_fps(60).unwrap(),
+        ResampleStrategy::Duplicate,
+    );
+    assert_eq!(
+        positions(&movie),
+        [
+            (5, -3, Relative),
+            (0, 0, Relative),
+            (10, 20, Absolute),
+            (10, 20, Absolute)
+        ]
+    );
+
+    // relative motions move the last absolute position, and absolute positions replace motions
+    let mut movie = movie_with_mice(
+        &[
+            mouse(10, 20, Absolute),
+            mouse(3, -2, Relative),
+            mouse(3, -2, Relative),
+            mouse(7, 7, Absolute),
+            mouse(i32::MAX, 0, Absolute),
+            mouse(1, 0, Relative),
+        ],
+        60,
+    );
+    movie.resample(
+        Framerate::from_fps(30).unwrap(),
+        ResampleStrategy::Duplicate,
+    );
+    assert_eq!(
+        positions(&movie),
+        [
+            (13, 18, Absolute),
+            (7, 7, Absolute),
+            (i32::MAX, 0, Absolute)
+        ]
+    );
+}
+
+#[test]
+fn test_resample_other_sections() {
+    let lines = ["|C1a|FR|", "|C1b|C2c|F1|X9|", "|C1d|X9|", "|"];
+    let mut movie = sample_movie(lines.len(), 60);
+    for (input, line) in movie.inputs.0.iter_mut().zip(lines) {
+        *input = line.parse().unwrap();
+    }
+    let original = movie.clone();
+
+    // merged frames keep the last controller states, all flags, and the other sections once
+    movie.resample(
+        Framerate::from_fps(30).unwrap(),
+        ResampleStrategy::Duplicate,
+    );
+    let frames: Vec<_> = movie.inputs.0.iter().map(Input::to_string).collect();
+    assert_eq!(frames, ["|C1b|C2c|FR1|X9|", "|C1d|X9|"]);
+
+    // held frames do not repeat the flags
+    let mut movie = original;
+    movie.resample(
+        Framerate::from_fps(120).unwrap(),
+        ResampleStrategy::Duplicate,
+    );
+    let frames: Vec<_> = movie.inputs.0.iter().map(Input::to_string).collect();
+    assert_eq!(
+        frames[..4],
+        ["|C1a|FR|", "|C1a|", "|C1b|C2c|F1|X9|", "|C1b|C2c|X9|"]
+    );
+}
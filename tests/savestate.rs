@@ -0,0 +1,45 @@
+use libtas_movie::{
+    editor::Marker, movie::load_movie_from_reader, testkit::sample_movie, time::TimeSpec,
+};
+
+#[test]
+fn test_savestate_anchored() {
+    let mut movie = sample_movie(120, 60);
+    movie.editor.markers = vec![
+        Marker {
+            frame: 10,
+            text: "before".to_owned(),
+        },
+        Marker {
+            frame: 70,
+            text: "after".to_owned(),
+        },
+    ];
+    assert!(!movie.is_savestate_anchored());
+
+    let segment = movie.to_savestate_anchored(60..120).unwrap();
+    assert_eq!(segment.anchor_frame(), Some(60));
+    assert_eq!(segment.inputs.0, movie.inputs.0[60..]);
+    let general = &segment.config.general;
+    assert_eq!(general.frame_count, 60);
+    assert_eq!(general.savestate_frame_count, 60);
+    assert_eq!(general.length, TimeSpec::new(1, 0));
+    assert_eq!(segment.editor.markers.len(), 1);
+    assert_eq!(segment.editor.markers[0].frame, 10);
+
+    // the anchor is not stored in the movie file
+    let data = segment.compress().unwrap();
+    let reloaded = load_movie_from_reader(data.as_slice()).unwrap();
+    assert!(!reloaded.is_savestate_anchored());
+    assert_eq!(reloaded.inputs, segment.inputs);
+
+    // nested segments are anchored to the power-on movie
+    let nested = segment.to_savestate_anchored(30..60).unwrap();
+    assert_eq!(nested.anchor_frame(), Some(90));
+    assert!(segment.to_savestate_anchored(30..61).is_none());
+
+    let power_on = segment.to_power_on(&movie).unwrap();
+    assert_eq!(power_on, movie);
+    assert!(movie.to_power_on(&movie).is_none());
+    assert!(segment.to_power_on(&sample_movie(10, 60)).is_none());
+}
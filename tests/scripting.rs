@@ -0,0 +1,102 @@
+#![cfg(feature = "scripting")]
+
+use libtas_movie::{
+    inputs::{KeyboardInput, MouseInput},
+    scripting::run_script,
+    testkit::{movie_with_mouse_path, sample_movie},
+};
+
+#[test]
+fn test_script_keys() {
+    let mut movie = sample_movie(8, 60);
+    let script = r#"
+        for frame in 0..movie.inputs.len() {
+            if movie.inputs.is_pressed(frame, "z") {
+                movie.inputs.set_key(frame, "z", false);
+                movie.inputs.set_key(frame, keysym("x"), true);
+            }
+        }
+        movie.inputs.set_key(0, 0x20, true);
+        movie.inputs.clear(3);
+    "#;
+    run_script(&mut movie, script).unwrap();
+
+    let keys = |frame: usize| movie.inputs.0[frame].keyboard.clone();
+    assert_eq!(keys(0), Some(KeyboardInput(vec![0x20])));
+    assert_eq!(keys(1), Some(KeyboardInput(vec![0x78])));
+    assert_eq!(keys(2), Some(KeyboardInput(vec![0xff53])));
+    assert_eq!(keys(3), None);
+    assert_eq!(keys(7), Some(KeyboardInput(vec![0xff53, 0x78])));
+}
+
+#[test]
+fn test_script_frames() {
+    let mut movie = sample_movie(8, 60);
+    let script = r#"
+        movie.inputs.remove(0, 2);
+        movie.inputs.insert(movie.inputs.len(), 3);
+        movie.game_name = "game";
+        movie.rerecord_count += 1;
+        if movie.frame_count != movie.inputs.len() {
+            throw "frame count out of sync";
+        }
+    "#;
+    run_script(&mut movie, script).unwrap();
+
+    assert_eq!(movie.inputs.0.len(), 9);
+    assert_eq!(movie.config.general.frame_count, 9);
+    assert_eq!(movie.config.general.game_name, "game");
+    assert_eq!(movie.config.general.rerecord_count, 1);
+    assert_eq!(
+        movie.inputs.0[0].keyboard,
+        Some(KeyboardInput(vec![0xff53]))
+    );
+    assert!(movie.inputs.0[8].keyboard.is_none());
+}
+
+#[test]
+fn test_script_mouse() {
+    let mut movie = movie_with_mouse_path(&[(1, 2), (3, 4)], 60);
+    let script = r#"
+        let x = movie.inputs.mouse_x(1);
+        movie.inputs.set_mouse(0, x * 10, movie.inputs.mouse_y(0) + 1);
+        movie.inputs.clear(1);
+    "#;
+    run_script(&mut movie, script).unwrap();
+
+    let mouse = |frame: usize| movie.inputs.0[frame].mouse.unwrap();
+    assert_eq!((mouse(0).xpos, mouse(0).ypos), (30, 3));
+    assert!(!mouse(1).left_click);
+    assert_eq!((mouse(1).xpos, mouse(1).ypos), (3, 4));
+
+    let mut movie = sample_movie(1, 60);
+    run_script(&mut movie, "movie.inputs.set_mouse(0, -5, 7);").unwrap();
+    assert_eq!(
+        movie.inputs.0[0].mouse,
+        Some(MouseInput {
+            xpos: -5,
+            ypos: 7,
+            ..MouseInput::default()
+        })
+    );
+}
+
+#[test]
+fn test_script_errors() {
+    let mut movie = sample_movie(4, 60);
+    for script in [
+        "movie.inputs.set_key(4, \"z\", true);",
+        "movie.inputs.set_key(0, \"NoSuchKey\", true);",
+        "movie.inputs.remove(2, 5);",
+        "movie.inputs.keys(-1);",
+        "let x = ;",
+    ] {
+        assert!(run_script(&mut movie, script).is_err(), "{script}");
+    }
+
+    // edits before an error are kept
+    let result = run_script(&mut movie, "movie.inputs.clear(1); movie.inputs.clear(9);");
+    assert!(result.is_err());
+    assert!(movie.inputs.0[1].keyboard.is_none());
+    assert_eq!(movie.inputs.0.len(), 4);
+}
@@ -0,0 +1,76 @@
+use libtas_movie::{
+    inputs::{Input, KeyboardInput, MouseInput},
+    search::MatchOptions,
+    testkit::{movie_with_mouse_path, sample_movie},
+};
+
+fn keys(keys: &[u32]) -> Input {
+    Input {
+        keyboard: Some(KeyboardInput(keys.to_vec())),
+        ..Input::default()
+    }
+}
+
+#[test]
+fn test_find_sequence() {
+    // frames cycle through nothing, z, Right, and z with Right
+    let inputs = sample_movie(12, 60).inputs;
+    let exact = MatchOptions::default();
+    assert_eq!(
+        inputs.find_sequence(&[keys(&[0xff53, 0x7a])], exact),
+        [3, 7, 11]
+    );
+    assert_eq!(inputs.find_sequence(&[Input::default()], exact), [0, 4, 8]);
+    assert_eq!(inputs.find_sequence(&[keys(&[0x7a])], exact), [1, 5, 9]);
+    assert!(inputs.find_sequence(&[], exact).is_empty());
+    assert!(
+        inputs
+            .find_sequence(&[inputs.0.clone(), inputs.0.clone()].concat(), exact)
+            .is_empty()
+    );
+
+    let extra = MatchOptions {
+        ignore_extra_keys: true,
+        ..MatchOptions::default()
+    };
+    assert_eq!(
+        inputs.find_sequence(&[keys(&[0x7a])], extra),
+        [1, 3, 5, 7, 9, 11]
+    );
+    assert_eq!(
+        inputs.find_sequence(&[keys(&[0x7a]), keys(&[0x7a])], extra),
+        []
+    );
+    assert_eq!(
+        inputs.find_sequence(&[keys(&[]), keys(&[])], extra),
+        (0..11).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_find_sequence_mouse() {
+    let mut inputs = movie_with_mouse_path(&[(0, 0), (5, 5), (0, 0), (5, 5)], 60).inputs;
+    inputs.0[1].keyboard = Some(KeyboardInput(vec![0x20]));
+    inputs.0[3].keyboard = Some(KeyboardInput(vec![0x20]));
+    let pattern = [keys(&[0x20])];
+    assert!(
+        inputs
+            .find_sequence(&pattern, MatchOptions::default())
+            .is_empty()
+    );
+    let ignore_mouse = MatchOptions {
+        ignore_mouse: true,
+        ..MatchOptions::default()
+    };
+    assert_eq!(inputs.find_sequence(&pattern, ignore_mouse), [1, 3]);
+
+    let pattern = [Input {
+        mouse: Some(MouseInput {
+            xpos: 5,
+            ypos: 5,
+            ..MouseInput::default()
+        }),
+        ..keys(&[0x20])
+    }];
+    assert_eq!(inputs.find_sequence(&pattern, MatchOptions::default()), [1]);
+}
@@ -0,0 +1,100 @@
+use libtas_movie::{
+    movie::{EntryError, LoadError, LoadOptions, load_movie_from_reader},
+    section::{InvalidSectionError, MovieSection, SectionRegistry},
+    testkit::{archive, sample_movie},
+};
+
+/// A log of RNG seeds, one per line.
+#[derive(Clone, Debug, PartialEq)]
+struct RngLog(Vec<u64>);
+
+impl MovieSection for RngLog {
+    fn name() -> &'static str {
+        "rng.log"
+    }
+
+    fn parse(data: &[u8]) -> Result<Self, InvalidSectionError> {
+        let string =
+            core::str::from_utf8(data).map_err(|err| InvalidSectionError(err.to_string()))?;
+        string
+            .lines()
+            .map(|line| {
+                line.parse()
+                    .map_err(|_| InvalidSectionError(line.to_owned()))
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+
+    fn serialize(&self) -> Vec<u8> {
+        self.0
+            .iter()
+            .map(|seed| format!("{seed}\n"))
+            .collect::<String>()
+            .into_bytes()
+    }
+}
+
+fn options() -> LoadOptions {
+    let mut sections = SectionRegistry::new();
+    sections.register::<RngLog>();
+    LoadOptions {
+        sections,
+        ..LoadOptions::default()
+    }
+}
+
+#[test]
+fn test_section_round_trip() {
+    let mut movie = sample_movie(10, 60);
+    assert!(movie.sections.insert(RngLog(vec![1, 2, 3])).is_none());
+    let data = movie.compress().unwrap();
+
+    let reloaded = options().load_from_reader(data.as_slice()).unwrap();
+    assert_eq!(
+        reloaded.sections.get::<RngLog>(),
+        Some(&RngLog(vec![1, 2, 3]))
+    );
+    assert_eq!(reloaded, movie);
+
+    // unregistered sections are still rejected
+    match load_movie_from_reader(data.as_slice()) {
+        Err(LoadError::UnexpectedEntry { path }) => assert_eq!(path.as_os_str(), "rng.log"),
+        result => panic!("loaded as {result:?}"),
+    }
+}
+
+#[test]
+fn test_section_edit() {
+    let mut movie = sample_movie(10, 60);
+    movie.sections.insert(RngLog(vec![1]));
+    movie.sections.get_mut::<RngLog>().unwrap().0.push(2);
+    assert_eq!(
+        movie.sections.insert(RngLog(vec![3])),
+        Some(RngLog(vec![1, 2]))
+    );
+    assert_eq!(movie.sections.remove::<RngLog>(), Some(RngLog(vec![3])));
+    assert!(movie.sections.is_empty());
+}
+
+#[test]
+fn test_invalid_section() {
+    let config = sample_movie(10, 60).config.to_string();
+    let bad = archive(&[
+        ("config.ini", config.as_bytes()),
+        ("inputs", b"|\n"),
+        ("annotations.txt", b""),
+        ("editor.ini", b""),
+        ("rng.log", b"seed\n"),
+    ]);
+    match options().load_from_reader(bad.as_slice()) {
+        Err(LoadError::InvalidEntry {
+            name,
+            source: EntryError::Section(InvalidSectionError(line)),
+        }) => {
+            assert_eq!(name, "rng.log");
+            assert_eq!(line, "seed");
+        }
+        result => panic!("loaded as {result:?}"),
+    }
+}
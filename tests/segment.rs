@@ -0,0 +1,72 @@
+use libtas_movie::{
+    editor::{Editor, Marker},
+    segment::{Segment, Segments},
+    testkit::sample_movie,
+};
+
+fn marker(frame: u64, text: &str) -> Marker {
+    Marker {
+        frame,
+        text: text.to_owned(),
+    }
+}
+
+#[test]
+fn test_from_markers() {
+    let inputs = sample_movie(100, 60).inputs;
+    let editor = Editor {
+        markers: vec![
+            marker(60, "Boss"),
+            marker(10, "Level 1"),
+            marker(60, "Duplicate"),
+            marker(200, "Past the end"),
+        ],
+        ..Editor::default()
+    };
+    let segments = Segments::from_markers(&editor, &inputs);
+    let segment = |frames, label: &str| Segment {
+        frames,
+        label: label.to_owned(),
+    };
+    assert_eq!(
+        segments.0,
+        [
+            segment(0..10, ""),
+            segment(10..60, "Level 1"),
+            segment(60..100, "Boss"),
+        ]
+    );
+    assert_eq!(segments.len(), 3);
+    assert_eq!(segments.segment_at(0).unwrap().label, "");
+    assert_eq!(segments.segment_at(59).unwrap().label, "Level 1");
+    assert_eq!(segments.segment_at(60).unwrap().label, "Boss");
+    assert_eq!(segments.segment_at(100), None);
+    assert_eq!(segments.segment("Boss").unwrap().frames, 60..100);
+    assert_eq!(
+        (&segments)
+            .into_iter()
+            .map(|segment| segment.frames.len())
+            .sum::<usize>(),
+        100
+    );
+
+    let editor = Editor {
+        markers: vec![marker(0, "Start")],
+        ..Editor::default()
+    };
+    let segments = Segments::from_markers(&editor, &inputs);
+    assert_eq!(segments.0, [segment(0..100, "Start")]);
+    assert!(Segments::from_markers(&editor, &sample_movie(0, 60).inputs).is_empty());
+    assert_eq!(
+        Segments::from_markers(&Editor::default(), &inputs).0,
+        [segment(0..100, "")]
+    );
+}
+
+#[test]
+fn test_windows() {
+    let segments = Segments::windows(10, 4);
+    assert_eq!(segments.segment_at(9).unwrap().frames, 8..10);
+    assert!(Segments::windows(0, 4).is_empty());
+    assert_eq!(Segments::windows(2, 0).len(), 2);
+}
@@ -0,0 +1,213 @@
+use core::time::Duration;
+
+use libtas_movie::{
+    editor::Marker,
+    inputs::{Input, Inputs},
+    splice::{AppendOptions, SpliceTiming},
+    testkit::sample_movie,
+    time::Framerate,
+};
+
+fn marked_movie() -> libtas_movie::movie::LibTASMovie {
+    let mut movie = sample_movie(6, 60);
+    movie.editor.markers.push(Marker {
+        frame: 5,
+        text: "end".to_owned(),
+    });
+    movie.editor.markers.push(Marker {
+        frame: 3,
+        text: "deleted".to_owned(),
+    });
+    movie
+}
+
+#[test]
+fn test_splice_shift() {
+    let mut movie = marked_movie();
+    let removed = movie.splice_inputs(2..4, vec![]).unwrap();
+    assert_eq!(removed.len(), 2);
+    assert_eq!(movie.editor.markers[0].frame, 3);
+    assert_eq!(movie.editor.markers[1].frame, 2);
+    assert_eq!(movie.duration(), Duration::from_nanos(66_666_667));
+    assert!(!movie.config.general.variable_framerate);
+}
+
+#[test]
+fn test_splice_preserve_delete() {
+    let mut movie = marked_movie();
+    let end = movie.timeline().time_of_frame(5);
+    movie
+        .splice_inputs_with(2..4, vec![], SpliceTiming::Preserve)
+        .unwrap();
+    assert_eq!(movie.inputs.0[1].framerate, Framerate::new(20, 1));
+    assert_eq!(movie.editor.markers[0].frame, 3);
+    assert_eq!(movie.timeline().time_of_frame(3), end);
+    // segment boundaries are rounded up to nanoseconds
+    assert!(movie.duration().abs_diff(Duration::from_millis(100)) < Duration::from_micros(1));
+    assert!(movie.config.general.variable_framerate);
+}
+
+#[test]
+fn test_splice_preserve_replace_and_insert() {
+    let mut movie = sample_movie(6, 60);
+    movie
+        .splice_inputs_with(0..3, vec![Input::default(); 2], SpliceTiming::Preserve)
+        .unwrap();
+    assert_eq!(movie.inputs.0.len(), 5);
+    assert_eq!(movie.inputs.0[0].framerate, Framerate::new(40, 1));
+    assert_eq!(movie.inputs.0[2].framerate, None);
+    assert_eq!(movie.duration(), Duration::from_millis(100));
+
+    let mut movie = sample_movie(6, 60);
+    movie
+        .splice_inputs_with(0..0, vec![Input::default(); 2], SpliceTiming::Preserve)
+        .unwrap();
+    assert_eq!(movie.inputs.0.len(), 8);
+    assert_eq!(movie.inputs.0[0].framerate, Framerate::new(180, 1));
+    assert_eq!(movie.inputs.0[2].framerate, Framerate::new(180, 1));
+    assert_eq!(movie.inputs.0[3].framerate, None);
+    assert_eq!(
+        movie.timeline().time_of_frame(3),
+        Duration::from_nanos(16_666_667)
+    );
+
+    // the duration of frames at large coprime framerates overflows, and is not preserved
+    let mut movie = sample_movie(5, 60);
+    movie.inputs.0[1].framerate = Framerate::new(18_446_744_073_709_551_557, 1);
+    movie.inputs.0[2].framerate = Framerate::new(18_446_744_073_709_551_533, 1);
+    movie.inputs.0[3].framerate = Framerate::new(18_446_744_073_709_551_521, 1);
+    movie
+        .splice_inputs_with(1..4, vec![Input::default()], SpliceTiming::Preserve)
+        .unwrap();
+    assert_eq!(movie.inputs.0.len(), 3);
+    assert_eq!(movie.inputs.0[1].framerate, None);
+
+    // deleting every frame cannot preserve the time
+    let mut movie = sample_movie(2, 60);
+    movie
+        .splice_inputs_with(0..2, vec![], SpliceTiming::Preserve)
+        .unwrap();
+    assert!(movie.inputs.0.is_empty());
+}
+
+#[test]
+fn test_append_from() {
+    let mut inputs: Inputs = "|K7a|\n# seam\n|\n".parse().unwrap();
+    let other: Inputs = "|T60:1|\n# other\n|K20|\n".parse().unwrap();
+    let options = AppendOptions {
+        framerate: Framerate::from_fps(60).unwrap(),
+        other_framerate: Framerate::from_fps(60).unwrap(),
+        restart: false,
+    };
+    let mut same = inputs.clone();
+    assert!(!same.append_from(&other, options));
+    assert_eq!(
+        same.to_string(),
+        "|K7a|\n# seam\n|\n|T60:1|\n# other\n|K20|\n"
+    );
+
+    // frames of `other` keep their duration at another framerate
+    let options = AppendOptions {
+        other_framerate: Framerate::from_fps(30).unwrap(),
+        ..options
+    };
+    assert!(inputs.append_from(&other, options));
+    assert_eq!(
+        inputs.to_string(),
+        "|K7a|\n# seam\n|\n|T60:1|\n# other\n|K20|T30:1|\n"
+    );
+}
+
+#[test]
+fn test_append_from_restart() {
+    let inputs: Inputs = "|K7a|\n".parse().unwrap();
+    let other: Inputs = "|K20|F1|\n|FR|\n".parse().unwrap();
+    let options = AppendOptions {
+        restart: true,
+        ..AppendOptions::default()
+    };
+    let mut restarted = inputs.clone();
+    restarted.append_from(&other, options);
+    assert_eq!(restarted.to_string(), "|K7a|\n|K20|F1R|\n|FR|\n");
+    assert!(restarted.0[1].restarts());
+
+    // without a restart, the flags of `other` are kept as they are
+    let mut appended = inputs.clone();
+    appended.append_from(&other, AppendOptions::default());
+    assert_eq!(appended.to_string(), "|K7a|\n|K20|F1|\n|FR|\n");
+
+    restarted.0[1].set_restart(false);
+    restarted.0[2].set_restart(false);
+    assert_eq!(restarted.to_string(), "|K7a|\n|K20|F1|\n|\n");
+}
+
+#[test]
+fn test_append() {
+    let mut movie = sample_movie(4, 60);
+    movie.config.general.rerecord_count = 3;
+    let mut other = sample_movie(3, 30);
+    other.config.general.rerecord_count = 4;
+    other.editor.markers.push(Marker {
+        frame: 1,
+        text: "other".to_owned(),
+    });
+    other.set_comment(2, "last");
+
+    movie.append(&other);
+    assert_eq!(movie.frame_count(), 7);
+    assert!(movie.is_length_consistent());
+    assert_eq!(movie.config.general.savestate_frame_count, 7);
+    assert_eq!(movie.config.general.rerecord_count, 7);
+    assert!(movie.config.general.variable_framerate);
+    assert_eq!(movie.inputs.0[4].framerate, Framerate::new(30, 1));
+    assert_eq!(
+        movie.duration(),
+        Duration::from_millis(4000 / 60 + 100) + Duration::from_nanos(666_667)
+    );
+    assert_eq!(movie.editor.marker_at(5).unwrap().text, "other");
+    assert_eq!(movie.comment(6), Some("last"));
+}
+
+#[test]
+fn test_extract() {
+    let mut movie = sample_movie(8, 60);
+    movie.config.general.rerecord_count = 12;
+    movie.inputs.insert_line(3, "# before 3".to_owned());
+    movie.inputs.insert_line(7, "# before 7".to_owned());
+    for (frame, text) in [(1, "outside"), (3, "start"), (5, "inside")] {
+        movie.editor.markers.push(Marker {
+            frame,
+            text: text.to_owned(),
+        });
+    }
+    movie.set_comment(4, "kept");
+    movie.set_comment(6, "dropped");
+
+    let segment = movie.extract(3..6).unwrap();
+    assert_eq!(segment.inputs.0, movie.inputs.0[3..6]);
+    assert_eq!(segment.inputs.other_lines(), [(0, "# before 3".to_owned())]);
+    assert_eq!(segment.frame_count(), 3);
+    assert!(segment.is_length_consistent());
+    assert_eq!(segment.duration(), Duration::from_millis(50));
+    assert_eq!(segment.config.general.savestate_frame_count, 3);
+    assert_eq!(segment.config.general.rerecord_count, 0);
+    assert_eq!(
+        segment.editor.markers,
+        [
+            Marker {
+                frame: 0,
+                text: "start".to_owned()
+            },
+            Marker {
+                frame: 2,
+                text: "inside".to_owned()
+            },
+        ]
+    );
+    assert_eq!(segment.comment(1), Some("kept"));
+    assert_eq!(segment.comment(3), None);
+
+    assert_eq!(movie.extract(0..8).unwrap().inputs, movie.inputs);
+    assert!(movie.extract(5..9).is_none());
+    assert_eq!(movie.extract(2..2).unwrap().frame_count(), 0);
+}
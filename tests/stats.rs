@@ -0,0 +1,114 @@
+use core::time::Duration;
+
+use libtas_movie::{
+    inputs::{MouseInput, ReferenceMode},
+    segment::{Segment, Segments},
+    stats::{HeatmapCell, MIN_APM_WINDOW, apm_series, key_histogram_by_segment, mouse_heatmap},
+    testkit::{movie_with_mouse_path, sample_movie},
+};
+
+#[test]
+fn test_mouse_heatmap() {
+    let mut movie = movie_with_mouse_path(&[(5, 5), (15, 5), (12, 8), (12, 8), (-3, 30)], 60);
+    movie.inputs.0[2].mouse.as_mut().unwrap().left_click = true;
+    movie.inputs.0[3].mouse.as_mut().unwrap().left_click = true;
+    movie.inputs.0[3].mouse.as_mut().unwrap().right_click = true;
+    movie.inputs.0.push(movie.inputs.0[0].clone());
+    movie.inputs.0[5].mouse = Some(MouseInput {
+        xpos: 1,
+        ypos: 1,
+        reference_mode: ReferenceMode::Relative,
+        ..MouseInput::default()
+    });
+
+    let heatmap = mouse_heatmap(&movie.inputs, 10);
+    let cell = |x, y, hovers, clicks| HeatmapCell {
+        x,
+        y,
+        hovers,
+        clicks,
+    };
+    assert_eq!(
+        heatmap.cells,
+        [cell(0, 0, 1, 0), cell(10, 0, 3, 2), cell(-10, 30, 1, 0)]
+    );
+    assert_eq!(
+        heatmap.to_csv(),
+        "x,y,hovers,clicks\n0,0,1,0\n10,0,3,2\n-10,30,1,0\n"
+    );
+    assert!(
+        heatmap
+            .to_json()
+            .starts_with("{\"bucket_size\": 10, \"cells\": [\n  {\"x\": 0, \"y\": 0, ")
+    );
+
+    assert_eq!(mouse_heatmap(&movie.inputs, 0).bucket_size, 1);
+    assert!(
+        mouse_heatmap(&sample_movie(10, 60).inputs, 10)
+            .cells
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_key_histogram_by_segment() {
+    // frames cycle through nothing, z, Right, and z with Right
+    let inputs = sample_movie(10, 60).inputs;
+    let segments = Segments::windows(10, 4);
+    assert_eq!(
+        segments
+            .0
+            .iter()
+            .map(|s| s.label.as_str())
+            .collect::<Vec<_>>(),
+        ["0-3", "4-7", "8-9"]
+    );
+    let histograms = key_histogram_by_segment(&inputs, &segments);
+    assert_eq!(histograms.len(), 3);
+    assert_eq!(histograms[0].frames, 4);
+    assert_eq!(
+        histograms[0].presses.iter().collect::<Vec<_>>(),
+        [(&0x7a, &2), (&0xff53, &1)]
+    );
+    assert_eq!(histograms[0].total(), 3);
+    assert_eq!(histograms[2].frames, 2);
+    assert_eq!(histograms[2].total(), 1);
+
+    // a key held since before the segment is not a press
+    let segments = Segments(vec![Segment {
+        frames: 3..20,
+        label: "end".to_owned(),
+    }]);
+    let histograms = key_histogram_by_segment(&inputs, &segments);
+    assert_eq!(histograms[0].frames, 7);
+    assert_eq!(
+        histograms[0].presses.iter().collect::<Vec<_>>(),
+        [(&0x7a, &4), (&0xff53, &1)]
+    );
+}
+
+#[test]
+fn test_apm_series() {
+    // 2 presses of z and 1 of Right every 4 frames, at 60 fps
+    let movie = sample_movie(150, 60);
+    let series = apm_series(&movie.inputs, Duration::from_secs(1), &movie.timeline());
+    assert_eq!(series.len(), 3);
+    assert_eq!(series[0].start, Duration::ZERO);
+    assert_eq!(series[0].actions, 45);
+    assert_eq!(series[0].apm, 2700.0);
+    assert_eq!(series[2].start, Duration::from_secs(2));
+    assert_eq!(series[2].actions, 22);
+
+    let clicks = movie_with_mouse_path(&[(0, 0); 30], 30);
+    let series = apm_series(&clicks.inputs, Duration::from_secs(2), &clicks.timeline());
+    assert_eq!(series.len(), 1);
+    assert_eq!(series[0].actions, 1);
+    assert_eq!(series[0].apm, 30.0);
+
+    assert!(apm_series(&movie.inputs, Duration::ZERO, &movie.timeline()).is_empty());
+
+    // a tiny window is lengthened rather than allocating a sample per nanosecond
+    let series = apm_series(&movie.inputs, Duration::from_nanos(1), &movie.timeline());
+    assert_eq!(series.len(), 25);
+    assert_eq!(series[1].start, MIN_APM_WINDOW);
+}
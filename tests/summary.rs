@@ -0,0 +1,21 @@
+use libtas_movie::{movie::load_movie, summary::SummaryFormat};
+
+#[test]
+fn test_submission_summary() {
+    let movie = load_movie("tests/movies/221769_Trapped_5.ltm").unwrap();
+    assert_eq!(
+        movie.submission_summary(SummaryFormat::Markdown),
+        "- **Game:** ruffle
+- **Length:** 22.800s (456 frames @ 20 fps)
+- **Frames:** 456
+- **Rerecords:** 101
+- **Authors:** synabler
+- **libTAS version:** 1.4.7
+- **MD5:** c9b4f1b544725cb0d9d784c35232a52d
+"
+    );
+
+    let bbcode = movie.submission_summary(SummaryFormat::BBCode);
+    assert!(bbcode.starts_with("[list]\n[*][b]Game:[/b] ruffle\n"));
+    assert!(bbcode.ends_with("[*][b]MD5:[/b] c9b4f1b544725cb0d9d784c35232a52d\n[/list]\n"));
+}
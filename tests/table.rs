@@ -0,0 +1,105 @@
+use libtas_movie::{
+    inputs::{Inputs, KeyboardInput, keysym_name},
+    table::{Cell, Column, InputTable, InputTableModel as _, render_text},
+    testkit::{movie_with_mouse_path, sample_movie},
+};
+
+#[test]
+fn test_columns() {
+    let inputs = sample_movie(4, 60).inputs;
+    assert_eq!(
+        Column::for_inputs(&inputs),
+        [Column::Key(0x7a), Column::Key(0xff53)]
+    );
+    let inputs = movie_with_mouse_path(&[(0, 0), (5, 5)], 60).inputs;
+    assert_eq!(
+        Column::for_inputs(&inputs),
+        [Column::MouseX, Column::MouseY, Column::MouseButton(1)]
+    );
+    assert_eq!(Column::Key(0xff53).label(), "Right");
+    assert_eq!(Column::MouseButton(1).label(), "Mouse button 1");
+    assert_eq!(keysym_name(0x20), "space");
+    assert_eq!(keysym_name(0xffbf), "F2");
+    assert_eq!(keysym_name(0x1008ff13), "0x1008ff13");
+}
+
+#[test]
+fn test_cells() {
+    let mut table = InputTable::new(sample_movie(4, 60).inputs);
+    assert_eq!(table.row_count(), 4);
+    assert_eq!(table.cell(1, 0), Some(Cell::Pressed(true)));
+    assert_eq!(table.cell(1, 1), Some(Cell::Pressed(false)));
+    assert_eq!(table.cell(4, 0), None);
+    assert_eq!(table.cell(0, 2), None);
+
+    assert!(table.set_cell(1, 0, Cell::Pressed(false)));
+    assert_eq!(table.inputs().0[1].keyboard, None);
+    assert!(table.set_cell(0, 1, Cell::Pressed(true)));
+    assert_eq!(
+        table.inputs().0[0].keyboard,
+        Some(KeyboardInput(vec![0xff53]))
+    );
+    assert!(!table.set_cell(0, 1, Cell::Coordinate(3)));
+
+    let x = table.add_column(Column::MouseX);
+    assert_eq!(table.add_column(Column::MouseX), x);
+    assert_eq!(table.cell(2, x), Some(Cell::Coordinate(0)));
+    assert!(table.set_cell(2, x, Cell::Coordinate(40)));
+    assert_eq!(table.inputs().0[2].mouse.unwrap().xpos, 40);
+    let button = table.add_column(Column::MouseButton(3));
+    assert!(table.set_cell(2, button, Cell::Pressed(true)));
+    assert!(table.inputs().0[2].mouse.unwrap().right_click);
+    let invalid = table.add_column(Column::MouseButton(6));
+    assert!(!table.set_cell(2, invalid, Cell::Pressed(true)));
+}
+
+#[test]
+fn test_rows() {
+    let mut table = InputTable::new(sample_movie(4, 60).inputs);
+    assert!(table.insert_rows(4, 2));
+    assert!(!table.insert_rows(7, 1));
+    assert_eq!(table.row_count(), 6);
+    assert!(table.remove_rows(0..2));
+    assert!(!table.remove_rows(3..5));
+    let inputs: Inputs = table.into_inputs();
+    assert_eq!(inputs.0.len(), 4);
+    assert_eq!(inputs.0[0].keyboard, Some(KeyboardInput(vec![0xff53])));
+
+    // lines that are not frames stay before the frames they precede
+    let mut table = InputTable::new("# a\n|\n# b\n|K7a|\n# c\n".parse().unwrap());
+    assert!(table.insert_rows(1, 2));
+    assert_eq!(
+        table.inputs().to_string(),
+        "# a\n|\n|\n|\n# b\n|K7a|\n# c\n"
+    );
+    assert!(table.remove_rows(0..2));
+    assert_eq!(table.inputs().to_string(), "# a\n|\n# b\n|K7a|\n# c\n");
+    assert!(table.remove_rows(0..2));
+    assert_eq!(table.inputs().to_string(), "# a\n# b\n# c\n");
+}
+
+#[test]
+fn test_render_text() {
+    let mut inputs = sample_movie(4, 60).inputs;
+    inputs.0[3].mouse = movie_with_mouse_path(&[(166, 270)], 60).inputs.0[0].mouse;
+    let columns = [
+        Column::Key(0x7a),
+        Column::Key(0xff53),
+        Column::MouseButton(1),
+        Column::MouseX,
+        Column::MouseY,
+    ];
+    assert_eq!(
+        render_text(&inputs, &columns, 0..10),
+        "frame | z | Right | LMB | x,y
+    0 |   |       |     |
+    1 | z |       |     |
+    2 |   | Right |     |
+    3 | z | Right | LMB | 166,270
+"
+    );
+    assert_eq!(
+        render_text(&inputs, &[Column::MouseY], 2..3),
+        "frame | y\n    2 |\n"
+    );
+}
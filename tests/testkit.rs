@@ -0,0 +1,62 @@
+use libtas_movie::{
+    movie::{EntryError, LoadError, load_movie_from_reader},
+    testkit::{BadArchive, movie_with_mouse_path, sample_movie},
+    time::TimeSpec,
+};
+
+#[test]
+fn test_sample_movie() {
+    let movie = sample_movie(50, 20);
+    let general = &movie.config.general;
+    assert_eq!(general.frame_count, 50);
+    assert_eq!(general.framerate_num, 20);
+    assert_eq!(general.length, TimeSpec::new(2, 500_000_000));
+    assert_eq!(movie.inputs.0.len(), 50);
+
+    let bytes = movie.compress().unwrap();
+    let reloaded = load_movie_from_reader(bytes.as_slice()).unwrap();
+    assert_eq!(movie, reloaded);
+}
+
+#[test]
+fn test_movie_with_mouse_path() {
+    let movie = movie_with_mouse_path(&[(0, 0), (10, 5), (20, 10)], 60);
+    let mouse = movie.inputs[1].mouse.unwrap();
+    assert_eq!((mouse.xpos, mouse.ypos), (10, 5));
+    assert!(!mouse.left_click);
+    assert!(movie.inputs[2].mouse.unwrap().left_click);
+
+    let bytes = movie.compress().unwrap();
+    let reloaded = load_movie_from_reader(bytes.as_slice()).unwrap();
+    assert_eq!(movie, reloaded);
+}
+
+/// Every known-bad archive should fail to load with the matching error.
+#[test]
+fn test_bad_archives() {
+    for kind in BadArchive::ALL {
+        let result = load_movie_from_reader(kind.bytes().as_slice());
+        match (kind, result) {
+            (BadArchive::NotGzip, Err(LoadError::InvalidArchive { .. }))
+            | (BadArchive::MissingEntry, Err(LoadError::MissingEntry { name: "inputs" })) => {}
+            (BadArchive::ExtraEntry, Err(LoadError::UnexpectedEntry { path })) => {
+                assert_eq!(path.as_os_str(), "extra.txt");
+            }
+            (
+                BadArchive::InvalidConfig,
+                Err(LoadError::InvalidEntry {
+                    name,
+                    source: EntryError::Config(_),
+                }),
+            ) => assert_eq!(name, "config.ini"),
+            (
+                BadArchive::InvalidInputs,
+                Err(LoadError::InvalidEntry {
+                    name,
+                    source: EntryError::Inputs(_),
+                }),
+            ) => assert_eq!(name, "inputs"),
+            (kind, result) => panic!("{kind:?} loaded as {result:?}"),
+        }
+    }
+}
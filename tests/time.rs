@@ -0,0 +1,296 @@
+use core::time::Duration;
+use std::time::UNIX_EPOCH;
+
+use libtas_movie::{
+    config::{GeneralConfig, InitialTimeError, MonotonicTime},
+    inputs::{Input, Inputs},
+    movie::load_movie,
+    time::{Framerate, TimeSpec, Timeline},
+};
+
+#[test]
+fn test_framerate() {
+    let framerate = Framerate::new(120, 2).unwrap();
+    assert_eq!(framerate, Framerate::from_fps(60).unwrap());
+    assert_eq!(framerate.reduced().num, 60);
+    assert_eq!(framerate.reduced().den, 1);
+    assert!(Framerate::new(30000, 1001).unwrap() < Framerate::from_fps(30).unwrap());
+    assert!(Framerate::new(0, 1).is_none());
+
+    let ntsc = Framerate::new(60000, 1001).unwrap();
+    assert!((ntsc.as_f64() - 59.94).abs() < 0.001);
+    assert_eq!(ntsc.frame_duration(), Duration::from_nanos(16_683_334));
+    assert_eq!(ntsc.duration_of(60000), Duration::from_secs(1001));
+    assert_eq!(ntsc.to_string(), "60000/1001");
+
+    // frames start within themselves, which rounding the durations down would break
+    let timeline = Timeline::new(ntsc, &Inputs::from(vec![Input::default(); 1000]));
+    for frame in 0..1000 {
+        assert_eq!(timeline.frame_at(timeline.time_of_frame(frame)), frame);
+    }
+}
+
+#[test]
+fn test_config_framerate() {
+    let mut movie = load_movie("tests/movies/221769_Trapped_5.ltm").unwrap();
+    let general = &mut movie.config.general;
+    let framerate = general.framerate();
+    assert_eq!(framerate, Framerate::from_fps(20).unwrap());
+    assert_eq!(
+        framerate.duration_of(general.frame_count),
+        Duration::from(general.length)
+    );
+
+    general.set_framerate(Framerate::new(30000, 1001).unwrap());
+    assert_eq!(general.framerate_num, 30000);
+    assert_eq!(general.framerate_den, 1001);
+}
+
+#[test]
+fn test_timeline() {
+    let movie = load_movie("tests/movies/221769_Trapped_5.ltm").unwrap();
+    let timeline = movie.timeline();
+    assert_eq!(timeline.len(), 456);
+    assert_eq!(timeline.time_of_frame(1), Duration::from_millis(50));
+    assert_eq!(timeline.time_of_frame(456), Duration::from_millis(22800));
+    assert_eq!(timeline.frame_at(Duration::from_millis(49)), 0);
+    assert_eq!(timeline.frame_at(Duration::from_millis(50)), 1);
+    assert_eq!(timeline.frame_at(Duration::from_secs(60)), 1200);
+}
+
+/// Frames with `T` sections override the config framerate.
+#[test]
+fn test_variable_timeline() {
+    let inputs: Inputs = "|\n|T30:1|\n|T30:1|\n|\n".parse().unwrap();
+    assert_eq!(inputs[1].framerate, Framerate::new(30, 1));
+    assert_eq!(inputs.to_string(), "|\n|T30:1|\n|T30:1|\n|\n");
+
+    let timeline = Timeline::new(Framerate::from_fps(60).unwrap(), &inputs);
+    let millis = (0..=4)
+        .map(|frame| timeline.time_of_frame(frame).as_nanos() / 1_000_000)
+        .collect::<Vec<_>>();
+    assert_eq!(millis, [0, 16, 50, 83, 100]);
+    assert_eq!(timeline.frame_at(Duration::from_millis(20)), 1);
+    assert_eq!(timeline.frame_at(Duration::from_millis(60)), 2);
+    assert_eq!(timeline.frame_at(Duration::from_millis(90)), 3);
+}
+
+#[test]
+fn test_initial_time() {
+    let mut movie = load_movie("tests/movies/221769_Trapped_5.ltm").unwrap();
+    let general = &mut movie.config.general;
+    assert_eq!(
+        general.initial_time(),
+        Some(UNIX_EPOCH + Duration::from_secs(1))
+    );
+    assert_eq!(general.initial_monotonic_time(), Duration::from_secs(1));
+
+    general
+        .set_initial_time(UNIX_EPOCH + Duration::new(1_700_000_000, 250_000_000))
+        .unwrap();
+    assert_eq!(
+        general.initial_time,
+        TimeSpec::new(1_700_000_000, 250_000_000)
+    );
+    assert!(
+        general
+            .set_initial_time(UNIX_EPOCH - Duration::from_secs(1))
+            .is_err()
+    );
+
+    general.set_initial_monotonic_time(Duration::from_millis(2500));
+    assert_eq!(
+        general.initial_monotonic_time,
+        TimeSpec::new(2, 500_000_000)
+    );
+
+    // a time too late for the system clock is not a panic
+    general.initial_time = TimeSpec::new(u64::MAX, 0);
+    assert_eq!(general.initial_time(), None);
+
+    // nanoseconds beyond 32 bits are read, and carried into seconds
+    let config = GeneralConfig::default()
+        .to_string()
+        .replace("initial_time_nsec=0", "initial_time_nsec=5000000000");
+    let general: GeneralConfig = config.parse().unwrap();
+    assert_eq!(general.initial_time, TimeSpec::new(1, 5_000_000_000));
+    assert_eq!(
+        general.initial_time(),
+        Some(UNIX_EPOCH + Duration::from_secs(6))
+    );
+}
+
+#[test]
+fn test_set_initial_times() {
+    let mut general = GeneralConfig::default();
+    general.set_initial_monotonic_time(Duration::from_secs(10));
+    general
+        .set_initial_time(UNIX_EPOCH + Duration::from_secs(100))
+        .unwrap();
+
+    let time = UNIX_EPOCH + Duration::from_millis(100_500);
+    general
+        .set_initial_times(time, MonotonicTime::KeepOffset)
+        .unwrap();
+    assert_eq!(general.initial_time(), Some(time));
+    assert_eq!(
+        general.initial_monotonic_time(),
+        Duration::from_millis(10_500)
+    );
+
+    let result = general.set_initial_times(
+        UNIX_EPOCH + Duration::from_secs(50),
+        MonotonicTime::KeepOffset,
+    );
+    assert!(matches!(
+        result,
+        Err(InitialTimeError::NegativeMonotonicTime)
+    ));
+    assert_eq!(general.initial_time(), Some(time));
+
+    let time = UNIX_EPOCH + Duration::from_secs(95);
+    general
+        .set_initial_times(time, MonotonicTime::KeepOffset)
+        .unwrap();
+    assert_eq!(general.initial_monotonic_time(), Duration::from_secs(5));
+    general
+        .set_initial_times(time, MonotonicTime::Same)
+        .unwrap();
+    assert_eq!(general.initial_monotonic_time(), Duration::from_secs(95));
+    general
+        .set_initial_times(UNIX_EPOCH, MonotonicTime::Unchanged)
+        .unwrap();
+    assert_eq!(general.initial_monotonic_time(), Duration::from_secs(95));
+    assert!(matches!(
+        general.set_initial_times(UNIX_EPOCH - Duration::from_secs(1), MonotonicTime::Same),
+        Err(InitialTimeError::BeforeEpoch(_))
+    ));
+}
+
+#[test]
+fn test_check_initial_times() {
+    let mut general = GeneralConfig::default();
+    assert!(general.check_initial_times().is_ok());
+    general.initial_monotonic_time.nsec = 1_000_000_000;
+    assert!(matches!(
+        general.check_initial_times(),
+        Err(InitialTimeError::InvalidNanoseconds(
+            "initial_monotonic_time_nsec",
+            1_000_000_000
+        ))
+    ));
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_initial_datetime() {
+    use chrono::{DateTime, TimeZone as _, Utc};
+
+    let mut movie = load_movie("tests/movies/221769_Trapped_5.ltm").unwrap();
+    let general = &mut movie.config.general;
+    assert_eq!(
+        general.initial_datetime(),
+        DateTime::<Utc>::from_timestamp(1, 0)
+    );
+
+    let date = Utc.with_ymd_and_hms(2000, 1, 1, 0, 0, 0).unwrap();
+    general.set_initial_datetime(&date).unwrap();
+    assert_eq!(general.initial_time.sec, 946_684_800);
+    assert_eq!(general.initial_datetime(), Some(date));
+
+    general.initial_time = TimeSpec::new(u64::MAX, 0);
+    assert_eq!(general.initial_datetime(), None);
+}
+
+#[test]
+fn test_duration_summary() {
+    let mut movie = load_movie("tests/movies/221769_Trapped_5.ltm").unwrap();
+    assert_eq!(movie.duration(), Duration::from_millis(22800));
+    assert_eq!(
+        movie.duration_summary().to_string(),
+        "22.800s (456 frames @ 20 fps)"
+    );
+
+    movie
+        .config
+        .general
+        .set_framerate(Framerate::new(60000, 1001).unwrap());
+    assert_eq!(
+        movie.duration_summary().to_string(),
+        "22.800s (456 frames @ 59.94 fps)"
+    );
+}
+
+#[test]
+fn test_time_spec() {
+    let a = TimeSpec::new(1, 700_000_000);
+    let b = TimeSpec::new(0, 400_000_000);
+    assert_eq!(a + b, TimeSpec::new(2, 100_000_000));
+    assert_eq!(a - b, TimeSpec::new(1, 300_000_000));
+    assert_eq!(b.checked_sub(a), None);
+    assert_eq!(TimeSpec::new(u64::MAX, 999_999_999).checked_add(b), None);
+
+    let unnormalized = TimeSpec::new(3, 2_500_000_000);
+    assert!(!unnormalized.is_normalized());
+    assert_eq!(unnormalized.normalized(), TimeSpec::new(5, 500_000_000));
+    assert_eq!(
+        unnormalized + TimeSpec::default(),
+        TimeSpec::new(5, 500_000_000)
+    );
+    assert_eq!(Duration::from(unnormalized), Duration::from_millis(5500));
+    assert_eq!(unnormalized.to_string(), "5.500000000");
+    assert_eq!(
+        TimeSpec::from(Duration::new(22, 800_000_000)),
+        TimeSpec::new(22, 800_000_000)
+    );
+    assert_eq!(
+        Duration::from(TimeSpec::new(u64::MAX, 2_000_000_000)),
+        Duration::MAX
+    );
+}
+
+#[test]
+fn test_recompute_length() {
+    let mut movie = load_movie("tests/movies/221769_Trapped_5.ltm").unwrap();
+    let timeline = movie.timeline();
+    assert_eq!(timeline.total_duration(), Duration::from_millis(22800));
+    assert_eq!(
+        timeline.total_duration(),
+        timeline.time_of_frame(timeline.len())
+    );
+    assert!(!movie.recompute_length());
+    assert_eq!(movie.config.general.length, TimeSpec::new(22, 800_000_000));
+
+    // a framerate of 60000/1001 fps does not divide nanoseconds
+    movie
+        .config
+        .general
+        .set_framerate(Framerate::new(60000, 1001).unwrap());
+    assert!(movie.recompute_length());
+    assert_eq!(movie.config.general.length, TimeSpec::new(7, 607_600_000));
+
+    // the start of each run is rounded, but the total is exact
+    let inputs: Inputs = "|\n|T120:1|\n|\n|T120:1|\n".parse().unwrap();
+    let timeline = Timeline::new(Framerate::from_fps(60).unwrap(), &inputs);
+    assert_eq!(timeline.time_of_frame(4), Duration::from_nanos(50_000_002));
+    assert_eq!(timeline.total_duration(), Duration::from_millis(50));
+    assert_eq!(
+        Timeline::new(Framerate::from_fps(60).unwrap(), &Inputs::default()).total_duration(),
+        Duration::ZERO
+    );
+}
+
+#[test]
+fn test_movie_accessors() {
+    let mut movie = load_movie("tests/movies/221769_Trapped_5.ltm").unwrap();
+    assert_eq!(movie.framerate(), Framerate::from_fps(20).unwrap());
+    assert_eq!(movie.fps_f64(), 20.0);
+    assert_eq!(movie.duration(), Duration::from_millis(22800));
+
+    movie
+        .config
+        .general
+        .set_framerate(Framerate::new(30000, 1001).unwrap());
+    assert_eq!(movie.framerate().num, 30000);
+    assert!((movie.fps_f64() - 29.97).abs() < 0.001);
+}
@@ -0,0 +1,124 @@
+use libtas_movie::{
+    testkit::sample_movie,
+    time::Framerate,
+    version::{CompatIssue, Feature, Migration, Version},
+};
+
+#[test]
+fn test_version() {
+    let version: Version = "1.4.7".parse().unwrap();
+    assert_eq!(version, Version::new(1, 4, 7));
+    assert_eq!(version.to_string(), "1.4.7");
+    assert!(Version::new(1, 3, 5) < version);
+    assert!("1.4".parse::<Version>().is_err());
+    assert_eq!(sample_movie(1, 60).config.general.libtas_version(), version);
+}
+
+#[test]
+fn test_migrate_down() {
+    const MIGRATIONS: &[Migration] = &[Migration {
+        version: Version::new(1, 4, 0),
+        description: "variable framerate",
+        upgrade: |_| {},
+        downgrade: |movie| {
+            movie.config.general.variable_framerate = false;
+            for input in &mut movie.inputs.0 {
+                input.framerate = None;
+            }
+        },
+    }];
+
+    let mut movie = sample_movie(4, 60);
+    movie.config.general.variable_framerate = true;
+    movie.inputs.0[1].framerate = Framerate::new(30, 1);
+
+    // nothing to change between 1.4.x versions
+    assert!(
+        movie
+            .migrate_with(Version::new(1, 4, 0), MIGRATIONS)
+            .is_empty()
+    );
+    assert_eq!(movie.config.general.libtas_version(), Version::new(1, 4, 0));
+    assert!(movie.inputs[1].framerate.is_some());
+
+    let applied = movie.migrate_with(Version::new(1, 3, 5), MIGRATIONS);
+    assert_eq!(applied.len(), 1);
+    assert!(!movie.config.general.variable_framerate);
+    assert!(movie.inputs[1].framerate.is_none());
+    assert_eq!(movie.config.general.libtas_minor_version, 3);
+}
+
+#[test]
+fn test_custom_migrations() {
+    const MIGRATIONS: &[Migration] = &[
+        Migration {
+            version: Version::new(1, 5, 0),
+            description: "renamed key",
+            upgrade: |movie| {
+                let value = movie.config.get_raw("General", "old_key").unwrap();
+                movie.config.set_raw("General", "new_key", &value).unwrap();
+            },
+            downgrade: |_| {},
+        },
+        Migration {
+            version: Version::new(1, 6, 0),
+            description: "not reached",
+            upgrade: |_| panic!("should not be applied"),
+            downgrade: |_| {},
+        },
+    ];
+
+    let mut movie = sample_movie(1, 60);
+    movie.config.set_raw("General", "old_key", "1").unwrap();
+    let applied = movie.migrate_with(Version::new(1, 5, 2), MIGRATIONS);
+    assert_eq!(applied, ["renamed key"]);
+    assert_eq!(
+        movie.config.get_raw("General", "new_key").as_deref(),
+        Some("1")
+    );
+}
+
+#[test]
+fn test_compatible_with() {
+    let introduced = [(Feature::VariableFramerate, Version::new(1, 4, 0))];
+    let mut movie = sample_movie(4, 60);
+    assert!(
+        movie
+            .compatible_with(Version::new(1, 3, 0), &introduced)
+            .is_compatible()
+    );
+
+    movie.inputs.0[2].framerate = Framerate::new(30, 1);
+    movie.config.set_raw("General", "future_key", "1").unwrap();
+    let report = movie.compatible_with(Version::new(1, 3, 0), &introduced);
+    assert_eq!(
+        report.issues,
+        [
+            CompatIssue {
+                feature: Feature::VariableFramerate,
+                required: Version::new(1, 4, 0),
+                first_frame: Some(2),
+            },
+            CompatIssue {
+                feature: Feature::UnknownConfigKey("General".to_owned(), "future_key".to_owned()),
+                required: Version::new(1, 4, 7),
+                first_frame: None,
+            },
+        ]
+    );
+
+    assert!(
+        movie
+            .compatible_with(Version::new(1, 4, 7), &introduced)
+            .is_compatible()
+    );
+
+    // features without a known version are not checked
+    assert_eq!(
+        movie
+            .compatible_with(Version::new(1, 3, 0), &[])
+            .issues
+            .len(),
+        1
+    );
+}
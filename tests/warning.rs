@@ -0,0 +1,157 @@
+use libtas_movie::{
+    inputs::Inputs,
+    movie::{LoadError, LoadOptions, load_movie_from_reader},
+    testkit::{archive, sample_movie},
+    warning::Warning,
+};
+
+#[test]
+fn test_no_warnings() {
+    let (_, warnings) = LoadOptions::default()
+        .load_with_warnings("tests/movies/221769_Trapped_5.ltm")
+        .unwrap();
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn test_warnings() {
+    let mut config = sample_movie(2, 60).config;
+    config.set_raw("General", "future_key", "1").unwrap();
+    let config = config.to_string();
+    let bytes = archive(&[
+        ("config.ini", config.as_bytes()),
+        ("inputs", b"|K7a|F1|X9|\n|K20000000|\n"),
+        ("annotations.txt", b"old"),
+        ("editor.ini", b""),
+        ("annotations.txt", b"new"),
+    ]);
+
+    let (movie, warnings) = LoadOptions::default()
+        .load_from_reader_with_warnings(bytes.as_slice())
+        .unwrap();
+    assert_eq!(movie.annotations, "new");
+    assert_eq!(movie.inputs.to_string(), "|K7a|F1|X9|\n|K20000000|\n");
+    assert_eq!(
+        warnings.0,
+        [
+            Warning::IgnoredInputSection {
+                frame: 0,
+                section: "F1".to_owned()
+            },
+            Warning::IgnoredInputSection {
+                frame: 0,
+                section: "X9".to_owned()
+            },
+            Warning::DuplicateEntry("annotations.txt".to_owned()),
+            Warning::UnknownConfigKey {
+                section: "General".to_owned(),
+                key: "future_key".to_owned()
+            },
+            Warning::KeysymOutOfRange {
+                frame: 1,
+                key: 0x2000_0000
+            },
+        ]
+    );
+
+    // warnings are not errors
+    assert_eq!(load_movie_from_reader(bytes.as_slice()).unwrap(), movie);
+}
+
+#[test]
+fn test_parse_lenient() {
+    let text = "|K7a|Z|\n|F1||\n";
+    assert!(text.parse::<Inputs>().is_err());
+    let (inputs, ignored) = Inputs::parse_lenient(text).unwrap();
+    assert_eq!(inputs.to_string(), "|K7a|Z|\n|F1|\n");
+    assert_eq!(
+        ignored,
+        [
+            (0, "Z".to_owned()),
+            (1, "F1".to_owned()),
+            (1, String::new())
+        ]
+    );
+}
+
+#[test]
+fn test_load_strict_inputs() {
+    let config = sample_movie(1, 60).config.to_string();
+    let bytes = archive(&[
+        ("config.ini", config.as_bytes()),
+        ("inputs", b"|K7a||\n"),
+        ("annotations.txt", b""),
+        ("editor.ini", b""),
+    ]);
+    assert!(matches!(
+        load_movie_from_reader(bytes.as_slice()),
+        Err(LoadError::InvalidEntry { .. })
+    ));
+}
+
+fn strict() -> LoadOptions {
+    LoadOptions {
+        strict: true,
+        ..LoadOptions::default()
+    }
+}
+
+#[test]
+fn test_strict() {
+    strict().load("tests/movies/221769_Trapped_5.ltm").unwrap();
+
+    let config = sample_movie(1, 60).config.to_string();
+    let bytes = archive(&[
+        ("config.ini", config.as_bytes()),
+        ("inputs", b"|K7a|\n"),
+        ("annotations.txt", b""),
+        (
+            "editor.ini",
+            b"[nondraw_frames]\nsize=0\n\n[lua]\nscript=a.lua\n",
+        ),
+    ]);
+    assert!(load_movie_from_reader(bytes.as_slice()).is_ok());
+    match strict().load_from_reader(bytes.as_slice()) {
+        Err(LoadError::Strict(warnings)) => assert_eq!(
+            warnings.0,
+            [Warning::UnknownEditorKey {
+                group: "lua".to_owned(),
+                key: "script".to_owned()
+            }]
+        ),
+        result => panic!("loaded as {result:?}"),
+    }
+
+    let bytes = archive(&[
+        ("config.ini", config.as_bytes()),
+        ("inputs", b"|F1|\n"),
+        ("annotations.txt", b""),
+        ("editor.ini", b""),
+    ]);
+    assert!(matches!(
+        strict().load_from_reader(bytes.as_slice()),
+        Err(LoadError::Strict(_))
+    ));
+}
+
+#[test]
+fn test_frame_count_warning() {
+    let config = sample_movie(3, 60).config.to_string();
+    let bytes = archive(&[
+        ("config.ini", config.as_bytes()),
+        ("inputs", b"|\n|\n"),
+        ("annotations.txt", b""),
+        ("editor.ini", b""),
+    ]);
+    let (movie, warnings) = LoadOptions::default()
+        .load_from_reader_with_warnings(bytes.as_slice())
+        .unwrap();
+    assert_eq!(movie.frame_count(), 2);
+    assert_eq!(
+        warnings.0,
+        [Warning::FrameCountMismatch {
+            config: 3,
+            inputs: 2
+        }]
+    );
+}
@@ -0,0 +1,9 @@
+#![cfg(feature = "xtest")]
+
+use libtas_movie::xtest::{XTestError, XTestSink};
+
+#[test]
+fn test_connect_failure() {
+    let result = XTestSink::connect_to(Some("invalid display name"));
+    assert!(matches!(result, Err(XTestError::Connect(_))));
+}